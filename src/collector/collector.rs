@@ -0,0 +1,135 @@
+use downcast_rs::impl_downcast;
+
+use crate::{DocId, Score, SegmentOrdinal, SegmentReader, Weight};
+
+/// Result of a collector.
+pub trait Fruit: Send + downcast_rs::Downcast {}
+
+impl<T> Fruit for T where T: Send + downcast_rs::Downcast {}
+
+/// Gathers the documents matching a query over all the segments of a `Searcher`.
+pub trait Collector: Sync {
+    /// Final result of the collector.
+    type Fruit: Fruit;
+
+    /// Collector of a single segment.
+    type Child: SegmentCollector;
+
+    /// Creates the collector of the segment `segment_reader`, whose ordinal in
+    /// the searcher is `segment_ord`.
+    fn for_segment(
+        &self,
+        segment_ord: SegmentOrdinal,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<Self::Child>;
+
+    /// Returns true if the collector needs the scores of the documents.
+    fn requires_scoring(&self) -> bool;
+
+    /// Merges the fruits of all the segments, in the order of the segments.
+    fn merge_fruits(
+        &self,
+        segment_fruits: Vec<<Self::Child as SegmentCollector>::Fruit>,
+    ) -> crate::Result<Self::Fruit>;
+
+    /// Runs `weight` over a segment and returns the fruit of the segment.
+    ///
+    /// Deleted documents are never passed to the segment collector.
+    fn collect_segment(
+        &self,
+        weight: &dyn Weight,
+        segment_ord: SegmentOrdinal,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<<Self::Child as SegmentCollector>::Fruit> {
+        let mut segment_collector = self.for_segment(segment_ord, segment_reader)?;
+        match segment_reader.alive_bitset() {
+            Some(alive_bitset) => weight.for_each(segment_reader, &mut |doc, score| {
+                if alive_bitset.is_alive(doc) {
+                    segment_collector.collect(doc, score);
+                }
+            })?,
+            None => weight.for_each(segment_reader, &mut |doc, score| {
+                segment_collector.collect(doc, score);
+            })?,
+        }
+        Ok(segment_collector.harvest())
+    }
+}
+
+/// Gathers the documents matching a query in a single segment.
+pub trait SegmentCollector: 'static {
+    type Fruit: Fruit;
+
+    /// Called for each matching document, in increasing doc id order.
+    fn collect(&mut self, doc: DocId, score: Score);
+
+    /// Returns the result of the segment.
+    fn harvest(self) -> Self::Fruit;
+}
+
+impl<Left: Collector, Right: Collector> Collector for (Left, Right) {
+    type Fruit = (Left::Fruit, Right::Fruit);
+    type Child = (Left::Child, Right::Child);
+
+    fn for_segment(
+        &self,
+        segment_ord: SegmentOrdinal,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<Self::Child> {
+        let left = self.0.for_segment(segment_ord, segment_reader)?;
+        let right = self.1.for_segment(segment_ord, segment_reader)?;
+        Ok((left, right))
+    }
+
+    fn requires_scoring(&self) -> bool {
+        self.0.requires_scoring() || self.1.requires_scoring()
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_fruits: Vec<<Self::Child as SegmentCollector>::Fruit>,
+    ) -> crate::Result<Self::Fruit> {
+        let (left_fruits, right_fruits): (Vec<_>, Vec<_>) = segment_fruits.into_iter().unzip();
+        Ok((
+            self.0.merge_fruits(left_fruits)?,
+            self.1.merge_fruits(right_fruits)?,
+        ))
+    }
+}
+
+impl<Left: SegmentCollector, Right: SegmentCollector> SegmentCollector for (Left, Right) {
+    type Fruit = (Left::Fruit, Right::Fruit);
+
+    fn collect(&mut self, doc: DocId, score: Score) {
+        self.0.collect(doc, score);
+        self.1.collect(doc, score);
+    }
+
+    fn harvest(self) -> Self::Fruit {
+        (self.0.harvest(), self.1.harvest())
+    }
+}
+
+/// Object-safe version of `SegmentCollector`, used by `MultiCollector`.
+pub trait BoxableSegmentCollector {
+    fn collect(&mut self, doc: DocId, score: Score);
+    fn harvest_from_box(self: Box<Self>) -> Box<dyn Fruit>;
+}
+
+pub(crate) struct SegmentCollectorWrapper<TSegmentCollector: SegmentCollector>(
+    pub(crate) TSegmentCollector,
+);
+
+impl<TSegmentCollector: SegmentCollector> BoxableSegmentCollector
+    for SegmentCollectorWrapper<TSegmentCollector>
+{
+    fn collect(&mut self, doc: DocId, score: Score) {
+        self.0.collect(doc, score);
+    }
+
+    fn harvest_from_box(self: Box<Self>) -> Box<dyn Fruit> {
+        Box::new(self.0.harvest())
+    }
+}
+
+impl_downcast!(Fruit);
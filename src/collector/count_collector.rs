@@ -0,0 +1,43 @@
+use crate::{Collector, DocId, Score, SegmentCollector, SegmentOrdinal, SegmentReader};
+
+/// Counts the matching documents.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Count;
+
+impl Collector for Count {
+    type Fruit = usize;
+    type Child = SegmentCountCollector;
+
+    fn for_segment(
+        &self,
+        _segment_ord: SegmentOrdinal,
+        _segment_reader: &SegmentReader,
+    ) -> crate::Result<SegmentCountCollector> {
+        Ok(SegmentCountCollector::default())
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(&self, segment_counts: Vec<usize>) -> crate::Result<usize> {
+        Ok(segment_counts.into_iter().sum())
+    }
+}
+
+#[derive(Default)]
+pub struct SegmentCountCollector {
+    count: usize,
+}
+
+impl SegmentCollector for SegmentCountCollector {
+    type Fruit = usize;
+
+    fn collect(&mut self, _doc: DocId, _score: Score) {
+        self.count += 1;
+    }
+
+    fn harvest(self) -> usize {
+        self.count
+    }
+}
@@ -0,0 +1,107 @@
+use crate::collector::top_collector::{TopCollector, TopSegmentCollector};
+use crate::{Collector, DocAddress, DocId, Score, SegmentCollector, SegmentOrdinal, SegmentReader};
+
+/// Computes the feature documents are ranked by in `TopDocs::custom_score`.
+pub trait CustomScorer<TScore>: Sync {
+    type Child: CustomSegmentScorer<TScore>;
+
+    /// Creates the scorer of a segment.
+    fn segment_scorer(&self, segment_reader: &SegmentReader) -> crate::Result<Self::Child>;
+}
+
+/// Computes the feature of the documents of a segment.
+pub trait CustomSegmentScorer<TScore>: 'static {
+    fn score(&mut self, doc: DocId) -> TScore;
+}
+
+impl<F, TCustomSegmentScorer, TScore> CustomScorer<TScore> for F
+where
+    F: 'static + Send + Sync + Fn(&SegmentReader) -> TCustomSegmentScorer,
+    TCustomSegmentScorer: CustomSegmentScorer<TScore>,
+{
+    type Child = TCustomSegmentScorer;
+
+    fn segment_scorer(&self, segment_reader: &SegmentReader) -> crate::Result<Self::Child> {
+        Ok((self)(segment_reader))
+    }
+}
+
+impl<F, TScore> CustomSegmentScorer<TScore> for F
+where
+    F: 'static + FnMut(DocId) -> TScore,
+{
+    fn score(&mut self, doc: DocId) -> TScore {
+        (self)(doc)
+    }
+}
+
+/// Collector returned by `TopDocs::custom_score`.
+pub struct CustomScoreTopCollector<TCustomScorer, TScore> {
+    custom_scorer: TCustomScorer,
+    collector: TopCollector<TScore>,
+}
+
+impl<TCustomScorer, TScore: PartialOrd + Clone> CustomScoreTopCollector<TCustomScorer, TScore> {
+    pub(crate) fn new(
+        custom_scorer: TCustomScorer,
+        limit: usize,
+        offset: usize,
+    ) -> CustomScoreTopCollector<TCustomScorer, TScore> {
+        CustomScoreTopCollector {
+            custom_scorer,
+            collector: TopCollector::with_limit(limit).and_offset(offset),
+        }
+    }
+}
+
+impl<TCustomScorer, TScore> Collector for CustomScoreTopCollector<TCustomScorer, TScore>
+where
+    TCustomScorer: CustomScorer<TScore> + Send + Sync,
+    TScore: 'static + PartialOrd + Clone + Send + Sync,
+{
+    type Fruit = Vec<(TScore, DocAddress)>;
+    type Child = CustomScoreTopSegmentCollector<TCustomScorer::Child, TScore>;
+
+    fn for_segment(
+        &self,
+        segment_ord: SegmentOrdinal,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<Self::Child> {
+        Ok(CustomScoreTopSegmentCollector {
+            segment_collector: self.collector.for_segment(segment_ord),
+            segment_scorer: self.custom_scorer.segment_scorer(segment_reader)?,
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(&self, segment_fruits: Vec<Self::Fruit>) -> crate::Result<Self::Fruit> {
+        Ok(self.collector.merge_fruits(segment_fruits))
+    }
+}
+
+/// Segment collector of `CustomScoreTopCollector`.
+pub struct CustomScoreTopSegmentCollector<TSegmentScorer, TScore> {
+    segment_collector: TopSegmentCollector<TScore>,
+    segment_scorer: TSegmentScorer,
+}
+
+impl<TSegmentScorer, TScore> SegmentCollector
+    for CustomScoreTopSegmentCollector<TSegmentScorer, TScore>
+where
+    TSegmentScorer: CustomSegmentScorer<TScore>,
+    TScore: 'static + PartialOrd + Clone + Send + Sync,
+{
+    type Fruit = Vec<(TScore, DocAddress)>;
+
+    fn collect(&mut self, doc: DocId, _score: Score) {
+        let score = self.segment_scorer.score(doc);
+        self.segment_collector.collect(doc, score);
+    }
+
+    fn harvest(self) -> Vec<(TScore, DocAddress)> {
+        self.segment_collector.harvest()
+    }
+}
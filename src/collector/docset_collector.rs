@@ -0,0 +1,59 @@
+use std::collections::HashSet;
+
+use crate::{Collector, DocAddress, DocId, Score, SegmentCollector, SegmentOrdinal, SegmentReader};
+
+/// Collects the addresses of all the matching documents.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DocSetCollector;
+
+impl Collector for DocSetCollector {
+    type Fruit = HashSet<DocAddress>;
+    type Child = DocSetChildCollector;
+
+    fn for_segment(
+        &self,
+        segment_ord: SegmentOrdinal,
+        _segment_reader: &SegmentReader,
+    ) -> crate::Result<DocSetChildCollector> {
+        Ok(DocSetChildCollector {
+            segment_ord,
+            docs: HashSet::new(),
+        })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        false
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_fruits: Vec<(SegmentOrdinal, HashSet<DocId>)>,
+    ) -> crate::Result<HashSet<DocAddress>> {
+        let len = segment_fruits.iter().map(|(_, docs)| docs.len()).sum();
+        let mut result = HashSet::with_capacity(len);
+        for (segment_ord, docs) in segment_fruits {
+            result.extend(
+                docs.into_iter()
+                    .map(|doc_id| DocAddress::new(segment_ord, doc_id)),
+            );
+        }
+        Ok(result)
+    }
+}
+
+pub struct DocSetChildCollector {
+    segment_ord: SegmentOrdinal,
+    docs: HashSet<DocId>,
+}
+
+impl SegmentCollector for DocSetChildCollector {
+    type Fruit = (SegmentOrdinal, HashSet<DocId>);
+
+    fn collect(&mut self, doc: DocId, _score: Score) {
+        self.docs.insert(doc);
+    }
+
+    fn harvest(self) -> (SegmentOrdinal, HashSet<DocId>) {
+        (self.segment_ord, self.docs)
+    }
+}
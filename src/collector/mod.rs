@@ -0,0 +1,19 @@
+//! Collectors gather the results of a search.
+//!
+//! A `Collector` creates one `SegmentCollector` per segment, which receives the
+//! matching documents of its segment and produces a fruit. The fruits of all
+//! the segments are then merged into the final result.
+mod collector;
+mod count_collector;
+mod custom_score_top_collector;
+mod docset_collector;
+mod multi_collector;
+mod top_collector;
+mod top_score_collector;
+
+pub use collector::*;
+pub use count_collector::*;
+pub use custom_score_top_collector::*;
+pub use docset_collector::*;
+pub use multi_collector::*;
+pub use top_score_collector::*;
@@ -0,0 +1,216 @@
+use std::marker::PhantomData;
+
+use crate::collector::collector::SegmentCollectorWrapper;
+use crate::{
+    BoxableSegmentCollector, Collector, DocId, Fruit, Score, SegmentCollector, SegmentOrdinal,
+    SegmentReader,
+};
+
+/// Object-safe version of `Collector`, used by `MultiCollector`.
+trait BoxableCollector: Sync {
+    fn for_segment(
+        &self,
+        segment_ord: SegmentOrdinal,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<Box<dyn BoxableSegmentCollector>>;
+
+    fn requires_scoring(&self) -> bool;
+
+    fn merge_fruits(&self, segment_fruits: Vec<Box<dyn Fruit>>) -> crate::Result<Box<dyn Fruit>>;
+}
+
+struct CollectorWrapper<TCollector: Collector>(TCollector);
+
+impl<TCollector: Collector> BoxableCollector for CollectorWrapper<TCollector> {
+    fn for_segment(
+        &self,
+        segment_ord: SegmentOrdinal,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<Box<dyn BoxableSegmentCollector>> {
+        let child = self.0.for_segment(segment_ord, segment_reader)?;
+        Ok(Box::new(SegmentCollectorWrapper(child)))
+    }
+
+    fn requires_scoring(&self) -> bool {
+        self.0.requires_scoring()
+    }
+
+    fn merge_fruits(&self, segment_fruits: Vec<Box<dyn Fruit>>) -> crate::Result<Box<dyn Fruit>> {
+        let typed_fruits = segment_fruits
+            .into_iter()
+            .map(|fruit| {
+                fruit
+                    .downcast::<<TCollector::Child as SegmentCollector>::Fruit>()
+                    .map(|fruit| *fruit)
+                    .unwrap_or_else(|_| panic!("a segment fruit has the wrong type"))
+            })
+            .collect();
+        let merged_fruit = self.0.merge_fruits(typed_fruits)?;
+        Ok(Box::new(merged_fruit))
+    }
+}
+
+/// Handle to extract the fruit of one of the collectors of a `MultiCollector`.
+pub struct FruitHandle<TFruit: Fruit> {
+    pos: usize,
+    _phantom: PhantomData<TFruit>,
+}
+
+impl<TFruit: Fruit> FruitHandle<TFruit> {
+    /// Takes the fruit out of `fruits`.
+    ///
+    /// Panics if `fruits` was not produced by the `MultiCollector` the handle
+    /// comes from, or if the fruit was already extracted.
+    pub fn extract(self, fruits: &mut MultiFruit) -> TFruit {
+        let boxed_fruit = fruits.sub_fruits[self.pos]
+            .take()
+            .expect("the fruit was already extracted");
+        *boxed_fruit
+            .downcast::<TFruit>()
+            .unwrap_or_else(|_| panic!("the fruit does not come from this handle's collector"))
+    }
+}
+
+/// Fruits of a `MultiCollector`, extracted with `FruitHandle::extract`.
+pub struct MultiFruit {
+    sub_fruits: Vec<Option<Box<dyn Fruit>>>,
+}
+
+/// Runs any number of collectors, possibly of different types, in one pass.
+///
+/// For a fixed set of collectors, a tuple such as `(Count, TopDocs)` is simpler.
+#[derive(Default)]
+pub struct MultiCollector<'a> {
+    collector_wrappers: Vec<Box<dyn BoxableCollector + 'a>>,
+}
+
+impl<'a> MultiCollector<'a> {
+    pub fn new() -> MultiCollector<'a> {
+        MultiCollector::default()
+    }
+
+    /// Adds a collector and returns the handle to get its fruit back.
+    pub fn add_collector<TCollector: Collector + 'a>(
+        &mut self,
+        collector: TCollector,
+    ) -> FruitHandle<TCollector::Fruit> {
+        let pos = self.collector_wrappers.len();
+        self.collector_wrappers
+            .push(Box::new(CollectorWrapper(collector)));
+        FruitHandle {
+            pos,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a> Collector for MultiCollector<'a> {
+    type Fruit = MultiFruit;
+    type Child = MultiCollectorChild;
+
+    fn for_segment(
+        &self,
+        segment_ord: SegmentOrdinal,
+        segment_reader: &SegmentReader,
+    ) -> crate::Result<MultiCollectorChild> {
+        let children = self
+            .collector_wrappers
+            .iter()
+            .map(|collector_wrapper| collector_wrapper.for_segment(segment_ord, segment_reader))
+            .collect::<crate::Result<Vec<_>>>()?;
+        Ok(MultiCollectorChild { children })
+    }
+
+    fn requires_scoring(&self) -> bool {
+        self.collector_wrappers
+            .iter()
+            .any(|collector_wrapper| collector_wrapper.requires_scoring())
+    }
+
+    fn merge_fruits(&self, segment_fruits: Vec<Vec<Box<dyn Fruit>>>) -> crate::Result<MultiFruit> {
+        let mut fruits_per_collector: Vec<Vec<Box<dyn Fruit>>> = self
+            .collector_wrappers
+            .iter()
+            .map(|_| Vec::with_capacity(segment_fruits.len()))
+            .collect();
+        for segment_fruit in segment_fruits {
+            for (pos, fruit) in segment_fruit.into_iter().enumerate() {
+                fruits_per_collector[pos].push(fruit);
+            }
+        }
+        let sub_fruits = self
+            .collector_wrappers
+            .iter()
+            .zip(fruits_per_collector)
+            .map(|(collector_wrapper, fruits)| collector_wrapper.merge_fruits(fruits).map(Some))
+            .collect::<crate::Result<Vec<_>>>()?;
+        Ok(MultiFruit { sub_fruits })
+    }
+}
+
+/// Segment collector of `MultiCollector`.
+pub struct MultiCollectorChild {
+    children: Vec<Box<dyn BoxableSegmentCollector>>,
+}
+
+impl SegmentCollector for MultiCollectorChild {
+    type Fruit = Vec<Box<dyn Fruit>>;
+
+    fn collect(&mut self, doc: DocId, score: Score) {
+        for child in &mut self.children {
+            child.collect(doc, score);
+        }
+    }
+
+    fn harvest(self) -> Vec<Box<dyn Fruit>> {
+        self.children
+            .into_iter()
+            .map(|child| child.harvest_from_box())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Count, DocAddress, DocSetCollector, Index, MultiCollector, QueryParser, Schema, TopDocs,
+        TEXT,
+    };
+
+    #[test]
+    fn test_multi_collector() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 10_000_000)?;
+        index_writer.add_document(doc!(text => "abc"))?;
+        index_writer.add_document(doc!(text => "abc abc abc"))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(text => "abc def"))?;
+        index_writer.add_document(doc!(text => "def"))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query = QueryParser::for_index(&index, vec![text])
+            .parse_query("abc")
+            .unwrap();
+
+        let mut collectors = MultiCollector::new();
+        let top_docs_handle = collectors.add_collector(TopDocs::with_limit(2));
+        let count_handle = collectors.add_collector(Count);
+        let docset_handle = collectors.add_collector(DocSetCollector);
+        let mut multi_fruit = searcher.search(&query, &collectors)?;
+
+        assert_eq!(count_handle.extract(&mut multi_fruit), 3);
+        let top_docs = top_docs_handle.extract(&mut multi_fruit);
+        assert_eq!(top_docs.len(), 2);
+        assert_eq!(top_docs[0].1, DocAddress::new(0, 1));
+        let docset = docset_handle.extract(&mut multi_fruit);
+        assert_eq!(docset.len(), 3);
+        assert!(docset.contains(&DocAddress::new(1, 0)));
+
+        let (count, top_docs) = searcher.search(&query, &(Count, TopDocs::with_limit(1)))?;
+        assert_eq!(count, 3);
+        assert_eq!(top_docs[0].1, DocAddress::new(0, 1));
+        Ok(())
+    }
+}
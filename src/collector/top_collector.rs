@@ -0,0 +1,177 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::{DocAddress, DocId, SegmentOrdinal};
+
+/// Document with the feature it is ranked by.
+///
+/// The ordering is reversed so that the head of a `BinaryHeap` is the worst
+/// document: the lowest feature and, on ties, the highest doc.
+struct ComparableDoc<T, D> {
+    feature: T,
+    doc: D,
+}
+
+impl<T: PartialOrd, D: Ord> PartialOrd for ComparableDoc<T, D> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: PartialOrd, D: Ord> Ord for ComparableDoc<T, D> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .feature
+            .partial_cmp(&self.feature)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| self.doc.cmp(&other.doc))
+    }
+}
+
+impl<T: PartialOrd, D: Ord> PartialEq for ComparableDoc<T, D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: PartialOrd, D: Ord> Eq for ComparableDoc<T, D> {}
+
+/// Keeps the `limit` best documents according to a feature, after skipping the
+/// `offset` first ones.
+pub(crate) struct TopCollector<T> {
+    pub limit: usize,
+    pub offset: usize,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<T: PartialOrd + Clone> TopCollector<T> {
+    /// Panics if `limit` is 0.
+    pub fn with_limit(limit: usize) -> TopCollector<T> {
+        assert!(limit >= 1, "the limit must be strictly greater than 0");
+        TopCollector {
+            limit,
+            offset: 0,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    pub fn and_offset(mut self, offset: usize) -> TopCollector<T> {
+        self.offset = offset;
+        self
+    }
+
+    pub fn for_segment(&self, segment_ord: SegmentOrdinal) -> TopSegmentCollector<T> {
+        TopSegmentCollector::new(segment_ord, self.limit + self.offset)
+    }
+
+    pub fn merge_fruits(&self, children: Vec<Vec<(T, DocAddress)>>) -> Vec<(T, DocAddress)> {
+        let mut top_collector = BinaryHeap::new();
+        for (feature, doc) in children.into_iter().flatten() {
+            push_bounded(
+                &mut top_collector,
+                self.limit + self.offset,
+                ComparableDoc { feature, doc },
+            );
+        }
+        top_collector
+            .into_sorted_vec()
+            .into_iter()
+            .skip(self.offset)
+            .map(|comparable_doc| (comparable_doc.feature, comparable_doc.doc))
+            .collect()
+    }
+}
+
+fn push_bounded<T: PartialOrd, D: Ord>(
+    heap: &mut BinaryHeap<ComparableDoc<T, D>>,
+    limit: usize,
+    comparable_doc: ComparableDoc<T, D>,
+) {
+    if heap.len() < limit {
+        heap.push(comparable_doc);
+    } else if let Some(mut head) = heap.peek_mut() {
+        if comparable_doc < *head {
+            *head = comparable_doc;
+        }
+    }
+}
+
+/// Keeps the `limit` best documents of a segment.
+pub(crate) struct TopSegmentCollector<T> {
+    limit: usize,
+    heap: BinaryHeap<ComparableDoc<T, DocId>>,
+    segment_ord: SegmentOrdinal,
+}
+
+impl<T: PartialOrd> TopSegmentCollector<T> {
+    fn new(segment_ord: SegmentOrdinal, limit: usize) -> TopSegmentCollector<T> {
+        TopSegmentCollector {
+            limit,
+            heap: BinaryHeap::with_capacity(limit),
+            segment_ord,
+        }
+    }
+
+    pub fn collect(&mut self, doc: DocId, feature: T) {
+        push_bounded(&mut self.heap, self.limit, ComparableDoc { feature, doc });
+    }
+
+    /// Returns the collected documents, best first.
+    pub fn harvest(self) -> Vec<(T, DocAddress)> {
+        let segment_ord = self.segment_ord;
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|comparable_doc| {
+                (
+                    comparable_doc.feature,
+                    DocAddress::new(segment_ord, comparable_doc.doc),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TopCollector;
+    use crate::DocAddress;
+
+    #[test]
+    fn test_top_segment_collector() {
+        let top_collector: TopCollector<f32> = TopCollector::with_limit(3);
+        let mut segment_collector = top_collector.for_segment(0);
+        segment_collector.collect(1, 0.8);
+        segment_collector.collect(3, 0.2);
+        segment_collector.collect(5, 0.3);
+        segment_collector.collect(7, 0.9);
+        segment_collector.collect(9, 0.3);
+        assert_eq!(
+            segment_collector.harvest(),
+            vec![
+                (0.9, DocAddress::new(0, 7)),
+                (0.8, DocAddress::new(0, 1)),
+                (0.3, DocAddress::new(0, 5)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_top_collector_merge_with_offset() {
+        let top_collector: TopCollector<u64> = TopCollector::with_limit(2).and_offset(1);
+        let merged = top_collector.merge_fruits(vec![
+            vec![(5, DocAddress::new(0, 1)), (2, DocAddress::new(0, 4))],
+            vec![(4, DocAddress::new(1, 0)), (4, DocAddress::new(1, 3))],
+        ]);
+        assert_eq!(
+            merged,
+            vec![(4, DocAddress::new(1, 0)), (4, DocAddress::new(1, 3))]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_top_collector_zero_limit_panics() {
+        let _: TopCollector<f32> = TopCollector::with_limit(0);
+    }
+}
@@ -0,0 +1,258 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::collector::top_collector::{TopCollector, TopSegmentCollector};
+use crate::{
+    Collector, CustomScoreTopCollector, CustomScorer, CustomSegmentScorer, DocAddress, DocId,
+    FastFieldReader, FastValue, Field, Score, SegmentCollector, SegmentOrdinal, SegmentReader,
+};
+
+/// Collects the best scored documents, best first.
+///
+/// Documents with the same score are sorted by `DocAddress`.
+///
+/// ```rust
+/// use mysearch::{doc, Index, IndexRecordOption, Schema, Term, TermQuery, TopDocs, TEXT};
+///
+/// # fn main() -> mysearch::Result<()> {
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT);
+/// let index = Index::create_in_ram(schema_builder.build());
+/// let mut index_writer = index.writer_with_num_threads(1, 10_000_000)?;
+/// index_writer.add_document(doc!(title => "the diary of a young girl"))?;
+/// index_writer.add_document(doc!(title => "the diary of muadib"))?;
+/// index_writer.add_document(doc!(title => "a dairy cow"))?;
+/// index_writer.commit()?;
+///
+/// let searcher = index.reader()?.searcher();
+/// let query = TermQuery::new(
+///     Term::from_field_text(title, "diary"),
+///     IndexRecordOption::WithFreqs,
+/// );
+/// let top_docs = searcher.search(&query, &TopDocs::with_limit(2))?;
+/// assert_eq!(top_docs.len(), 2);
+/// assert_eq!(top_docs[0].1.doc_id, 1);
+/// # Ok(())
+/// # }
+/// ```
+pub struct TopDocs(TopCollector<Score>);
+
+impl fmt::Debug for TopDocs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "TopDocs(limit={}, offset={})",
+            self.0.limit, self.0.offset
+        )
+    }
+}
+
+impl TopDocs {
+    /// Keeps the `limit` best documents.
+    ///
+    /// Panics if `limit` is 0.
+    pub fn with_limit(limit: usize) -> TopDocs {
+        TopDocs(TopCollector::with_limit(limit))
+    }
+
+    /// Skips the `offset` best documents, e.g. to display the next page of
+    /// results.
+    pub fn and_offset(self, offset: usize) -> TopDocs {
+        TopDocs(self.0.and_offset(offset))
+    }
+
+    /// Ranks the documents by a custom feature instead of their score, highest
+    /// first.
+    ///
+    /// `custom_scorer` is asked for a `CustomSegmentScorer` for each segment,
+    /// which computes the feature of each matching document.
+    pub fn custom_score<TScore, TCustomSegmentScorer, TCustomScorer>(
+        self,
+        custom_scorer: TCustomScorer,
+    ) -> impl Collector<Fruit = Vec<(TScore, DocAddress)>>
+    where
+        TScore: 'static + PartialOrd + Clone + Send + Sync,
+        TCustomSegmentScorer: CustomSegmentScorer<TScore> + 'static,
+        TCustomScorer: CustomScorer<TScore, Child = TCustomSegmentScorer> + Send + Sync,
+    {
+        CustomScoreTopCollector::new(custom_scorer, self.0.limit, self.0.offset)
+    }
+
+    /// Ranks the documents by the value of a single-valued fast field, highest
+    /// first.
+    ///
+    /// Fails on search if `field` is not a single-valued fast field of type
+    /// `TFastValue`.
+    pub fn order_by_fast_field<TFastValue: FastValue>(
+        self,
+        field: Field,
+    ) -> impl Collector<Fruit = Vec<(TFastValue, DocAddress)>> {
+        self.custom_score(ScorerByFastField {
+            field,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+struct ScorerByFastField<TFastValue> {
+    field: Field,
+    _phantom: PhantomData<TFastValue>,
+}
+
+impl<TFastValue: FastValue> CustomScorer<TFastValue> for ScorerByFastField<TFastValue> {
+    type Child = FastFieldSegmentScorer<TFastValue>;
+
+    fn segment_scorer(&self, segment_reader: &SegmentReader) -> crate::Result<Self::Child> {
+        let fast_field_reader = segment_reader
+            .fast_fields()
+            .typed_fast_field_reader(self.field)?;
+        Ok(FastFieldSegmentScorer(fast_field_reader))
+    }
+}
+
+struct FastFieldSegmentScorer<TFastValue: FastValue>(FastFieldReader<TFastValue>);
+
+impl<TFastValue: FastValue> CustomSegmentScorer<TFastValue> for FastFieldSegmentScorer<TFastValue> {
+    fn score(&mut self, doc: DocId) -> TFastValue {
+        self.0.get(doc)
+    }
+}
+
+impl Collector for TopDocs {
+    type Fruit = Vec<(Score, DocAddress)>;
+    type Child = TopScoreSegmentCollector;
+
+    fn for_segment(
+        &self,
+        segment_ord: SegmentOrdinal,
+        _segment_reader: &SegmentReader,
+    ) -> crate::Result<TopScoreSegmentCollector> {
+        Ok(TopScoreSegmentCollector(self.0.for_segment(segment_ord)))
+    }
+
+    fn requires_scoring(&self) -> bool {
+        true
+    }
+
+    fn merge_fruits(
+        &self,
+        segment_fruits: Vec<Vec<(Score, DocAddress)>>,
+    ) -> crate::Result<Vec<(Score, DocAddress)>> {
+        Ok(self.0.merge_fruits(segment_fruits))
+    }
+}
+
+/// Segment collector of `TopDocs`.
+pub struct TopScoreSegmentCollector(TopSegmentCollector<Score>);
+
+impl SegmentCollector for TopScoreSegmentCollector {
+    type Fruit = Vec<(Score, DocAddress)>;
+
+    fn collect(&mut self, doc: DocId, score: Score) {
+        self.0.collect(doc, score);
+    }
+
+    fn harvest(self) -> Vec<(Score, DocAddress)> {
+        self.0.harvest()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        AllQuery, DocAddress, DocId, Index, MySearchError, Query, QueryParser, Schema, Searcher,
+        SegmentReader, TopDocs, FAST, TEXT,
+    };
+
+    fn test_searcher() -> crate::Result<(Searcher, Box<dyn Query>)> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 10_000_000)?;
+        index_writer.add_document(doc!(text => "hello happy tax payer"))?;
+        index_writer.add_document(doc!(text => "hello"))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(text => "hello hello happy"))?;
+        index_writer.add_document(doc!(text => "goodbye"))?;
+        index_writer.commit()?;
+        let query = QueryParser::for_index(&index, vec![text])
+            .parse_query("hello")
+            .unwrap();
+        Ok((index.reader()?.searcher(), query))
+    }
+
+    #[test]
+    fn test_top_docs() -> crate::Result<()> {
+        let (searcher, query) = test_searcher()?;
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(10))?;
+        assert_eq!(top_docs.len(), 3);
+        assert!(top_docs.windows(2).all(|pair| pair[0].0 >= pair[1].0));
+        // the longest document comes last.
+        assert_eq!(top_docs[2].1, DocAddress::new(0, 0));
+
+        let page = searcher.search(&query, &TopDocs::with_limit(1).and_offset(1))?;
+        assert_eq!(page, vec![top_docs[1]]);
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(5).and_offset(3))?;
+        assert!(top_docs.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_top_docs_custom_score() -> crate::Result<()> {
+        let (searcher, _) = test_searcher()?;
+        // ranks the documents by decreasing doc id.
+        let collector = TopDocs::with_limit(3)
+            .custom_score(|_segment_reader: &SegmentReader| move |doc: DocId| doc);
+        let top_docs = searcher.search(&AllQuery, &collector)?;
+        assert_eq!(
+            top_docs,
+            vec![
+                (1, DocAddress::new(0, 1)),
+                (1, DocAddress::new(1, 1)),
+                (0, DocAddress::new(0, 0)),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_top_docs_order_by_fast_field() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let price = schema_builder.add_f64_field("price", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 10_000_000)?;
+        index_writer.add_document(doc!(title => "cheap book", price => 3.5f64))?;
+        index_writer.add_document(doc!(title => "expensive book", price => 99.0f64))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(title => "average book", price => 20.0f64))?;
+        index_writer.add_document(doc!(title => "free pen", price => 0.0f64))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query = QueryParser::for_index(&index, vec![title])
+            .parse_query("book")
+            .unwrap();
+
+        let top_docs = searcher.search(
+            &query,
+            &TopDocs::with_limit(2).order_by_fast_field::<f64>(price),
+        )?;
+        let prices: Vec<f64> = top_docs.iter().map(|(price, _)| *price).collect();
+        assert_eq!(prices, vec![99.0, 20.0]);
+        let top_docs = searcher.search(
+            &query,
+            &TopDocs::with_limit(5).order_by_fast_field::<f64>(price),
+        )?;
+        assert_eq!(top_docs.len(), 3);
+        assert_eq!(top_docs[2], (3.5, DocAddress::new(0, 0)));
+
+        assert!(matches!(
+            searcher.search(
+                &query,
+                &TopDocs::with_limit(2).order_by_fast_field::<u64>(price)
+            ),
+            Err(MySearchError::SchemaError(_))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,144 @@
+use std::io::{self, Write};
+
+/// Number of bits required to represent any value in `[0, amplitude]`.
+pub fn compute_num_bits(amplitude: u64) -> u8 {
+    (64 - amplitude.leading_zeros()) as u8
+}
+
+/// Writes integers using a fixed number of bits each, little-endian.
+///
+/// `close` pads the output so that `BitUnpacker` can always read a full word.
+#[derive(Default)]
+pub struct BitPacker {
+    mini_buffer: u64,
+    mini_buffer_written: usize,
+}
+
+impl BitPacker {
+    pub fn new() -> BitPacker {
+        BitPacker::default()
+    }
+
+    /// Writes the `num_bits` lowest bits of `val`.
+    pub fn write<W: Write>(&mut self, val: u64, num_bits: u8, output: &mut W) -> io::Result<()> {
+        let num_bits = num_bits as usize;
+        debug_assert!(num_bits == 64 || val >> num_bits == 0);
+        if self.mini_buffer_written + num_bits > 64 {
+            self.mini_buffer |= val << self.mini_buffer_written;
+            output.write_all(&self.mini_buffer.to_le_bytes())?;
+            self.mini_buffer = val
+                .checked_shr((64 - self.mini_buffer_written) as u32)
+                .unwrap_or(0);
+            self.mini_buffer_written = self.mini_buffer_written + num_bits - 64;
+        } else {
+            self.mini_buffer |= val
+                .checked_shl(self.mini_buffer_written as u32)
+                .unwrap_or(0);
+            self.mini_buffer_written += num_bits;
+            if self.mini_buffer_written == 64 {
+                output.write_all(&self.mini_buffer.to_le_bytes())?;
+                self.mini_buffer = 0;
+                self.mini_buffer_written = 0;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the pending bits and the padding.
+    pub fn close<W: Write>(mut self, output: &mut W) -> io::Result<()> {
+        if self.mini_buffer_written > 0 {
+            let num_bytes = self.mini_buffer_written.div_ceil(8);
+            output.write_all(&self.mini_buffer.to_le_bytes()[..num_bytes])?;
+            self.mini_buffer_written = 0;
+        }
+        output.write_all(&[0u8; BitUnpacker::PADDING_NUM_BYTES])
+    }
+}
+
+/// Reads the integers written by a `BitPacker`, in O(1) each.
+#[derive(Clone, Copy, Debug)]
+pub struct BitUnpacker {
+    num_bits: u64,
+    mask: u64,
+}
+
+impl BitUnpacker {
+    /// Bytes written after the data so that reading the last value never
+    /// goes out of bounds.
+    pub const PADDING_NUM_BYTES: usize = 9;
+
+    pub fn new(num_bits: u8) -> BitUnpacker {
+        let mask = if num_bits == 64 {
+            u64::MAX
+        } else {
+            (1u64 << num_bits) - 1
+        };
+        BitUnpacker {
+            num_bits: u64::from(num_bits),
+            mask,
+        }
+    }
+
+    /// Returns the value at `idx` of the bitpacked `data`.
+    #[inline]
+    pub fn get(&self, idx: u64, data: &[u8]) -> u64 {
+        if self.num_bits == 0 {
+            return 0;
+        }
+        let addr_in_bits = idx * self.num_bits;
+        let addr = (addr_in_bits >> 3) as usize;
+        let bit_shift = (addr_in_bits & 7) as u32;
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&data[addr..addr + 8]);
+        let mut val = u64::from_le_bytes(word) >> bit_shift;
+        if bit_shift as u64 + self.num_bits > 64 {
+            val |= u64::from(data[addr + 8]) << (64 - bit_shift);
+        }
+        val & self.mask
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compute_num_bits, BitPacker, BitUnpacker};
+
+    fn roundtrip(num_bits: u8, vals: &[u64]) {
+        let mut data = Vec::new();
+        let mut bit_packer = BitPacker::new();
+        for &val in vals {
+            bit_packer.write(val, num_bits, &mut data).unwrap();
+        }
+        bit_packer.close(&mut data).unwrap();
+        let num_data_bytes = (vals.len() * num_bits as usize).div_ceil(8);
+        assert_eq!(data.len(), num_data_bytes + BitUnpacker::PADDING_NUM_BYTES);
+        let bit_unpacker = BitUnpacker::new(num_bits);
+        for (idx, &val) in vals.iter().enumerate() {
+            assert_eq!(bit_unpacker.get(idx as u64, &data), val);
+        }
+    }
+
+    #[test]
+    fn test_compute_num_bits() {
+        assert_eq!(compute_num_bits(0), 0);
+        assert_eq!(compute_num_bits(1), 1);
+        assert_eq!(compute_num_bits(255), 8);
+        assert_eq!(compute_num_bits(256), 9);
+        assert_eq!(compute_num_bits(u64::MAX), 64);
+    }
+
+    #[test]
+    fn test_bitpacker() {
+        for num_bits in 0..=64u8 {
+            let max_val = if num_bits == 64 {
+                u64::MAX
+            } else {
+                (1u64 << num_bits) - 1
+            };
+            let vals: Vec<u64> = (0..100u64)
+                .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15) & max_val)
+                .chain(vec![max_val, 0, max_val])
+                .collect();
+            roundtrip(num_bits, &vals);
+        }
+    }
+}
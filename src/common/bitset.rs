@@ -0,0 +1,172 @@
+use std::io::{self, Read, Write};
+
+use crate::BinarySerializable;
+
+/// Fixed-size set of `u32` values, one bit per value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitSet {
+    words: Vec<u64>,
+    len: u32,
+    max_value: u32,
+}
+
+impl BitSet {
+    /// Creates a set holding all the values in `[0, max_value)`.
+    pub fn full(max_value: u32) -> BitSet {
+        let mut bitset = BitSet::with_max_value(max_value);
+        for (word_ord, word) in bitset.words.iter_mut().enumerate() {
+            let num_bits = (max_value as usize - word_ord * 64).min(64);
+            *word = if num_bits == 64 {
+                u64::MAX
+            } else {
+                (1u64 << num_bits) - 1
+            };
+        }
+        bitset.len = max_value;
+        bitset
+    }
+
+    /// Creates an empty set accepting values in `[0, max_value)`.
+    pub fn with_max_value(max_value: u32) -> BitSet {
+        let num_words = (max_value as usize).div_ceil(64);
+        BitSet {
+            words: vec![0u64; num_words],
+            len: 0,
+            max_value,
+        }
+    }
+
+    /// Upper bound (excluded) of the values of the set.
+    pub fn max_value(&self) -> u32 {
+        self.max_value
+    }
+
+    /// Number of values in the set.
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn insert(&mut self, el: u32) {
+        debug_assert!(el < self.max_value);
+        let word = &mut self.words[(el / 64) as usize];
+        let mask = 1u64 << (el % 64);
+        if *word & mask == 0 {
+            *word |= mask;
+            self.len += 1;
+        }
+    }
+
+    pub fn remove(&mut self, el: u32) {
+        debug_assert!(el < self.max_value);
+        let word = &mut self.words[(el / 64) as usize];
+        let mask = 1u64 << (el % 64);
+        if *word & mask != 0 {
+            *word &= !mask;
+            self.len -= 1;
+        }
+    }
+
+    pub fn contains(&self, el: u32) -> bool {
+        el < self.max_value && self.words[(el / 64) as usize] & (1u64 << (el % 64)) != 0
+    }
+
+    /// Returns the smallest value of the set greater than or equal to `from`.
+    pub fn next_from(&self, from: u32) -> Option<u32> {
+        if from >= self.max_value {
+            return None;
+        }
+        let mut word_ord = (from / 64) as usize;
+        let mut word = self.words[word_ord] & (u64::MAX << (from % 64));
+        loop {
+            if word != 0 {
+                return Some(word_ord as u32 * 64 + word.trailing_zeros());
+            }
+            word_ord += 1;
+            word = *self.words.get(word_ord)?;
+        }
+    }
+}
+
+/// `[max_value: u32][words: u64...]`
+impl BinarySerializable for BitSet {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.max_value.serialize(writer)?;
+        for word in &self.words {
+            word.serialize(writer)?;
+        }
+        Ok(())
+    }
+
+    fn deserialize<R: Read>(reader: &mut R) -> io::Result<BitSet> {
+        let mut bitset = BitSet::with_max_value(u32::deserialize(reader)?);
+        for word in bitset.words.iter_mut() {
+            *word = u64::deserialize(reader)?;
+        }
+        if let Some(last_word) = bitset.words.last() {
+            let num_bits = bitset.max_value as usize - (bitset.words.len() - 1) * 64;
+            if num_bits < 64 && last_word >> num_bits != 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "bitset holds values out of its range",
+                ));
+            }
+        }
+        bitset.len = bitset.words.iter().map(|word| word.count_ones()).sum();
+        Ok(bitset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BitSet;
+    use crate::BinarySerializable;
+
+    #[test]
+    fn test_bitset() {
+        let mut bitset = BitSet::with_max_value(200);
+        assert!(bitset.is_empty());
+        bitset.insert(3);
+        bitset.insert(3);
+        bitset.insert(64);
+        bitset.insert(199);
+        assert_eq!(bitset.len(), 3);
+        assert!(bitset.contains(64));
+        assert!(!bitset.contains(65));
+        assert!(!bitset.contains(1_000));
+        assert_eq!(bitset.next_from(0), Some(3));
+        assert_eq!(bitset.next_from(4), Some(64));
+        assert_eq!(bitset.next_from(65), Some(199));
+        assert_eq!(bitset.next_from(200), None);
+        bitset.remove(64);
+        bitset.remove(64);
+        assert_eq!(bitset.len(), 2);
+        assert_eq!(bitset.next_from(4), Some(199));
+    }
+
+    #[test]
+    fn test_bitset_full_and_serialization() {
+        for &max_value in &[0u32, 1, 63, 64, 65, 200] {
+            let mut bitset = BitSet::full(max_value);
+            assert_eq!(bitset.len(), max_value);
+            assert_eq!(
+                bitset.next_from(0),
+                if max_value == 0 { None } else { Some(0) }
+            );
+            if max_value > 1 {
+                bitset.remove(max_value - 1);
+                assert!(!bitset.contains(max_value - 1));
+            }
+            let mut buffer = Vec::new();
+            bitset.serialize(&mut buffer).unwrap();
+            assert_eq!(BitSet::deserialize(&mut &buffer[..]).unwrap(), bitset);
+        }
+        let mut buffer = Vec::new();
+        BitSet::full(65).serialize(&mut buffer).unwrap();
+        buffer[12] = 0xff;
+        assert!(BitSet::deserialize(&mut &buffer[..]).is_err());
+    }
+}
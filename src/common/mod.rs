@@ -0,0 +1,73 @@
+mod bitpacker;
+mod bitset;
+mod counting_writer;
+mod serialize;
+mod vint;
+
+pub use bitpacker::*;
+pub use bitset::*;
+pub use counting_writer::*;
+pub use serialize::*;
+pub use vint::*;
+
+const HIGHEST_BIT: u64 = 1 << 63;
+
+/// Maps an `i64` to a `u64` while preserving the order.
+#[inline(always)]
+pub fn i64_to_u64(val: i64) -> u64 {
+    (val as u64) ^ HIGHEST_BIT
+}
+
+/// Reverse of `i64_to_u64`.
+#[inline(always)]
+pub fn u64_to_i64(val: u64) -> i64 {
+    (val ^ HIGHEST_BIT) as i64
+}
+
+/// Maps an `f64` to a `u64` while preserving the order.
+#[inline(always)]
+pub fn f64_to_u64(val: f64) -> u64 {
+    let bits = val.to_bits();
+    if val.is_sign_positive() {
+        bits ^ HIGHEST_BIT
+    } else {
+        !bits
+    }
+}
+
+/// Reverse of `f64_to_u64`.
+#[inline(always)]
+pub fn u64_to_f64(val: u64) -> f64 {
+    f64::from_bits(if val & HIGHEST_BIT != 0 {
+        val ^ HIGHEST_BIT
+    } else {
+        !val
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{f64_to_u64, i64_to_u64, u64_to_f64, u64_to_i64};
+
+    #[test]
+    fn test_i64_to_u64_preserves_order() {
+        let values = [i64::MIN, -10, -1, 0, 1, 10, i64::MAX];
+        for window in values.windows(2) {
+            assert!(i64_to_u64(window[0]) < i64_to_u64(window[1]));
+        }
+        for &val in &values {
+            assert_eq!(u64_to_i64(i64_to_u64(val)), val);
+        }
+    }
+
+    #[test]
+    fn test_f64_to_u64_preserves_order() {
+        let values = [f64::NEG_INFINITY, -10.5, -1.0, 0.0, 0.5, 1.0, f64::MAX];
+        for window in values.windows(2) {
+            assert!(f64_to_u64(window[0]) < f64_to_u64(window[1]));
+        }
+        for &val in &values {
+            assert_eq!(u64_to_f64(f64_to_u64(val)), val);
+        }
+    }
+}
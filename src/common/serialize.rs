@@ -0,0 +1,123 @@
+use std::io::{self, Read, Write};
+
+use crate::VInt;
+
+/// Compact little-endian binary encoding used by the index file formats.
+pub trait BinarySerializable: Sized {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+
+    fn deserialize<R: Read>(reader: &mut R) -> io::Result<Self>;
+}
+
+impl BinarySerializable for u8 {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&[*self])
+    }
+
+    fn deserialize<R: Read>(reader: &mut R) -> io::Result<u8> {
+        let mut buffer = [0u8; 1];
+        reader.read_exact(&mut buffer)?;
+        Ok(buffer[0])
+    }
+}
+
+impl BinarySerializable for u32 {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.to_le_bytes())
+    }
+
+    fn deserialize<R: Read>(reader: &mut R) -> io::Result<u32> {
+        let mut buffer = [0u8; 4];
+        reader.read_exact(&mut buffer)?;
+        Ok(u32::from_le_bytes(buffer))
+    }
+}
+
+impl BinarySerializable for u64 {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.to_le_bytes())
+    }
+
+    fn deserialize<R: Read>(reader: &mut R) -> io::Result<u64> {
+        let mut buffer = [0u8; 8];
+        reader.read_exact(&mut buffer)?;
+        Ok(u64::from_le_bytes(buffer))
+    }
+}
+
+impl BinarySerializable for i64 {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.to_le_bytes())
+    }
+
+    fn deserialize<R: Read>(reader: &mut R) -> io::Result<i64> {
+        let mut buffer = [0u8; 8];
+        reader.read_exact(&mut buffer)?;
+        Ok(i64::from_le_bytes(buffer))
+    }
+}
+
+impl BinarySerializable for f64 {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.to_bits().serialize(writer)
+    }
+
+    fn deserialize<R: Read>(reader: &mut R) -> io::Result<f64> {
+        u64::deserialize(reader).map(f64::from_bits)
+    }
+}
+
+impl BinarySerializable for Vec<u8> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        VInt(self.len() as u64).serialize(writer)?;
+        writer.write_all(self)
+    }
+
+    fn deserialize<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+        let len = VInt::deserialize(reader)?.val() as usize;
+        let mut buffer = vec![0u8; len];
+        reader.read_exact(&mut buffer)?;
+        Ok(buffer)
+    }
+}
+
+impl BinarySerializable for String {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        VInt(self.len() as u64).serialize(writer)?;
+        writer.write_all(self.as_bytes())
+    }
+
+    fn deserialize<R: Read>(reader: &mut R) -> io::Result<String> {
+        let bytes = Vec::<u8>::deserialize(reader)?;
+        String::from_utf8(bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BinarySerializable;
+
+    fn serialize_test<T: BinarySerializable + PartialEq + std::fmt::Debug>(value: T) -> usize {
+        let mut buffer = Vec::new();
+        value.serialize(&mut buffer).unwrap();
+        let num_bytes = buffer.len();
+        assert_eq!(T::deserialize(&mut &buffer[..]).unwrap(), value);
+        num_bytes
+    }
+
+    #[test]
+    fn test_serialize_fixed_size() {
+        assert_eq!(serialize_test(3u8), 1);
+        assert_eq!(serialize_test(u32::MAX), 4);
+        assert_eq!(serialize_test(u64::MAX - 3), 8);
+        assert_eq!(serialize_test(-3i64), 8);
+        assert_eq!(serialize_test(-0.5f64), 8);
+    }
+
+    #[test]
+    fn test_serialize_string_and_bytes() {
+        assert_eq!(serialize_test(String::from("hello")), 6);
+        assert_eq!(serialize_test(String::new()), 1);
+        assert_eq!(serialize_test(vec![1u8, 2, 3]), 4);
+    }
+}
@@ -0,0 +1,129 @@
+use std::io::{self, Read, Write};
+
+use crate::BinarySerializable;
+
+const STOP_BIT: u8 = 128;
+
+/// Variable-length encoded unsigned integer.
+///
+/// Values are written 7 bits at a time, least significant bits first. The last
+/// byte has its most significant bit set.
+#[derive(Debug, Eq, PartialEq)]
+pub struct VInt(pub u64);
+
+impl VInt {
+    pub fn val(&self) -> u64 {
+        self.0
+    }
+
+    /// Serializes the value into `buffer` and returns the number of bytes used.
+    pub fn serialize_into(&self, buffer: &mut [u8; 10]) -> usize {
+        let mut remaining = self.0;
+        for (i, b) in buffer.iter_mut().enumerate() {
+            let next_byte: u8 = (remaining % 128u64) as u8;
+            remaining /= 128u64;
+            if remaining == 0u64 {
+                *b = next_byte | STOP_BIT;
+                return i + 1;
+            } else {
+                *b = next_byte;
+            }
+        }
+        unreachable!();
+    }
+
+    pub fn serialize_into_vec(&self, output: &mut Vec<u8>) {
+        let mut buffer = [0u8; 10];
+        let num_bytes = self.serialize_into(&mut buffer);
+        output.extend_from_slice(&buffer[..num_bytes]);
+    }
+}
+
+impl BinarySerializable for VInt {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut buffer = [0u8; 10];
+        let num_bytes = self.serialize_into(&mut buffer);
+        writer.write_all(&buffer[..num_bytes])
+    }
+
+    fn deserialize<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut buffer = [0u8; 1];
+        let mut result = 0u64;
+        let mut shift = 0u64;
+        loop {
+            if reader.read(&mut buffer)? == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Reach end of buffer while reading VInt",
+                ));
+            }
+            let b = buffer[0];
+            result |= u64::from(b % 128u8) << shift;
+            if b >= STOP_BIT {
+                return Ok(VInt(result));
+            }
+            shift += 7;
+        }
+    }
+}
+
+pub fn write_u32_vint<W: Write>(val: u32, writer: &mut W) -> io::Result<()> {
+    VInt(u64::from(val)).serialize(writer)
+}
+
+/// Reads a `u32` VInt from the beginning of `data` and advances the slice.
+///
+/// # Panics
+///
+/// Panics if `data` ends in the middle of the VInt.
+pub fn read_u32_vint(data: &mut &[u8]) -> u32 {
+    let mut result = 0u32;
+    let mut shift = 0u32;
+    for (i, &b) in data.iter().enumerate() {
+        result |= u32::from(b % 128u8) << shift;
+        if b >= STOP_BIT {
+            *data = &data[i + 1..];
+            return result;
+        }
+        shift += 7;
+    }
+    panic!("Reach end of buffer while reading VInt");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_u32_vint, write_u32_vint, VInt};
+    use crate::BinarySerializable;
+
+    #[test]
+    fn test_vint_serialize_deserialize() {
+        for &val in &[
+            0u64,
+            1,
+            127,
+            128,
+            16_383,
+            16_384,
+            u64::from(u32::MAX),
+            u64::MAX,
+        ] {
+            let mut buffer = Vec::new();
+            VInt(val).serialize(&mut buffer).unwrap();
+            assert_eq!(VInt::deserialize(&mut &buffer[..]).unwrap(), VInt(val));
+        }
+    }
+
+    #[test]
+    fn test_u32_vint() {
+        let values = [0u32, 5, 127, 128, 300, 1 << 21, u32::MAX];
+        let mut buffer = Vec::new();
+        for &val in &values {
+            write_u32_vint(val, &mut buffer).unwrap();
+        }
+        let mut data = &buffer[..];
+        for &val in &values {
+            assert_eq!(read_u32_vint(&mut data), val);
+        }
+        assert!(data.is_empty());
+    }
+}
@@ -0,0 +1,75 @@
+use std::io;
+use std::sync::Arc;
+
+use crate::{BinarySerializable, BitSet, DocId, FileSlice, TerminatingWrite, WritePtr};
+
+/// Set of the documents of a segment that are not deleted.
+///
+/// Cloning an `AliveBitSet` is cheap.
+#[derive(Clone, Debug)]
+pub struct AliveBitSet {
+    bitset: Arc<BitSet>,
+}
+
+impl AliveBitSet {
+    pub fn open(file: FileSlice) -> io::Result<AliveBitSet> {
+        let bytes = file.read_bytes()?;
+        let bitset = BitSet::deserialize(&mut bytes.as_slice())?;
+        Ok(AliveBitSet::from(bitset))
+    }
+
+    /// Writes the delete file of a segment.
+    pub fn write(alive_bitset: &BitSet, mut writer: WritePtr) -> io::Result<()> {
+        alive_bitset.serialize(&mut writer)?;
+        writer.terminate()
+    }
+
+    #[inline]
+    pub fn is_alive(&self, doc: DocId) -> bool {
+        self.bitset.contains(doc)
+    }
+
+    #[inline]
+    pub fn is_deleted(&self, doc: DocId) -> bool {
+        !self.is_alive(doc)
+    }
+
+    pub fn num_alive_docs(&self) -> u32 {
+        self.bitset.len()
+    }
+
+    pub fn bitset(&self) -> &BitSet {
+        &self.bitset
+    }
+}
+
+impl From<BitSet> for AliveBitSet {
+    fn from(bitset: BitSet) -> AliveBitSet {
+        AliveBitSet {
+            bitset: Arc::new(bitset),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AliveBitSet, BitSet, Directory, RAMDirectory};
+    use std::path::Path;
+
+    #[test]
+    fn test_alive_bitset_write_open() -> crate::Result<()> {
+        let directory = RAMDirectory::create();
+        let path = Path::new("segment.1.del");
+        let mut bitset = BitSet::full(5);
+        bitset.remove(1);
+        bitset.remove(4);
+        AliveBitSet::write(&bitset, directory.open_write(path)?)?;
+
+        let alive_bitset = AliveBitSet::open(directory.open_read(path)?)?;
+        assert_eq!(alive_bitset.num_alive_docs(), 3);
+        assert!(alive_bitset.is_alive(0));
+        assert!(alive_bitset.is_deleted(1));
+        assert!(alive_bitset.is_deleted(4));
+        Ok(())
+    }
+}
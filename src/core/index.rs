@@ -1,25 +1,477 @@
-use crate::{Directory, RAMDirectory, TokenizerManager};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use crate::{
+    Directory, Field, FieldType, GarbageCollectionResult, IndexMeta, IndexReader,
+    IndexReaderBuilder, IndexSettings, IndexWriter, LockError, ManagedDirectory, MmapDirectory,
+    MySearchError, RAMDirectory, Schema, Segment, SegmentId, SegmentMeta, TextAnalyzer,
+    TokenizerManager, INDEX_WRITER_LOCK, MAX_NUM_THREAD, MEMORY_ARENA_NUM_BYTES_MIN, META_FILEPATH,
+    META_LOCK,
+};
 
 pub struct Index {
-    directory: Box<dyn Directory>,
+    directory: ManagedDirectory,
+    schema: Schema,
+    settings: IndexSettings,
     tokenizers: TokenizerManager,
 }
 
 impl Index {
-    pub fn create_in_ram() -> Index {
+    pub fn create_in_ram(schema: Schema) -> Index {
         let ram_directory = RAMDirectory::create();
-        Index {
-            directory: Box::new(ram_directory),
+        Index::create(ram_directory, schema).expect("creating an index in RAM should never fail")
+    }
+
+    /// Creates an index stored in `directory_path`, creating the directory if needed.
+    pub fn create_in_dir<P: AsRef<Path>>(
+        directory_path: P,
+        schema: Schema,
+    ) -> crate::Result<Index> {
+        fs::create_dir_all(directory_path.as_ref())?;
+        let mmap_directory = MmapDirectory::open(directory_path.as_ref())?;
+        if mmap_directory.exists(Path::new(META_FILEPATH))? {
+            return Err(MySearchError::IndexAlreadyExists(
+                directory_path.as_ref().to_owned(),
+            ));
+        }
+        Index::create(mmap_directory, schema)
+    }
+
+    /// Opens an index previously created in `directory_path`.
+    pub fn open_in_dir<P: AsRef<Path>>(directory_path: P) -> crate::Result<Index> {
+        let mmap_directory = MmapDirectory::open(directory_path.as_ref())?;
+        if !mmap_directory.exists(Path::new(META_FILEPATH))? {
+            return Err(MySearchError::IndexNotFound(
+                directory_path.as_ref().to_owned(),
+            ));
+        }
+        Index::open(mmap_directory)
+    }
+
+    /// Creates a new index in `directory` by committing an empty `meta.json`
+    /// holding `schema`.
+    pub fn create<D: Directory>(directory: D, schema: Schema) -> crate::Result<Index> {
+        Index::create_with_settings(directory, schema, IndexSettings::default())
+    }
+
+    /// Same as `create`, with non-default `settings`.
+    pub fn create_with_settings<D: Directory>(
+        directory: D,
+        schema: Schema,
+        settings: IndexSettings,
+    ) -> crate::Result<Index> {
+        let mut index_meta = IndexMeta::with_schema(schema.clone());
+        index_meta.index_settings = settings.clone();
+        index_meta.save(&directory)?;
+        Index::from_directory(directory, schema, settings)
+    }
+
+    /// Opens the index stored in `directory`, reading its schema from `meta.json`.
+    ///
+    /// Only the segments listed in `meta.json` are ever read. If no writer
+    /// currently holds the index, the files left behind by a writer that
+    /// stopped before committing are deleted.
+    pub fn open<D: Directory>(directory: D) -> crate::Result<Index> {
+        let index_meta = IndexMeta::load(&directory)?;
+        let index = Index::from_directory(directory, index_meta.schema, index_meta.index_settings)?;
+        index.recover_from_crash()?;
+        Ok(index)
+    }
+
+    fn recover_from_crash(&self) -> crate::Result<()> {
+        let _writer_lock = match self.directory.acquire_lock(&INDEX_WRITER_LOCK) {
+            Ok(writer_lock) => writer_lock,
+            // the files that are not committed yet belong to the running writer.
+            Err(LockError::LockBusy) => return Ok(()),
+            Err(err) => return Err(err.into()),
+        };
+        self.garbage_collect_files()?;
+        Ok(())
+    }
+
+    fn from_directory<D: Directory>(
+        directory: D,
+        schema: Schema,
+        settings: IndexSettings,
+    ) -> crate::Result<Index> {
+        let directory = ManagedDirectory::wrap(Box::new(directory))?;
+        Ok(Index {
+            directory,
+            schema,
+            settings,
             tokenizers: TokenizerManager::default(),
+        })
+    }
+
+    pub fn schema(&self) -> Schema {
+        self.schema.clone()
+    }
+
+    pub fn settings(&self) -> &IndexSettings {
+        &self.settings
+    }
+
+    pub fn directory(&self) -> &dyn Directory {
+        &self.directory
+    }
+
+    pub fn tokenizers(&self) -> &TokenizerManager {
+        &self.tokenizers
+    }
+
+    /// Returns the `TextAnalyzer` registered under the tokenizer name of a text field.
+    pub fn tokenizer_for_field(&self, field: Field) -> crate::Result<TextAnalyzer> {
+        let field_entry = self.schema.get_field_entry(field);
+        let tokenizer_name = match field_entry.field_type() {
+            FieldType::Str(text_options) => text_options
+                .get_indexing_options()
+                .map(|indexing_options| indexing_options.tokenizer())
+                .ok_or_else(|| {
+                    MySearchError::SchemaError(format!(
+                        "'{}' is not an indexed text field",
+                        field_entry.name()
+                    ))
+                })?,
+            field_type => {
+                return Err(MySearchError::SchemaError(format!(
+                    "'{}' is a {} field, only text fields are tokenized",
+                    field_entry.name(),
+                    field_type.type_name()
+                )))
+            }
+        };
+        self.tokenizers.get(tokenizer_name).ok_or_else(|| {
+            MySearchError::SchemaError(format!(
+                "no tokenizer '{}' is registered (used by field '{}')",
+                tokenizer_name,
+                field_entry.name()
+            ))
+        })
+    }
+
+    /// Creates an `IndexWriter` using `num_threads` indexing threads.
+    ///
+    /// `overall_memory_budget` is split evenly between the threads, each of which
+    /// must get at least `MEMORY_ARENA_NUM_BYTES_MIN` bytes.
+    ///
+    /// Fails with `MySearchError::LockFailure` if another writer is open on the
+    /// index, in this process or another one.
+    pub fn writer_with_num_threads(
+        &self,
+        num_threads: usize,
+        overall_memory_budget: usize,
+    ) -> crate::Result<IndexWriter> {
+        let memory_budget_per_thread = overall_memory_budget / num_threads.max(1);
+        IndexWriter::new(self, num_threads, memory_budget_per_thread)
+    }
+
+    /// Creates an `IndexWriter` with one indexing thread per CPU, up to
+    /// `MAX_NUM_THREAD`, as long as each thread gets enough memory.
+    pub fn writer(&self, overall_memory_budget: usize) -> crate::Result<IndexWriter> {
+        let mut num_threads = num_cpus::get().min(MAX_NUM_THREAD);
+        if overall_memory_budget / num_threads < MEMORY_ARENA_NUM_BYTES_MIN {
+            num_threads = (overall_memory_budget / MEMORY_ARENA_NUM_BYTES_MIN).max(1);
         }
+        self.writer_with_num_threads(num_threads, overall_memory_budget)
+    }
+
+    /// Creates an `IndexReader` reloading itself on every commit.
+    pub fn reader(&self) -> crate::Result<IndexReader> {
+        self.reader_builder().try_into()
+    }
+
+    /// Returns a builder to configure an `IndexReader`.
+    pub fn reader_builder(&self) -> IndexReaderBuilder {
+        IndexReaderBuilder::new(self.clone())
+    }
+
+    /// Reads the last committed `meta.json`.
+    pub fn load_metas(&self) -> crate::Result<IndexMeta> {
+        IndexMeta::load(self.directory())
+    }
+
+    /// Returns the segments listed in the last commit.
+    pub fn searchable_segment_metas(&self) -> crate::Result<Vec<SegmentMeta>> {
+        Ok(self.load_metas()?.segments)
+    }
+
+    /// Creates a new, empty segment with a random id.
+    pub fn new_segment(&self) -> Segment {
+        let segment_meta = SegmentMeta::new(SegmentId::generate_random(), 0);
+        self.segment(segment_meta)
+    }
+
+    pub fn segment(&self, segment_meta: SegmentMeta) -> Segment {
+        Segment::for_index(self.clone(), segment_meta)
+    }
+
+    /// Verifies the checksums of the files of the last commit, and returns the
+    /// paths of the damaged or missing ones.
+    ///
+    /// This reads every file entirely, while opening a file only checks that its
+    /// footer is present.
+    pub fn validate_checksum(&self) -> crate::Result<Vec<PathBuf>> {
+        let _meta_lock = self.directory.acquire_lock(&META_LOCK)?;
+        let mut files: Vec<PathBuf> = self
+            .load_metas()?
+            .list_files()
+            .into_iter()
+            .filter(|path| path != Path::new(META_FILEPATH))
+            .collect();
+        files.sort();
+        let mut damaged_files = Vec::new();
+        for path in files {
+            match self.directory.validate_checksum(&path) {
+                Ok(true) => {}
+                Ok(false) => damaged_files.push(path),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => damaged_files.push(path),
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(damaged_files)
+    }
+
+    /// Deletes the index files that the last commit does not reference anymore.
+    pub fn garbage_collect_files(&self) -> crate::Result<GarbageCollectionResult> {
+        self.garbage_collect_files_except(HashSet::new())
+    }
+
+    /// Same as `garbage_collect_files`, also keeping `protected_files`.
+    pub(crate) fn garbage_collect_files_except(
+        &self,
+        protected_files: HashSet<PathBuf>,
+    ) -> crate::Result<GarbageCollectionResult> {
+        let _meta_lock = self.directory.acquire_lock(&META_LOCK)?;
+        let index_meta = self.load_metas()?;
+        self.directory.garbage_collect(|| {
+            let mut living_files = index_meta.list_files();
+            living_files.extend(protected_files);
+            living_files
+        })
     }
 }
 
 impl Clone for Index {
     fn clone(&self) -> Self {
         Index {
-            directory: self.directory.box_clone(),
+            directory: self.directory.clone(),
+            schema: self.schema.clone(),
+            settings: self.settings.clone(),
             tokenizers: self.tokenizers.clone(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Index, IndexMeta, MySearchError, Schema, SegmentId, SegmentMeta, TextFieldIndexing,
+        TextOptions, INDEXED, MEMORY_ARENA_NUM_BYTES_MIN, STORED, TEXT,
+    };
+    use crate::{SegmentComponent, TerminatingWrite};
+    use std::io;
+    use std::path::Path;
+
+    fn test_schema() -> Schema {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", TEXT | STORED);
+        schema_builder.build()
+    }
+
+    #[test]
+    fn test_index_survives_reopen() -> crate::Result<()> {
+        let tempdir = tempfile::TempDir::new()?;
+        let index_path = tempdir.path().join("index");
+        {
+            let index = Index::create_in_dir(&index_path, test_schema())?;
+            // files created with `open_write` but not committed would be
+            // garbage collected on open.
+            index
+                .directory()
+                .atomic_write(Path::new("data"), b"persisted")?;
+        }
+        let index = Index::open_in_dir(&index_path)?;
+        assert_eq!(
+            index.directory().atomic_read(Path::new("data"))?,
+            b"persisted"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_in_missing_dir_fails() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        assert!(Index::open_in_dir(tempdir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_open_in_dir_without_meta_fails() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        assert!(matches!(
+            Index::open_in_dir(tempdir.path()),
+            Err(MySearchError::IndexNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_create_in_dir_twice_fails() -> crate::Result<()> {
+        let tempdir = tempfile::TempDir::new()?;
+        Index::create_in_dir(tempdir.path(), test_schema())?;
+        assert!(matches!(
+            Index::create_in_dir(tempdir.path(), test_schema()),
+            Err(MySearchError::IndexAlreadyExists(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_reads_committed_segments() -> crate::Result<()> {
+        let tempdir = tempfile::TempDir::new()?;
+        let segment_meta = SegmentMeta::new(SegmentId::generate_random(), 10);
+        {
+            let index = Index::create_in_dir(tempdir.path(), test_schema())?;
+            assert!(index.searchable_segment_metas()?.is_empty());
+            let index_meta = IndexMeta {
+                index_settings: index.settings().clone(),
+                segments: vec![segment_meta.clone()],
+                schema: index.schema(),
+                opstamp: 1,
+                payload: None,
+            };
+            index_meta.save(index.directory())?;
+        }
+        let index = Index::open_in_dir(tempdir.path())?;
+        assert_eq!(index.searchable_segment_metas()?, vec![segment_meta]);
+        assert_eq!(index.schema(), test_schema());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tokenizer_for_field() {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let count = schema_builder.add_u64_field("count", INDEXED);
+        let body = schema_builder.add_text_field(
+            "body",
+            TextOptions::default()
+                .set_indexing_options(TextFieldIndexing::default().set_tokenizer("unknown")),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+
+        let analyzer = index.tokenizer_for_field(title).unwrap();
+        let mut token_stream = analyzer.token_stream("hello world");
+        assert_eq!(token_stream.next().unwrap().text, "hello");
+        assert!(matches!(
+            index.tokenizer_for_field(count),
+            Err(MySearchError::SchemaError(_))
+        ));
+        assert!(matches!(
+            index.tokenizer_for_field(body),
+            Err(MySearchError::SchemaError(_))
+        ));
+    }
+
+    #[test]
+    fn test_garbage_collect_keeps_committed_segment_files() -> crate::Result<()> {
+        let index = Index::create_in_ram(test_schema());
+        let committed = SegmentMeta::new(SegmentId::generate_random(), 1);
+        let uncommitted = SegmentMeta::new(SegmentId::generate_random(), 1);
+        for segment_meta in &[&committed, &uncommitted] {
+            for path in segment_meta.list_files() {
+                index.directory().open_write(&path)?.terminate()?;
+            }
+        }
+        IndexMeta {
+            index_settings: index.settings().clone(),
+            segments: vec![committed.clone()],
+            schema: index.schema(),
+            opstamp: 1,
+            payload: None,
+        }
+        .save(index.directory())?;
+
+        let result = index.garbage_collect_files()?;
+        assert_eq!(result.deleted_files.len(), uncommitted.list_files().len());
+        for path in committed.list_files() {
+            assert!(index.directory().exists(&path)?);
+        }
+        for path in uncommitted.list_files() {
+            assert!(!index.directory().exists(&path)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_recovers_from_crashed_writer() -> crate::Result<()> {
+        let tempdir = tempfile::TempDir::new()?;
+        let title = test_schema().get_field("title").unwrap();
+        let orphan = SegmentMeta::new(SegmentId::generate_random(), 1);
+        {
+            let index = Index::create_in_dir(tempdir.path(), test_schema())?;
+            let mut index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+            index_writer.add_document(doc!(title => "committed"))?;
+            index_writer.commit()?;
+            // segment flushed by a writer that died before committing.
+            for path in orphan.list_files() {
+                index.directory().open_write(&path)?.terminate()?;
+            }
+        }
+        let index = Index::open_in_dir(tempdir.path())?;
+        for path in orphan.list_files() {
+            assert!(!index.directory().exists(&path)?);
+        }
+        assert_eq!(index.reader()?.searcher().num_docs(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_keeps_files_of_running_writer() -> crate::Result<()> {
+        let tempdir = tempfile::TempDir::new()?;
+        let index = Index::create_in_dir(tempdir.path(), test_schema())?;
+        let _index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+        let uncommitted = SegmentMeta::new(SegmentId::generate_random(), 1);
+        for path in uncommitted.list_files() {
+            index.directory().open_write(&path)?.terminate()?;
+        }
+        Index::open_in_dir(tempdir.path())?;
+        for path in uncommitted.list_files() {
+            assert!(index.directory().exists(&path)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_checksum() -> crate::Result<()> {
+        let index = Index::create_in_ram(test_schema());
+        let title = index.schema().get_field("title").unwrap();
+        let mut index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+        index_writer.add_document(doc!(title => "hello checksum"))?;
+        index_writer.commit()?;
+        drop(index_writer);
+        assert!(index.validate_checksum()?.is_empty());
+
+        let segment_meta = index.searchable_segment_metas()?[0].clone();
+        let segment = index.segment(segment_meta.clone());
+        let store_path = segment_meta.relative_path(SegmentComponent::Store);
+        let mut store_data = index.directory().atomic_read(&store_path)?;
+        store_data[0] ^= 1;
+        index.directory().atomic_write(&store_path, &store_data)?;
+        let terms_path = segment_meta.relative_path(SegmentComponent::Terms);
+        let terms_data = index.directory().atomic_read(&terms_path)?;
+        index
+            .directory()
+            .atomic_write(&terms_path, &terms_data[..terms_data.len() - 1])?;
+
+        assert_eq!(index.validate_checksum()?, {
+            let mut damaged_files = vec![store_path, terms_path];
+            damaged_files.sort();
+            damaged_files
+        });
+        // a flipped bit goes unnoticed on open, a truncation does not.
+        assert!(segment.open_read(SegmentComponent::Store).is_ok());
+        let err = segment.open_read(SegmentComponent::Terms).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        Ok(())
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Compressor, Directory, Opstamp, Schema, SegmentComponent, SegmentId};
+
+pub const META_FILEPATH: &str = "meta.json";
+
+/// Deletes applied to a segment, as of the commit `opstamp`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeleteMeta {
+    num_deleted_docs: u32,
+    opstamp: Opstamp,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SegmentMeta {
+    segment_id: SegmentId,
+    max_doc: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deletes: Option<DeleteMeta>,
+}
+
+impl SegmentMeta {
+    pub fn new(segment_id: SegmentId, max_doc: u32) -> SegmentMeta {
+        SegmentMeta {
+            segment_id,
+            max_doc,
+            deletes: None,
+        }
+    }
+
+    /// Returns the same segment with `num_deleted_docs` deleted documents, as
+    /// recorded in the delete file of the commit `opstamp`.
+    pub fn with_delete_meta(self, num_deleted_docs: u32, opstamp: Opstamp) -> SegmentMeta {
+        assert!(num_deleted_docs <= self.max_doc);
+        SegmentMeta {
+            deletes: Some(DeleteMeta {
+                num_deleted_docs,
+                opstamp,
+            }),
+            ..self
+        }
+    }
+
+    pub fn id(&self) -> SegmentId {
+        self.segment_id
+    }
+
+    pub fn max_doc(&self) -> u32 {
+        self.max_doc
+    }
+
+    /// Number of documents that are not deleted.
+    pub fn num_docs(&self) -> u32 {
+        self.max_doc - self.num_deleted_docs()
+    }
+
+    pub fn num_deleted_docs(&self) -> u32 {
+        self.deletes
+            .map(|deletes| deletes.num_deleted_docs)
+            .unwrap_or(0)
+    }
+
+    pub fn has_deletes(&self) -> bool {
+        self.deletes.is_some()
+    }
+
+    /// Opstamp of the commit that wrote the current delete file, if any.
+    pub fn delete_opstamp(&self) -> Option<Opstamp> {
+        self.deletes.map(|deletes| deletes.opstamp)
+    }
+
+    /// Delete files are versioned by the opstamp of the commit writing them,
+    /// so that readers of previous commits keep their own version.
+    pub fn relative_path(&self, component: SegmentComponent) -> PathBuf {
+        match component {
+            SegmentComponent::Delete => PathBuf::from(format!(
+                "{}.{}.{}",
+                self.segment_id.uuid_string(),
+                self.delete_opstamp().unwrap_or(0),
+                component.extension()
+            )),
+            _ => PathBuf::from(format!(
+                "{}.{}",
+                self.segment_id.uuid_string(),
+                component.extension()
+            )),
+        }
+    }
+
+    /// Returns the paths of all the files belonging to this segment.
+    pub fn list_files(&self) -> HashSet<PathBuf> {
+        SegmentComponent::iterator()
+            .filter(|&&component| component != SegmentComponent::Delete || self.has_deletes())
+            .map(|component| self.relative_path(*component))
+            .collect()
+    }
+}
+
+/// Settings of an index, chosen on creation and persisted in `meta.json`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexSettings {
+    #[serde(default)]
+    pub docstore_compression: Compressor,
+}
+
+/// Content of the `meta.json` file.
+///
+/// The segments it lists are the only ones visible to readers: publishing a new
+/// `IndexMeta` through `Directory::atomic_write` is what makes a commit durable.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexMeta {
+    #[serde(default)]
+    pub index_settings: IndexSettings,
+    pub segments: Vec<SegmentMeta>,
+    pub schema: Schema,
+    pub opstamp: Opstamp,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<String>,
+}
+
+impl IndexMeta {
+    /// Creates the metas of an empty index.
+    pub fn with_schema(schema: Schema) -> IndexMeta {
+        IndexMeta {
+            index_settings: IndexSettings::default(),
+            segments: Vec::new(),
+            schema,
+            opstamp: 0,
+            payload: None,
+        }
+    }
+
+    pub fn load(directory: &dyn Directory) -> crate::Result<IndexMeta> {
+        let meta_data = directory.atomic_read(Path::new(META_FILEPATH))?;
+        let index_meta = serde_json::from_slice(&meta_data)?;
+        Ok(index_meta)
+    }
+
+    pub fn save(&self, directory: &dyn Directory) -> crate::Result<()> {
+        let mut buffer = serde_json::to_vec_pretty(self)?;
+        buffer.push(b'\n');
+        directory.atomic_write(Path::new(META_FILEPATH), &buffer)?;
+        Ok(())
+    }
+
+    /// Returns the files referenced by this commit, `meta.json` included.
+    pub fn list_files(&self) -> HashSet<PathBuf> {
+        let mut files: HashSet<PathBuf> = self
+            .segments
+            .iter()
+            .flat_map(SegmentMeta::list_files)
+            .collect();
+        files.insert(PathBuf::from(META_FILEPATH));
+        files
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Compressor, IndexMeta, IndexSettings, RAMDirectory, Schema, SegmentId, SegmentMeta, STORED,
+        TEXT,
+    };
+
+    #[test]
+    fn test_index_meta_save_load() -> crate::Result<()> {
+        let directory = RAMDirectory::create();
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", TEXT | STORED);
+        let index_meta = IndexMeta {
+            index_settings: IndexSettings {
+                docstore_compression: Compressor::Snappy,
+            },
+            segments: vec![SegmentMeta::new(SegmentId::generate_random(), 3)],
+            schema: schema_builder.build(),
+            opstamp: 7,
+            payload: Some("first commit".to_string()),
+        };
+        index_meta.save(&directory)?;
+        assert_eq!(IndexMeta::load(&directory)?, index_meta);
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_meta_without_settings() -> crate::Result<()> {
+        let directory = RAMDirectory::create();
+        let meta_path = std::path::Path::new(super::META_FILEPATH);
+        let meta_json = r#"{"segments": [], "schema": [], "opstamp": 0}"#;
+        crate::Directory::atomic_write(&directory, meta_path, meta_json.as_bytes())?;
+        assert_eq!(
+            IndexMeta::load(&directory)?
+                .index_settings
+                .docstore_compression,
+            Compressor::Lz4
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_meta_load_corrupted() {
+        let directory = RAMDirectory::create();
+        let meta_path = std::path::Path::new(super::META_FILEPATH);
+        crate::Directory::atomic_write(&directory, meta_path, b"{not json").unwrap();
+        assert!(matches!(
+            IndexMeta::load(&directory),
+            Err(crate::MySearchError::DataCorruption(_))
+        ));
+    }
+
+    #[test]
+    fn test_segment_meta_deletes() {
+        let segment_meta = SegmentMeta::new(SegmentId::generate_random(), 10);
+        assert_eq!(segment_meta.num_docs(), 10);
+        assert!(!segment_meta
+            .list_files()
+            .iter()
+            .any(|path| path.extension().unwrap() == "del"));
+
+        let segment_meta = segment_meta.with_delete_meta(3, 12);
+        assert_eq!(segment_meta.num_docs(), 7);
+        assert_eq!(segment_meta.delete_opstamp(), Some(12));
+        let delete_path = format!("{}.12.del", segment_meta.id().uuid_string());
+        assert!(segment_meta
+            .list_files()
+            .contains(std::path::Path::new(&delete_path)));
+        let json = serde_json::to_string(&segment_meta).unwrap();
+        assert_eq!(
+            serde_json::from_str::<SegmentMeta>(&json).unwrap(),
+            segment_meta
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use crate::{
+    Field, IndexRecordOption, OwnedBytes, SegmentPostings, Term, TermDictionary, TermInfo,
+    TermStreamerBuilder,
+};
+
+/// Gives access to the terms of a field in a segment and to their postings.
+///
+/// Obtained through `SegmentReader::inverted_index`, it shares the files of the
+/// segment and is cheap to create.
+#[derive(Clone)]
+pub struct InvertedIndexReader {
+    termdict: Arc<TermDictionary>,
+    postings_data: OwnedBytes,
+    positions_data: OwnedBytes,
+    field: Field,
+    record_option: IndexRecordOption,
+}
+
+impl InvertedIndexReader {
+    pub(crate) fn new(
+        termdict: Arc<TermDictionary>,
+        postings_data: OwnedBytes,
+        positions_data: OwnedBytes,
+        field: Field,
+        record_option: IndexRecordOption,
+    ) -> InvertedIndexReader {
+        InvertedIndexReader {
+            termdict,
+            postings_data,
+            positions_data,
+            field,
+            record_option,
+        }
+    }
+
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    /// What the field records in its postings.
+    pub fn record_option(&self) -> IndexRecordOption {
+        self.record_option
+    }
+
+    /// Returns the term dictionary of the segment, shared by all its fields.
+    pub fn terms(&self) -> &TermDictionary {
+        &self.termdict
+    }
+
+    /// Returns a builder streaming over the terms of this field only.
+    pub fn field_terms(&self) -> TermStreamerBuilder<'_> {
+        self.termdict
+            .prefix_range(Term::for_field(self.field).as_slice())
+    }
+
+    pub fn get_term_info(&self, term: &Term) -> Option<TermInfo> {
+        debug_assert_eq!(term.field(), self.field);
+        self.termdict.get(term.as_slice())
+    }
+
+    /// Number of documents of the segment containing `term`.
+    pub fn doc_freq(&self, term: &Term) -> u32 {
+        self.get_term_info(term)
+            .map(|term_info| term_info.doc_freq)
+            .unwrap_or(0u32)
+    }
+
+    /// Opens the postings described by `term_info`.
+    ///
+    /// Positions are only read if both `requested_option` and the field have
+    /// them.
+    pub fn read_postings_from_terminfo(
+        &self,
+        term_info: &TermInfo,
+        requested_option: IndexRecordOption,
+    ) -> SegmentPostings {
+        let record_option = requested_option.min(self.record_option);
+        let postings_data = self
+            .postings_data
+            .slice(term_info.postings_offset as usize..self.postings_data.len());
+        let positions_data = if record_option.has_positions() {
+            Some(
+                self.positions_data
+                    .slice(term_info.positions_offset as usize..self.positions_data.len()),
+            )
+        } else {
+            None
+        };
+        // the postings are decoded with the layout they were written with.
+        SegmentPostings::open(
+            term_info.doc_freq,
+            postings_data,
+            self.record_option,
+            positions_data,
+        )
+    }
+
+    /// Opens the postings of `term`, or returns `None` if the segment does not
+    /// contain it.
+    pub fn read_postings(
+        &self,
+        term: &Term,
+        requested_option: IndexRecordOption,
+    ) -> Option<SegmentPostings> {
+        self.get_term_info(term)
+            .map(|term_info| self.read_postings_from_terminfo(&term_info, requested_option))
+    }
+}
@@ -1,3 +1,19 @@
+mod alive_bitset;
 mod index;
+mod index_meta;
+mod inverted_index_reader;
+mod searcher;
+mod segment;
+mod segment_component;
+mod segment_id;
+mod segment_reader;
 
+pub use alive_bitset::*;
 pub use index::*;
+pub use index_meta::*;
+pub use inverted_index_reader::*;
+pub use searcher::*;
+pub use segment::*;
+pub use segment_component::*;
+pub use segment_id::*;
+pub use segment_reader::*;
@@ -0,0 +1,184 @@
+use std::fmt;
+
+use crate::{
+    Collector, DocAddress, Document, Field, Index, MySearchError, Query, Schema, SegmentOrdinal,
+    SegmentReader, StoreReader, Term,
+};
+
+/// Snapshot of the index used to run searches.
+///
+/// A `Searcher` sees the segments of the commit it was created from, even if
+/// later commits or merges remove them from the index.
+#[derive(Clone)]
+pub struct Searcher {
+    schema: Schema,
+    index: Index,
+    segment_readers: Vec<SegmentReader>,
+    store_readers: Vec<StoreReader>,
+}
+
+impl Searcher {
+    pub(crate) fn new(
+        schema: Schema,
+        index: Index,
+        segment_readers: Vec<SegmentReader>,
+    ) -> crate::Result<Searcher> {
+        let store_readers = segment_readers
+            .iter()
+            .map(SegmentReader::get_store_reader)
+            .collect::<crate::Result<Vec<_>>>()?;
+        Ok(Searcher {
+            schema,
+            index,
+            segment_readers,
+            store_readers,
+        })
+    }
+
+    pub fn index(&self) -> &Index {
+        &self.index
+    }
+
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Fetches the stored fields of the document at `doc_address`.
+    ///
+    /// Fields that are not `STORED` are not part of the returned document.
+    pub fn doc(&self, doc_address: DocAddress) -> crate::Result<Document> {
+        let store_reader = self
+            .store_readers
+            .get(doc_address.segment_ord as usize)
+            .ok_or_else(|| {
+                MySearchError::InvalidArgument(format!(
+                    "segment ordinal {} is out of range",
+                    doc_address.segment_ord
+                ))
+            })?;
+        store_reader.get(doc_address.doc_id)
+    }
+
+    /// Total number of documents of the snapshot.
+    pub fn num_docs(&self) -> u64 {
+        self.segment_readers
+            .iter()
+            .map(|segment_reader| u64::from(segment_reader.num_docs()))
+            .sum()
+    }
+
+    /// Total number of documents of the snapshot, deleted documents included.
+    pub fn max_doc(&self) -> u64 {
+        self.segment_readers
+            .iter()
+            .map(|segment_reader| u64::from(segment_reader.max_doc()))
+            .sum()
+    }
+
+    /// Number of documents containing `term`, across all segments.
+    pub fn doc_freq(&self, term: &Term) -> u64 {
+        self.segment_readers
+            .iter()
+            .map(|segment_reader| {
+                u64::from(segment_reader.inverted_index(term.field()).doc_freq(term))
+            })
+            .sum()
+    }
+
+    /// Total number of tokens of `field`, across all segments.
+    ///
+    /// Fields without fieldnorms count one token per document.
+    pub fn total_num_tokens(&self, field: Field) -> crate::Result<u64> {
+        let mut total_num_tokens = 0u64;
+        for segment_reader in &self.segment_readers {
+            total_num_tokens += match segment_reader.fieldnorm_readers().get_field(field)? {
+                Some(fieldnorm_reader) => fieldnorm_reader.total_num_tokens(),
+                None => u64::from(segment_reader.max_doc()),
+            };
+        }
+        Ok(total_num_tokens)
+    }
+
+    /// Runs `query` over all the segments and gathers the results with
+    /// `collector`.
+    pub fn search<C: Collector>(
+        &self,
+        query: &dyn Query,
+        collector: &C,
+    ) -> crate::Result<C::Fruit> {
+        let weight = query.weight(self, collector.requires_scoring())?;
+        let mut segment_fruits = Vec::with_capacity(self.segment_readers.len());
+        for (segment_ord, segment_reader) in self.segment_readers.iter().enumerate() {
+            segment_fruits.push(collector.collect_segment(
+                weight.as_ref(),
+                segment_ord as SegmentOrdinal,
+                segment_reader,
+            )?);
+        }
+        collector.merge_fruits(segment_fruits)
+    }
+
+    pub fn segment_readers(&self) -> &[SegmentReader] {
+        &self.segment_readers
+    }
+
+    pub fn segment_reader(&self, segment_ord: SegmentOrdinal) -> &SegmentReader {
+        &self.segment_readers[segment_ord as usize]
+    }
+}
+
+impl fmt::Debug for Searcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let segment_ids: Vec<String> = self
+            .segment_readers
+            .iter()
+            .map(|segment_reader| segment_reader.segment_id().short_uuid_string())
+            .collect();
+        write!(f, "Searcher({:?})", segment_ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Compressor, DocAddress, Index, IndexRecordOption, IndexSettings, MySearchError,
+        RAMDirectory, Schema, Term, TermQuery, TopDocs, Value, STORED, TEXT,
+    };
+
+    #[test]
+    fn test_searcher_doc() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT | STORED);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let settings = IndexSettings {
+            docstore_compression: Compressor::Snappy,
+        };
+        let index =
+            Index::create_with_settings(RAMDirectory::create(), schema_builder.build(), settings)?;
+        let mut index_writer = index.writer_with_num_threads(1, 10_000_000)?;
+        index_writer.add_document(doc!(title => "first", body => "not stored"))?;
+        index_writer.add_document(doc!(body => "no stored field"))?;
+        index_writer.add_document(doc!(title => "third", title => "again"))?;
+        index_writer.commit()?;
+
+        let searcher = index.reader()?.searcher();
+        let query = TermQuery::new(
+            Term::from_field_text(title, "third"),
+            IndexRecordOption::Basic,
+        );
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(1))?;
+        let doc = searcher.doc(top_docs[0].1)?;
+        let titles: Vec<&Value> = doc.get_all(title).collect();
+        assert_eq!(titles, vec![&Value::from("third"), &Value::from("again")]);
+
+        let first_doc = searcher.doc(DocAddress::new(0, 0))?;
+        assert_eq!(first_doc.get_first(title), Some(&Value::from("first")));
+        assert!(first_doc.get_first(body).is_none());
+        assert!(searcher.doc(DocAddress::new(0, 1))?.is_empty());
+        assert!(matches!(
+            searcher.doc(DocAddress::new(1, 0)),
+            Err(MySearchError::InvalidArgument(_))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+use crate::{
+    FileSlice, Index, Opstamp, Schema, SegmentComponent, SegmentId, SegmentMeta, WritePtr,
+};
+
+/// A segment of an index: a self-contained subset of its documents.
+#[derive(Clone)]
+pub struct Segment {
+    index: Index,
+    meta: SegmentMeta,
+}
+
+impl fmt::Debug for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Segment({:?})", self.id().uuid_string())
+    }
+}
+
+impl Segment {
+    pub(crate) fn for_index(index: Index, meta: SegmentMeta) -> Segment {
+        Segment { index, meta }
+    }
+
+    pub fn index(&self) -> &Index {
+        &self.index
+    }
+
+    pub fn schema(&self) -> Schema {
+        self.index.schema()
+    }
+
+    pub fn meta(&self) -> &SegmentMeta {
+        &self.meta
+    }
+
+    pub fn id(&self) -> SegmentId {
+        self.meta.id()
+    }
+
+    /// Returns the same segment with its number of documents updated.
+    pub fn with_max_doc(self, max_doc: u32) -> Segment {
+        Segment {
+            index: self.index,
+            meta: SegmentMeta::new(self.meta.id(), max_doc),
+        }
+    }
+
+    /// Returns the same segment with its deletes updated, see
+    /// `SegmentMeta::with_delete_meta`.
+    pub fn with_delete_meta(self, num_deleted_docs: u32, opstamp: Opstamp) -> Segment {
+        Segment {
+            index: self.index,
+            meta: self.meta.with_delete_meta(num_deleted_docs, opstamp),
+        }
+    }
+
+    pub fn relative_path(&self, component: SegmentComponent) -> PathBuf {
+        self.meta.relative_path(component)
+    }
+
+    pub fn open_read(&self, component: SegmentComponent) -> io::Result<FileSlice> {
+        let path = self.relative_path(component);
+        self.index.directory().open_read(&path)
+    }
+
+    pub fn open_write(&self, component: SegmentComponent) -> io::Result<WritePtr> {
+        let path = self.relative_path(component);
+        self.index.directory().open_write(&path)
+    }
+}
@@ -0,0 +1,42 @@
+use std::slice;
+
+/// Kinds of files a segment is made of.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SegmentComponent {
+    Postings,
+    Positions,
+    Terms,
+    FieldNorms,
+    FastFields,
+    Store,
+    /// Alive bitset of the segment, rewritten by every commit deleting some of
+    /// its documents.
+    Delete,
+}
+
+impl SegmentComponent {
+    pub fn iterator() -> slice::Iter<'static, SegmentComponent> {
+        static SEGMENT_COMPONENTS: [SegmentComponent; 7] = [
+            SegmentComponent::Postings,
+            SegmentComponent::Positions,
+            SegmentComponent::Terms,
+            SegmentComponent::FieldNorms,
+            SegmentComponent::FastFields,
+            SegmentComponent::Store,
+            SegmentComponent::Delete,
+        ];
+        SEGMENT_COMPONENTS.iter()
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            SegmentComponent::Postings => "idx",
+            SegmentComponent::Positions => "pos",
+            SegmentComponent::Terms => "term",
+            SegmentComponent::FieldNorms => "fieldnorm",
+            SegmentComponent::FastFields => "fast",
+            SegmentComponent::Store => "store",
+            SegmentComponent::Delete => "del",
+        }
+    }
+}
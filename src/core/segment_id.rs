@@ -0,0 +1,28 @@
+use std::fmt;
+use std::fmt::Formatter;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct SegmentId(Uuid);
+
+impl SegmentId {
+    pub fn generate_random() -> SegmentId {
+        SegmentId(Uuid::new_v4())
+    }
+
+    pub fn short_uuid_string(&self) -> String {
+        self.0.to_simple_ref().to_string()[..8].to_string()
+    }
+
+    pub fn uuid_string(&self) -> String {
+        self.0.to_simple_ref().to_string()
+    }
+}
+
+impl fmt::Debug for SegmentId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Seg({:?})", self.short_uuid_string())
+    }
+}
@@ -0,0 +1,160 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{
+    AliveBitSet, DocId, FastFieldReaders, Field, FieldNormReader, FieldNormReaders, FieldType,
+    FileSlice, IndexRecordOption, InvertedIndexReader, MySearchError, OwnedBytes, Schema, Segment,
+    SegmentComponent, SegmentId, StoreReader, TermDictionary,
+};
+
+/// Read access to the files of a segment.
+///
+/// Cloning a `SegmentReader` is cheap. As long as it is alive, the files of the
+/// segment stay readable, even if the segment is deleted from the index.
+#[derive(Clone)]
+pub struct SegmentReader {
+    segment_id: SegmentId,
+    max_doc: DocId,
+    num_docs: DocId,
+    alive_bitset: Option<AliveBitSet>,
+    schema: Schema,
+    termdict: Arc<TermDictionary>,
+    postings_data: OwnedBytes,
+    positions_data: OwnedBytes,
+    fieldnorm_readers: FieldNormReaders,
+    fast_fields_readers: FastFieldReaders,
+    store_file: FileSlice,
+}
+
+impl SegmentReader {
+    pub fn open(segment: &Segment) -> crate::Result<SegmentReader> {
+        let termdict = TermDictionary::open(segment.open_read(SegmentComponent::Terms)?)?;
+        let postings_data = segment
+            .open_read(SegmentComponent::Postings)?
+            .read_bytes()?;
+        let positions_data = segment
+            .open_read(SegmentComponent::Positions)?
+            .read_bytes()?;
+        let fieldnorm_readers =
+            FieldNormReaders::open(segment.open_read(SegmentComponent::FieldNorms)?)?;
+        let fast_fields_readers = FastFieldReaders::open(
+            segment.open_read(SegmentComponent::FastFields)?,
+            segment.schema(),
+        )?;
+        let store_file = segment.open_read(SegmentComponent::Store)?;
+        let alive_bitset = if segment.meta().has_deletes() {
+            Some(AliveBitSet::open(
+                segment.open_read(SegmentComponent::Delete)?,
+            )?)
+        } else {
+            None
+        };
+        Ok(SegmentReader {
+            segment_id: segment.id(),
+            max_doc: segment.meta().max_doc(),
+            num_docs: segment.meta().num_docs(),
+            alive_bitset,
+            schema: segment.schema(),
+            termdict: Arc::new(termdict),
+            postings_data,
+            positions_data,
+            fieldnorm_readers,
+            fast_fields_readers,
+            store_file,
+        })
+    }
+
+    pub fn segment_id(&self) -> SegmentId {
+        self.segment_id
+    }
+
+    /// Number of documents of the segment. Doc ids range in `[0, max_doc)`.
+    pub fn max_doc(&self) -> DocId {
+        self.max_doc
+    }
+
+    /// Number of documents of the segment that are not deleted.
+    pub fn num_docs(&self) -> DocId {
+        self.num_docs
+    }
+
+    pub fn num_deleted_docs(&self) -> DocId {
+        self.max_doc - self.num_docs
+    }
+
+    /// Returns the alive bitset, or `None` if the segment has no deletes.
+    pub fn alive_bitset(&self) -> Option<&AliveBitSet> {
+        self.alive_bitset.as_ref()
+    }
+
+    #[inline]
+    pub fn is_deleted(&self, doc: DocId) -> bool {
+        self.alive_bitset
+            .as_ref()
+            .map(|alive_bitset| alive_bitset.is_deleted(doc))
+            .unwrap_or(false)
+    }
+
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    pub fn fieldnorm_readers(&self) -> &FieldNormReaders {
+        &self.fieldnorm_readers
+    }
+
+    /// Returns the fast field readers of the segment.
+    pub fn fast_fields(&self) -> &FastFieldReaders {
+        &self.fast_fields_readers
+    }
+
+    /// Returns the fieldnorms of `field`.
+    ///
+    /// Fails if the field has no fieldnorms, i.e. if it is not an indexed text field.
+    pub fn get_fieldnorms_reader(&self, field: Field) -> crate::Result<FieldNormReader> {
+        self.fieldnorm_readers.get_field(field)?.ok_or_else(|| {
+            MySearchError::SchemaError(format!(
+                "field '{}' has no fieldnorms",
+                self.schema.get_field_name(field)
+            ))
+        })
+    }
+
+    /// Opens a reader over the doc store of the segment.
+    ///
+    /// Each call creates a reader with its own block cache.
+    pub fn get_store_reader(&self) -> crate::Result<StoreReader> {
+        Ok(StoreReader::open(self.store_file.clone())?)
+    }
+
+    /// Returns the inverted index of `field`.
+    ///
+    /// A field that is not indexed simply has no terms.
+    pub fn inverted_index(&self, field: Field) -> InvertedIndexReader {
+        let record_option = match self.schema.get_field_entry(field).field_type() {
+            FieldType::Str(text_options) => text_options
+                .get_indexing_options()
+                .map(|indexing_options| indexing_options.index_option())
+                .unwrap_or(IndexRecordOption::Basic),
+            _ => IndexRecordOption::Basic,
+        };
+        InvertedIndexReader::new(
+            self.termdict.clone(),
+            self.postings_data.clone(),
+            self.positions_data.clone(),
+            field,
+            record_option,
+        )
+    }
+}
+
+impl fmt::Debug for SegmentReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "SegmentReader({}, max_doc={})",
+            self.segment_id.short_uuid_string(),
+            self.max_doc
+        )
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::ops::Range;
+
+use crate::common::CountingWriter;
+use crate::{BinarySerializable, Field, FileSlice, HasLen, VInt};
+
+/// Writes a file made of one section per field.
+///
+/// The sections are followed by a footer mapping each field to the start of
+/// its section:
+///
+/// ```text
+/// [sections][num fields: VInt][(field id: VInt, offset: VInt)...][footer length: u32]
+/// ```
+pub struct CompositeWrite<W: Write> {
+    write: CountingWriter<W>,
+    offsets: Vec<(Field, u64)>,
+}
+
+impl<W: Write> CompositeWrite<W> {
+    pub fn wrap(write: W) -> CompositeWrite<W> {
+        CompositeWrite {
+            write: CountingWriter::wrap(write),
+            offsets: Vec::new(),
+        }
+    }
+
+    /// Starts the section of `field` and returns the writer to fill it with.
+    ///
+    /// # Panics
+    ///
+    /// If `field` already has a section.
+    pub fn for_field(&mut self, field: Field) -> &mut CountingWriter<W> {
+        assert!(
+            self.offsets.iter().all(|(other, _)| *other != field),
+            "field {:?} already has a section",
+            field
+        );
+        let offset = self.write.written_bytes();
+        self.offsets.push((field, offset));
+        &mut self.write
+    }
+
+    /// Writes the footer and returns the underlying writer.
+    pub fn close(mut self) -> io::Result<W> {
+        let footer_start = self.write.written_bytes();
+        VInt(self.offsets.len() as u64).serialize(&mut self.write)?;
+        for (field, offset) in &self.offsets {
+            VInt(u64::from(field.field_id())).serialize(&mut self.write)?;
+            VInt(*offset).serialize(&mut self.write)?;
+        }
+        let footer_len = (self.write.written_bytes() - footer_start) as u32;
+        footer_len.serialize(&mut self.write)?;
+        self.write.finish()
+    }
+}
+
+/// Read-only view of a file written by `CompositeWrite`.
+#[derive(Clone)]
+pub struct CompositeFile {
+    data: FileSlice,
+    offsets_index: HashMap<Field, Range<usize>>,
+}
+
+impl CompositeFile {
+    pub fn open(data: &FileSlice) -> io::Result<CompositeFile> {
+        let end = data.len();
+        if end < 4 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "composite file is too short to hold a footer",
+            ));
+        }
+        let footer_len = u32::deserialize(&mut data.slice(end - 4..end).read_bytes()?)? as usize;
+        if footer_len + 4 > end {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("composite file footer length {} is invalid", footer_len),
+            ));
+        }
+        let footer_start = end - 4 - footer_len;
+        let mut footer_data = data.slice(footer_start..end - 4).read_bytes()?;
+        let num_fields = VInt::deserialize(&mut footer_data)?.0 as usize;
+        let mut sections = Vec::with_capacity(num_fields);
+        for _ in 0..num_fields {
+            let field_id = VInt::deserialize(&mut footer_data)?.0 as u32;
+            let offset = VInt::deserialize(&mut footer_data)?.0 as usize;
+            sections.push((Field::from_field_id(field_id), offset));
+        }
+        let mut offsets_index = HashMap::with_capacity(num_fields);
+        for (i, &(field, start)) in sections.iter().enumerate() {
+            let stop = sections
+                .get(i + 1)
+                .map(|&(_, next_start)| next_start)
+                .unwrap_or(footer_start);
+            if start > stop {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "composite file sections are not sorted",
+                ));
+            }
+            offsets_index.insert(field, start..stop);
+        }
+        Ok(CompositeFile {
+            data: data.slice_to(footer_start),
+            offsets_index,
+        })
+    }
+
+    /// Returns a composite file without any section.
+    pub fn empty() -> CompositeFile {
+        CompositeFile {
+            data: FileSlice::empty(),
+            offsets_index: HashMap::new(),
+        }
+    }
+
+    /// Returns the section of `field`, if any.
+    pub fn open_read(&self, field: Field) -> Option<FileSlice> {
+        self.offsets_index
+            .get(&field)
+            .map(|range| self.data.slice(range.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CompositeFile, CompositeWrite, Field, FileSlice, HasLen};
+    use std::io::{self, Write};
+
+    #[test]
+    fn test_composite_file() -> io::Result<()> {
+        let mut composite_write = CompositeWrite::wrap(Vec::new());
+        composite_write
+            .for_field(Field::from_field_id(3))
+            .write_all(b"three")?;
+        composite_write.for_field(Field::from_field_id(1));
+        composite_write
+            .for_field(Field::from_field_id(200))
+            .write_all(b"two hundred")?;
+        let buffer = composite_write.close()?;
+
+        let composite_file = CompositeFile::open(&FileSlice::from(buffer))?;
+        let read = |field_id: u32| -> Option<Vec<u8>> {
+            composite_file
+                .open_read(Field::from_field_id(field_id))
+                .map(|file_slice| file_slice.read_bytes().unwrap().as_slice().to_vec())
+        };
+        assert_eq!(read(3), Some(b"three".to_vec()));
+        assert_eq!(read(1), Some(Vec::new()));
+        assert_eq!(read(200), Some(b"two hundred".to_vec()));
+        assert_eq!(read(2), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_composite_file_rejects_garbage() {
+        assert!(CompositeFile::open(&FileSlice::from(vec![1u8, 2])).is_err());
+        assert!(CompositeFile::open(&FileSlice::from(vec![255u8; 8])).is_err());
+        assert_eq!(
+            CompositeFile::empty()
+                .open_read(Field::from_field_id(0))
+                .map(|f| f.len()),
+            None
+        );
+    }
+}
@@ -1,7 +1,9 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::{fmt, io};
 
-use crate::{FileHandle, FileSlice, WritePtr};
+use crate::{
+    DirectoryLock, FileHandle, FileSlice, Lock, LockError, WatchCallback, WatchHandle, WritePtr,
+};
 
 pub trait Directory: DirectoryClone + fmt::Debug + Send + Sync + 'static {
     fn get_file_handle(&self, path: &Path) -> io::Result<Box<dyn FileHandle>>;
@@ -13,9 +15,35 @@ pub trait Directory: DirectoryClone + fmt::Debug + Send + Sync + 'static {
 
     fn exists(&self, path: &Path) -> io::Result<bool>;
 
+    /// Removes the file at `path`.
+    ///
+    /// `FileSlice`s opened before the deletion remain readable.
+    fn delete(&self, path: &Path) -> io::Result<()>;
+
+    /// Lists the paths of all the files stored in the directory.
+    fn list_files(&self) -> io::Result<Vec<PathBuf>>;
+
     fn open_write(&self, path: &Path) -> io::Result<WritePtr>;
 
     fn atomic_read(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+    /// Replaces the content of `path` with `data` so that concurrent readers observe
+    /// either the previous content or the new one, never a partial write.
+    fn atomic_write(&self, path: &Path, data: &[u8]) -> io::Result<()>;
+
+    /// Registers a callback called whenever `meta.json` changes, i.e. after
+    /// every commit.
+    ///
+    /// The callback stays registered as long as the returned `WatchHandle` is
+    /// alive.
+    fn watch(&self, watch_callback: WatchCallback) -> io::Result<WatchHandle>;
+
+    /// Acquires `lock`, which stays held until the returned `DirectoryLock` is
+    /// dropped.
+    ///
+    /// If the lock is already held, this either waits for it to be released or
+    /// fails with `LockError::LockBusy`, depending on `lock.is_blocking`.
+    fn acquire_lock(&self, lock: &Lock) -> Result<DirectoryLock, LockError>;
 }
 
 pub trait DirectoryClone {
@@ -0,0 +1,54 @@
+use std::io;
+use std::path::PathBuf;
+
+use once_cell::sync::Lazy;
+use thiserror::Error;
+
+/// A lock file guarding exclusive access to a resource of the index.
+#[derive(Debug, Clone)]
+pub struct Lock {
+    /// Path of the lock file, relative to the directory root.
+    pub filepath: PathBuf,
+    /// Whether `Directory::acquire_lock` should wait for the lock to be released
+    /// instead of failing right away when it is already held.
+    pub is_blocking: bool,
+}
+
+/// Lock held by the `IndexWriter`, ensuring there is at most one writer per
+/// index, whichever process it lives in.
+///
+/// Acquiring it fails right away if another writer holds it.
+pub static INDEX_WRITER_LOCK: Lazy<Lock> = Lazy::new(|| Lock {
+    filepath: PathBuf::from(".writer.lock"),
+    is_blocking: false,
+});
+
+/// Lock held while loading a commit, and while garbage collecting files, so
+/// that the files of a commit cannot be deleted between the moment its
+/// `meta.json` is read and the moment its segments are opened.
+///
+/// Acquiring it waits for the current holder to release it.
+pub static META_LOCK: Lazy<Lock> = Lazy::new(|| Lock {
+    filepath: PathBuf::from(".meta.lock"),
+    is_blocking: true,
+});
+
+#[derive(Debug, Error)]
+pub enum LockError {
+    #[error("the lock is already held, possibly by another process")]
+    LockBusy,
+    #[error("an IO error occurred while acquiring the lock: '{0}'")]
+    IoError(#[from] io::Error),
+}
+
+/// Guard returned by `Directory::acquire_lock`. The lock is released when it
+/// is dropped.
+pub struct DirectoryLock {
+    _guard: Box<dyn Send + Sync + 'static>,
+}
+
+impl<T: Send + Sync + 'static> From<Box<T>> for DirectoryLock {
+    fn from(underlying: Box<T>) -> DirectoryLock {
+        DirectoryLock { _guard: underlying }
+    }
+}
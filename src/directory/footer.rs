@@ -0,0 +1,188 @@
+use std::io::{self, Write};
+
+use crc32fast::Hasher;
+
+use crate::{BinarySerializable, FileSlice, HasLen, TerminatingWrite};
+
+/// Version of the index file format, recorded in the footer of every file.
+pub const INDEX_FORMAT_VERSION: u32 = 1;
+
+/// Marks the end of a file carrying a footer.
+const FOOTER_MAGIC_NUMBER: u32 = 0x4d59_5352;
+
+/// Number of bytes of a serialized `Footer`.
+pub const FOOTER_LEN: usize = 12;
+
+/// Trailer appended to the files written through a `ManagedDirectory`.
+///
+/// It is serialized as `[crc: u32][version: u32][magic number: u32]`, the CRC32
+/// covering all the bytes preceding the footer.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Footer {
+    crc: u32,
+    version: u32,
+}
+
+impl Footer {
+    pub fn new(crc: u32) -> Footer {
+        Footer {
+            crc,
+            version: INDEX_FORMAT_VERSION,
+        }
+    }
+
+    pub fn crc(&self) -> u32 {
+        self.crc
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Splits `file` into its footer and the data it covers.
+    ///
+    /// Fails with `io::ErrorKind::InvalidData` if the file is too short or does
+    /// not end with a footer, typically because it was truncated.
+    pub fn extract_footer(file: FileSlice) -> io::Result<(Footer, FileSlice)> {
+        if file.len() < FOOTER_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "file is {} bytes long, too short to hold a footer",
+                    file.len()
+                ),
+            ));
+        }
+        let (body, footer_slice) = file.split_from_end(FOOTER_LEN);
+        let footer = Footer::deserialize(&mut footer_slice.read_bytes()?.as_slice())?;
+        Ok((footer, body))
+    }
+
+    /// Checks that the file was written in a format this version can read.
+    pub fn is_compatible(&self) -> io::Result<()> {
+        if self.version != INDEX_FORMAT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "unsupported index format version {}, expected {}",
+                    self.version, INDEX_FORMAT_VERSION
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns true if `body` matches the CRC recorded in the footer.
+    pub fn is_valid_checksum(&self, body: &[u8]) -> bool {
+        let mut hasher = Hasher::new();
+        hasher.update(body);
+        hasher.finalize() == self.crc
+    }
+}
+
+impl BinarySerializable for Footer {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.crc.serialize(writer)?;
+        self.version.serialize(writer)?;
+        FOOTER_MAGIC_NUMBER.serialize(writer)
+    }
+
+    fn deserialize<R: io::Read>(reader: &mut R) -> io::Result<Footer> {
+        let crc = u32::deserialize(reader)?;
+        let version = u32::deserialize(reader)?;
+        if u32::deserialize(reader)? != FOOTER_MAGIC_NUMBER {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "file does not end with a footer, it may be truncated",
+            ));
+        }
+        Ok(Footer { crc, version })
+    }
+}
+
+/// Writer computing the CRC of the data going through it, and appending the
+/// `Footer` when it is terminated.
+pub(crate) struct FooterProxy<W: TerminatingWrite> {
+    hasher: Option<Hasher>,
+    writer: W,
+}
+
+impl<W: TerminatingWrite> FooterProxy<W> {
+    pub fn new(writer: W) -> FooterProxy<W> {
+        FooterProxy {
+            hasher: Some(Hasher::new()),
+            writer,
+        }
+    }
+}
+
+impl<W: TerminatingWrite> Write for FooterProxy<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let hasher = self
+            .hasher
+            .as_mut()
+            .ok_or_else(|| io::Error::other("cannot write to a file after its footer"))?;
+        let written_len = self.writer.write(buf)?;
+        hasher.update(&buf[..written_len]);
+        Ok(written_len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl<W: TerminatingWrite> TerminatingWrite for FooterProxy<W> {
+    fn terminate_ref(&mut self) -> io::Result<()> {
+        let hasher = self
+            .hasher
+            .take()
+            .ok_or_else(|| io::Error::other("the file is already terminated"))?;
+        Footer::new(hasher.finalize()).serialize(&mut self.writer)?;
+        self.writer.terminate_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FooterProxy;
+    use crate::{FileSlice, Footer, TerminatingWrite, FOOTER_LEN, INDEX_FORMAT_VERSION};
+    use std::io::{self, Write};
+
+    fn write_with_footer(data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        let mut wrt = FooterProxy::new(&mut buffer);
+        wrt.write_all(data)?;
+        // flushing does not append the footer.
+        wrt.flush()?;
+        wrt.terminate_ref()?;
+        assert!(wrt.write_all(b"late").is_err());
+        assert!(wrt.terminate_ref().is_err());
+        Ok(buffer)
+    }
+
+    #[test]
+    fn test_footer_roundtrip() -> io::Result<()> {
+        let buffer = write_with_footer(b"hello footer")?;
+        assert_eq!(buffer.len(), 12 + FOOTER_LEN);
+        let (footer, body) = Footer::extract_footer(FileSlice::from(buffer))?;
+        assert_eq!(footer.version(), INDEX_FORMAT_VERSION);
+        footer.is_compatible()?;
+        let body = body.read_bytes()?;
+        assert_eq!(body.as_slice(), b"hello footer");
+        assert!(footer.is_valid_checksum(body.as_slice()));
+        assert!(!footer.is_valid_checksum(b"hello fooder"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_truncated_file_has_no_footer() -> io::Result<()> {
+        let buffer = write_with_footer(b"hello footer")?;
+        for len in &[0, FOOTER_LEN - 1, buffer.len() - 1] {
+            let truncated = FileSlice::from(buffer[..*len].to_vec());
+            let err = Footer::extract_footer(truncated).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,339 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Formatter;
+use std::io::BufWriter;
+use std::ops::{Deref, Range};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock, Weak};
+use std::{fmt, io};
+
+use stable_deref_trait::StableDeref;
+
+use crate::directory::footer::FooterProxy;
+use crate::{
+    Directory, DirectoryLock, FileHandle, FileSlice, Footer, HasLen, Lock, LockError, OwnedBytes,
+    WatchCallback, WatchHandle, WritePtr,
+};
+
+pub const MANAGED_FILEPATH: &str = ".managed.json";
+
+/// Outcome of a `ManagedDirectory::garbage_collect` pass.
+#[derive(Debug, Default)]
+pub struct GarbageCollectionResult {
+    pub deleted_files: Vec<PathBuf>,
+    /// Files that are not referenced anymore but could not be deleted yet, either
+    /// because a `FileSlice` still reads them or because the deletion failed.
+    /// They are retried on the next pass.
+    pub failed_to_delete_files: Vec<PathBuf>,
+}
+
+#[derive(Default)]
+struct MetaInformation {
+    managed_paths: HashSet<PathBuf>,
+    leases: HashMap<PathBuf, Weak<()>>,
+}
+
+impl MetaInformation {
+    fn acquire_lease(&mut self, path: &Path) -> Arc<()> {
+        if let Some(lease) = self.leases.get(path).and_then(Weak::upgrade) {
+            return lease;
+        }
+        let lease = Arc::new(());
+        self.leases.insert(path.to_owned(), Arc::downgrade(&lease));
+        lease
+    }
+
+    fn is_leased(&self, path: &Path) -> bool {
+        self.leases
+            .get(path)
+            .map(|lease| lease.strong_count() > 0)
+            .unwrap_or(false)
+    }
+}
+
+/// File handle keeping its file protected from garbage collection while alive.
+struct LeasedFileHandle {
+    file_handle: Box<dyn FileHandle>,
+    _lease: Arc<()>,
+}
+
+impl fmt::Debug for LeasedFileHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "LeasedFileHandle({:?})", self.file_handle)
+    }
+}
+
+impl HasLen for LeasedFileHandle {
+    fn len(&self) -> usize {
+        self.file_handle.len()
+    }
+}
+
+impl FileHandle for LeasedFileHandle {
+    fn read_bytes(&self, range: Range<usize>) -> io::Result<OwnedBytes> {
+        let bytes = self.file_handle.read_bytes(range)?;
+        Ok(OwnedBytes::new(LeasedBytes {
+            bytes,
+            _lease: self._lease.clone(),
+        }))
+    }
+}
+
+/// Bytes read from a leased file, extending the lease to their own lifetime.
+struct LeasedBytes {
+    bytes: OwnedBytes,
+    _lease: Arc<()>,
+}
+
+impl Deref for LeasedBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+}
+
+// `OwnedBytes` points to data it does not own inline.
+unsafe impl StableDeref for LeasedBytes {}
+
+/// Wrapper around a `Directory` keeping track of the files the index created.
+///
+/// Every file created through `open_write` is recorded in `.managed.json`, so that
+/// `garbage_collect` only ever removes files that belong to the index.
+///
+/// These files also get a `Footer` holding the format version and a CRC32 of
+/// their content. `open_read` checks that the footer is present and compatible,
+/// and strips it, while `validate_checksum` verifies the CRC.
+pub struct ManagedDirectory {
+    directory: Box<dyn Directory>,
+    meta_informations: Arc<RwLock<MetaInformation>>,
+}
+
+impl ManagedDirectory {
+    pub fn wrap(directory: Box<dyn Directory>) -> crate::Result<ManagedDirectory> {
+        let managed_path = Path::new(MANAGED_FILEPATH);
+        let managed_paths = if directory.exists(managed_path)? {
+            let data = directory.atomic_read(managed_path)?;
+            serde_json::from_slice(&data)?
+        } else {
+            HashSet::new()
+        };
+        Ok(ManagedDirectory {
+            directory,
+            meta_informations: Arc::new(RwLock::new(MetaInformation {
+                managed_paths,
+                leases: HashMap::new(),
+            })),
+        })
+    }
+
+    /// Deletes the managed files that are neither listed by `get_living_files` nor
+    /// currently opened for reading.
+    pub fn garbage_collect<L: FnOnce() -> HashSet<PathBuf>>(
+        &self,
+        get_living_files: L,
+    ) -> crate::Result<GarbageCollectionResult> {
+        let living_files = get_living_files();
+        let mut meta_informations = self.meta_informations.write().unwrap();
+        let mut files_to_delete: Vec<PathBuf> = meta_informations
+            .managed_paths
+            .iter()
+            .filter(|path| !living_files.contains(*path))
+            .cloned()
+            .collect();
+        files_to_delete.sort();
+
+        let mut result = GarbageCollectionResult::default();
+        for path in files_to_delete {
+            if meta_informations.is_leased(&path) {
+                result.failed_to_delete_files.push(path);
+                continue;
+            }
+            match self.directory.delete(&path) {
+                Ok(()) => result.deleted_files.push(path),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                    result.deleted_files.push(path)
+                }
+                Err(_) => result.failed_to_delete_files.push(path),
+            }
+        }
+
+        if !result.deleted_files.is_empty() {
+            for path in &result.deleted_files {
+                meta_informations.managed_paths.remove(path);
+                meta_informations.leases.remove(path);
+            }
+            self.save_managed_paths(&meta_informations.managed_paths)?;
+        }
+        Ok(result)
+    }
+
+    /// Returns true if the content of the file at `path` matches the CRC of its
+    /// footer, false if the footer is missing or the content was altered.
+    pub fn validate_checksum(&self, path: &Path) -> io::Result<bool> {
+        let file_slice = FileSlice::new(self.get_file_handle(path)?);
+        let (footer, body) = match Footer::extract_footer(file_slice) {
+            Ok(footer_and_body) => footer_and_body,
+            Err(err) if err.kind() == io::ErrorKind::InvalidData => return Ok(false),
+            Err(err) => return Err(err),
+        };
+        Ok(footer.is_valid_checksum(body.read_bytes()?.as_slice()))
+    }
+
+    fn register_file_as_managed(&self, path: &Path) -> io::Result<()> {
+        let mut meta_informations = self.meta_informations.write().unwrap();
+        if meta_informations.managed_paths.insert(path.to_owned()) {
+            self.save_managed_paths(&meta_informations.managed_paths)?;
+        }
+        Ok(())
+    }
+
+    fn save_managed_paths(&self, managed_paths: &HashSet<PathBuf>) -> io::Result<()> {
+        let mut managed_paths: Vec<&PathBuf> = managed_paths.iter().collect();
+        managed_paths.sort();
+        let mut buffer = serde_json::to_vec(&managed_paths)?;
+        buffer.push(b'\n');
+        self.directory
+            .atomic_write(Path::new(MANAGED_FILEPATH), &buffer)
+    }
+}
+
+impl Directory for ManagedDirectory {
+    fn get_file_handle(&self, path: &Path) -> io::Result<Box<dyn FileHandle>> {
+        let file_handle = self.directory.get_file_handle(path)?;
+        let lease = self.meta_informations.write().unwrap().acquire_lease(path);
+        Ok(Box::new(LeasedFileHandle {
+            file_handle,
+            _lease: lease,
+        }))
+    }
+
+    fn open_read(&self, path: &Path) -> io::Result<FileSlice> {
+        let file_slice = FileSlice::new(self.get_file_handle(path)?);
+        let with_path = |err: io::Error| io::Error::new(err.kind(), format!("{:?}: {}", path, err));
+        let (footer, body) = Footer::extract_footer(file_slice).map_err(with_path)?;
+        footer.is_compatible().map_err(with_path)?;
+        Ok(body)
+    }
+
+    fn exists(&self, path: &Path) -> io::Result<bool> {
+        self.directory.exists(path)
+    }
+
+    fn delete(&self, path: &Path) -> io::Result<()> {
+        self.directory.delete(path)
+    }
+
+    fn list_files(&self) -> io::Result<Vec<PathBuf>> {
+        self.directory.list_files()
+    }
+
+    fn open_write(&self, path: &Path) -> io::Result<WritePtr> {
+        self.register_file_as_managed(path)?;
+        let write = self.directory.open_write(path)?;
+        Ok(BufWriter::new(Box::new(FooterProxy::new(write))))
+    }
+
+    fn atomic_read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.directory.atomic_read(path)
+    }
+
+    fn atomic_write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        self.directory.atomic_write(path, data)
+    }
+
+    fn watch(&self, watch_callback: WatchCallback) -> io::Result<WatchHandle> {
+        self.directory.watch(watch_callback)
+    }
+
+    fn acquire_lock(&self, lock: &Lock) -> Result<DirectoryLock, LockError> {
+        self.directory.acquire_lock(lock)
+    }
+}
+
+impl Clone for ManagedDirectory {
+    fn clone(&self) -> Self {
+        ManagedDirectory {
+            directory: self.directory.box_clone(),
+            meta_informations: self.meta_informations.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for ManagedDirectory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "ManagedDirectory({:?})", self.directory)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Directory, ManagedDirectory, RAMDirectory, TerminatingWrite};
+    use std::collections::HashSet;
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+
+    fn write_file(directory: &dyn Directory, path: &Path) {
+        let mut wrt = directory.open_write(path).unwrap();
+        wrt.write_all(b"content").unwrap();
+        wrt.terminate().unwrap();
+    }
+
+    #[test]
+    fn test_garbage_collect_removes_unreferenced_files() -> crate::Result<()> {
+        let ram_directory = RAMDirectory::create();
+        ram_directory.atomic_write(Path::new("unmanaged"), b"user data")?;
+        let managed_directory = ManagedDirectory::wrap(Box::new(ram_directory.clone()))?;
+        write_file(&managed_directory, Path::new("living"));
+        write_file(&managed_directory, Path::new("orphan"));
+
+        let result = managed_directory
+            .garbage_collect(|| vec![PathBuf::from("living")].into_iter().collect())?;
+        assert_eq!(result.deleted_files, vec![PathBuf::from("orphan")]);
+        assert!(result.failed_to_delete_files.is_empty());
+        assert!(managed_directory.exists(Path::new("living"))?);
+        assert!(!managed_directory.exists(Path::new("orphan"))?);
+        assert!(managed_directory.exists(Path::new("unmanaged"))?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_managed_paths_survive_reopen() -> crate::Result<()> {
+        let ram_directory = RAMDirectory::create();
+        {
+            let managed_directory = ManagedDirectory::wrap(Box::new(ram_directory.clone()))?;
+            write_file(&managed_directory, Path::new("orphan"));
+        }
+        let managed_directory = ManagedDirectory::wrap(Box::new(ram_directory))?;
+        let result = managed_directory.garbage_collect(HashSet::new)?;
+        assert_eq!(result.deleted_files, vec![PathBuf::from("orphan")]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_garbage_collect_protects_open_files() -> crate::Result<()> {
+        let managed_directory = ManagedDirectory::wrap(Box::new(RAMDirectory::create()))?;
+        let path = Path::new("in_use");
+        write_file(&managed_directory, path);
+        let file_slice = managed_directory.open_read(path)?;
+        {
+            let result = managed_directory.garbage_collect(HashSet::new)?;
+            assert!(result.deleted_files.is_empty());
+            assert_eq!(result.failed_to_delete_files, vec![path.to_owned()]);
+            assert!(managed_directory.exists(path)?);
+        }
+        let bytes = file_slice.read_bytes()?;
+        drop(file_slice);
+        assert_eq!(
+            managed_directory
+                .garbage_collect(HashSet::new)?
+                .failed_to_delete_files,
+            vec![path.to_owned()]
+        );
+        assert_eq!(bytes.as_slice(), b"content");
+        drop(bytes);
+        let result = managed_directory.garbage_collect(HashSet::new)?;
+        assert_eq!(result.deleted_files, vec![path.to_owned()]);
+        assert!(!managed_directory.exists(path)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,476 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt::Formatter;
+use std::fs::{self, File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::Duration;
+use std::{fmt, io, thread};
+
+use fs2::FileExt;
+use memmap::Mmap;
+use stable_deref_trait::StableDeref;
+
+use crate::{
+    ArcBytes, Directory, DirectoryLock, FileHandle, Lock, LockError, OwnedBytes, TerminatingWrite,
+    WatchCallback, WatchCallbackList, WatchHandle, WeakBytes, WritePtr, META_FILEPATH,
+};
+
+/// Interval at which `meta.json` is polled for changes.
+const META_POLLING_INTERVAL: Duration = Duration::from_millis(500);
+
+fn open_mmap(full_path: &Path) -> io::Result<Option<Mmap>> {
+    let file = File::open(full_path)?;
+    let meta_data = file.metadata()?;
+    if meta_data.len() == 0 {
+        // an empty file cannot be mmapped.
+        return Ok(None);
+    }
+    unsafe { memmap::Mmap::map(&file).map(Some) }
+}
+
+#[derive(Default)]
+struct MmapCache {
+    cache: HashMap<PathBuf, WeakBytes>,
+}
+
+impl MmapCache {
+    fn remove_weak_ref(&mut self) {
+        let keys_to_remove: Vec<PathBuf> = self
+            .cache
+            .iter()
+            .filter(|(_, mmap_weakref)| mmap_weakref.upgrade().is_none())
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in keys_to_remove {
+            self.cache.remove(&key);
+        }
+    }
+
+    /// Forgets the mapping of a file that is being replaced or removed.
+    ///
+    /// Readers holding the previous mapping keep it alive.
+    fn discard(&mut self, full_path: &Path) {
+        self.cache.remove(full_path);
+    }
+
+    fn get_mmap(&mut self, full_path: &Path) -> io::Result<Option<ArcBytes>> {
+        if let Some(mmap_weak) = self.cache.get(full_path) {
+            if let Some(mmap_arc) = mmap_weak.upgrade() {
+                return Ok(Some(mmap_arc));
+            }
+        }
+        self.cache.remove(full_path);
+        Ok(open_mmap(full_path)?.map(|mmap| {
+            let mmap_arc: ArcBytes = Arc::new(mmap);
+            let mmap_weak = Arc::downgrade(&mmap_arc);
+            self.cache.insert(full_path.to_owned(), mmap_weak);
+            mmap_arc
+        }))
+    }
+}
+
+struct MmapArc(ArcBytes);
+
+impl Deref for MmapArc {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.0.deref()
+    }
+}
+
+unsafe impl StableDeref for MmapArc {}
+
+struct SafeFileWriter(File);
+
+impl SafeFileWriter {
+    fn new(file: File) -> SafeFileWriter {
+        SafeFileWriter(file)
+    }
+}
+
+impl Write for SafeFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()?;
+        self.0.sync_all()
+    }
+}
+
+impl TerminatingWrite for SafeFileWriter {
+    fn terminate_ref(&mut self) -> io::Result<()> {
+        self.flush()
+    }
+}
+
+impl Seek for SafeFileWriter {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.0.seek(pos)
+    }
+}
+
+/// Advisory lock on a lock file, released when dropped.
+struct ReleaseLockFile {
+    file: File,
+}
+
+impl Drop for ReleaseLockFile {
+    fn drop(&mut self) {
+        // closing the file releases the lock anyway.
+        let _ = self.file.unlock();
+    }
+}
+
+fn hash_file_content(path: &Path) -> Option<u64> {
+    let data = fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// Polls `meta_path` and calls the callbacks whenever its content changes,
+/// whichever process wrote it.
+///
+/// The thread stops once the callback list is dropped.
+fn spawn_meta_file_watcher(
+    meta_path: PathBuf,
+    watch_callbacks: Weak<WatchCallbackList>,
+) -> io::Result<()> {
+    let mut current_hash = hash_file_content(&meta_path);
+    thread::Builder::new()
+        .name("meta-file-watcher".to_string())
+        .spawn(move || loop {
+            thread::sleep(META_POLLING_INTERVAL);
+            let watch_callbacks = match watch_callbacks.upgrade() {
+                Some(watch_callbacks) => watch_callbacks,
+                None => return,
+            };
+            let new_hash = hash_file_content(&meta_path);
+            if new_hash != current_hash {
+                current_hash = new_hash;
+                watch_callbacks.broadcast();
+            }
+        })?;
+    Ok(())
+}
+
+struct MmapDirectoryInner {
+    root_path: PathBuf,
+    mmap_cache: RwLock<MmapCache>,
+    watch_callbacks: Arc<WatchCallbackList>,
+    watcher_started: Mutex<bool>,
+}
+
+/// Directory storing its files on disk and serving reads through memory maps.
+#[derive(Clone)]
+pub struct MmapDirectory {
+    inner: Arc<MmapDirectoryInner>,
+}
+
+impl MmapDirectory {
+    /// Opens a `MmapDirectory` rooted at an existing directory path.
+    pub fn open<P: AsRef<Path>>(directory_path: P) -> io::Result<MmapDirectory> {
+        let directory_path = directory_path.as_ref();
+        if !directory_path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("directory does not exist: {:?}", directory_path),
+            ));
+        }
+        if !directory_path.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("path is not a directory: {:?}", directory_path),
+            ));
+        }
+        let root_path = directory_path.canonicalize()?;
+        Ok(MmapDirectory {
+            inner: Arc::new(MmapDirectoryInner {
+                root_path,
+                mmap_cache: Default::default(),
+                watch_callbacks: Arc::default(),
+                watcher_started: Mutex::new(false),
+            }),
+        })
+    }
+
+    fn resolve_path(&self, relative_path: &Path) -> PathBuf {
+        self.inner.root_path.join(relative_path)
+    }
+
+    fn sync_directory(&self) -> io::Result<()> {
+        let mut open_opts = OpenOptions::new();
+        open_opts.read(true);
+        let fd = open_opts.open(&self.inner.root_path)?;
+        fd.sync_all()
+    }
+}
+
+impl Directory for MmapDirectory {
+    fn get_file_handle(&self, path: &Path) -> io::Result<Box<dyn FileHandle>> {
+        let full_path = self.resolve_path(path);
+        let mut mmap_cache = self.inner.mmap_cache.write().unwrap();
+        mmap_cache.remove_weak_ref();
+        let owned_bytes = mmap_cache
+            .get_mmap(&full_path)?
+            .map(|mmap_arc| OwnedBytes::new(MmapArc(mmap_arc)))
+            .unwrap_or_else(OwnedBytes::empty);
+        Ok(Box::new(owned_bytes))
+    }
+
+    fn exists(&self, path: &Path) -> io::Result<bool> {
+        let full_path = self.resolve_path(path);
+        Ok(full_path.exists())
+    }
+
+    fn delete(&self, path: &Path) -> io::Result<()> {
+        let full_path = self.resolve_path(path);
+        self.inner.mmap_cache.write().unwrap().discard(&full_path);
+        fs::remove_file(&full_path)?;
+        self.sync_directory()
+    }
+
+    fn list_files(&self) -> io::Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(&self.inner.root_path)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                files.push(PathBuf::from(entry.file_name()));
+            }
+        }
+        Ok(files)
+    }
+
+    fn open_write(&self, path: &Path) -> io::Result<WritePtr> {
+        let full_path = self.resolve_path(path);
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(full_path)?;
+
+        // makes sure the file creation itself is persisted.
+        self.sync_directory()?;
+
+        let writer = SafeFileWriter::new(file);
+        Ok(BufWriter::new(Box::new(writer)))
+    }
+
+    fn atomic_read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let full_path = self.resolve_path(path);
+        fs::read(full_path)
+    }
+
+    fn atomic_write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        let full_path = self.resolve_path(path);
+        let mut tempfile = tempfile::Builder::new().tempfile_in(&self.inner.root_path)?;
+        tempfile.write_all(data)?;
+        tempfile.flush()?;
+        tempfile.as_file().sync_all()?;
+        self.inner.mmap_cache.write().unwrap().discard(&full_path);
+        tempfile.persist(full_path)?;
+        self.sync_directory()
+    }
+
+    fn watch(&self, watch_callback: WatchCallback) -> io::Result<WatchHandle> {
+        let mut watcher_started = self.inner.watcher_started.lock().unwrap();
+        if !*watcher_started {
+            spawn_meta_file_watcher(
+                self.resolve_path(Path::new(META_FILEPATH)),
+                Arc::downgrade(&self.inner.watch_callbacks),
+            )?;
+            *watcher_started = true;
+        }
+        Ok(self.inner.watch_callbacks.subscribe(watch_callback))
+    }
+
+    fn acquire_lock(&self, lock: &Lock) -> Result<DirectoryLock, LockError> {
+        let full_path = self.resolve_path(&lock.filepath);
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(full_path)?;
+        if lock.is_blocking {
+            file.lock_exclusive()?;
+        } else {
+            file.try_lock_exclusive().map_err(|err| {
+                if err.raw_os_error() == fs2::lock_contended_error().raw_os_error() {
+                    LockError::LockBusy
+                } else {
+                    LockError::IoError(err)
+                }
+            })?;
+        }
+        Ok(DirectoryLock::from(Box::new(ReleaseLockFile { file })))
+    }
+}
+
+impl fmt::Debug for MmapDirectory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "MmapDirectory({:?})", self.inner.root_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Directory, HasLen, LockError, MmapDirectory, INDEX_WRITER_LOCK, META_FILEPATH};
+    use std::io::{self, Write};
+    use std::path::Path;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_open_non_existent_path() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let missing = tempdir.path().join("missing");
+        let err = MmapDirectory::open(&missing).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_write_then_read() -> io::Result<()> {
+        let tempdir = tempfile::TempDir::new()?;
+        let directory = MmapDirectory::open(tempdir.path())?;
+        let path = Path::new("hello");
+        assert!(!directory.exists(path)?);
+        {
+            let mut wrt = directory.open_write(path)?;
+            wrt.write_all(b"hello world")?;
+            wrt.flush()?;
+        }
+        assert!(directory.exists(path)?);
+        let file_slice = directory.open_read(path)?;
+        assert_eq!(file_slice.len(), 11);
+        assert_eq!(file_slice.slice(6..11).read_bytes()?.as_slice(), b"world");
+        assert_eq!(directory.atomic_read(path)?, b"hello world");
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_write_twice_fails() -> io::Result<()> {
+        let tempdir = tempfile::TempDir::new()?;
+        let directory = MmapDirectory::open(tempdir.path())?;
+        let path = Path::new("twice");
+        directory.open_write(path)?.flush()?;
+        let err = directory.open_write(path).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_read_empty_file() -> io::Result<()> {
+        let tempdir = tempfile::TempDir::new()?;
+        let directory = MmapDirectory::open(tempdir.path())?;
+        let path = Path::new("empty");
+        directory.open_write(path)?.flush()?;
+        let file_slice = directory.open_read(path)?;
+        assert_eq!(file_slice.len(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_mmap_is_shared_while_alive() -> io::Result<()> {
+        let tempdir = tempfile::TempDir::new()?;
+        let directory = MmapDirectory::open(tempdir.path())?;
+        let path = Path::new("shared");
+        {
+            let mut wrt = directory.open_write(path)?;
+            wrt.write_all(b"abc")?;
+            wrt.flush()?;
+        }
+        let first = directory.open_read(path)?.read_bytes()?;
+        let second = directory.open_read(path)?.read_bytes()?;
+        assert_eq!(first.as_slice().as_ptr(), second.as_slice().as_ptr());
+        Ok(())
+    }
+
+    #[test]
+    fn test_atomic_write_overwrites() -> io::Result<()> {
+        let tempdir = tempfile::TempDir::new()?;
+        let directory = MmapDirectory::open(tempdir.path())?;
+        let path = Path::new("meta.json");
+        directory.atomic_write(path, b"{}")?;
+        assert_eq!(directory.atomic_read(path)?, b"{}");
+        directory.atomic_write(path, b"{\"opstamp\":1}")?;
+        assert_eq!(directory.atomic_read(path)?, b"{\"opstamp\":1}");
+        assert_eq!(std::fs::read_dir(tempdir.path())?.count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_atomic_write_is_visible_to_open_read() -> io::Result<()> {
+        let tempdir = tempfile::TempDir::new()?;
+        let directory = MmapDirectory::open(tempdir.path())?;
+        let path = Path::new("meta.json");
+        directory.atomic_write(path, b"old")?;
+        let old_slice = directory.open_read(path)?;
+        directory.atomic_write(path, b"new!")?;
+        assert_eq!(directory.open_read(path)?.read_bytes()?.as_slice(), b"new!");
+        assert_eq!(old_slice.read_bytes()?.as_slice(), b"old");
+        Ok(())
+    }
+
+    #[test]
+    fn test_delete_and_list_files() -> io::Result<()> {
+        let tempdir = tempfile::TempDir::new()?;
+        let directory = MmapDirectory::open(tempdir.path())?;
+        directory.atomic_write(Path::new("a"), b"a")?;
+        directory.atomic_write(Path::new("b"), b"b")?;
+        let mut files = directory.list_files()?;
+        files.sort();
+        assert_eq!(files, vec![Path::new("a"), Path::new("b")]);
+        directory.delete(Path::new("a"))?;
+        assert_eq!(directory.list_files()?, vec![Path::new("b")]);
+        assert_eq!(
+            directory.delete(Path::new("a")).unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_watch_detects_meta_changes() -> io::Result<()> {
+        let tempdir = tempfile::TempDir::new()?;
+        let directory = MmapDirectory::open(tempdir.path())?;
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_clone = counter.clone();
+        let _handle = directory.watch(Box::new(move || {
+            counter_clone.fetch_add(1, Ordering::SeqCst);
+        }))?;
+        // another directory instance plays the role of another process.
+        let other_directory = MmapDirectory::open(tempdir.path())?;
+        other_directory.atomic_write(Path::new(META_FILEPATH), b"{}")?;
+        for _ in 0..50 {
+            if counter.load(Ordering::SeqCst) > 0 {
+                break;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_writer_lock_across_directories() -> io::Result<()> {
+        let tempdir = tempfile::TempDir::new()?;
+        let directory = MmapDirectory::open(tempdir.path())?;
+        let other_directory = MmapDirectory::open(tempdir.path())?;
+        let lock = directory.acquire_lock(&INDEX_WRITER_LOCK).unwrap();
+        assert!(matches!(
+            other_directory.acquire_lock(&INDEX_WRITER_LOCK),
+            Err(LockError::LockBusy)
+        ));
+        drop(lock);
+        assert!(other_directory.acquire_lock(&INDEX_WRITER_LOCK).is_ok());
+        Ok(())
+    }
+}
@@ -1,17 +1,70 @@
-use std::io::{BufWriter, Write};
+use std::io::{self, BufWriter, Write};
 
+mod composite_file;
 mod directory;
+mod directory_lock;
 mod file_slice;
+mod footer;
+mod managed_directory;
+mod mmap_directory;
 mod owned_bytes;
 mod ram_directory;
+mod watch_event_router;
 
+pub use composite_file::*;
 pub use directory::*;
+pub use directory_lock::*;
 pub use file_slice::*;
+pub use footer::*;
+pub use managed_directory::*;
+pub use mmap_directory::*;
 pub use owned_bytes::*;
 pub use ram_directory::*;
+pub use watch_event_router::*;
 
 pub trait HasLen {
     fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Writer that has to be terminated once all the data is written, finalizing
+/// the file.
+pub trait TerminatingWrite: Write {
+    /// Flushes the data and finalizes the file. Nothing can be written to it
+    /// afterwards.
+    fn terminate(mut self) -> io::Result<()>
+    where
+        Self: Sized,
+    {
+        self.terminate_ref()
+    }
+
+    /// Same as `terminate`, callable on a trait object.
+    fn terminate_ref(&mut self) -> io::Result<()>;
+}
+
+impl<W: TerminatingWrite + ?Sized> TerminatingWrite for Box<W> {
+    fn terminate_ref(&mut self) -> io::Result<()> {
+        self.as_mut().terminate_ref()
+    }
+}
+
+impl<W: TerminatingWrite> TerminatingWrite for BufWriter<W> {
+    fn terminate_ref(&mut self) -> io::Result<()> {
+        self.flush()?;
+        self.get_mut().terminate_ref()
+    }
+}
+
+impl TerminatingWrite for &mut Vec<u8> {
+    fn terminate_ref(&mut self) -> io::Result<()> {
+        Ok(())
+    }
 }
 
-pub type WritePtr = BufWriter<Box<dyn Write>>;
+/// Writer returned by `Directory::open_write`. It must be terminated, not just
+/// flushed, once the file is complete.
+pub type WritePtr = BufWriter<Box<dyn TerminatingWrite>>;
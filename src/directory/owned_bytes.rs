@@ -30,7 +30,7 @@ impl OwnedBytes {
     ) -> Self {
         let box_stable_deref = Arc::new(data_holder);
         let bytes: &[u8] = box_stable_deref.as_ref();
-        let data = unsafe { mem::transmute::<_, &'static [u8]>(bytes.deref()) };
+        let data = unsafe { mem::transmute::<&[u8], &'static [u8]>(bytes) };
         OwnedBytes {
             box_stable_deref,
             data,
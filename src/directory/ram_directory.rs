@@ -1,11 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Formatter;
 use std::io::{BufWriter, Cursor, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
 use std::{fmt, io};
 
-use crate::{Directory, FileHandle, FileSlice, HasLen, WritePtr};
+use crate::{
+    Directory, DirectoryLock, FileHandle, FileSlice, HasLen, Lock, LockError, TerminatingWrite,
+    WatchCallback, WatchCallbackList, WatchHandle, WritePtr, META_FILEPATH,
+};
 
 struct VecWriter {
     path: PathBuf,
@@ -42,6 +45,12 @@ impl Seek for VecWriter {
     }
 }
 
+impl TerminatingWrite for VecWriter {
+    fn terminate_ref(&mut self) -> io::Result<()> {
+        self.flush()
+    }
+}
+
 impl Write for VecWriter {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         self.is_flushed = false;
@@ -71,22 +80,72 @@ impl InnerDirectory {
     fn open_read(&self, path: &Path) -> io::Result<FileSlice> {
         self.fs
             .get(path)
+            .cloned()
             .ok_or_else(|| io::ErrorKind::NotFound.into())
-            .map(Clone::clone)
     }
 
     fn exists(&self, path: &Path) -> bool {
         self.fs.contains_key(path)
     }
 
+    fn delete(&mut self, path: &Path) -> io::Result<()> {
+        self.fs
+            .remove(path)
+            .map(|_| ())
+            .ok_or_else(|| io::ErrorKind::NotFound.into())
+    }
+
+    fn list_files(&self) -> Vec<PathBuf> {
+        self.fs.keys().cloned().collect()
+    }
+
     fn total_mem_usage(&self) -> usize {
         self.fs.values().map(|f| f.len()).sum()
     }
 }
 
+/// Locks held on a `RAMDirectory`, shared by all its clones.
+#[derive(Default)]
+struct LockTable {
+    held_locks: Mutex<HashSet<PathBuf>>,
+    lock_released: Condvar,
+}
+
+impl LockTable {
+    fn acquire(self: &Arc<Self>, lock: &Lock) -> Result<DirectoryLock, LockError> {
+        let mut held_locks = self.held_locks.lock().unwrap();
+        while held_locks.contains(&lock.filepath) {
+            if !lock.is_blocking {
+                return Err(LockError::LockBusy);
+            }
+            held_locks = self.lock_released.wait(held_locks).unwrap();
+        }
+        held_locks.insert(lock.filepath.clone());
+        Ok(DirectoryLock::from(Box::new(ReleaseLock {
+            lock_table: self.clone(),
+            filepath: lock.filepath.clone(),
+        })))
+    }
+}
+
+struct ReleaseLock {
+    lock_table: Arc<LockTable>,
+    filepath: PathBuf,
+}
+
+impl Drop for ReleaseLock {
+    fn drop(&mut self) {
+        let mut held_locks = self.lock_table.held_locks.lock().unwrap();
+        held_locks.remove(&self.filepath);
+        self.lock_table.lock_released.notify_all();
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct RAMDirectory {
     fs: Arc<RwLock<InnerDirectory>>,
+    watch_callbacks: Arc<WatchCallbackList>,
+    locks: Arc<LockTable>,
 }
 
 impl RAMDirectory {
@@ -103,7 +162,7 @@ impl RAMDirectory {
         for (path, file) in wlock.fs.iter() {
             let mut dest_wrt = dest.open_write(path)?;
             dest_wrt.write_all(file.read_bytes()?.as_slice())?;
-            dest_wrt.flush()?;
+            dest_wrt.terminate()?;
         }
         Ok(())
     }
@@ -127,6 +186,14 @@ impl Directory for RAMDirectory {
             .exists(path))
     }
 
+    fn delete(&self, path: &Path) -> io::Result<()> {
+        self.fs.write().unwrap().delete(path)
+    }
+
+    fn list_files(&self) -> io::Result<Vec<PathBuf>> {
+        Ok(self.fs.read().unwrap().list_files())
+    }
+
     fn open_write(&self, path: &Path) -> io::Result<WritePtr> {
         let mut fs = self.fs.write().unwrap();
         let path_buf = PathBuf::from(path);
@@ -143,6 +210,22 @@ impl Directory for RAMDirectory {
         let bytes = self.open_read(path)?.read_bytes()?;
         Ok(bytes.as_slice().to_owned())
     }
+
+    fn atomic_write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        self.fs.write().unwrap().write(PathBuf::from(path), data);
+        if path == Path::new(META_FILEPATH) {
+            self.watch_callbacks.broadcast();
+        }
+        Ok(())
+    }
+
+    fn watch(&self, watch_callback: WatchCallback) -> io::Result<WatchHandle> {
+        Ok(self.watch_callbacks.subscribe(watch_callback))
+    }
+
+    fn acquire_lock(&self, lock: &Lock) -> Result<DirectoryLock, LockError> {
+        self.locks.acquire(lock)
+    }
 }
 
 impl fmt::Debug for RAMDirectory {
@@ -153,9 +236,13 @@ impl fmt::Debug for RAMDirectory {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Directory, RAMDirectory};
+    use crate::{Directory, LockError, RAMDirectory, INDEX_WRITER_LOCK, META_FILEPATH, META_LOCK};
     use std::io::Write;
     use std::path::Path;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
 
     #[test]
     fn test_persist() {
@@ -170,4 +257,84 @@ mod tests {
         assert!(directory.persist(&directory_copy).is_ok());
         assert_eq!(directory_copy.atomic_read(path).unwrap(), msg);
     }
+
+    #[test]
+    fn test_atomic_write_replaces_content() {
+        let path: &'static Path = Path::new("meta");
+        let directory = RAMDirectory::create();
+        directory.atomic_write(path, b"first version").unwrap();
+        assert_eq!(directory.atomic_read(path).unwrap(), b"first version");
+        let previous = directory.open_read(path).unwrap();
+        directory.atomic_write(path, b"second").unwrap();
+        assert_eq!(directory.atomic_read(path).unwrap(), b"second");
+        assert_eq!(previous.read_bytes().unwrap().as_slice(), b"first version");
+    }
+
+    #[test]
+    fn test_watch_is_called_on_meta_write() {
+        let directory = RAMDirectory::create();
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_clone = counter.clone();
+        let _handle = directory
+            .watch(Box::new(move || {
+                counter_clone.fetch_add(1, Ordering::SeqCst);
+            }))
+            .unwrap();
+        directory.atomic_write(Path::new("other"), b"data").unwrap();
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+        directory
+            .atomic_write(Path::new(META_FILEPATH), b"{}")
+            .unwrap();
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_delete_keeps_open_slices_readable() {
+        let path: &'static Path = Path::new("deleted");
+        let directory = RAMDirectory::create();
+        directory.atomic_write(path, b"still here").unwrap();
+        let file_slice = directory.open_read(path).unwrap();
+        directory.delete(path).unwrap();
+        assert!(!directory.exists(path).unwrap());
+        assert!(directory.list_files().unwrap().is_empty());
+        assert_eq!(file_slice.read_bytes().unwrap().as_slice(), b"still here");
+        assert_eq!(
+            directory.delete(path).unwrap_err().kind(),
+            std::io::ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn test_non_blocking_lock_is_exclusive() {
+        let directory = RAMDirectory::create();
+        let lock = directory.acquire_lock(&INDEX_WRITER_LOCK).unwrap();
+        assert!(matches!(
+            directory.clone().acquire_lock(&INDEX_WRITER_LOCK),
+            Err(LockError::LockBusy)
+        ));
+        // other locks are independent.
+        let _meta_lock = directory.acquire_lock(&META_LOCK).unwrap();
+        drop(lock);
+        assert!(directory.acquire_lock(&INDEX_WRITER_LOCK).is_ok());
+    }
+
+    #[test]
+    fn test_blocking_lock_waits_for_release() {
+        let directory = RAMDirectory::create();
+        let lock = directory.acquire_lock(&META_LOCK).unwrap();
+        let acquired = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let directory = directory.clone();
+            let acquired = acquired.clone();
+            thread::spawn(move || {
+                let _lock = directory.acquire_lock(&META_LOCK).unwrap();
+                acquired.store(true, Ordering::SeqCst);
+            })
+        };
+        thread::sleep(Duration::from_millis(50));
+        assert!(!acquired.load(Ordering::SeqCst));
+        drop(lock);
+        handle.join().unwrap();
+        assert!(acquired.load(Ordering::SeqCst));
+    }
 }
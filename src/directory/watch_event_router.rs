@@ -0,0 +1,77 @@
+use std::sync::{Arc, RwLock, Weak};
+
+/// Callback called when the watched file changes.
+pub type WatchCallback = Box<dyn Fn() + Sync + Send>;
+
+/// Keeps a `WatchCallback` subscribed for as long as it is alive.
+#[derive(Clone)]
+pub struct WatchHandle(#[allow(dead_code)] Arc<WatchCallback>);
+
+impl WatchHandle {
+    /// Returns a handle that is not attached to any callback list.
+    pub fn empty() -> WatchHandle {
+        WatchHandle(Arc::new(Box::new(|| {})))
+    }
+}
+
+/// List of the callbacks subscribed to the changes of a file.
+///
+/// The list only holds weak references: dropping the `WatchHandle` returned by
+/// `subscribe` is enough to unsubscribe.
+#[derive(Default)]
+pub struct WatchCallbackList {
+    router: RwLock<Vec<Weak<WatchCallback>>>,
+}
+
+impl WatchCallbackList {
+    pub fn subscribe(&self, watch_callback: WatchCallback) -> WatchHandle {
+        let watch_callback = Arc::new(watch_callback);
+        self.router
+            .write()
+            .unwrap()
+            .push(Arc::downgrade(&watch_callback));
+        WatchHandle(watch_callback)
+    }
+
+    fn list_callbacks(&self) -> Vec<Arc<WatchCallback>> {
+        let mut router = self.router.write().unwrap();
+        router.retain(|weak_callback| weak_callback.strong_count() > 0);
+        router.iter().flat_map(Weak::upgrade).collect()
+    }
+
+    /// Calls all the callbacks that are still subscribed.
+    ///
+    /// Callbacks run on the calling thread, outside of any lock of the list.
+    pub fn broadcast(&self) {
+        for callback in self.list_callbacks() {
+            callback();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WatchCallbackList;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_watch_callback_list() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let watch_callback_list = WatchCallbackList::default();
+        let counter_clone = counter.clone();
+        let handle = watch_callback_list.subscribe(Box::new(move || {
+            counter_clone.fetch_add(1, Ordering::SeqCst);
+        }));
+        watch_callback_list.broadcast();
+        watch_callback_list.broadcast();
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+        let handle_clone = handle.clone();
+        drop(handle);
+        watch_callback_list.broadcast();
+        assert_eq!(counter.load(Ordering::SeqCst), 3);
+        drop(handle_clone);
+        watch_callback_list.broadcast();
+        assert_eq!(counter.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,71 @@
+use crate::DocId;
+
+/// Sentinel value returned by a `DocSet` once it is exhausted.
+///
+/// It is greater than any valid `DocId`.
+pub const TERMINATED: DocId = i32::MAX as u32;
+
+/// Sorted set of documents, iterated over in increasing order.
+///
+/// A freshly created `DocSet` is positioned on its first document, or on
+/// `TERMINATED` if it is empty.
+pub trait DocSet: Send {
+    /// Moves to the next document and returns it, or `TERMINATED` once the set
+    /// is exhausted.
+    fn advance(&mut self) -> DocId;
+
+    /// Moves to the first document greater than or equal to `target` and returns
+    /// it.
+    ///
+    /// `target` must be greater than or equal to the current document.
+    fn seek(&mut self, target: DocId) -> DocId {
+        let mut doc = self.doc();
+        debug_assert!(doc <= target);
+        while doc < target {
+            doc = self.advance();
+        }
+        doc
+    }
+
+    /// Returns the current document.
+    fn doc(&self) -> DocId;
+
+    /// Returns an upper bound of the number of documents in the set.
+    fn size_hint(&self) -> u32;
+}
+
+impl<TDocSet: DocSet + ?Sized> DocSet for &mut TDocSet {
+    fn advance(&mut self) -> DocId {
+        (**self).advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        (**self).seek(target)
+    }
+
+    fn doc(&self) -> DocId {
+        (**self).doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        (**self).size_hint()
+    }
+}
+
+impl<TDocSet: DocSet + ?Sized> DocSet for Box<TDocSet> {
+    fn advance(&mut self) -> DocId {
+        (**self).advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        (**self).seek(target)
+    }
+
+    fn doc(&self) -> DocId {
+        (**self).doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        (**self).size_hint()
+    }
+}
@@ -0,0 +1,32 @@
+use std::io;
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::LockError;
+
+#[derive(Debug, Error)]
+pub enum MySearchError {
+    #[error("An IO error occurred: '{0}'")]
+    IoError(#[from] io::Error),
+    #[error("Index already exists at '{0:?}'")]
+    IndexAlreadyExists(PathBuf),
+    #[error("No index found at '{0:?}'")]
+    IndexNotFound(PathBuf),
+    #[error("Data corrupted: '{0}'")]
+    DataCorruption(String),
+    #[error("Schema error: '{0}'")]
+    SchemaError(String),
+    #[error("An invalid argument was passed: '{0}'")]
+    InvalidArgument(String),
+    #[error("Failed to acquire a lock: '{0}'")]
+    LockFailure(#[from] LockError),
+    #[error("An error occurred in a thread: '{0}'")]
+    ErrorInThread(String),
+}
+
+impl From<serde_json::Error> for MySearchError {
+    fn from(error: serde_json::Error) -> MySearchError {
+        MySearchError::DataCorruption(error.to_string())
+    }
+}
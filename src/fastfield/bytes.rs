@@ -0,0 +1,40 @@
+use std::io;
+
+use crate::fastfield::open_offset_column;
+use crate::{DocId, FastFieldReader, OwnedBytes};
+
+/// Reads the values of a bytes fast field.
+#[derive(Clone)]
+pub struct BytesFastFieldReader {
+    doc_offsets: FastFieldReader<u64>,
+    values: OwnedBytes,
+}
+
+impl BytesFastFieldReader {
+    pub fn open(bytes: OwnedBytes) -> io::Result<BytesFastFieldReader> {
+        let (doc_offsets, values) = open_offset_column(bytes)?;
+        let num_docs = doc_offsets.num_vals();
+        if num_docs > 0 && doc_offsets.get((num_docs - 1) as DocId) as usize > values.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "bytes fast field values are truncated",
+            ));
+        }
+        Ok(BytesFastFieldReader {
+            doc_offsets,
+            values,
+        })
+    }
+
+    /// Returns the bytes of `doc`, empty if the document has none.
+    pub fn get_bytes(&self, doc: DocId) -> &[u8] {
+        let start = self.doc_offsets.get(doc) as usize;
+        let stop = self.doc_offsets.get(doc + 1) as usize;
+        &self.values.as_slice()[start..stop]
+    }
+
+    /// Total number of bytes of the segment.
+    pub fn total_num_bytes(&self) -> usize {
+        self.values.len()
+    }
+}
@@ -0,0 +1,294 @@
+use std::convert::TryFrom;
+use std::io::{self, Write};
+
+use crate::common::{compute_num_bits, BitPacker, BitUnpacker};
+use crate::{BinarySerializable, OwnedBytes};
+
+/// Encoding of a fast field column.
+///
+/// Every column starts with the same header, followed by the codec data:
+///
+/// ```text
+/// [codec: u8][num_vals: u64][min_value: u64][max_value: u64]
+/// Bitpacked:      [num_bits: u8][bitpacked val - min_value...]
+/// LinearInterpol: [first_val: u64][last_val: u64][offset: u64][num_bits: u8]
+///                 [bitpacked val - interpolation + offset...]
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FastFieldCodecType {
+    /// Values minus the minimum value of the column.
+    Bitpacked,
+    /// Residuals from the line going through the first and the last values.
+    /// Wins over `Bitpacked` on (nearly) monotonic columns, e.g. timestamps.
+    LinearInterpol,
+}
+
+impl FastFieldCodecType {
+    fn to_code(self) -> u8 {
+        match self {
+            FastFieldCodecType::Bitpacked => 1,
+            FastFieldCodecType::LinearInterpol => 2,
+        }
+    }
+
+    fn from_code(code: u8) -> io::Result<FastFieldCodecType> {
+        match code {
+            1 => Ok(FastFieldCodecType::Bitpacked),
+            2 => Ok(FastFieldCodecType::LinearInterpol),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown fast field codec {}", code),
+            )),
+        }
+    }
+}
+
+/// Line going through the first and the last values of a column.
+#[derive(Clone, Copy, Debug)]
+struct LinearInterpolation {
+    first_val: u64,
+    slope: f64,
+}
+
+impl LinearInterpolation {
+    fn new(first_val: u64, last_val: u64, num_vals: u64) -> LinearInterpolation {
+        let slope = if num_vals > 1 {
+            (last_val as f64 - first_val as f64) / (num_vals - 1) as f64
+        } else {
+            0.0
+        };
+        LinearInterpolation { first_val, slope }
+    }
+
+    #[inline]
+    fn interpolate(&self, idx: u64) -> i128 {
+        i128::from(self.first_val) + (self.slope * idx as f64) as i128
+    }
+}
+
+/// Parameters of the linear interpolation of `vals`, if its residuals fit in
+/// a `u64`: the interpolation, the offset making the residuals positive and
+/// their number of bits.
+fn linear_interpol_params(vals: &[u64]) -> Option<(LinearInterpolation, u64, u8)> {
+    let (&first_val, &last_val) = (vals.first()?, vals.last()?);
+    let interpolation = LinearInterpolation::new(first_val, last_val, vals.len() as u64);
+    let mut min_diff = 0i128;
+    let mut max_diff = 0i128;
+    for (idx, &val) in vals.iter().enumerate() {
+        let diff = i128::from(val) - interpolation.interpolate(idx as u64);
+        min_diff = min_diff.min(diff);
+        max_diff = max_diff.max(diff);
+    }
+    let offset = u64::try_from(-min_diff).ok()?;
+    let amplitude = u64::try_from(max_diff - min_diff).ok()?;
+    Some((interpolation, offset, compute_num_bits(amplitude)))
+}
+
+/// Serializes `vals` as a column, using the codec producing the smallest
+/// output, and returns that codec.
+pub fn serialize_column<W: Write>(write: &mut W, vals: &[u64]) -> io::Result<FastFieldCodecType> {
+    let min_value = vals.iter().cloned().min().unwrap_or(0);
+    let max_value = vals.iter().cloned().max().unwrap_or(0);
+    let bitpacked_num_bits = compute_num_bits(max_value - min_value);
+    let linear_interpol = linear_interpol_params(vals).filter(|&(_, _, num_bits)| {
+        // the interpolation parameters take 24 more bytes.
+        (vals.len() as u64) * u64::from(bitpacked_num_bits - num_bits.min(bitpacked_num_bits))
+            > 24 * 8
+    });
+    let codec = if linear_interpol.is_some() {
+        FastFieldCodecType::LinearInterpol
+    } else {
+        FastFieldCodecType::Bitpacked
+    };
+
+    codec.to_code().serialize(write)?;
+    (vals.len() as u64).serialize(write)?;
+    min_value.serialize(write)?;
+    max_value.serialize(write)?;
+    let mut bit_packer = BitPacker::new();
+    match linear_interpol {
+        None => {
+            bitpacked_num_bits.serialize(write)?;
+            for &val in vals {
+                bit_packer.write(val - min_value, bitpacked_num_bits, write)?;
+            }
+        }
+        Some((interpolation, offset, num_bits)) => {
+            vals[0].serialize(write)?;
+            vals[vals.len() - 1].serialize(write)?;
+            offset.serialize(write)?;
+            num_bits.serialize(write)?;
+            for (idx, &val) in vals.iter().enumerate() {
+                let residual =
+                    i128::from(val) - interpolation.interpolate(idx as u64) + i128::from(offset);
+                bit_packer.write(residual as u64, num_bits, write)?;
+            }
+        }
+    }
+    bit_packer.close(write)?;
+    Ok(codec)
+}
+
+#[derive(Clone, Copy, Debug)]
+enum ColumnCodec {
+    Bitpacked,
+    LinearInterpol {
+        interpolation: LinearInterpolation,
+        offset: u64,
+    },
+}
+
+/// Reads a column written by `serialize_column`, directly from its bytes.
+#[derive(Clone)]
+pub struct ColumnReader {
+    codec: ColumnCodec,
+    num_vals: u64,
+    min_value: u64,
+    max_value: u64,
+    bit_unpacker: BitUnpacker,
+    data: OwnedBytes,
+}
+
+impl ColumnReader {
+    pub fn open(bytes: OwnedBytes) -> io::Result<ColumnReader> {
+        let mut cursor = bytes.as_slice();
+        let codec_type = FastFieldCodecType::from_code(u8::deserialize(&mut cursor)?)?;
+        let num_vals = u64::deserialize(&mut cursor)?;
+        let min_value = u64::deserialize(&mut cursor)?;
+        let max_value = u64::deserialize(&mut cursor)?;
+        let codec = match codec_type {
+            FastFieldCodecType::Bitpacked => ColumnCodec::Bitpacked,
+            FastFieldCodecType::LinearInterpol => {
+                let first_val = u64::deserialize(&mut cursor)?;
+                let last_val = u64::deserialize(&mut cursor)?;
+                let offset = u64::deserialize(&mut cursor)?;
+                ColumnCodec::LinearInterpol {
+                    interpolation: LinearInterpolation::new(first_val, last_val, num_vals),
+                    offset,
+                }
+            }
+        };
+        let num_bits = u8::deserialize(&mut cursor)?;
+        if num_bits > 64 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid fast field bit width {}", num_bits),
+            ));
+        }
+        let data = bytes.slice(bytes.len() - cursor.len()..bytes.len());
+        let num_data_bytes = (num_vals * u64::from(num_bits)).div_ceil(8) as usize;
+        if data.len() < num_data_bytes + BitUnpacker::PADDING_NUM_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "fast field column is truncated",
+            ));
+        }
+        Ok(ColumnReader {
+            codec,
+            num_vals,
+            min_value,
+            max_value,
+            bit_unpacker: BitUnpacker::new(num_bits),
+            data,
+        })
+    }
+
+    pub fn codec_type(&self) -> FastFieldCodecType {
+        match self.codec {
+            ColumnCodec::Bitpacked => FastFieldCodecType::Bitpacked,
+            ColumnCodec::LinearInterpol { .. } => FastFieldCodecType::LinearInterpol,
+        }
+    }
+
+    pub fn num_vals(&self) -> u64 {
+        self.num_vals
+    }
+
+    pub fn min_value(&self) -> u64 {
+        self.min_value
+    }
+
+    pub fn max_value(&self) -> u64 {
+        self.max_value
+    }
+
+    /// Returns the value at `idx`.
+    ///
+    /// Panics if `idx` is out of bounds.
+    #[inline]
+    pub fn get(&self, idx: u64) -> u64 {
+        assert!(
+            idx < self.num_vals,
+            "index {} is out of the fast field column range [0, {})",
+            idx,
+            self.num_vals
+        );
+        let packed = self.bit_unpacker.get(idx, self.data.as_slice());
+        match self.codec {
+            ColumnCodec::Bitpacked => self.min_value + packed,
+            ColumnCodec::LinearInterpol {
+                interpolation,
+                offset,
+            } => (interpolation.interpolate(idx) + i128::from(packed) - i128::from(offset)) as u64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{serialize_column, ColumnReader, FastFieldCodecType};
+    use crate::OwnedBytes;
+
+    fn roundtrip(vals: &[u64]) -> (FastFieldCodecType, usize) {
+        let mut buffer = Vec::new();
+        let codec = serialize_column(&mut buffer, vals).unwrap();
+        let num_bytes = buffer.len();
+        let column = ColumnReader::open(OwnedBytes::new(buffer)).unwrap();
+        assert_eq!(column.codec_type(), codec);
+        assert_eq!(column.num_vals(), vals.len() as u64);
+        for (idx, &val) in vals.iter().enumerate() {
+            assert_eq!(column.get(idx as u64), val);
+        }
+        if !vals.is_empty() {
+            assert_eq!(column.min_value(), *vals.iter().min().unwrap());
+            assert_eq!(column.max_value(), *vals.iter().max().unwrap());
+        }
+        (codec, num_bytes)
+    }
+
+    #[test]
+    fn test_bitpacked_column() {
+        assert_eq!(roundtrip(&[]).0, FastFieldCodecType::Bitpacked);
+        assert_eq!(roundtrip(&[5]).0, FastFieldCodecType::Bitpacked);
+        let (codec, num_bytes) = roundtrip(&[1000, 1003, 1001, 1007, 1000]);
+        assert_eq!(codec, FastFieldCodecType::Bitpacked);
+        // header, 3 bits per value and padding.
+        assert_eq!(num_bytes, 25 + 1 + 2 + 9);
+        assert_eq!(
+            roundtrip(&[0, u64::MAX, 3, u64::MAX - 1]).0,
+            FastFieldCodecType::Bitpacked
+        );
+    }
+
+    #[test]
+    fn test_linear_interpol_column() {
+        let timestamps: Vec<u64> = (0..10_000u64)
+            .map(|i| 1_600_000_000 + i * 60 + (i * 7919) % 5)
+            .collect();
+        let (codec, num_bytes) = roundtrip(&timestamps);
+        assert_eq!(codec, FastFieldCodecType::LinearInterpol);
+        assert!(num_bytes < 10_000);
+
+        let decreasing: Vec<u64> = (0..1_000u64).map(|i| u64::MAX - i * 1_000).collect();
+        assert_eq!(roundtrip(&decreasing).0, FastFieldCodecType::LinearInterpol);
+    }
+
+    #[test]
+    fn test_column_rejects_truncated_data() {
+        let mut buffer = Vec::new();
+        serialize_column(&mut buffer, &[1, 2, 3, 100]).unwrap();
+        buffer.truncate(buffer.len() - 5);
+        assert!(ColumnReader::open(OwnedBytes::new(buffer)).is_err());
+        assert!(ColumnReader::open(OwnedBytes::new(vec![7u8; 40])).is_err());
+    }
+}
@@ -0,0 +1,153 @@
+//! Fast fields: the values of a field stored as a column, for random access
+//! by doc id.
+//!
+//! Values are mapped to `u64` in an order-preserving way (see `FastValue`)
+//! and bitpacked, so that reading the value of a document is O(1). Columns
+//! whose values grow linearly with the doc id are stored as residuals from a
+//! linear interpolation instead.
+//!
+//! Multi-valued fields and bytes fields are stored as two columns: an offset
+//! column with `max_doc + 1` entries, and the values themselves.
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::common::{f64_to_u64, i64_to_u64, u64_to_f64, u64_to_i64};
+use crate::{Cardinality, FieldType, Value};
+
+mod bytes;
+mod codecs;
+mod multivalued;
+mod reader;
+mod serializer;
+mod writer;
+
+pub use bytes::*;
+pub use codecs::*;
+pub use multivalued::*;
+pub use reader::*;
+pub use serializer::*;
+pub use writer::*;
+
+/// Types that can be stored in a numeric fast field.
+pub trait FastValue: Clone + Copy + Send + Sync + PartialOrd + 'static {
+    /// Reverse of `to_u64`.
+    fn from_u64(val: u64) -> Self;
+
+    /// Maps the value to a `u64`, preserving the order.
+    fn to_u64(&self) -> u64;
+
+    /// Returns the fast field cardinality of `field_type`, or `None` if it is
+    /// not a fast field of this type.
+    fn fast_field_cardinality(field_type: &FieldType) -> Option<Cardinality>;
+
+    /// Value of the documents that do not have any value for the field.
+    fn make_zero() -> Self {
+        Self::from_u64(0i64.to_u64())
+    }
+}
+
+impl FastValue for u64 {
+    fn from_u64(val: u64) -> Self {
+        val
+    }
+
+    fn to_u64(&self) -> u64 {
+        *self
+    }
+
+    fn fast_field_cardinality(field_type: &FieldType) -> Option<Cardinality> {
+        match field_type {
+            FieldType::U64(int_options) => int_options.get_fastfield_cardinality(),
+            _ => None,
+        }
+    }
+
+    fn make_zero() -> Self {
+        0
+    }
+}
+
+impl FastValue for i64 {
+    fn from_u64(val: u64) -> Self {
+        u64_to_i64(val)
+    }
+
+    fn to_u64(&self) -> u64 {
+        i64_to_u64(*self)
+    }
+
+    fn fast_field_cardinality(field_type: &FieldType) -> Option<Cardinality> {
+        match field_type {
+            FieldType::I64(int_options) => int_options.get_fastfield_cardinality(),
+            _ => None,
+        }
+    }
+}
+
+impl FastValue for f64 {
+    fn from_u64(val: u64) -> Self {
+        u64_to_f64(val)
+    }
+
+    fn to_u64(&self) -> u64 {
+        f64_to_u64(*self)
+    }
+
+    fn fast_field_cardinality(field_type: &FieldType) -> Option<Cardinality> {
+        match field_type {
+            FieldType::F64(int_options) => int_options.get_fastfield_cardinality(),
+            _ => None,
+        }
+    }
+
+    fn make_zero() -> Self {
+        0.0
+    }
+}
+
+/// Dates are stored with a precision of one second.
+impl FastValue for DateTime<Utc> {
+    fn from_u64(val: u64) -> Self {
+        Utc.timestamp(u64_to_i64(val), 0)
+    }
+
+    fn to_u64(&self) -> u64 {
+        i64_to_u64(self.timestamp())
+    }
+
+    fn fast_field_cardinality(field_type: &FieldType) -> Option<Cardinality> {
+        match field_type {
+            FieldType::Date(int_options) => int_options.get_fastfield_cardinality(),
+            _ => None,
+        }
+    }
+}
+
+/// Maps a numeric or date `Value` to its fast field representation.
+pub(crate) fn value_to_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::U64(val) => Some(val.to_u64()),
+        Value::I64(val) => Some(val.to_u64()),
+        Value::F64(val) => Some(val.to_u64()),
+        Value::Date(date) => Some(date.to_u64()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use crate::FastValue;
+
+    #[test]
+    fn test_fast_value_roundtrip() {
+        assert_eq!(u64::from_u64(7u64.to_u64()), 7);
+        assert_eq!(i64::from_u64((-7i64).to_u64()), -7);
+        assert_eq!(f64::from_u64((-0.5f64).to_u64()), -0.5);
+        let date = Utc.timestamp(1_600_000_000, 0);
+        assert_eq!(chrono::DateTime::<Utc>::from_u64(date.to_u64()), date);
+        assert!((-1i64).to_u64() < 1i64.to_u64());
+        assert_eq!(i64::make_zero(), 0);
+        assert_eq!(f64::make_zero(), 0.0);
+    }
+}
@@ -0,0 +1,64 @@
+use std::io;
+use std::ops::Range;
+
+use crate::{BinarySerializable, DocId, FastFieldReader, FastValue, OwnedBytes};
+
+/// Splits the data of a multi-valued or bytes fast field into its offset
+/// column and its values.
+pub(crate) fn open_offset_column(
+    mut bytes: OwnedBytes,
+) -> io::Result<(FastFieldReader<u64>, OwnedBytes)> {
+    let offset_column_len = u64::deserialize(&mut bytes)? as usize;
+    if offset_column_len > bytes.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "fast field offset column is truncated",
+        ));
+    }
+    let (offset_column, vals) = bytes.split(offset_column_len);
+    Ok((FastFieldReader::open(offset_column)?, vals))
+}
+
+/// Reads the values of a multi-valued fast field.
+#[derive(Clone)]
+pub struct MultiValuedFastFieldReader<Item: FastValue> {
+    doc_offsets: FastFieldReader<u64>,
+    vals: FastFieldReader<Item>,
+}
+
+impl<Item: FastValue> MultiValuedFastFieldReader<Item> {
+    pub fn open(bytes: OwnedBytes) -> io::Result<MultiValuedFastFieldReader<Item>> {
+        let (doc_offsets, vals) = open_offset_column(bytes)?;
+        Ok(MultiValuedFastFieldReader {
+            doc_offsets,
+            vals: FastFieldReader::open(vals)?,
+        })
+    }
+
+    fn range(&self, doc: DocId) -> Range<u64> {
+        let start = self.doc_offsets.get(doc);
+        let stop = self.doc_offsets.get(doc + 1);
+        start..stop
+    }
+
+    /// Replaces the content of `vals` with the values of `doc`, in insertion
+    /// order.
+    pub fn get_vals(&self, doc: DocId, vals: &mut Vec<Item>) {
+        vals.clear();
+        vals.extend(
+            self.range(doc)
+                .map(|idx| Item::from_u64(self.vals.get_u64(idx))),
+        );
+    }
+
+    /// Number of values of `doc`.
+    pub fn num_vals(&self, doc: DocId) -> usize {
+        let range = self.range(doc);
+        (range.end - range.start) as usize
+    }
+
+    /// Total number of values of the segment.
+    pub fn total_num_vals(&self) -> u64 {
+        self.vals.num_vals()
+    }
+}
@@ -0,0 +1,337 @@
+use std::io;
+use std::marker::PhantomData;
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    BytesFastFieldReader, Cardinality, ColumnReader, CompositeFile, DocId, FastFieldCodecType,
+    FastValue, Field, FieldType, FileSlice, MultiValuedFastFieldReader, MySearchError, OwnedBytes,
+    Schema,
+};
+
+/// Reads the values of a single-valued fast field.
+#[derive(Clone)]
+pub struct FastFieldReader<Item: FastValue> {
+    column: ColumnReader,
+    _phantom: PhantomData<Item>,
+}
+
+impl<Item: FastValue> FastFieldReader<Item> {
+    pub fn open(bytes: OwnedBytes) -> io::Result<FastFieldReader<Item>> {
+        Ok(FastFieldReader {
+            column: ColumnReader::open(bytes)?,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the value of `doc`.
+    ///
+    /// Panics if `doc` is not a doc id of the segment.
+    #[inline]
+    pub fn get(&self, doc: DocId) -> Item {
+        Item::from_u64(self.column.get(u64::from(doc)))
+    }
+
+    /// Returns the value at `idx` as its `u64` representation.
+    #[inline]
+    pub(crate) fn get_u64(&self, idx: u64) -> u64 {
+        self.column.get(idx)
+    }
+
+    /// Number of values of the column, i.e. `max_doc` of the segment.
+    pub fn num_vals(&self) -> u64 {
+        self.column.num_vals()
+    }
+
+    /// Smallest value of the column. Only meaningful if the column is not empty.
+    pub fn min_value(&self) -> Item {
+        Item::from_u64(self.column.min_value())
+    }
+
+    /// Largest value of the column. Only meaningful if the column is not empty.
+    pub fn max_value(&self) -> Item {
+        Item::from_u64(self.column.max_value())
+    }
+
+    pub fn codec_type(&self) -> FastFieldCodecType {
+        self.column.codec_type()
+    }
+}
+
+/// Gives access to the fast fields of a segment.
+#[derive(Clone)]
+pub struct FastFieldReaders {
+    schema: Schema,
+    fast_fields_composite: CompositeFile,
+}
+
+impl FastFieldReaders {
+    pub fn open(file: FileSlice, schema: Schema) -> io::Result<FastFieldReaders> {
+        Ok(FastFieldReaders {
+            schema,
+            fast_fields_composite: CompositeFile::open(&file)?,
+        })
+    }
+
+    fn field_data(&self, field: Field) -> crate::Result<OwnedBytes> {
+        let file = self.fast_fields_composite.open_read(field).ok_or_else(|| {
+            MySearchError::DataCorruption(format!(
+                "fast field '{}' is missing from the segment",
+                self.schema.get_field_name(field)
+            ))
+        })?;
+        Ok(file.read_bytes()?)
+    }
+
+    fn check_type<TFastValue: FastValue>(
+        &self,
+        field: Field,
+        expected_cardinality: Cardinality,
+    ) -> crate::Result<()> {
+        let field_entry = self.schema.get_field_entry(field);
+        match TFastValue::fast_field_cardinality(field_entry.field_type()) {
+            Some(cardinality) if cardinality == expected_cardinality => Ok(()),
+            Some(cardinality) => Err(MySearchError::SchemaError(format!(
+                "field '{}' is a {:?} fast field, not a {:?} one",
+                field_entry.name(),
+                cardinality,
+                expected_cardinality
+            ))),
+            None => Err(MySearchError::SchemaError(format!(
+                "field '{}' is not a fast field of type {}",
+                field_entry.name(),
+                std::any::type_name::<TFastValue>()
+            ))),
+        }
+    }
+
+    /// Returns the reader of a single-valued fast field of type `TFastValue`.
+    pub fn typed_fast_field_reader<TFastValue: FastValue>(
+        &self,
+        field: Field,
+    ) -> crate::Result<FastFieldReader<TFastValue>> {
+        self.check_type::<TFastValue>(field, Cardinality::SingleValue)?;
+        Ok(FastFieldReader::open(self.field_data(field)?)?)
+    }
+
+    /// Returns the reader of a multi-valued fast field of type `TFastValue`.
+    pub fn typed_fast_field_multi_reader<TFastValue: FastValue>(
+        &self,
+        field: Field,
+    ) -> crate::Result<MultiValuedFastFieldReader<TFastValue>> {
+        self.check_type::<TFastValue>(field, Cardinality::MultiValues)?;
+        Ok(MultiValuedFastFieldReader::open(self.field_data(field)?)?)
+    }
+
+    /// Returns the column of a single-valued numeric fast field as its `u64`
+    /// representation, whatever the type of the field.
+    pub(crate) fn u64_lenient(&self, field: Field) -> crate::Result<FastFieldReader<u64>> {
+        Ok(FastFieldReader::open(self.field_data(field)?)?)
+    }
+
+    /// Multi-valued counterpart of `u64_lenient`.
+    pub(crate) fn u64s_lenient(
+        &self,
+        field: Field,
+    ) -> crate::Result<MultiValuedFastFieldReader<u64>> {
+        Ok(MultiValuedFastFieldReader::open(self.field_data(field)?)?)
+    }
+
+    pub fn u64(&self, field: Field) -> crate::Result<FastFieldReader<u64>> {
+        self.typed_fast_field_reader(field)
+    }
+
+    pub fn i64(&self, field: Field) -> crate::Result<FastFieldReader<i64>> {
+        self.typed_fast_field_reader(field)
+    }
+
+    pub fn f64(&self, field: Field) -> crate::Result<FastFieldReader<f64>> {
+        self.typed_fast_field_reader(field)
+    }
+
+    pub fn date(&self, field: Field) -> crate::Result<FastFieldReader<DateTime<Utc>>> {
+        self.typed_fast_field_reader(field)
+    }
+
+    pub fn u64s(&self, field: Field) -> crate::Result<MultiValuedFastFieldReader<u64>> {
+        self.typed_fast_field_multi_reader(field)
+    }
+
+    pub fn i64s(&self, field: Field) -> crate::Result<MultiValuedFastFieldReader<i64>> {
+        self.typed_fast_field_multi_reader(field)
+    }
+
+    pub fn f64s(&self, field: Field) -> crate::Result<MultiValuedFastFieldReader<f64>> {
+        self.typed_fast_field_multi_reader(field)
+    }
+
+    pub fn dates(&self, field: Field) -> crate::Result<MultiValuedFastFieldReader<DateTime<Utc>>> {
+        self.typed_fast_field_multi_reader(field)
+    }
+
+    /// Returns the reader of a bytes fast field.
+    pub fn bytes(&self, field: Field) -> crate::Result<BytesFastFieldReader> {
+        let field_entry = self.schema.get_field_entry(field);
+        match field_entry.field_type() {
+            FieldType::Bytes(bytes_options) if bytes_options.is_fast() => {
+                Ok(BytesFastFieldReader::open(self.field_data(field)?)?)
+            }
+            _ => Err(MySearchError::SchemaError(format!(
+                "field '{}' is not a bytes fast field",
+                field_entry.name()
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use crate::{
+        Cardinality, FastFieldCodecType, Index, IntOptions, MySearchError, Schema, SegmentReader,
+        FAST, STORED,
+    };
+
+    fn single_segment(index: &Index) -> crate::Result<SegmentReader> {
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 1);
+        Ok(searcher.segment_reader(0).clone())
+    }
+
+    #[test]
+    fn test_single_value_fast_fields() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let count = schema_builder.add_u64_field("count", FAST);
+        let delta = schema_builder.add_i64_field("delta", FAST | STORED);
+        let ratio = schema_builder.add_f64_field("ratio", FAST);
+        let date = schema_builder.add_date_field("date", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 10_000_000)?;
+        let timestamp = Utc.timestamp(1_600_000_000, 0);
+        index_writer.add_document(doc!(
+            count => 4u64,
+            delta => -3i64,
+            ratio => 0.5f64,
+            date => timestamp,
+        ))?;
+        index_writer.add_document(doc!(count => 13u64, count => 14u64))?;
+        index_writer.add_document(doc!(delta => 1_000i64))?;
+        index_writer.commit()?;
+
+        let segment_reader = single_segment(&index)?;
+        let fast_fields = segment_reader.fast_fields();
+        let count_reader = fast_fields.u64(count)?;
+        assert_eq!(count_reader.num_vals(), 3);
+        assert_eq!(
+            (0..3).map(|doc| count_reader.get(doc)).collect::<Vec<_>>(),
+            vec![4, 13, 0]
+        );
+        assert_eq!(
+            (count_reader.min_value(), count_reader.max_value()),
+            (0, 13)
+        );
+        let delta_reader = fast_fields.i64(delta)?;
+        assert_eq!(
+            (0..3).map(|doc| delta_reader.get(doc)).collect::<Vec<_>>(),
+            vec![-3, 0, 1_000]
+        );
+        assert_eq!(delta_reader.min_value(), -3);
+        let ratio_reader = fast_fields.f64(ratio)?;
+        assert_eq!(ratio_reader.get(0), 0.5);
+        assert_eq!(ratio_reader.get(1), 0.0);
+        let date_reader = fast_fields.date(date)?;
+        assert_eq!(date_reader.get(0), timestamp);
+        assert_eq!(date_reader.get(2), Utc.timestamp(0, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_value_and_bytes_fast_fields() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let tags = schema_builder.add_i64_field(
+            "tags",
+            IntOptions::default().set_fast(Cardinality::MultiValues),
+        );
+        let payload = schema_builder.add_bytes_field("payload", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 10_000_000)?;
+        index_writer.add_document(doc!(tags => 3i64, tags => -1i64, tags => 3i64))?;
+        index_writer.add_document(doc!(payload => vec![1u8, 2, 3]))?;
+        index_writer.add_document(doc!(tags => 7i64, payload => b"".to_vec()))?;
+        index_writer.add_document(doc!(payload => b"abc".to_vec()))?;
+        index_writer.commit()?;
+
+        let segment_reader = single_segment(&index)?;
+        let tags_reader = segment_reader.fast_fields().i64s(tags)?;
+        let mut vals = Vec::new();
+        tags_reader.get_vals(0, &mut vals);
+        assert_eq!(vals, vec![3, -1, 3]);
+        tags_reader.get_vals(1, &mut vals);
+        assert!(vals.is_empty());
+        tags_reader.get_vals(2, &mut vals);
+        assert_eq!(vals, vec![7]);
+        assert_eq!(tags_reader.num_vals(3), 0);
+        assert_eq!(tags_reader.total_num_vals(), 4);
+
+        let payload_reader = segment_reader.fast_fields().bytes(payload)?;
+        assert_eq!(payload_reader.get_bytes(0), b"");
+        assert_eq!(payload_reader.get_bytes(1), &[1u8, 2, 3]);
+        assert_eq!(payload_reader.get_bytes(2), b"");
+        assert_eq!(payload_reader.get_bytes(3), b"abc");
+        assert_eq!(payload_reader.total_num_bytes(), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_fast_field_type_mismatch() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let count = schema_builder.add_u64_field("count", FAST);
+        let not_fast = schema_builder.add_u64_field("not_fast", STORED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 10_000_000)?;
+        index_writer.add_document(doc!(count => 1u64))?;
+        index_writer.commit()?;
+
+        let segment_reader = single_segment(&index)?;
+        let fast_fields = segment_reader.fast_fields();
+        assert!(matches!(
+            fast_fields.i64(count),
+            Err(MySearchError::SchemaError(_))
+        ));
+        assert!(matches!(
+            fast_fields.u64s(count),
+            Err(MySearchError::SchemaError(_))
+        ));
+        assert!(matches!(
+            fast_fields.u64(not_fast),
+            Err(MySearchError::SchemaError(_))
+        ));
+        assert!(matches!(
+            fast_fields.bytes(count),
+            Err(MySearchError::SchemaError(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_monotonic_fast_field_uses_linear_interpolation() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let date = schema_builder.add_date_field("date", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 50_000_000)?;
+        for i in 0..5_000i64 {
+            index_writer.add_document(doc!(date => Utc.timestamp(1_600_000_000 + i * 3_600, 0)))?;
+        }
+        index_writer.commit()?;
+
+        let segment_reader = single_segment(&index)?;
+        let date_reader = segment_reader.fast_fields().date(date)?;
+        assert_eq!(date_reader.codec_type(), FastFieldCodecType::LinearInterpol);
+        assert_eq!(
+            date_reader.get(4_321),
+            Utc.timestamp(1_600_000_000 + 4_321 * 3_600, 0)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use std::io::{self, Write};
+
+use crate::fastfield::serialize_column;
+use crate::{
+    BinarySerializable, CompositeWrite, FastFieldCodecType, Field, TerminatingWrite, WritePtr,
+};
+
+/// Writes the fast field file of a segment, one section per fast field.
+///
+/// Single-valued fields are a single column. Multi-valued and bytes fields
+/// start with the length of their offset column, followed by the offset column
+/// and the values.
+pub struct FastFieldSerializer {
+    composite_write: CompositeWrite<WritePtr>,
+}
+
+impl FastFieldSerializer {
+    pub fn from_write(write: WritePtr) -> FastFieldSerializer {
+        FastFieldSerializer {
+            composite_write: CompositeWrite::wrap(write),
+        }
+    }
+
+    /// Writes the column of a single-valued field.
+    pub fn write_single_value(
+        &mut self,
+        field: Field,
+        vals: &[u64],
+    ) -> io::Result<FastFieldCodecType> {
+        serialize_column(self.composite_write.for_field(field), vals)
+    }
+
+    /// Writes a multi-valued field. The values of the doc `doc` are
+    /// `vals[doc_offsets[doc]..doc_offsets[doc + 1]]`.
+    pub fn write_multi_values(
+        &mut self,
+        field: Field,
+        doc_offsets: &[u64],
+        vals: &[u64],
+    ) -> io::Result<()> {
+        let write = self.composite_write.for_field(field);
+        write_offset_column(write, doc_offsets)?;
+        serialize_column(write, vals)?;
+        Ok(())
+    }
+
+    /// Writes a bytes field. The bytes of the doc `doc` are
+    /// `bytes[doc_offsets[doc]..doc_offsets[doc + 1]]`.
+    pub fn write_bytes(
+        &mut self,
+        field: Field,
+        doc_offsets: &[u64],
+        bytes: &[u8],
+    ) -> io::Result<()> {
+        let write = self.composite_write.for_field(field);
+        write_offset_column(write, doc_offsets)?;
+        write.write_all(bytes)
+    }
+
+    pub fn close(self) -> io::Result<()> {
+        self.composite_write.close()?.terminate()
+    }
+}
+
+fn write_offset_column<W: Write>(write: &mut W, doc_offsets: &[u64]) -> io::Result<()> {
+    let mut offset_column = Vec::new();
+    serialize_column(&mut offset_column, doc_offsets)?;
+    (offset_column.len() as u64).serialize(write)?;
+    write.write_all(&offset_column)
+}
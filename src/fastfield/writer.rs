@@ -0,0 +1,170 @@
+use std::io;
+
+use crate::fastfield::value_to_u64;
+use crate::{
+    Cardinality, Document, FastFieldSerializer, FastValue, Field, FieldType, Schema, Value,
+};
+
+/// Accumulates the fast field values of the documents of a segment.
+pub struct FastFieldsWriter {
+    single_value_writers: Vec<IntFastFieldWriter>,
+    multi_values_writers: Vec<MultiValuedFastFieldWriter>,
+    bytes_value_writers: Vec<BytesFastFieldWriter>,
+}
+
+impl FastFieldsWriter {
+    pub fn from_schema(schema: &Schema) -> FastFieldsWriter {
+        let mut single_value_writers = Vec::new();
+        let mut multi_values_writers = Vec::new();
+        let mut bytes_value_writers = Vec::new();
+        for (field, field_entry) in schema.fields() {
+            match field_entry.field_type() {
+                FieldType::U64(int_options)
+                | FieldType::I64(int_options)
+                | FieldType::F64(int_options)
+                | FieldType::Date(int_options) => match int_options.get_fastfield_cardinality() {
+                    Some(Cardinality::SingleValue) => {
+                        let val_if_missing = match field_entry.field_type() {
+                            FieldType::U64(_) => u64::make_zero().to_u64(),
+                            FieldType::F64(_) => f64::make_zero().to_u64(),
+                            _ => i64::make_zero().to_u64(),
+                        };
+                        single_value_writers.push(IntFastFieldWriter::new(field, val_if_missing))
+                    }
+                    Some(Cardinality::MultiValues) => {
+                        multi_values_writers.push(MultiValuedFastFieldWriter::new(field))
+                    }
+                    None => {}
+                },
+                FieldType::Bytes(bytes_options) if bytes_options.is_fast() => {
+                    bytes_value_writers.push(BytesFastFieldWriter::new(field))
+                }
+                _ => {}
+            }
+        }
+        FastFieldsWriter {
+            single_value_writers,
+            multi_values_writers,
+            bytes_value_writers,
+        }
+    }
+
+    /// Approximate number of bytes used by the values accumulated so far.
+    pub fn mem_usage(&self) -> usize {
+        self.single_value_writers
+            .iter()
+            .map(|writer| writer.vals.capacity() * 8)
+            .chain(
+                self.multi_values_writers
+                    .iter()
+                    .map(|writer| (writer.vals.capacity() + writer.doc_offsets.capacity()) * 8),
+            )
+            .chain(
+                self.bytes_value_writers
+                    .iter()
+                    .map(|writer| writer.bytes.capacity() + writer.doc_offsets.capacity() * 8),
+            )
+            .sum()
+    }
+
+    /// Appends the values of the next document.
+    pub fn add_document(&mut self, doc: &Document) {
+        for writer in &mut self.single_value_writers {
+            writer.add_document(doc);
+        }
+        for writer in &mut self.multi_values_writers {
+            writer.add_document(doc);
+        }
+        for writer in &mut self.bytes_value_writers {
+            writer.add_document(doc);
+        }
+    }
+
+    pub fn serialize(&self, mut serializer: FastFieldSerializer) -> io::Result<()> {
+        for writer in &self.single_value_writers {
+            serializer.write_single_value(writer.field, &writer.vals)?;
+        }
+        for writer in &self.multi_values_writers {
+            serializer.write_multi_values(writer.field, &writer.doc_offsets, &writer.vals)?;
+        }
+        for writer in &self.bytes_value_writers {
+            serializer.write_bytes(writer.field, &writer.doc_offsets, &writer.bytes)?;
+        }
+        serializer.close()
+    }
+}
+
+/// Column of a single-valued numeric fast field.
+///
+/// Only the first value of a document is kept. Documents without any value
+/// get the zero of the field type.
+struct IntFastFieldWriter {
+    field: Field,
+    vals: Vec<u64>,
+    val_if_missing: u64,
+}
+
+impl IntFastFieldWriter {
+    fn new(field: Field, val_if_missing: u64) -> IntFastFieldWriter {
+        IntFastFieldWriter {
+            field,
+            vals: Vec::new(),
+            val_if_missing,
+        }
+    }
+
+    fn add_document(&mut self, doc: &Document) {
+        let val = doc
+            .get_first(self.field)
+            .and_then(value_to_u64)
+            .unwrap_or(self.val_if_missing);
+        self.vals.push(val);
+    }
+}
+
+/// Columns of a multi-valued numeric fast field.
+struct MultiValuedFastFieldWriter {
+    field: Field,
+    vals: Vec<u64>,
+    doc_offsets: Vec<u64>,
+}
+
+impl MultiValuedFastFieldWriter {
+    fn new(field: Field) -> MultiValuedFastFieldWriter {
+        MultiValuedFastFieldWriter {
+            field,
+            vals: Vec::new(),
+            doc_offsets: vec![0],
+        }
+    }
+
+    fn add_document(&mut self, doc: &Document) {
+        self.vals
+            .extend(doc.get_all(self.field).filter_map(value_to_u64));
+        self.doc_offsets.push(self.vals.len() as u64);
+    }
+}
+
+/// Columns of a bytes fast field. Only the first value of a document is kept.
+struct BytesFastFieldWriter {
+    field: Field,
+    bytes: Vec<u8>,
+    doc_offsets: Vec<u64>,
+}
+
+impl BytesFastFieldWriter {
+    fn new(field: Field) -> BytesFastFieldWriter {
+        BytesFastFieldWriter {
+            field,
+            bytes: Vec::new(),
+            doc_offsets: vec![0],
+        }
+    }
+
+    fn add_document(&mut self, doc: &Document) {
+        if let Some(bytes) = doc.get_first(self.field).and_then(Value::bytes_value) {
+            self.bytes.extend_from_slice(bytes);
+        }
+        self.doc_offsets.push(self.bytes.len() as u64);
+    }
+}
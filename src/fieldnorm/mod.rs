@@ -0,0 +1,13 @@
+//! Fieldnorms: the number of tokens of each text field of each document.
+//!
+//! BM25 uses them to favor matches in short fields. They are quantized to a
+//! single byte per document, see `FIELD_NORMS_TABLE`.
+mod code;
+mod reader;
+mod serializer;
+mod writer;
+
+pub use code::*;
+pub use reader::*;
+pub use serializer::*;
+pub use writer::*;
@@ -0,0 +1,136 @@
+use std::io;
+
+use crate::{
+    fieldnorm_to_id, id_to_fieldnorm, BinarySerializable, CompositeFile, DocId, Field, FileSlice,
+    OwnedBytes,
+};
+
+/// Gives access to the fieldnorm readers of the fields of a segment.
+#[derive(Clone)]
+pub struct FieldNormReaders {
+    data: CompositeFile,
+}
+
+impl FieldNormReaders {
+    pub fn open(file: FileSlice) -> io::Result<FieldNormReaders> {
+        let data = CompositeFile::open(&file)?;
+        Ok(FieldNormReaders { data })
+    }
+
+    /// Returns the fieldnorm reader of `field`, or `None` if the field has no
+    /// fieldnorms.
+    pub fn get_field(&self, field: Field) -> io::Result<Option<FieldNormReader>> {
+        self.data
+            .open_read(field)
+            .map(FieldNormReader::open)
+            .transpose()
+    }
+}
+
+/// Reads the fieldnorms of a field, one byte per document.
+#[derive(Clone)]
+pub struct FieldNormReader {
+    fieldnorm_ids: OwnedBytes,
+    total_num_tokens: u64,
+}
+
+impl FieldNormReader {
+    pub fn open(file: FileSlice) -> io::Result<FieldNormReader> {
+        let mut data = file.read_bytes()?;
+        let total_num_tokens = u64::deserialize(&mut data)?;
+        Ok(FieldNormReader {
+            fieldnorm_ids: data,
+            total_num_tokens,
+        })
+    }
+
+    /// Returns a reader giving the same fieldnorm to all the documents.
+    pub fn constant(num_docs: DocId, fieldnorm: u32) -> FieldNormReader {
+        let fieldnorm_id = fieldnorm_to_id(fieldnorm);
+        FieldNormReader {
+            fieldnorm_ids: OwnedBytes::new(vec![fieldnorm_id; num_docs as usize]),
+            total_num_tokens: u64::from(id_to_fieldnorm(fieldnorm_id)) * u64::from(num_docs),
+        }
+    }
+
+    /// Number of documents the reader covers.
+    pub fn num_docs(&self) -> DocId {
+        self.fieldnorm_ids.len() as DocId
+    }
+
+    /// Total number of tokens of the field in the segment, before quantization.
+    pub fn total_num_tokens(&self) -> u64 {
+        self.total_num_tokens
+    }
+
+    /// Returns the quantized number of tokens of the field in `doc`.
+    #[inline]
+    pub fn fieldnorm(&self, doc: DocId) -> u32 {
+        id_to_fieldnorm(self.fieldnorm_id(doc))
+    }
+
+    /// Returns the fieldnorm id of `doc`, as used by `BM25Weight::score`.
+    #[inline]
+    pub fn fieldnorm_id(&self, doc: DocId) -> u8 {
+        self.fieldnorm_ids.as_slice()[doc as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Directory, Field, FieldNormReader, FieldNormReaders, FieldNormsSerializer,
+        FieldNormsWriter, RAMDirectory, Schema, STRING, TEXT,
+    };
+    use std::path::Path;
+
+    #[test]
+    fn test_fieldnorms_roundtrip() -> std::io::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let count = schema_builder.add_u64_field("count", crate::INDEXED);
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let schema = schema_builder.build();
+        assert_eq!(
+            FieldNormsWriter::fields_with_fieldnorm(&schema),
+            vec![title, tag]
+        );
+
+        let mut fieldnorms_writer = FieldNormsWriter::for_schema(&schema);
+        fieldnorms_writer.record(0, title, 3);
+        fieldnorms_writer.record(2, title, 1_000);
+        fieldnorms_writer.record(1, tag, 1);
+        fieldnorms_writer.fill_up_to_max_doc(4);
+
+        let directory = RAMDirectory::create();
+        let path = Path::new("fieldnorms");
+        fieldnorms_writer.serialize(FieldNormsSerializer::from_write(
+            directory.open_write(path)?,
+        ))?;
+
+        let fieldnorm_readers = FieldNormReaders::open(directory.open_read(path)?)?;
+        let title_reader = fieldnorm_readers.get_field(title)?.unwrap();
+        assert_eq!(title_reader.num_docs(), 4);
+        assert_eq!(title_reader.total_num_tokens(), 1_003);
+        assert_eq!(title_reader.fieldnorm(0), 3);
+        assert_eq!(title_reader.fieldnorm(1), 0);
+        assert_eq!(title_reader.fieldnorm(2), 984);
+        assert_eq!(title_reader.fieldnorm(3), 0);
+        let tag_reader = fieldnorm_readers.get_field(tag)?.unwrap();
+        assert_eq!(tag_reader.fieldnorm(1), 1);
+        assert_eq!(tag_reader.num_docs(), 4);
+        assert!(fieldnorm_readers.get_field(count)?.is_none());
+        assert!(fieldnorm_readers
+            .get_field(Field::from_field_id(10))?
+            .is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_constant_fieldnorm_reader() {
+        let fieldnorm_reader = FieldNormReader::constant(3, 1);
+        assert_eq!(fieldnorm_reader.num_docs(), 3);
+        assert_eq!(fieldnorm_reader.fieldnorm(2), 1);
+        assert_eq!(fieldnorm_reader.total_num_tokens(), 3);
+    }
+}
@@ -0,0 +1,34 @@
+use std::io::{self, Write};
+
+use crate::{BinarySerializable, CompositeWrite, Field, TerminatingWrite, WritePtr};
+
+/// Writes the fieldnorm file of a segment.
+///
+/// Each field gets a section holding the total number of tokens of the field
+/// in the segment, followed by one fieldnorm id per document.
+pub struct FieldNormsSerializer {
+    composite_write: CompositeWrite<WritePtr>,
+}
+
+impl FieldNormsSerializer {
+    pub fn from_write(write: WritePtr) -> FieldNormsSerializer {
+        FieldNormsSerializer {
+            composite_write: CompositeWrite::wrap(write),
+        }
+    }
+
+    pub fn serialize_field(
+        &mut self,
+        field: Field,
+        total_num_tokens: u64,
+        fieldnorm_ids: &[u8],
+    ) -> io::Result<()> {
+        let write = self.composite_write.for_field(field);
+        total_num_tokens.serialize(write)?;
+        write.write_all(fieldnorm_ids)
+    }
+
+    pub fn close(self) -> io::Result<()> {
+        self.composite_write.close()?.terminate()
+    }
+}
@@ -0,0 +1,67 @@
+use std::io;
+
+use crate::{fieldnorm_to_id, DocId, Field, FieldNormsSerializer, FieldType, Schema};
+
+/// Records the fieldnorms of the indexed text fields of a segment being built.
+pub struct FieldNormsWriter {
+    fields: Vec<Field>,
+    fieldnorms_buffers: Vec<Vec<u8>>,
+    total_num_tokens: Vec<u64>,
+}
+
+impl FieldNormsWriter {
+    /// Returns the fields that have fieldnorms.
+    pub fn fields_with_fieldnorm(schema: &Schema) -> Vec<Field> {
+        schema
+            .fields()
+            .filter(|(_, field_entry)| match field_entry.field_type() {
+                FieldType::Str(text_options) => text_options.get_indexing_options().is_some(),
+                _ => false,
+            })
+            .map(|(field, _)| field)
+            .collect()
+    }
+
+    pub fn for_schema(schema: &Schema) -> FieldNormsWriter {
+        FieldNormsWriter {
+            fields: FieldNormsWriter::fields_with_fieldnorm(schema),
+            fieldnorms_buffers: vec![Vec::new(); schema.num_fields()],
+            total_num_tokens: vec![0u64; schema.num_fields()],
+        }
+    }
+
+    /// Records the number of tokens of `field` in `doc`.
+    ///
+    /// Documents must be recorded in increasing order. Skipped documents get a
+    /// fieldnorm of 0.
+    pub fn record(&mut self, doc: DocId, field: Field, fieldnorm: u32) {
+        let field_id = field.field_id() as usize;
+        let fieldnorms_buffer = &mut self.fieldnorms_buffers[field_id];
+        assert!(
+            fieldnorms_buffer.len() <= doc as usize,
+            "fieldnorms must be recorded in increasing doc order"
+        );
+        fieldnorms_buffer.resize(doc as usize, 0u8);
+        fieldnorms_buffer.push(fieldnorm_to_id(fieldnorm));
+        self.total_num_tokens[field_id] += u64::from(fieldnorm);
+    }
+
+    /// Gives a fieldnorm of 0 to all the documents that were not recorded.
+    pub fn fill_up_to_max_doc(&mut self, max_doc: DocId) {
+        for &field in &self.fields {
+            self.fieldnorms_buffers[field.field_id() as usize].resize(max_doc as usize, 0u8);
+        }
+    }
+
+    pub fn serialize(&self, mut serializer: FieldNormsSerializer) -> io::Result<()> {
+        for &field in &self.fields {
+            let field_id = field.field_id() as usize;
+            serializer.serialize_field(
+                field,
+                self.total_num_tokens[field_id],
+                &self.fieldnorms_buffers[field_id],
+            )?;
+        }
+        serializer.close()
+    }
+}
@@ -0,0 +1,998 @@
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crossbeam::channel;
+
+use crate::indexer::segment_writer::validate_document;
+use crate::indexer::SegmentUpdater;
+use crate::{
+    AddOperation, AliveBitSet, BitSet, DeleteOperation, DirectoryLock, DocId, Document, Index,
+    IndexRecordOption, MergePolicy, MySearchError, Opstamp, Query, Searcher, Segment,
+    SegmentComponent, SegmentId, SegmentMeta, SegmentReader, SegmentWriter, Stamper, Term,
+    TermQuery, INDEX_WRITER_LOCK,
+};
+
+/// Memory kept in reserve by each indexing thread: a segment is flushed as soon
+/// as its memory usage gets within this margin of the thread budget.
+pub const MARGIN_IN_BYTES: usize = 1_000_000;
+
+/// Minimum memory budget of an indexing thread.
+pub const MEMORY_ARENA_NUM_BYTES_MIN: usize = MARGIN_IN_BYTES * 3;
+
+/// Maximum memory budget of an indexing thread.
+pub const MEMORY_ARENA_NUM_BYTES_MAX: usize = u32::MAX as usize - MARGIN_IN_BYTES;
+
+/// Maximum number of indexing threads picked by `Index::writer`.
+pub const MAX_NUM_THREAD: usize = 8;
+
+/// Maximum number of documents waiting to be picked by an indexing thread.
+const PIPELINE_MAX_SIZE_IN_DOCS: usize = 10_000;
+
+type OperationSender = channel::Sender<AddOperation>;
+type OperationReceiver = channel::Receiver<AddOperation>;
+
+/// Segment written by an indexing thread, with the opstamps of its documents.
+///
+/// The opstamps tell which of the pending deletes were issued after each
+/// document was added, and therefore apply to it.
+struct SegmentEntry {
+    meta: SegmentMeta,
+    doc_opstamps: Vec<Opstamp>,
+}
+
+/// Segments written by the indexing threads since the last commit.
+type UncommittedSegments = Arc<Mutex<Vec<SegmentEntry>>>;
+
+/// Adds documents to an index.
+///
+/// Documents are dispatched to a pool of indexing threads, each building its own
+/// segment in memory and writing it to the `Directory` once its memory budget
+/// is exhausted. Nothing is visible to readers until `commit` is called.
+///
+/// After each commit, the `MergePolicy` picks segments to merge together in
+/// background threads, without blocking indexing.
+///
+/// An index has at most one writer at a time: the writer holds the
+/// `INDEX_WRITER_LOCK` of the directory until it is dropped.
+pub struct IndexWriter {
+    index: Index,
+    memory_budget_per_thread: usize,
+    num_threads: usize,
+    workers_join_handle: Vec<JoinHandle<crate::Result<()>>>,
+    operation_sender: OperationSender,
+    uncommitted_segments: UncommittedSegments,
+    delete_operations: Mutex<Vec<DeleteOperation>>,
+    segment_updater: SegmentUpdater,
+    stamper: Stamper,
+    committed_opstamp: Opstamp,
+    /// Error of an indexing thread that failed since the last commit, losing
+    /// the documents it had not flushed yet.
+    indexing_error: Option<String>,
+    // declared last so that the lock is released after everything else.
+    _directory_lock: DirectoryLock,
+}
+
+impl IndexWriter {
+    pub(crate) fn new(
+        index: &Index,
+        num_threads: usize,
+        memory_budget_per_thread: usize,
+    ) -> crate::Result<IndexWriter> {
+        if memory_budget_per_thread < MEMORY_ARENA_NUM_BYTES_MIN {
+            return Err(MySearchError::InvalidArgument(format!(
+                "the memory budget per thread must be at least {} bytes, got {}",
+                MEMORY_ARENA_NUM_BYTES_MIN, memory_budget_per_thread
+            )));
+        }
+        if memory_budget_per_thread >= MEMORY_ARENA_NUM_BYTES_MAX {
+            return Err(MySearchError::InvalidArgument(format!(
+                "the memory budget per thread must be lower than {} bytes, got {}",
+                MEMORY_ARENA_NUM_BYTES_MAX, memory_budget_per_thread
+            )));
+        }
+        if num_threads == 0 {
+            return Err(MySearchError::InvalidArgument(
+                "an index writer needs at least one indexing thread".to_string(),
+            ));
+        }
+        let directory_lock = index.directory().acquire_lock(&INDEX_WRITER_LOCK)?;
+        let committed_opstamp = index.load_metas()?.opstamp;
+        let (operation_sender, _) = channel::bounded(0);
+        let mut index_writer = IndexWriter {
+            index: index.clone(),
+            memory_budget_per_thread,
+            num_threads,
+            workers_join_handle: Vec::new(),
+            operation_sender,
+            uncommitted_segments: Arc::default(),
+            delete_operations: Mutex::default(),
+            segment_updater: SegmentUpdater::new(index.clone()),
+            stamper: Stamper::new(committed_opstamp + 1),
+            committed_opstamp,
+            indexing_error: None,
+            _directory_lock: directory_lock,
+        };
+        index_writer.start_workers()?;
+        Ok(index_writer)
+    }
+
+    pub fn index(&self) -> &Index {
+        &self.index
+    }
+
+    /// Opstamp of the last commit.
+    pub fn commit_opstamp(&self) -> Opstamp {
+        self.committed_opstamp
+    }
+
+    pub fn get_merge_policy(&self) -> Arc<dyn MergePolicy> {
+        self.segment_updater.get_merge_policy()
+    }
+
+    /// Sets the merge policy used from the next commit on.
+    pub fn set_merge_policy(&self, merge_policy: Box<dyn MergePolicy>) {
+        self.segment_updater.set_merge_policy(merge_policy);
+    }
+
+    /// Queues a document for indexing and returns its opstamp.
+    ///
+    /// The document is validated against the schema right away, but it only
+    /// becomes searchable after the next `commit`.
+    pub fn add_document(&self, document: Document) -> crate::Result<Opstamp> {
+        validate_document(&self.index.schema(), &document)?;
+        let opstamp = self.stamper.stamp();
+        let add_operation = AddOperation { opstamp, document };
+        self.operation_sender.send(add_operation).map_err(|_| {
+            MySearchError::ErrorInThread("the indexing threads have stopped".to_string())
+        })?;
+        Ok(opstamp)
+    }
+
+    /// Deletes the documents containing `term` and returns the opstamp of the
+    /// deletion.
+    ///
+    /// Only the documents added before the deletion are affected, and the
+    /// deletion is applied on the next `commit`.
+    pub fn delete_term(&self, term: Term) -> Opstamp {
+        self.delete_query(Box::new(TermQuery::new(term, IndexRecordOption::Basic)))
+    }
+
+    /// Deletes the documents matching `query`, see `delete_term`.
+    pub fn delete_query(&self, query: Box<dyn Query>) -> Opstamp {
+        let opstamp = self.stamper.stamp();
+        self.delete_operations
+            .lock()
+            .unwrap()
+            .push(DeleteOperation {
+                opstamp,
+                target: query,
+            });
+        opstamp
+    }
+
+    /// Replaces the documents containing `term` by `document`.
+    ///
+    /// This is a `delete_term` immediately followed by an `add_document`, the
+    /// returned opstamp being the one of the addition.
+    pub fn update_document(&self, term: Term, document: Document) -> crate::Result<Opstamp> {
+        validate_document(&self.index.schema(), &document)?;
+        let mut opstamps = self.stamper.stamps(2);
+        let delete_opstamp = opstamps.next().expect("two opstamps were reserved");
+        let add_opstamp = opstamps.next().expect("two opstamps were reserved");
+        self.delete_operations
+            .lock()
+            .unwrap()
+            .push(DeleteOperation {
+                opstamp: delete_opstamp,
+                target: Box::new(TermQuery::new(term, IndexRecordOption::Basic)),
+            });
+        let add_operation = AddOperation {
+            opstamp: add_opstamp,
+            document,
+        };
+        self.operation_sender.send(add_operation).map_err(|_| {
+            MySearchError::ErrorInThread("the indexing threads have stopped".to_string())
+        })?;
+        Ok(add_opstamp)
+    }
+
+    /// Makes all the documents added and deleted so far visible, and durable.
+    ///
+    /// The indexing threads flush their segments and the pending deletes are
+    /// written to the delete files of the segments, then a new `meta.json`
+    /// listing them is atomically published. Returns the opstamp of the commit.
+    ///
+    /// If the commit fails, the flushed segments are kept and the indexing
+    /// threads are restarted, so the commit can be retried, or abandoned with
+    /// `rollback`.
+    ///
+    /// If an indexing thread failed, the documents it had not flushed are lost
+    /// even though `add_document` returned their opstamps. The commit then
+    /// fails, and so do the following ones until `rollback` is called.
+    pub fn commit(&mut self) -> crate::Result<Opstamp> {
+        if let Some(indexing_error) = &self.indexing_error {
+            return Err(MySearchError::ErrorInThread(format!(
+                "an indexing thread failed since the last commit, losing documents, \
+                 the writer must be rolled back: {}",
+                indexing_error
+            )));
+        }
+        let commit_opstamp = self.stamper.stamp();
+        let join_result = self.join_workers();
+        if let Err(error) = &join_result {
+            self.indexing_error = Some(error.to_string());
+        }
+        let publish_result = join_result.and_then(|()| self.publish_commit(commit_opstamp));
+        if publish_result.is_ok() {
+            self.committed_opstamp = commit_opstamp;
+            // the commit is durable at this point: files left behind by a failed
+            // collection are collected by the next commit, or when the index is
+            // opened.
+            let _ = self.segment_updater.garbage_collect_files();
+        }
+        self.start_workers()?;
+        publish_result?;
+        self.segment_updater.consider_merge_options()?;
+        Ok(commit_opstamp)
+    }
+
+    /// Publishes the uncommitted segments and the pending deletes in a new
+    /// `meta.json`. The indexing threads must be stopped.
+    ///
+    /// The uncommitted segments and the pending deletes are only cleared once
+    /// `meta.json` is saved.
+    fn publish_commit(&self, commit_opstamp: Opstamp) -> crate::Result<()> {
+        let mut uncommitted_segments = self.uncommitted_segments.lock().unwrap();
+        let mut delete_operations = self.delete_operations.lock().unwrap();
+        let _meta_lock = self.segment_updater.lock_meta();
+        let mut index_meta = self.index.load_metas()?;
+        let mut segments =
+            Vec::with_capacity(index_meta.segments.len() + uncommitted_segments.len());
+        // committed documents all predate the pending deletes.
+        for segment_meta in index_meta.segments {
+            let segment = self.index.segment(segment_meta);
+            segments.push(apply_deletes(
+                segment,
+                &delete_operations,
+                None,
+                commit_opstamp,
+            )?);
+        }
+        for segment_entry in uncommitted_segments.iter() {
+            let segment = self.index.segment(segment_entry.meta.clone());
+            segments.push(apply_deletes(
+                segment,
+                &delete_operations,
+                Some(&segment_entry.doc_opstamps),
+                commit_opstamp,
+            )?);
+        }
+        index_meta.segments = segments
+            .into_iter()
+            .filter(|segment_meta| segment_meta.num_docs() > 0)
+            .collect();
+        index_meta.opstamp = commit_opstamp;
+        index_meta.save(self.index.directory())?;
+        uncommitted_segments.clear();
+        delete_operations.clear();
+        Ok(())
+    }
+
+    /// Discards all the documents added and deleted since the last commit.
+    ///
+    /// Returns the opstamp of the last commit.
+    pub fn rollback(&mut self) -> crate::Result<Opstamp> {
+        // the work of the indexing threads is thrown away, errors included.
+        let _ = self.join_workers();
+        self.uncommitted_segments.lock().unwrap().clear();
+        self.delete_operations.lock().unwrap().clear();
+        self.indexing_error = None;
+        // the files are collected before the indexing threads write new ones.
+        let gc_result = self.segment_updater.garbage_collect_files();
+        self.stamper.revert(self.committed_opstamp + 1);
+        self.start_workers()?;
+        gc_result?;
+        Ok(self.committed_opstamp)
+    }
+
+    /// Merges the committed segments `segment_ids` into a new segment, in the
+    /// calling thread, and publishes it in place of the merged ones.
+    ///
+    /// Returns the meta of the new segment.
+    pub fn merge(&mut self, segment_ids: &[SegmentId]) -> crate::Result<SegmentMeta> {
+        self.segment_updater.merge(segment_ids)
+    }
+
+    /// Waits for the merges running in the background to complete.
+    pub fn wait_merging_threads(mut self) -> crate::Result<()> {
+        self.join_workers()?;
+        self.segment_updater.wait_merging_threads()
+    }
+
+    fn start_workers(&mut self) -> crate::Result<()> {
+        let (operation_sender, operation_receiver) = channel::bounded(PIPELINE_MAX_SIZE_IN_DOCS);
+        self.operation_sender = operation_sender;
+        for worker_id in 0..self.num_threads {
+            let index = self.index.clone();
+            let operation_receiver = operation_receiver.clone();
+            let uncommitted_segments = self.uncommitted_segments.clone();
+            let memory_budget = self.memory_budget_per_thread;
+            let join_handle = thread::Builder::new()
+                .name(format!("indexing-thread-{}", worker_id))
+                .spawn(move || {
+                    index_documents(
+                        &index,
+                        operation_receiver,
+                        memory_budget,
+                        &uncommitted_segments,
+                    )
+                })?;
+            self.workers_join_handle.push(join_handle);
+        }
+        Ok(())
+    }
+
+    /// Closes the document channel and waits for the indexing threads to flush
+    /// their last segment.
+    fn join_workers(&mut self) -> crate::Result<()> {
+        let (closed_sender, _) = channel::bounded(0);
+        drop(mem::replace(&mut self.operation_sender, closed_sender));
+        let mut result = Ok(());
+        for join_handle in self.workers_join_handle.drain(..) {
+            let worker_result = join_handle.join().unwrap_or_else(|_| {
+                Err(MySearchError::ErrorInThread(
+                    "an indexing thread panicked".to_string(),
+                ))
+            });
+            if result.is_ok() {
+                result = worker_result;
+            }
+        }
+        result
+    }
+}
+
+impl Drop for IndexWriter {
+    fn drop(&mut self) {
+        let _ = self.join_workers();
+        let _ = self.segment_updater.wait_merging_threads();
+    }
+}
+
+/// Body of an indexing thread: builds segments out of the received documents
+/// until the channel is closed.
+fn index_documents(
+    index: &Index,
+    operation_receiver: OperationReceiver,
+    memory_budget: usize,
+    uncommitted_segments: &UncommittedSegments,
+) -> crate::Result<()> {
+    let mut segment_writer: Option<SegmentWriter> = None;
+    let mut doc_opstamps: Vec<Opstamp> = Vec::new();
+    for add_operation in operation_receiver {
+        let writer = match segment_writer.as_mut() {
+            Some(writer) => writer,
+            None => segment_writer.get_or_insert(SegmentWriter::for_segment(index.new_segment())?),
+        };
+        writer.add_document(&add_operation.document)?;
+        doc_opstamps.push(add_operation.opstamp);
+        if writer.mem_usage() >= memory_budget - MARGIN_IN_BYTES {
+            let writer = segment_writer
+                .take()
+                .expect("the segment writer was just used");
+            flush_segment(writer, mem::take(&mut doc_opstamps), uncommitted_segments)?;
+        }
+    }
+    if let Some(writer) = segment_writer {
+        flush_segment(writer, doc_opstamps, uncommitted_segments)?;
+    }
+    Ok(())
+}
+
+fn flush_segment(
+    segment_writer: SegmentWriter,
+    doc_opstamps: Vec<Opstamp>,
+    uncommitted_segments: &UncommittedSegments,
+) -> crate::Result<()> {
+    let segment = segment_writer.finalize()?;
+    uncommitted_segments.lock().unwrap().push(SegmentEntry {
+        meta: segment.meta().clone(),
+        doc_opstamps,
+    });
+    Ok(())
+}
+
+/// Applies `delete_operations` to `segment` and returns its updated meta.
+///
+/// If `doc_opstamps` is given, a document is only deleted by the operations
+/// issued after it was added. A new delete file, versioned by
+/// `commit_opstamp`, is written only if some documents were deleted.
+fn apply_deletes(
+    segment: Segment,
+    delete_operations: &[DeleteOperation],
+    doc_opstamps: Option<&[Opstamp]>,
+    commit_opstamp: Opstamp,
+) -> crate::Result<SegmentMeta> {
+    if delete_operations.is_empty() {
+        return Ok(segment.meta().clone());
+    }
+    let segment_reader = SegmentReader::open(&segment)?;
+    let searcher = Searcher::new(
+        segment.schema(),
+        segment.index().clone(),
+        vec![segment_reader.clone()],
+    )?;
+    let mut alive_bitset = match segment_reader.alive_bitset() {
+        Some(alive_bitset) => alive_bitset.bitset().clone(),
+        None => BitSet::full(segment_reader.max_doc()),
+    };
+    let num_alive_docs = alive_bitset.len();
+    for delete_operation in delete_operations {
+        let is_before_delete = |doc: DocId| {
+            doc_opstamps
+                .map(|doc_opstamps| doc_opstamps[doc as usize] < delete_operation.opstamp)
+                .unwrap_or(true)
+        };
+        let weight = delete_operation.target.weight(&searcher, false)?;
+        weight.for_each(&segment_reader, &mut |doc, _| {
+            if is_before_delete(doc) {
+                alive_bitset.remove(doc);
+            }
+        })?;
+    }
+    if alive_bitset.len() == num_alive_docs {
+        return Ok(segment.meta().clone());
+    }
+    let num_deleted_docs = segment_reader.max_doc() - alive_bitset.len();
+    let segment = segment.with_delete_meta(num_deleted_docs, commit_opstamp);
+    AliveBitSet::write(&alive_bitset, segment.open_write(SegmentComponent::Delete)?)?;
+    Ok(segment.meta().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        AllQuery, Count, Index, MySearchError, Schema, SegmentComponent, Term, TermDictionary,
+        TermQuery, INDEXED, STRING, TEXT,
+    };
+    use crate::{Directory, DirectoryLock, FileHandle, Lock, RAMDirectory, WatchCallback};
+    use crate::{IndexRecordOption, LockError, LogMergePolicy, NoMergePolicy, Query, SegmentId};
+    use crate::{
+        WatchHandle, WritePtr, MANAGED_FILEPATH, MEMORY_ARENA_NUM_BYTES_MIN, META_FILEPATH,
+    };
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    fn num_docs(index: &Index) -> crate::Result<u32> {
+        Ok(index
+            .searchable_segment_metas()?
+            .iter()
+            .map(|segment_meta| segment_meta.max_doc())
+            .sum())
+    }
+
+    fn doc_freq(index: &Index, term: &Term) -> crate::Result<u32> {
+        let mut doc_freq = 0;
+        for segment_meta in index.searchable_segment_metas()? {
+            let segment = index.segment(segment_meta);
+            let term_dictionary =
+                TermDictionary::open(segment.open_read(SegmentComponent::Terms)?)?;
+            if let Some(term_info) = term_dictionary.get(term) {
+                doc_freq += term_info.doc_freq;
+            }
+        }
+        Ok(doc_freq)
+    }
+
+    #[test]
+    fn test_commit_makes_documents_visible() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(2, 2 * MEMORY_ARENA_NUM_BYTES_MIN)?;
+        let opstamp_a = index_writer.add_document(doc!(text => "a b"))?;
+        let opstamp_b = index_writer.add_document(doc!(text => "a c"))?;
+        assert!(opstamp_a < opstamp_b);
+        assert_eq!(num_docs(&index)?, 0);
+
+        let commit_opstamp = index_writer.commit()?;
+        assert!(commit_opstamp > opstamp_b);
+        assert_eq!(index.load_metas()?.opstamp, commit_opstamp);
+        assert_eq!(num_docs(&index)?, 2);
+        assert_eq!(doc_freq(&index, &Term::from_field_text(text, "a"))?, 2);
+
+        index_writer.add_document(doc!(text => "a"))?;
+        index_writer.commit()?;
+        assert_eq!(num_docs(&index)?, 3);
+        assert_eq!(doc_freq(&index, &Term::from_field_text(text, "a"))?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rollback_discards_uncommitted_documents() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+        index_writer.add_document(doc!(text => "kept"))?;
+        let commit_opstamp = index_writer.commit()?;
+        let committed_files = index.directory().list_files()?.len();
+
+        index_writer.add_document(doc!(text => "discarded"))?;
+        assert_eq!(index_writer.rollback()?, commit_opstamp);
+        assert!(index_writer.add_document(doc!(text => "kept too"))? > commit_opstamp);
+        index_writer.commit()?;
+        assert_eq!(num_docs(&index)?, 2);
+        assert_eq!(
+            doc_freq(&index, &Term::from_field_text(text, "discarded"))?,
+            0
+        );
+        assert_eq!(doc_freq(&index, &Term::from_field_text(text, "kept"))?, 2);
+        // the files of the rolled back segment are gone.
+        let num_files_per_segment = committed_files - 2;
+        assert_eq!(
+            index.directory().list_files()?.len(),
+            2 + 2 * num_files_per_segment
+        );
+        assert!(index.directory().exists(Path::new(META_FILEPATH))?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_segments_are_flushed_when_memory_budget_is_exhausted() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_text_field("id", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+        let num_docs_added = 60_000u32;
+        for i in 0..num_docs_added {
+            index_writer.add_document(doc!(id => format!("id-{}", i)))?;
+        }
+        index_writer.commit()?;
+        assert!(index.searchable_segment_metas()?.len() > 1);
+        assert_eq!(num_docs(&index)?, num_docs_added);
+        assert_eq!(doc_freq(&index, &Term::from_field_text(id, "id-12345"))?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_documents_are_sharded_across_threads() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(4, 4 * MEMORY_ARENA_NUM_BYTES_MIN)?;
+        for _ in 0..1_000 {
+            index_writer.add_document(doc!(text => "hello"))?;
+        }
+        index_writer.commit()?;
+        assert!(index.searchable_segment_metas()?.len() <= 4);
+        assert_eq!(num_docs(&index)?, 1_000);
+        assert_eq!(
+            doc_freq(&index, &Term::from_field_text(text, "hello"))?,
+            1_000
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_writer_survives_reopen() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let tempdir = tempfile::TempDir::new()?;
+        let commit_opstamp = {
+            let index = Index::create_in_dir(tempdir.path(), schema_builder.build())?;
+            let mut index_writer = index.writer(MEMORY_ARENA_NUM_BYTES_MIN)?;
+            index_writer.add_document(doc!(text => "persisted"))?;
+            index_writer.commit()?
+        };
+        let index = Index::open_in_dir(tempdir.path())?;
+        assert_eq!(num_docs(&index)?, 1);
+        let index_writer = index.writer(MEMORY_ARENA_NUM_BYTES_MIN)?;
+        assert_eq!(index_writer.commit_opstamp(), commit_opstamp);
+        assert!(index_writer.add_document(doc!(text => "more"))? > commit_opstamp);
+        Ok(())
+    }
+
+    /// Directory whose writes of `meta.json` fail while `fail_meta_writes` is set,
+    /// and whose writes of the other files fail while `fail_file_writes` is set.
+    #[derive(Clone, Debug, Default)]
+    struct FailingDirectory {
+        inner: RAMDirectory,
+        fail_meta_writes: Arc<AtomicBool>,
+        fail_file_writes: Arc<AtomicBool>,
+    }
+
+    impl Directory for FailingDirectory {
+        fn get_file_handle(&self, path: &Path) -> io::Result<Box<dyn FileHandle>> {
+            self.inner.get_file_handle(path)
+        }
+
+        fn exists(&self, path: &Path) -> io::Result<bool> {
+            self.inner.exists(path)
+        }
+
+        fn delete(&self, path: &Path) -> io::Result<()> {
+            self.inner.delete(path)
+        }
+
+        fn list_files(&self) -> io::Result<Vec<PathBuf>> {
+            self.inner.list_files()
+        }
+
+        fn open_write(&self, path: &Path) -> io::Result<WritePtr> {
+            if self.fail_file_writes.load(Ordering::SeqCst) {
+                return Err(io::Error::other("injected failure"));
+            }
+            self.inner.open_write(path)
+        }
+
+        fn atomic_read(&self, path: &Path) -> io::Result<Vec<u8>> {
+            self.inner.atomic_read(path)
+        }
+
+        fn atomic_write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+            if path == Path::new(META_FILEPATH) && self.fail_meta_writes.load(Ordering::SeqCst) {
+                return Err(io::Error::other("injected failure"));
+            }
+            self.inner.atomic_write(path, data)
+        }
+
+        fn watch(&self, watch_callback: WatchCallback) -> io::Result<WatchHandle> {
+            self.inner.watch(watch_callback)
+        }
+
+        fn acquire_lock(&self, lock: &Lock) -> Result<DirectoryLock, LockError> {
+            self.inner.acquire_lock(lock)
+        }
+    }
+
+    #[test]
+    fn test_failed_commit_can_be_retried() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let directory = FailingDirectory::default();
+        let index = Index::create(directory.clone(), schema_builder.build())?;
+        let mut index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+        index_writer.add_document(doc!(text => "committed"))?;
+        let commit_opstamp = index_writer.commit()?;
+
+        index_writer.add_document(doc!(text => "retried"))?;
+        directory.fail_meta_writes.store(true, Ordering::SeqCst);
+        assert!(index_writer.commit().is_err());
+        assert_eq!(index_writer.commit_opstamp(), commit_opstamp);
+        assert_eq!(num_docs(&index)?, 1);
+        // the indexing threads are running again.
+        index_writer.add_document(doc!(text => "added after the failure"))?;
+        directory.fail_meta_writes.store(false, Ordering::SeqCst);
+        assert!(index_writer.commit()? > commit_opstamp);
+        assert_eq!(num_docs(&index)?, 3);
+        assert_eq!(
+            doc_freq(&index, &Term::from_field_text(text, "retried"))?,
+            1
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_commit_keeps_pending_deletes() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_text_field("id", STRING);
+        let directory = FailingDirectory::default();
+        let index = Index::create(directory.clone(), schema_builder.build())?;
+        let mut index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+        index_writer.add_document(doc!(id => "a"))?;
+        index_writer.add_document(doc!(id => "b"))?;
+        index_writer.commit()?;
+
+        index_writer.delete_term(Term::from_field_text(id, "a"));
+        directory.fail_meta_writes.store(true, Ordering::SeqCst);
+        assert!(index_writer.commit().is_err());
+        directory.fail_meta_writes.store(false, Ordering::SeqCst);
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let count = |term: &str| {
+            TermQuery::new(Term::from_field_text(id, term), IndexRecordOption::Basic)
+                .count(&searcher)
+        };
+        assert_eq!(count("a")?, 0);
+        assert_eq!(count("b")?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_indexing_thread_requires_rollback() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let directory = FailingDirectory::default();
+        let index = Index::create(directory.clone(), schema_builder.build())?;
+        let mut index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+        index_writer.add_document(doc!(text => "committed"))?;
+        let commit_opstamp = index_writer.commit()?;
+
+        index_writer.add_document(doc!(text => "lost"))?;
+        // the indexing thread fails to flush its segment.
+        directory.fail_file_writes.store(true, Ordering::SeqCst);
+        assert!(index_writer.commit().is_err());
+        directory.fail_file_writes.store(false, Ordering::SeqCst);
+        index_writer.add_document(doc!(text => "added after the failure"))?;
+        assert!(matches!(
+            index_writer.commit(),
+            Err(MySearchError::ErrorInThread(_))
+        ));
+        assert_eq!(index_writer.commit_opstamp(), commit_opstamp);
+
+        assert_eq!(index_writer.rollback()?, commit_opstamp);
+        index_writer.add_document(doc!(text => "added after the rollback"))?;
+        index_writer.commit()?;
+        assert_eq!(num_docs(&index)?, 2);
+        assert_eq!(doc_freq(&index, &Term::from_field_text(text, "lost"))?, 0);
+        assert_eq!(
+            doc_freq(&index, &Term::from_field_text(text, "rollback"))?,
+            1
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_single_writer_per_index() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("text", TEXT);
+        let tempdir = tempfile::TempDir::new()?;
+        let index = Index::create_in_dir(tempdir.path(), schema_builder.build())?;
+        let index_writer = index.writer(MEMORY_ARENA_NUM_BYTES_MIN)?;
+        assert!(matches!(
+            index.writer(MEMORY_ARENA_NUM_BYTES_MIN),
+            Err(MySearchError::LockFailure(LockError::LockBusy))
+        ));
+        let other_index = Index::open_in_dir(tempdir.path())?;
+        assert!(matches!(
+            other_index.writer(MEMORY_ARENA_NUM_BYTES_MIN),
+            Err(MySearchError::LockFailure(LockError::LockBusy))
+        ));
+        drop(index_writer);
+        assert!(other_index.writer(MEMORY_ARENA_NUM_BYTES_MIN).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_writer_arguments() {
+        let mut schema_builder = Schema::builder();
+        let count = schema_builder.add_u64_field("count", INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        assert!(matches!(
+            index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN - 1),
+            Err(MySearchError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            index.writer_with_num_threads(0, MEMORY_ARENA_NUM_BYTES_MIN),
+            Err(MySearchError::InvalidArgument(_))
+        ));
+        let index_writer = index
+            .writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)
+            .unwrap();
+        assert!(matches!(
+            index_writer.add_document(doc!(count => "four")),
+            Err(MySearchError::SchemaError(_))
+        ));
+    }
+
+    fn count_term(index: &Index, term: Term) -> crate::Result<usize> {
+        let searcher = index.reader()?.searcher();
+        let query = TermQuery::new(term, IndexRecordOption::Basic);
+        assert_eq!(query.count(&searcher)?, searcher.search(&query, &Count)?);
+        searcher.search(&query, &Count)
+    }
+
+    #[test]
+    fn test_delete_term_only_affects_previous_documents() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_text_field("id", STRING);
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+        index_writer.add_document(doc!(id => "1", text => "committed"))?;
+        index_writer.add_document(doc!(id => "2", text => "committed"))?;
+        index_writer.commit()?;
+
+        index_writer.add_document(doc!(id => "1", text => "before"))?;
+        let delete_opstamp = index_writer.delete_term(Term::from_field_text(id, "1"));
+        let add_opstamp = index_writer.add_document(doc!(id => "1", text => "after"))?;
+        assert!(delete_opstamp < add_opstamp);
+        // nothing is deleted before the commit.
+        assert_eq!(count_term(&index, Term::from_field_text(id, "1"))?, 1);
+        index_writer.commit()?;
+
+        assert_eq!(index.reader()?.searcher().num_docs(), 2);
+        assert_eq!(count_term(&index, Term::from_field_text(id, "1"))?, 1);
+        assert_eq!(count_term(&index, Term::from_field_text(id, "2"))?, 1);
+        assert_eq!(count_term(&index, Term::from_field_text(text, "after"))?, 1);
+        assert_eq!(
+            count_term(&index, Term::from_field_text(text, "before"))?,
+            0
+        );
+        assert_eq!(
+            count_term(&index, Term::from_field_text(text, "committed"))?,
+            1
+        );
+        let segment_metas = index.searchable_segment_metas()?;
+        assert_eq!(
+            segment_metas
+                .iter()
+                .map(|segment_meta| segment_meta.num_deleted_docs())
+                .sum::<u32>(),
+            2
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_document() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_text_field("id", STRING);
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(2, 2 * MEMORY_ARENA_NUM_BYTES_MIN)?;
+        index_writer.add_document(doc!(id => "1", text => "old"))?;
+        index_writer.commit()?;
+        index_writer.update_document(
+            Term::from_field_text(id, "1"),
+            doc!(id => "1", text => "new"),
+        )?;
+        index_writer.update_document(
+            Term::from_field_text(id, "1"),
+            doc!(id => "1", text => "newer"),
+        )?;
+        index_writer.commit()?;
+
+        assert_eq!(index.reader()?.searcher().num_docs(), 1);
+        assert_eq!(count_term(&index, Term::from_field_text(text, "old"))?, 0);
+        assert_eq!(count_term(&index, Term::from_field_text(text, "new"))?, 0);
+        assert_eq!(count_term(&index, Term::from_field_text(text, "newer"))?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_delete_query_drops_fully_deleted_segments() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+        index_writer.add_document(doc!(text => "a"))?;
+        index_writer.add_document(doc!(text => "b"))?;
+        index_writer.commit()?;
+        index_writer.delete_query(Box::new(AllQuery));
+        index_writer.add_document(doc!(text => "c"))?;
+        index_writer.commit()?;
+
+        let segment_metas = index.searchable_segment_metas()?;
+        assert_eq!(segment_metas.len(), 1);
+        assert!(!segment_metas[0].has_deletes());
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.num_docs(), 1);
+        assert_eq!(searcher.search(&AllQuery, &Count)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_delete_files_are_versioned_by_commit() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_text_field("id", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+        for i in 0..3 {
+            index_writer.add_document(doc!(id => i.to_string()))?;
+        }
+        index_writer.commit()?;
+        let reader = index.reader()?;
+
+        index_writer.delete_term(Term::from_field_text(id, "0"));
+        let first_commit = index_writer.commit()?;
+        let first_meta = index.searchable_segment_metas()?[0].clone();
+        assert_eq!(first_meta.delete_opstamp(), Some(first_commit));
+        reader.reload()?;
+        let first_searcher = reader.searcher();
+
+        index_writer.delete_term(Term::from_field_text(id, "1"));
+        let second_commit = index_writer.commit()?;
+        let second_meta = index.searchable_segment_metas()?[0].clone();
+        assert_eq!(second_meta.delete_opstamp(), Some(second_commit));
+        assert_eq!(second_meta.num_docs(), 1);
+        let first_delete_file = first_meta.relative_path(SegmentComponent::Delete);
+        assert_ne!(
+            first_delete_file,
+            second_meta.relative_path(SegmentComponent::Delete)
+        );
+        // the previous searcher still reads its own version of the deletes.
+        assert_eq!(first_searcher.num_docs(), 2);
+        assert_eq!(first_searcher.search(&AllQuery, &Count)?, 2);
+        drop(first_searcher);
+        reader.reload()?;
+        index.garbage_collect_files()?;
+        assert!(!index.directory().exists(&first_delete_file)?);
+        assert_eq!(reader.searcher().search(&AllQuery, &Count)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rollback_discards_deletes() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_text_field("id", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+        index_writer.add_document(doc!(id => "1"))?;
+        index_writer.commit()?;
+        index_writer.delete_term(Term::from_field_text(id, "1"));
+        index_writer.rollback()?;
+        index_writer.commit()?;
+        assert_eq!(count_term(&index, Term::from_field_text(id, "1"))?, 1);
+        assert!(!index.searchable_segment_metas()?[0].has_deletes());
+        Ok(())
+    }
+
+    #[test]
+    fn test_background_merges() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+        let mut merge_policy = LogMergePolicy::default();
+        merge_policy.set_min_num_segments(3);
+        index_writer.set_merge_policy(Box::new(merge_policy));
+        for i in 0..6 {
+            index_writer.add_document(doc!(text => format!("hello doc{}", i)))?;
+            index_writer.commit()?;
+        }
+        index_writer.wait_merging_threads()?;
+
+        let segment_metas = index.searchable_segment_metas()?;
+        assert!(segment_metas.len() < 3);
+        assert_eq!(num_docs(&index)?, 6);
+        assert_eq!(count_term(&index, Term::from_field_text(text, "hello"))?, 6);
+        assert_eq!(count_term(&index, Term::from_field_text(text, "doc4"))?, 1);
+        // the files of the merged segments are gone.
+        let mut living_files = index.load_metas()?.list_files();
+        living_files.insert(Path::new(META_FILEPATH).to_owned());
+        index.garbage_collect_files()?;
+        let mut files = index.directory().list_files()?;
+        files.retain(|path| !living_files.contains(path));
+        assert_eq!(files, vec![Path::new(MANAGED_FILEPATH).to_owned()]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_merge_policy() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        for _ in 0..10 {
+            index_writer.add_document(doc!(text => "hello"))?;
+            index_writer.commit()?;
+        }
+        index_writer.wait_merging_threads()?;
+        assert_eq!(index.searchable_segment_metas()?.len(), 10);
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_invalid_segments() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+        index_writer.add_document(doc!(text => "hello"))?;
+        assert!(matches!(
+            index_writer.merge(&[]),
+            Err(MySearchError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            index_writer.merge(&[SegmentId::generate_random()]),
+            Err(MySearchError::InvalidArgument(_))
+        ));
+        Ok(())
+    }
+}
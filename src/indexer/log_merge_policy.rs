@@ -0,0 +1,169 @@
+use std::cmp::Reverse;
+
+use crate::{MergeCandidate, MergePolicy, SegmentMeta};
+
+const DEFAULT_LEVEL_LOG_SIZE: f64 = 0.75;
+const DEFAULT_MIN_LAYER_SIZE: u32 = 10_000;
+const DEFAULT_MIN_NUM_SEGMENTS_IN_MERGE: usize = 8;
+const DEFAULT_MAX_DOCS_BEFORE_MERGE: u32 = 10_000_000;
+
+/// Merge policy grouping the segments into levels of similar sizes.
+///
+/// Segments are sorted by decreasing number of documents. A level starts with
+/// the largest remaining segment and holds the segments whose log2 size is
+/// within `level_log_size` of it. Segments smaller than `min_layer_size` all
+/// count as being of that size. Each level with at least
+/// `min_num_segments` segments is merged.
+#[derive(Clone, Debug)]
+pub struct LogMergePolicy {
+    min_num_segments: usize,
+    max_docs_before_merge: u32,
+    min_layer_size: u32,
+    level_log_size: f64,
+}
+
+impl LogMergePolicy {
+    fn clip_min_size(&self, size: u32) -> u32 {
+        size.max(self.min_layer_size)
+    }
+
+    /// Sets the minimum number of segments of a merge.
+    pub fn set_min_num_segments(&mut self, min_num_segments: usize) {
+        self.min_num_segments = min_num_segments;
+    }
+
+    /// Sets the number of documents above which a segment is never merged.
+    pub fn set_max_docs_before_merge(&mut self, max_docs_before_merge: u32) {
+        self.max_docs_before_merge = max_docs_before_merge;
+    }
+
+    /// Sets the size under which all the segments belong to the same level.
+    pub fn set_min_layer_size(&mut self, min_layer_size: u32) {
+        self.min_layer_size = min_layer_size;
+    }
+
+    /// Sets the span of a level, in log2 of the number of documents.
+    pub fn set_level_log_size(&mut self, level_log_size: f64) {
+        self.level_log_size = level_log_size;
+    }
+}
+
+impl Default for LogMergePolicy {
+    fn default() -> LogMergePolicy {
+        LogMergePolicy {
+            min_num_segments: DEFAULT_MIN_NUM_SEGMENTS_IN_MERGE,
+            max_docs_before_merge: DEFAULT_MAX_DOCS_BEFORE_MERGE,
+            min_layer_size: DEFAULT_MIN_LAYER_SIZE,
+            level_log_size: DEFAULT_LEVEL_LOG_SIZE,
+        }
+    }
+}
+
+impl MergePolicy for LogMergePolicy {
+    fn compute_merge_candidates(&self, segments: &[SegmentMeta]) -> Vec<MergeCandidate> {
+        let mut size_sorted_segments: Vec<&SegmentMeta> = segments
+            .iter()
+            .filter(|segment_meta| segment_meta.num_docs() <= self.max_docs_before_merge)
+            .collect();
+        size_sorted_segments.sort_by_key(|segment_meta| Reverse(segment_meta.num_docs()));
+
+        let mut levels: Vec<Vec<&SegmentMeta>> = Vec::new();
+        let mut current_max_log_size = f64::MAX;
+        for segment_meta in size_sorted_segments {
+            let log_size = f64::from(self.clip_min_size(segment_meta.num_docs())).log2();
+            if levels.is_empty() || log_size < current_max_log_size - self.level_log_size {
+                current_max_log_size = log_size;
+                levels.push(Vec::new());
+            }
+            levels
+                .last_mut()
+                .expect("a level was just pushed")
+                .push(segment_meta);
+        }
+
+        levels
+            .into_iter()
+            .filter(|level| level.len() >= self.min_num_segments)
+            .map(|level| {
+                MergeCandidate(level.iter().map(|segment_meta| segment_meta.id()).collect())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{LogMergePolicy, MergePolicy, SegmentId, SegmentMeta};
+
+    fn segment_meta(num_docs: u32) -> SegmentMeta {
+        SegmentMeta::new(SegmentId::generate_random(), num_docs)
+    }
+
+    fn test_policy() -> LogMergePolicy {
+        let mut policy = LogMergePolicy::default();
+        policy.set_min_num_segments(3);
+        policy.set_min_layer_size(2);
+        policy
+    }
+
+    #[test]
+    fn test_log_merge_policy_empty() {
+        assert!(test_policy().compute_merge_candidates(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_log_merge_policy_not_enough_segments() {
+        let segments = vec![segment_meta(10), segment_meta(10)];
+        assert!(test_policy().compute_merge_candidates(&segments).is_empty());
+    }
+
+    #[test]
+    fn test_log_merge_policy_levels() {
+        let segments = vec![
+            segment_meta(10),
+            segment_meta(1_000),
+            segment_meta(9),
+            segment_meta(1_100),
+            segment_meta(11),
+            segment_meta(1_050),
+            segment_meta(100),
+        ];
+        let candidates = test_policy().compute_merge_candidates(&segments);
+        assert_eq!(candidates.len(), 2);
+        let ids = |indices: &[usize]| {
+            let mut ids: Vec<SegmentId> = indices.iter().map(|&i| segments[i].id()).collect();
+            ids.sort();
+            ids
+        };
+        let merged: Vec<Vec<SegmentId>> = candidates
+            .into_iter()
+            .map(|candidate| {
+                let mut ids = candidate.0;
+                ids.sort();
+                ids
+            })
+            .collect();
+        assert!(merged.contains(&ids(&[1, 3, 5])));
+        assert!(merged.contains(&ids(&[0, 2, 4])));
+    }
+
+    #[test]
+    fn test_log_merge_policy_small_segments_share_a_level() {
+        let segments: Vec<SegmentMeta> = (1..=8).map(segment_meta).collect();
+        let candidates = LogMergePolicy::default().compute_merge_candidates(&segments);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].0.len(), 8);
+    }
+
+    #[test]
+    fn test_log_merge_policy_skips_large_segments() {
+        let mut policy = test_policy();
+        policy.set_max_docs_before_merge(100);
+        let segments = vec![
+            segment_meta(1_000),
+            segment_meta(1_000),
+            segment_meta(1_000),
+        ];
+        assert!(policy.compute_merge_candidates(&segments).is_empty());
+    }
+}
@@ -0,0 +1,25 @@
+use std::fmt;
+
+use crate::{SegmentId, SegmentMeta};
+
+/// Segments that a `MergePolicy` wants merged together.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergeCandidate(pub Vec<SegmentId>);
+
+/// Decides which segments of an index get merged.
+///
+/// The policy is consulted after each commit and each merge, with the
+/// committed segments that are not already being merged.
+pub trait MergePolicy: Send + Sync + fmt::Debug {
+    fn compute_merge_candidates(&self, segments: &[SegmentMeta]) -> Vec<MergeCandidate>;
+}
+
+/// Merge policy that never merges anything.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoMergePolicy;
+
+impl MergePolicy for NoMergePolicy {
+    fn compute_merge_candidates(&self, _segments: &[SegmentMeta]) -> Vec<MergeCandidate> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,389 @@
+use crate::{
+    Cardinality, DocId, DocSet, FastFieldSerializer, Field, FieldNormsSerializer, FieldNormsWriter,
+    FieldType, InvertedIndexReader, InvertedIndexSerializer, Postings, Schema, Segment,
+    SegmentComponent, SegmentReader, StoreWriter, TermMerger, TERMINATED,
+};
+
+/// Maps the doc ids of the merged segments to the doc ids of the new segment.
+pub struct DocIdMapping {
+    new_doc_ids: Vec<Vec<Option<DocId>>>,
+}
+
+impl DocIdMapping {
+    fn for_readers(readers: &[SegmentReader]) -> DocIdMapping {
+        let mut new_doc = 0u32;
+        let new_doc_ids = readers
+            .iter()
+            .map(|reader| {
+                (0..reader.max_doc())
+                    .map(|doc| {
+                        if reader.is_deleted(doc) {
+                            None
+                        } else {
+                            new_doc += 1;
+                            Some(new_doc - 1)
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        DocIdMapping { new_doc_ids }
+    }
+
+    /// Returns the new doc id of the document `doc` of the `segment_ord`-th
+    /// merged segment, or `None` if it was deleted.
+    #[inline]
+    pub fn get(&self, segment_ord: usize, doc: DocId) -> Option<DocId> {
+        self.new_doc_ids[segment_ord][doc as usize]
+    }
+
+    /// Returns the old doc ids of the `segment_ord`-th merged segment that
+    /// made it into the new segment.
+    fn alive_docs(&self, segment_ord: usize) -> impl Iterator<Item = DocId> + '_ {
+        self.new_doc_ids[segment_ord]
+            .iter()
+            .enumerate()
+            .filter(|(_, new_doc)| new_doc.is_some())
+            .map(|(doc, _)| doc as DocId)
+    }
+}
+
+/// Merges several segments into a new one.
+///
+/// Deleted documents are dropped and the remaining ones are renumbered in
+/// order: the documents of the first segment come first, then the ones of the
+/// second segment, and so on. Every component of the segments is merged:
+/// terms, postings, positions, fieldnorms, fast fields and doc store.
+pub struct IndexMerger {
+    schema: Schema,
+    readers: Vec<SegmentReader>,
+    doc_id_mapping: DocIdMapping,
+    max_doc: DocId,
+}
+
+impl IndexMerger {
+    pub fn open(schema: Schema, segments: &[Segment]) -> crate::Result<IndexMerger> {
+        let readers = segments
+            .iter()
+            .map(SegmentReader::open)
+            .collect::<crate::Result<Vec<_>>>()?;
+        let max_doc = readers.iter().map(SegmentReader::num_docs).sum();
+        let doc_id_mapping = DocIdMapping::for_readers(&readers);
+        Ok(IndexMerger {
+            schema,
+            readers,
+            doc_id_mapping,
+            max_doc,
+        })
+    }
+
+    /// Number of documents of the merged segment.
+    pub fn max_doc(&self) -> DocId {
+        self.max_doc
+    }
+
+    pub fn doc_id_mapping(&self) -> &DocIdMapping {
+        &self.doc_id_mapping
+    }
+
+    /// Writes the merged segment into `segment` and returns it with its
+    /// number of documents updated.
+    pub fn write(&self, segment: Segment) -> crate::Result<Segment> {
+        self.write_fieldnorms(FieldNormsSerializer::from_write(
+            segment.open_write(SegmentComponent::FieldNorms)?,
+        ))?;
+        self.write_fast_fields(FastFieldSerializer::from_write(
+            segment.open_write(SegmentComponent::FastFields)?,
+        ))?;
+        let mut serializer = InvertedIndexSerializer::open(&segment)?;
+        self.write_postings(&mut serializer)?;
+        serializer.close()?;
+        self.write_store(StoreWriter::new(
+            segment.open_write(SegmentComponent::Store)?,
+            segment.index().settings().docstore_compression,
+        ))?;
+        Ok(segment.with_max_doc(self.max_doc))
+    }
+
+    fn write_fieldnorms(&self, mut serializer: FieldNormsSerializer) -> crate::Result<()> {
+        let mut fieldnorm_ids = Vec::with_capacity(self.max_doc as usize);
+        for field in FieldNormsWriter::fields_with_fieldnorm(&self.schema) {
+            fieldnorm_ids.clear();
+            let mut total_num_tokens = 0u64;
+            for (segment_ord, reader) in self.readers.iter().enumerate() {
+                let fieldnorm_reader = reader.get_fieldnorms_reader(field)?;
+                let has_deletes = reader.alive_bitset().is_some();
+                if !has_deletes {
+                    total_num_tokens += fieldnorm_reader.total_num_tokens();
+                }
+                for doc in self.doc_id_mapping.alive_docs(segment_ord) {
+                    fieldnorm_ids.push(fieldnorm_reader.fieldnorm_id(doc));
+                    // the exact token count of the deleted documents is lost,
+                    // the alive ones are summed from their fieldnorms instead.
+                    if has_deletes {
+                        total_num_tokens += u64::from(fieldnorm_reader.fieldnorm(doc));
+                    }
+                }
+            }
+            serializer.serialize_field(field, total_num_tokens, &fieldnorm_ids)?;
+        }
+        serializer.close()?;
+        Ok(())
+    }
+
+    fn write_fast_fields(&self, mut serializer: FastFieldSerializer) -> crate::Result<()> {
+        for (field, field_entry) in self.schema.fields() {
+            match field_entry.field_type() {
+                FieldType::U64(int_options)
+                | FieldType::I64(int_options)
+                | FieldType::F64(int_options)
+                | FieldType::Date(int_options) => match int_options.get_fastfield_cardinality() {
+                    Some(Cardinality::SingleValue) => {
+                        self.write_single_value_fast_field(field, &mut serializer)?
+                    }
+                    Some(Cardinality::MultiValues) => {
+                        self.write_multi_values_fast_field(field, &mut serializer)?
+                    }
+                    None => {}
+                },
+                FieldType::Bytes(bytes_options) if bytes_options.is_fast() => {
+                    self.write_bytes_fast_field(field, &mut serializer)?
+                }
+                _ => {}
+            }
+        }
+        serializer.close()?;
+        Ok(())
+    }
+
+    fn write_single_value_fast_field(
+        &self,
+        field: Field,
+        serializer: &mut FastFieldSerializer,
+    ) -> crate::Result<()> {
+        let mut vals = Vec::with_capacity(self.max_doc as usize);
+        for (segment_ord, reader) in self.readers.iter().enumerate() {
+            let fast_field_reader = reader.fast_fields().u64_lenient(field)?;
+            vals.extend(
+                self.doc_id_mapping
+                    .alive_docs(segment_ord)
+                    .map(|doc| fast_field_reader.get(doc)),
+            );
+        }
+        serializer.write_single_value(field, &vals)?;
+        Ok(())
+    }
+
+    fn write_multi_values_fast_field(
+        &self,
+        field: Field,
+        serializer: &mut FastFieldSerializer,
+    ) -> crate::Result<()> {
+        let mut doc_offsets = Vec::with_capacity(self.max_doc as usize + 1);
+        doc_offsets.push(0u64);
+        let mut vals = Vec::new();
+        let mut doc_vals = Vec::new();
+        for (segment_ord, reader) in self.readers.iter().enumerate() {
+            let fast_field_reader = reader.fast_fields().u64s_lenient(field)?;
+            for doc in self.doc_id_mapping.alive_docs(segment_ord) {
+                fast_field_reader.get_vals(doc, &mut doc_vals);
+                vals.extend_from_slice(&doc_vals);
+                doc_offsets.push(vals.len() as u64);
+            }
+        }
+        serializer.write_multi_values(field, &doc_offsets, &vals)?;
+        Ok(())
+    }
+
+    fn write_bytes_fast_field(
+        &self,
+        field: Field,
+        serializer: &mut FastFieldSerializer,
+    ) -> crate::Result<()> {
+        let mut doc_offsets = Vec::with_capacity(self.max_doc as usize + 1);
+        doc_offsets.push(0u64);
+        let mut bytes = Vec::new();
+        for (segment_ord, reader) in self.readers.iter().enumerate() {
+            let bytes_reader = reader.fast_fields().bytes(field)?;
+            for doc in self.doc_id_mapping.alive_docs(segment_ord) {
+                bytes.extend_from_slice(bytes_reader.get_bytes(doc));
+                doc_offsets.push(bytes.len() as u64);
+            }
+        }
+        serializer.write_bytes(field, &doc_offsets, &bytes)?;
+        Ok(())
+    }
+
+    fn write_postings(&self, serializer: &mut InvertedIndexSerializer) -> crate::Result<()> {
+        let mut positions = Vec::new();
+        for (field, field_entry) in self.schema.fields() {
+            let record_option = match field_entry.field_type().get_index_record_option() {
+                Some(record_option) => record_option,
+                None => continue,
+            };
+            let inverted_indexes: Vec<InvertedIndexReader> = self
+                .readers
+                .iter()
+                .map(|reader| reader.inverted_index(field))
+                .collect();
+            let mut term_merger = TermMerger::new(
+                inverted_indexes
+                    .iter()
+                    .map(|inverted_index| inverted_index.field_terms().into_stream())
+                    .collect(),
+            );
+            let mut field_serializer = serializer.new_field(field, record_option);
+            while term_merger.advance() {
+                // a term is only written if one of its documents is alive.
+                let mut term_open = false;
+                for (segment_ord, term_info) in term_merger.current_segment_ords_and_term_infos() {
+                    let mut postings = inverted_indexes[segment_ord]
+                        .read_postings_from_terminfo(&term_info, record_option);
+                    while postings.doc() != TERMINATED {
+                        if let Some(new_doc) = self.doc_id_mapping.get(segment_ord, postings.doc())
+                        {
+                            if !term_open {
+                                field_serializer.new_term(term_merger.key())?;
+                                term_open = true;
+                            }
+                            postings.positions(&mut positions);
+                            field_serializer.write_doc(
+                                new_doc,
+                                postings.term_freq(),
+                                &positions,
+                            )?;
+                        }
+                        postings.advance();
+                    }
+                }
+                field_serializer.close_term()?;
+            }
+            field_serializer.close()?;
+        }
+        Ok(())
+    }
+
+    fn write_store(&self, mut store_writer: StoreWriter) -> crate::Result<()> {
+        for (segment_ord, reader) in self.readers.iter().enumerate() {
+            let store_reader = reader.get_store_reader()?;
+            for (doc, document) in store_reader.iter().enumerate() {
+                let document = document?;
+                if self.doc_id_mapping.get(segment_ord, doc as DocId).is_some() {
+                    store_writer.store(&document)?;
+                }
+            }
+        }
+        store_writer.close()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Cardinality, Count, Document, Index, IndexRecordOption, IntOptions, NoMergePolicy,
+        PhraseQuery, Query, Schema, SegmentReader, Term, TermQuery, Value, FAST, INDEXED, STORED,
+        STRING, TEXT,
+    };
+    use crate::{DocAddress, MEMORY_ARENA_NUM_BYTES_MIN};
+
+    #[test]
+    fn test_merge_drops_deleted_documents() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_text_field("id", STRING | STORED);
+        let text = schema_builder.add_text_field("text", TEXT | STORED);
+        let score = schema_builder.add_u64_field("score", INDEXED | FAST);
+        let tags = schema_builder.add_i64_field(
+            "tags",
+            IntOptions::default().set_fast(Cardinality::MultiValues),
+        );
+        let payload = schema_builder.add_bytes_field("payload", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        index_writer.add_document(doc!(
+            id => "a", text => "the quick brown fox", score => 1u64,
+            tags => 1i64, tags => -1i64, payload => b"a".to_vec()
+        ))?;
+        index_writer.add_document(doc!(
+            id => "b", text => "the lazy dog", score => 2u64, payload => b"b".to_vec()
+        ))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(
+            id => "c", text => "a quick brown dog", score => 3u64, tags => 3i64
+        ))?;
+        index_writer.add_document(doc!(
+            id => "d", text => "quick quick fox", score => 4u64, payload => b"dd".to_vec()
+        ))?;
+        index_writer.commit()?;
+        index_writer.delete_term(Term::from_field_text(id, "b"));
+        index_writer.commit()?;
+
+        let segment_ids: Vec<_> = index
+            .searchable_segment_metas()?
+            .iter()
+            .map(|segment_meta| segment_meta.id())
+            .collect();
+        assert_eq!(segment_ids.len(), 2);
+        let merged_meta = index_writer.merge(&segment_ids)?;
+        assert_eq!(merged_meta.max_doc(), 3);
+        assert!(!merged_meta.has_deletes());
+        assert_eq!(index.searchable_segment_metas()?, vec![merged_meta.clone()]);
+
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.num_docs(), 3);
+        let count = |query: &dyn Query| searcher.search(query, &Count).unwrap();
+        let term_query = |term| TermQuery::new(term, IndexRecordOption::WithFreqsAndPositions);
+        assert_eq!(count(&term_query(Term::from_field_text(text, "quick"))), 3);
+        assert_eq!(count(&term_query(Term::from_field_text(text, "lazy"))), 0);
+        assert_eq!(count(&term_query(Term::from_field_text(text, "dog"))), 1);
+        assert_eq!(count(&term_query(Term::from_field_u64(score, 4))), 1);
+        assert_eq!(count(&term_query(Term::from_field_u64(score, 2))), 0);
+        let phrase = PhraseQuery::new(vec![
+            Term::from_field_text(text, "quick"),
+            Term::from_field_text(text, "brown"),
+        ]);
+        assert_eq!(count(&phrase), 2);
+        let phrase = PhraseQuery::new(vec![
+            Term::from_field_text(text, "quick"),
+            Term::from_field_text(text, "fox"),
+        ]);
+        assert_eq!(count(&phrase), 1);
+
+        let ids: Vec<String> = (0..3)
+            .map(|doc_id| {
+                let doc: Document = searcher.doc(DocAddress::new(0, doc_id)).unwrap();
+                doc.get_first(id).and_then(Value::text).unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(ids, vec!["a", "c", "d"]);
+
+        let segment_reader: &SegmentReader = searcher.segment_reader(0);
+        let fieldnorms = segment_reader.get_fieldnorms_reader(text)?;
+        assert_eq!(
+            (0..3)
+                .map(|doc| fieldnorms.fieldnorm(doc))
+                .collect::<Vec<_>>(),
+            vec![4, 4, 3]
+        );
+        assert_eq!(fieldnorms.total_num_tokens(), 11);
+        let scores = segment_reader.fast_fields().u64(score)?;
+        assert_eq!(
+            (0..3).map(|doc| scores.get(doc)).collect::<Vec<_>>(),
+            vec![1, 3, 4]
+        );
+        let tags_reader = segment_reader.fast_fields().i64s(tags)?;
+        let mut vals = Vec::new();
+        tags_reader.get_vals(0, &mut vals);
+        assert_eq!(vals, vec![1, -1]);
+        tags_reader.get_vals(1, &mut vals);
+        assert_eq!(vals, vec![3]);
+        tags_reader.get_vals(2, &mut vals);
+        assert!(vals.is_empty());
+        let payload_reader = segment_reader.fast_fields().bytes(payload)?;
+        assert_eq!(payload_reader.get_bytes(0), b"a");
+        assert_eq!(payload_reader.get_bytes(1), b"");
+        assert_eq!(payload_reader.get_bytes(2), b"dd");
+        Ok(())
+    }
+}
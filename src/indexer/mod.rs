@@ -0,0 +1,17 @@
+mod index_writer;
+mod log_merge_policy;
+mod merge_policy;
+mod merger;
+mod operation;
+mod segment_updater;
+mod segment_writer;
+mod stamper;
+
+pub use index_writer::*;
+pub use log_merge_policy::*;
+pub use merge_policy::*;
+pub use merger::*;
+pub use operation::*;
+pub(crate) use segment_updater::*;
+pub use segment_writer::*;
+pub use stamper::*;
@@ -0,0 +1,13 @@
+use crate::{Document, Opstamp, Query};
+
+/// Document sent by the `IndexWriter` to its indexing workers.
+pub struct AddOperation {
+    pub opstamp: Opstamp,
+    pub document: Document,
+}
+
+/// Deletion of the documents matching `target`, added before `opstamp`.
+pub struct DeleteOperation {
+    pub opstamp: Opstamp,
+    pub target: Box<dyn Query>,
+}
@@ -0,0 +1,388 @@
+use std::collections::{HashMap, HashSet};
+use std::mem;
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::thread::{self, JoinHandle};
+
+use crate::{
+    AliveBitSet, BitSet, GarbageCollectionResult, Index, IndexMerger, LogMergePolicy, MergePolicy,
+    MySearchError, Segment, SegmentComponent, SegmentId, SegmentMeta,
+};
+
+/// Merges in progress and the threads running them.
+#[derive(Default)]
+struct MergeOperations {
+    /// Segments being merged into another one, as they were when the merge
+    /// started.
+    merged_segments: HashMap<SegmentId, SegmentMeta>,
+    /// Segments being written by a merge.
+    merge_targets: HashSet<SegmentId>,
+    join_handles: Vec<JoinHandle<crate::Result<()>>>,
+}
+
+struct InnerSegmentUpdater {
+    index: Index,
+    /// Held while a new `meta.json` is computed and published, so that commits
+    /// and merges never overwrite each other's changes.
+    meta_lock: Mutex<()>,
+    merge_policy: RwLock<Arc<dyn MergePolicy>>,
+    merge_operations: Mutex<MergeOperations>,
+}
+
+/// Publishes the changes of the `IndexWriter` to the list of segments of the
+/// index, and runs the merges asked by the `MergePolicy` in background
+/// threads.
+///
+/// Lock order: `meta_lock` first, then `merge_operations`.
+#[derive(Clone)]
+pub(crate) struct SegmentUpdater(Arc<InnerSegmentUpdater>);
+
+impl SegmentUpdater {
+    pub fn new(index: Index) -> SegmentUpdater {
+        SegmentUpdater(Arc::new(InnerSegmentUpdater {
+            index,
+            meta_lock: Mutex::default(),
+            merge_policy: RwLock::new(Arc::new(LogMergePolicy::default())),
+            merge_operations: Mutex::default(),
+        }))
+    }
+
+    /// Must be held while reading, updating and saving `meta.json`.
+    pub fn lock_meta(&self) -> MutexGuard<'_, ()> {
+        self.0.meta_lock.lock().unwrap()
+    }
+
+    pub fn get_merge_policy(&self) -> Arc<dyn MergePolicy> {
+        self.0.merge_policy.read().unwrap().clone()
+    }
+
+    pub fn set_merge_policy(&self, merge_policy: Box<dyn MergePolicy>) {
+        *self.0.merge_policy.write().unwrap() = Arc::from(merge_policy);
+    }
+
+    /// Deletes the files that neither the last commit nor a running merge
+    /// reference.
+    pub fn garbage_collect_files(&self) -> crate::Result<GarbageCollectionResult> {
+        let _meta_lock = self.lock_meta();
+        let merge_operations = self.0.merge_operations.lock().unwrap();
+        let merge_files = merge_operations
+            .merged_segments
+            .values()
+            .flat_map(SegmentMeta::list_files)
+            .chain(
+                merge_operations
+                    .merge_targets
+                    .iter()
+                    .flat_map(|&segment_id| SegmentMeta::new(segment_id, 0).list_files()),
+            )
+            .collect();
+        self.0.index.garbage_collect_files_except(merge_files)
+    }
+
+    /// Asks the merge policy which of the committed segments to merge, and
+    /// starts a merging thread for each candidate.
+    pub fn consider_merge_options(&self) -> crate::Result<()> {
+        let _meta_lock = self.lock_meta();
+        let mut merge_operations = self.0.merge_operations.lock().unwrap();
+        let segment_metas: HashMap<SegmentId, SegmentMeta> = self
+            .0
+            .index
+            .searchable_segment_metas()?
+            .into_iter()
+            .filter(|segment_meta| {
+                !merge_operations
+                    .merged_segments
+                    .contains_key(&segment_meta.id())
+            })
+            .map(|segment_meta| (segment_meta.id(), segment_meta))
+            .collect();
+        let mut candidate_metas: Vec<SegmentMeta> = segment_metas.values().cloned().collect();
+        candidate_metas.sort_by_key(|segment_meta| segment_meta.id());
+        let merge_candidates = self
+            .get_merge_policy()
+            .compute_merge_candidates(&candidate_metas);
+        for merge_candidate in merge_candidates {
+            let source_metas: Vec<SegmentMeta> = merge_candidate
+                .0
+                .iter()
+                .filter(|segment_id| !merge_operations.merged_segments.contains_key(segment_id))
+                .filter_map(|segment_id| segment_metas.get(segment_id).cloned())
+                .collect();
+            if source_metas.is_empty() {
+                continue;
+            }
+            let target = self.register_merge(&mut merge_operations, &source_metas);
+            let segment_updater = self.clone();
+            let join_handle = thread::Builder::new()
+                .name("merging-thread".to_string())
+                .spawn(move || {
+                    segment_updater.run_merge(source_metas, target)?;
+                    segment_updater.consider_merge_options()
+                })?;
+            merge_operations.join_handles.push(join_handle);
+        }
+        Ok(())
+    }
+
+    /// Merges the committed segments `segment_ids` in the calling thread, and
+    /// returns the meta of the new segment.
+    pub fn merge(&self, segment_ids: &[SegmentId]) -> crate::Result<SegmentMeta> {
+        if segment_ids.is_empty() {
+            return Err(MySearchError::InvalidArgument(
+                "a merge needs at least one segment".to_string(),
+            ));
+        }
+        let (source_metas, target) = {
+            let _meta_lock = self.lock_meta();
+            let mut merge_operations = self.0.merge_operations.lock().unwrap();
+            let segment_metas = self.0.index.searchable_segment_metas()?;
+            let source_metas = segment_ids
+                .iter()
+                .map(|segment_id| {
+                    if merge_operations.merged_segments.contains_key(segment_id) {
+                        return Err(MySearchError::InvalidArgument(format!(
+                            "segment {:?} is already being merged",
+                            segment_id
+                        )));
+                    }
+                    segment_metas
+                        .iter()
+                        .find(|segment_meta| segment_meta.id() == *segment_id)
+                        .cloned()
+                        .ok_or_else(|| {
+                            MySearchError::InvalidArgument(format!(
+                                "segment {:?} is not a committed segment",
+                                segment_id
+                            ))
+                        })
+                })
+                .collect::<crate::Result<Vec<_>>>()?;
+            let target = self.register_merge(&mut merge_operations, &source_metas);
+            (source_metas, target)
+        };
+        self.run_merge(source_metas, target)
+    }
+
+    /// Waits for all the merging threads, including the ones started by
+    /// merges finishing meanwhile, and returns the first error they hit.
+    pub fn wait_merging_threads(&self) -> crate::Result<()> {
+        let mut result = Ok(());
+        loop {
+            let join_handles = mem::take(&mut self.0.merge_operations.lock().unwrap().join_handles);
+            if join_handles.is_empty() {
+                return result;
+            }
+            for join_handle in join_handles {
+                let merge_result = join_handle.join().unwrap_or_else(|_| {
+                    Err(MySearchError::ErrorInThread(
+                        "a merging thread panicked".to_string(),
+                    ))
+                });
+                if result.is_ok() {
+                    result = merge_result;
+                }
+            }
+        }
+    }
+
+    fn register_merge(
+        &self,
+        merge_operations: &mut MergeOperations,
+        source_metas: &[SegmentMeta],
+    ) -> Segment {
+        let target = self.0.index.new_segment();
+        merge_operations.merged_segments.extend(
+            source_metas
+                .iter()
+                .map(|segment_meta| (segment_meta.id(), segment_meta.clone())),
+        );
+        merge_operations.merge_targets.insert(target.id());
+        target
+    }
+
+    fn run_merge(
+        &self,
+        source_metas: Vec<SegmentMeta>,
+        target: Segment,
+    ) -> crate::Result<SegmentMeta> {
+        let target_id = target.id();
+        let result = self.merge_and_publish(&source_metas, target);
+        let mut merge_operations = self.0.merge_operations.lock().unwrap();
+        for source_meta in &source_metas {
+            merge_operations.merged_segments.remove(&source_meta.id());
+        }
+        merge_operations.merge_targets.remove(&target_id);
+        result
+    }
+
+    fn merge_and_publish(
+        &self,
+        source_metas: &[SegmentMeta],
+        target: Segment,
+    ) -> crate::Result<SegmentMeta> {
+        let index = &self.0.index;
+        let source_segments: Vec<Segment> = source_metas
+            .iter()
+            .map(|segment_meta| index.segment(segment_meta.clone()))
+            .collect();
+        let merger = IndexMerger::open(index.schema(), &source_segments)?;
+        let merged_segment = merger.write(target)?;
+        self.publish_merge(source_metas, &merger, merged_segment)
+    }
+
+    /// Replaces the merged segments by `merged_segment` in `meta.json`.
+    fn publish_merge(
+        &self,
+        source_metas: &[SegmentMeta],
+        merger: &IndexMerger,
+        merged_segment: Segment,
+    ) -> crate::Result<SegmentMeta> {
+        let index = &self.0.index;
+        let _meta_lock = self.lock_meta();
+        let mut index_meta = index.load_metas()?;
+        // the commits that happened during the merge may have deleted some of
+        // the merged documents.
+        let mut alive_bitset: Option<BitSet> = None;
+        for (segment_ord, source_meta) in source_metas.iter().enumerate() {
+            let current_meta = index_meta
+                .segments
+                .iter()
+                .find(|segment_meta| segment_meta.id() == source_meta.id());
+            let current_alive_bitset = match current_meta {
+                Some(current_meta)
+                    if current_meta.delete_opstamp() == source_meta.delete_opstamp() =>
+                {
+                    continue
+                }
+                Some(current_meta) => Some(AliveBitSet::open(
+                    index
+                        .segment(current_meta.clone())
+                        .open_read(SegmentComponent::Delete)?,
+                )?),
+                // the segment was dropped, all its documents being deleted.
+                None => None,
+            };
+            let alive_bitset = alive_bitset.get_or_insert_with(|| BitSet::full(merger.max_doc()));
+            for doc in 0..source_meta.max_doc() {
+                let is_deleted = current_alive_bitset
+                    .as_ref()
+                    .map(|current_alive_bitset| current_alive_bitset.is_deleted(doc))
+                    .unwrap_or(true);
+                if is_deleted {
+                    if let Some(new_doc) = merger.doc_id_mapping().get(segment_ord, doc) {
+                        alive_bitset.remove(new_doc);
+                    }
+                }
+            }
+        }
+        let merged_segment = match alive_bitset {
+            Some(alive_bitset) if alive_bitset.len() < merger.max_doc() => {
+                let num_deleted_docs = merger.max_doc() - alive_bitset.len();
+                let merged_segment =
+                    merged_segment.with_delete_meta(num_deleted_docs, index_meta.opstamp);
+                AliveBitSet::write(
+                    &alive_bitset,
+                    merged_segment.open_write(SegmentComponent::Delete)?,
+                )?;
+                merged_segment
+            }
+            _ => merged_segment,
+        };
+
+        let merged_meta = merged_segment.meta().clone();
+        let source_ids: HashSet<SegmentId> = source_metas.iter().map(SegmentMeta::id).collect();
+        let position = index_meta
+            .segments
+            .iter()
+            .position(|segment_meta| source_ids.contains(&segment_meta.id()))
+            .unwrap_or(0);
+        index_meta
+            .segments
+            .retain(|segment_meta| !source_ids.contains(&segment_meta.id()));
+        if merged_meta.num_docs() > 0 {
+            index_meta.segments.insert(position, merged_meta.clone());
+        }
+        index_meta.save(index.directory())?;
+        Ok(merged_meta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SegmentUpdater;
+    use crate::{
+        Count, Index, NoMergePolicy, Schema, Term, TermQuery, MEMORY_ARENA_NUM_BYTES_MIN, STRING,
+    };
+    use crate::{IndexMerger, IndexRecordOption, Query, SegmentMeta};
+
+    fn open_merger(index: &Index, segment_metas: &[SegmentMeta]) -> crate::Result<IndexMerger> {
+        let segments: Vec<_> = segment_metas
+            .iter()
+            .map(|segment_meta| index.segment(segment_meta.clone()))
+            .collect();
+        IndexMerger::open(index.schema(), &segments)
+    }
+
+    #[test]
+    fn test_deletes_committed_during_merge_are_kept() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_text_field("id", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        for ids in &[["a", "b"], ["c", "d"]] {
+            for &doc_id in ids {
+                index_writer.add_document(doc!(id => doc_id))?;
+            }
+            index_writer.commit()?;
+        }
+        index_writer.delete_term(Term::from_field_text(id, "a"));
+        index_writer.commit()?;
+        // the merge starts from this state of the segments...
+        let source_metas = index.searchable_segment_metas()?;
+        let merger = open_merger(&index, &source_metas)?;
+        // ... while a commit deletes more documents.
+        index_writer.delete_term(Term::from_field_text(id, "b"));
+        index_writer.delete_term(Term::from_field_text(id, "c"));
+        index_writer.commit()?;
+
+        let merged_segment = merger.write(index.new_segment())?;
+        let segment_updater = SegmentUpdater::new(index.clone());
+        let merged_meta = segment_updater.publish_merge(&source_metas, &merger, merged_segment)?;
+        assert_eq!(merged_meta.max_doc(), 3);
+        assert_eq!(merged_meta.num_docs(), 1);
+        assert_eq!(index.searchable_segment_metas()?, vec![merged_meta]);
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.num_docs(), 1);
+        for (doc_id, expected_count) in &[("a", 0), ("b", 0), ("c", 0), ("d", 1)] {
+            let query = TermQuery::new(Term::from_field_text(id, doc_id), IndexRecordOption::Basic);
+            assert_eq!(searcher.search(&query, &Count)?, *expected_count);
+            assert_eq!(query.count(&searcher)?, *expected_count);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_of_a_segment_dropped_during_merge() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let id = schema_builder.add_text_field("id", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        index_writer.add_document(doc!(id => "a"))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(id => "b"))?;
+        index_writer.commit()?;
+        let source_metas = index.searchable_segment_metas()?;
+        let merger = open_merger(&index, &source_metas)?;
+        index_writer.delete_term(Term::from_field_text(id, "a"));
+        index_writer.commit()?;
+        assert_eq!(index.searchable_segment_metas()?.len(), 1);
+
+        let merged_segment = merger.write(index.new_segment())?;
+        let segment_updater = SegmentUpdater::new(index.clone());
+        let merged_meta = segment_updater.publish_merge(&source_metas, &merger, merged_segment)?;
+        assert_eq!(merged_meta.num_docs(), 1);
+        assert_eq!(index.searchable_segment_metas()?, vec![merged_meta]);
+        Ok(())
+    }
+}
@@ -0,0 +1,335 @@
+use crate::{
+    DocId, Document, FastFieldSerializer, FastFieldsWriter, FieldNormsSerializer, FieldNormsWriter,
+    FieldType, IndexingPosition, InvertedIndexSerializer, MultiFieldPostingsWriter, MySearchError,
+    Schema, Segment, SegmentComponent, StoreWriter, Term, TextAnalyzer, Value,
+};
+
+fn value_matches_field_type(value: &Value, field_type: &FieldType) -> bool {
+    matches!(
+        (value, field_type),
+        (Value::Str(_), FieldType::Str(_))
+            | (Value::U64(_), FieldType::U64(_))
+            | (Value::I64(_), FieldType::I64(_))
+            | (Value::F64(_), FieldType::F64(_))
+            | (Value::Date(_), FieldType::Date(_))
+            | (Value::Bytes(_), FieldType::Bytes(_))
+            | (Value::Facet(_), FieldType::HierarchicalFacet(_))
+    )
+}
+
+/// Checks that every value of `doc` matches the type of its field.
+pub(crate) fn validate_document(schema: &Schema, doc: &Document) -> crate::Result<()> {
+    for field_value in doc.field_values() {
+        let field_entry = schema.get_field_entry(field_value.field());
+        if !value_matches_field_type(field_value.value(), field_entry.field_type()) {
+            return Err(MySearchError::SchemaError(format!(
+                "expected a {} value for field '{}', got {:?}",
+                field_entry.field_type().type_name(),
+                field_entry.name(),
+                field_value.value()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Builds a segment in memory, document after document.
+///
+/// Text fields go through the `TextAnalyzer` of their field, other indexed fields
+/// produce one term per value. The number of tokens of each text field is
+/// recorded as its fieldnorm. Fast fields are accumulated as columns and the
+/// stored fields go to the doc store.
+pub struct SegmentWriter {
+    max_doc: DocId,
+    multifield_postings: MultiFieldPostingsWriter,
+    fieldnorms_writer: FieldNormsWriter,
+    fast_field_writers: FastFieldsWriter,
+    store_writer: StoreWriter,
+    segment: Segment,
+    schema: Schema,
+    tokenizers: Vec<Option<TextAnalyzer>>,
+    term_buffer: Term,
+}
+
+impl SegmentWriter {
+    pub fn for_segment(segment: Segment) -> crate::Result<SegmentWriter> {
+        let schema = segment.schema();
+        let tokenizers = schema
+            .fields()
+            .map(|(field, field_entry)| match field_entry.field_type() {
+                FieldType::Str(text_options) if text_options.get_indexing_options().is_some() => {
+                    segment.index().tokenizer_for_field(field).map(Some)
+                }
+                _ => Ok(None),
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+        let store_writer = StoreWriter::new(
+            segment.open_write(SegmentComponent::Store)?,
+            segment.index().settings().docstore_compression,
+        );
+        Ok(SegmentWriter {
+            max_doc: 0,
+            multifield_postings: MultiFieldPostingsWriter::new(&schema),
+            fieldnorms_writer: FieldNormsWriter::for_schema(&schema),
+            fast_field_writers: FastFieldsWriter::from_schema(&schema),
+            store_writer,
+            segment,
+            schema,
+            tokenizers,
+            term_buffer: Term::for_field(crate::Field::from_field_id(0)),
+        })
+    }
+
+    pub fn max_doc(&self) -> DocId {
+        self.max_doc
+    }
+
+    pub fn mem_usage(&self) -> usize {
+        self.multifield_postings.mem_usage() + self.fast_field_writers.mem_usage()
+    }
+
+    /// Indexes a document and assigns it the next doc id.
+    ///
+    /// Fails without indexing anything if a value does not match its field type.
+    pub fn add_document(&mut self, doc: &Document) -> crate::Result<DocId> {
+        validate_document(&self.schema, doc)?;
+
+        let doc_id = self.max_doc;
+        for (field, values) in doc.get_sorted_field_values() {
+            let field_entry = self.schema.get_field_entry(field);
+            if !field_entry.is_indexed() {
+                continue;
+            }
+            let term_buffer = &mut self.term_buffer;
+            term_buffer.set_field(field);
+            match field_entry.field_type() {
+                FieldType::Str(_) => {
+                    let text_analyzer = self.tokenizers[field.field_id() as usize]
+                        .as_ref()
+                        .expect("indexed text fields have an analyzer");
+                    let mut indexing_position = IndexingPosition::default();
+                    for text in values.iter().flat_map(|value| value.text()) {
+                        let mut token_stream = text_analyzer.token_stream(text);
+                        self.multifield_postings.index_text(
+                            doc_id,
+                            field,
+                            &mut token_stream,
+                            term_buffer,
+                            &mut indexing_position,
+                        );
+                    }
+                    self.fieldnorms_writer
+                        .record(doc_id, field, indexing_position.num_tokens);
+                }
+                FieldType::HierarchicalFacet(_) => {
+                    for facet in values.iter().flat_map(|value| value.facet()) {
+                        // a facet matches the queries on all of its ancestors.
+                        let path = facet.to_path();
+                        for depth in 1..=path.len() {
+                            term_buffer.set_text(&path[..depth].join("\u{0}"));
+                            self.multifield_postings.subscribe(doc_id, term_buffer);
+                        }
+                    }
+                }
+                _ => {
+                    for value in values {
+                        match value {
+                            Value::U64(val) => term_buffer.set_u64(*val),
+                            Value::I64(val) => term_buffer.set_i64(*val),
+                            Value::F64(val) => term_buffer.set_f64(*val),
+                            Value::Date(date) => term_buffer.set_i64(date.timestamp()),
+                            Value::Bytes(bytes) => term_buffer.set_bytes(bytes),
+                            Value::Str(_) | Value::Facet(_) => unreachable!(),
+                        }
+                        self.multifield_postings.subscribe(doc_id, term_buffer);
+                    }
+                }
+            }
+        }
+        self.fast_field_writers.add_document(doc);
+        let mut stored_doc = doc.clone();
+        stored_doc.filter_fields(|field| self.schema.get_field_entry(field).is_stored());
+        self.store_writer.store(&stored_doc)?;
+        self.max_doc += 1;
+        Ok(doc_id)
+    }
+
+    /// Serializes the segment files and returns the segment with its final doc count.
+    pub fn finalize(mut self) -> crate::Result<Segment> {
+        self.fieldnorms_writer.fill_up_to_max_doc(self.max_doc);
+        let fieldnorms_serializer = FieldNormsSerializer::from_write(
+            self.segment.open_write(SegmentComponent::FieldNorms)?,
+        );
+        self.fieldnorms_writer.serialize(fieldnorms_serializer)?;
+        let fast_field_serializer =
+            FastFieldSerializer::from_write(self.segment.open_write(SegmentComponent::FastFields)?);
+        self.fast_field_writers.serialize(fast_field_serializer)?;
+        let mut serializer = InvertedIndexSerializer::open(&self.segment)?;
+        self.multifield_postings.serialize(&mut serializer)?;
+        serializer.close()?;
+        self.store_writer.close()?;
+        Ok(self.segment.with_max_doc(self.max_doc))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        DocSet, Facet, Index, IndexRecordOption, MySearchError, Postings, Schema, SegmentComponent,
+        SegmentPostings, SegmentWriter, Term, TermDictionary, TermInfo, TextFieldIndexing,
+        TextOptions, FAST, INDEXED, STRING, TERMINATED, TEXT,
+    };
+
+    fn read_postings(
+        segment: &crate::Segment,
+        term_info: &TermInfo,
+        record_option: IndexRecordOption,
+    ) -> Vec<(u32, u32, Vec<u32>)> {
+        let postings = segment
+            .open_read(SegmentComponent::Postings)
+            .unwrap()
+            .read_bytes()
+            .unwrap();
+        let positions = segment
+            .open_read(SegmentComponent::Positions)
+            .unwrap()
+            .read_bytes()
+            .unwrap();
+        let mut segment_postings = SegmentPostings::open(
+            term_info.doc_freq,
+            postings.slice(term_info.postings_offset as usize..postings.len()),
+            record_option,
+            Some(positions.slice(term_info.positions_offset as usize..positions.len())),
+        );
+        let mut result = Vec::new();
+        while segment_postings.doc() != TERMINATED {
+            let term_freq = if record_option.has_freq() {
+                segment_postings.term_freq()
+            } else {
+                0
+            };
+            let mut doc_positions = Vec::new();
+            segment_postings.positions(&mut doc_positions);
+            result.push((segment_postings.doc(), term_freq, doc_positions));
+            segment_postings.advance();
+        }
+        result
+    }
+
+    #[test]
+    fn test_segment_writer_text_postings() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let body = schema_builder.add_text_field("body", TEXT);
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let freq_only = schema_builder.add_text_field(
+            "freq_only",
+            TextOptions::default().set_indexing_options(
+                TextFieldIndexing::default().set_index_option(IndexRecordOption::WithFreqs),
+            ),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+
+        let mut segment_writer = SegmentWriter::for_segment(index.new_segment())?;
+        segment_writer.add_document(&doc!(
+            body => "a b a",
+            tag => "Hello World",
+            freq_only => "x x x",
+        ))?;
+        segment_writer.add_document(&doc!(body => "c"))?;
+        segment_writer.add_document(&doc!(body => "b c", body => "a"))?;
+        let segment = segment_writer.finalize()?;
+        assert_eq!(segment.meta().max_doc(), 3);
+
+        let term_dictionary = TermDictionary::open(segment.open_read(SegmentComponent::Terms)?)?;
+        assert_eq!(term_dictionary.num_terms(), 5);
+
+        let with_positions = IndexRecordOption::WithFreqsAndPositions;
+        let term_a = term_dictionary
+            .get(Term::from_field_text(body, "a"))
+            .unwrap();
+        assert_eq!(
+            read_postings(&segment, &term_a, with_positions),
+            vec![(0, 2, vec![0, 2]), (2, 1, vec![3])]
+        );
+        let term_c = term_dictionary
+            .get(Term::from_field_text(body, "c"))
+            .unwrap();
+        assert_eq!(
+            read_postings(&segment, &term_c, with_positions),
+            vec![(1, 1, vec![0]), (2, 1, vec![1])]
+        );
+        let term_tag = term_dictionary
+            .get(Term::from_field_text(tag, "Hello World"))
+            .unwrap();
+        assert_eq!(
+            read_postings(&segment, &term_tag, IndexRecordOption::Basic),
+            vec![(0, 0, vec![])]
+        );
+        let term_x = term_dictionary
+            .get(Term::from_field_text(freq_only, "x"))
+            .unwrap();
+        assert_eq!(
+            read_postings(&segment, &term_x, IndexRecordOption::WithFreqs),
+            vec![(0, 3, vec![])]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_segment_writer_non_text_fields() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let count = schema_builder.add_u64_field("count", INDEXED);
+        let delta = schema_builder.add_i64_field("delta", INDEXED | FAST);
+        let not_indexed = schema_builder.add_u64_field("not_indexed", FAST);
+        let category = schema_builder.add_facet_field("category", INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+
+        let mut segment_writer = SegmentWriter::for_segment(index.new_segment())?;
+        segment_writer.add_document(&doc!(
+            count => 4u64,
+            delta => -3i64,
+            not_indexed => 7u64,
+            category => Facet::from_text("/a/b").unwrap(),
+        ))?;
+        segment_writer.add_document(&doc!(count => 4u64, count => 5u64))?;
+        let segment = segment_writer.finalize()?;
+
+        let term_dictionary = TermDictionary::open(segment.open_read(SegmentComponent::Terms)?)?;
+        assert_eq!(
+            term_dictionary
+                .get(Term::from_field_u64(count, 4))
+                .map(|term_info| term_info.doc_freq),
+            Some(2)
+        );
+        assert!(term_dictionary
+            .get(Term::from_field_i64(delta, -3))
+            .is_some());
+        assert!(term_dictionary
+            .get(Term::from_field_u64(not_indexed, 7))
+            .is_none());
+        assert!(term_dictionary
+            .get(Term::from_facet(category, &Facet::from_text("/a").unwrap()))
+            .is_some());
+        assert!(term_dictionary
+            .get(Term::from_facet(
+                category,
+                &Facet::from_text("/a/b").unwrap()
+            ))
+            .is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_segment_writer_rejects_type_mismatch() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let count = schema_builder.add_u64_field("count", INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut segment_writer = SegmentWriter::for_segment(index.new_segment())?;
+        assert!(matches!(
+            segment_writer.add_document(&doc!(count => "four")),
+            Err(MySearchError::SchemaError(_))
+        ));
+        assert_eq!(segment_writer.max_doc(), 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::Opstamp;
+
+/// Hands out increasing operation stamps, shared by the clones of the stamper.
+///
+/// Opstamps order the operations of an `IndexWriter` with respect to each other
+/// and to commits.
+#[derive(Clone, Default)]
+pub struct Stamper(Arc<AtomicU64>);
+
+impl Stamper {
+    /// Creates a stamper whose first stamp is `first_opstamp`.
+    pub fn new(first_opstamp: Opstamp) -> Stamper {
+        Stamper(Arc::new(AtomicU64::new(first_opstamp)))
+    }
+
+    pub fn stamp(&self) -> Opstamp {
+        self.0.fetch_add(1u64, Ordering::SeqCst)
+    }
+
+    /// Reserves `n` consecutive opstamps.
+    pub fn stamps(&self, n: u64) -> Range<Opstamp> {
+        let start = self.0.fetch_add(n, Ordering::SeqCst);
+        start..start + n
+    }
+
+    /// Makes `to_opstamp` the next stamp handed out.
+    pub fn revert(&self, to_opstamp: Opstamp) {
+        self.0.store(to_opstamp, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stamper;
+
+    #[test]
+    fn test_stamper() {
+        let stamper = Stamper::new(7);
+        let stamper_clone = stamper.clone();
+        assert_eq!(stamper.stamp(), 7);
+        assert_eq!(stamper_clone.stamp(), 8);
+        assert_eq!(stamper.stamps(3), 9..12);
+        stamper.revert(8);
+        assert_eq!(stamper_clone.stamp(), 8);
+    }
+}
@@ -1,7 +1,69 @@
+#![allow(clippy::module_inception)]
+
+#[macro_use]
+mod macros;
+
+mod collector;
+pub(crate) mod common;
 mod core;
 mod directory;
+mod docset;
+mod error;
+mod fastfield;
+mod fieldnorm;
+mod indexer;
+mod postings;
+mod query;
+mod query_parser;
+mod reader;
+mod schema;
+mod store;
+mod termdict;
 mod tokenizer;
 
 pub use crate::core::*;
+pub use collector::*;
+pub use common::{BinarySerializable, BitSet, VInt};
 pub use directory::*;
+pub use docset::*;
+pub use error::*;
+pub use fastfield::*;
+pub use fieldnorm::*;
+pub use indexer::*;
+pub use postings::*;
+pub use query::*;
+pub use query_parser::*;
+pub use reader::*;
+pub use schema::*;
+pub use store::*;
+pub use termdict::*;
 pub use tokenizer::*;
+
+pub type Result<T> = std::result::Result<T, MySearchError>;
+
+pub type Opstamp = u64;
+
+/// Identifier of a document within a segment.
+pub type DocId = u32;
+
+/// Position of a segment in the list of segments of a `Searcher`.
+pub type SegmentOrdinal = u32;
+
+/// Address of a document within a `Searcher`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DocAddress {
+    pub segment_ord: SegmentOrdinal,
+    pub doc_id: DocId,
+}
+
+impl DocAddress {
+    pub fn new(segment_ord: SegmentOrdinal, doc_id: DocId) -> DocAddress {
+        DocAddress {
+            segment_ord,
+            doc_id,
+        }
+    }
+}
+
+/// Relevance score of a document.
+pub type Score = f32;
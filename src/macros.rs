@@ -0,0 +1,46 @@
+/// Creates a `Document` from `field => value` pairs.
+///
+/// ```
+/// use mysearch::{doc, Schema, STORED, TEXT};
+///
+/// let mut schema_builder = Schema::builder();
+/// let title = schema_builder.add_text_field("title", TEXT | STORED);
+/// let body = schema_builder.add_text_field("body", TEXT);
+/// let _schema = schema_builder.build();
+///
+/// let doc = doc!(
+///     title => "The Story of Mankind",
+///     body => "When I was twelve or thirteen years old...",
+/// );
+/// assert_eq!(doc.len(), 2);
+/// ```
+///
+/// The macro does not need to be imported:
+///
+/// ```
+/// let mut schema_builder = mysearch::Schema::builder();
+/// let title = schema_builder.add_text_field("title", mysearch::TEXT);
+///
+/// let doc = mysearch::doc!(title => "The Story of Mankind",);
+/// assert_eq!(doc.len(), 1);
+/// ```
+#[macro_export]
+macro_rules! doc(
+    () => {
+        {
+            ($crate::Document::default())
+        }
+    };
+    ($($field:expr => $value:expr),*) => {
+        {
+            let mut document = $crate::Document::default();
+            $(
+                document.add($crate::FieldValue::new($field, $value.into()));
+            )*
+            document
+        }
+    };
+    ($($field:expr => $value:expr),+ ,) => {
+        $crate::doc!( $( $field => $value ), *)
+    };
+);
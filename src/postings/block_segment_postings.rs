@@ -0,0 +1,171 @@
+use crate::common::read_u32_vint;
+use crate::postings::compression::{BlockDecoder, COMPRESSION_BLOCK_SIZE};
+use crate::postings::skip::{BlockInfo, SkipReader};
+use crate::{DocId, IndexRecordOption, OwnedBytes, TERMINATED};
+
+/// Reads the postings of a term one block at a time.
+///
+/// The postings of a term are laid out as
+///
+/// ```text
+/// [skip list length: VInt][skip list][bitpacked blocks][VInt tail]
+/// ```
+///
+/// Every complete block of `COMPRESSION_BLOCK_SIZE` documents is bitpacked:
+/// first the doc deltas, then the term frequencies if the field records them.
+/// The remaining documents are VInt-encoded: all their doc deltas, followed
+/// by all their term frequencies.
+pub struct BlockSegmentPostings {
+    doc_decoder: BlockDecoder,
+    freq_decoder: BlockDecoder,
+    record_option: IndexRecordOption,
+    doc_freq: u32,
+    data: OwnedBytes,
+    skip_reader: SkipReader,
+}
+
+impl BlockSegmentPostings {
+    /// Opens the postings of a term. `data` starts at the term postings offset.
+    pub fn open(
+        doc_freq: u32,
+        mut data: OwnedBytes,
+        record_option: IndexRecordOption,
+    ) -> BlockSegmentPostings {
+        let (skip_data, block_data) = if doc_freq == 0 {
+            (OwnedBytes::empty(), OwnedBytes::empty())
+        } else {
+            let mut cursor = data.as_slice();
+            let skip_len = read_u32_vint(&mut cursor) as usize;
+            let vint_len = data.len() - cursor.len();
+            data.advance(vint_len);
+            data.split(skip_len)
+        };
+        let mut block_segment_postings = BlockSegmentPostings {
+            doc_decoder: BlockDecoder::new(),
+            freq_decoder: BlockDecoder::new(),
+            record_option,
+            doc_freq,
+            data: block_data,
+            skip_reader: SkipReader::new(skip_data, doc_freq, record_option),
+        };
+        block_segment_postings.load_block();
+        block_segment_postings
+    }
+
+    /// Returns postings without any document.
+    pub fn empty() -> BlockSegmentPostings {
+        BlockSegmentPostings::open(0, OwnedBytes::empty(), IndexRecordOption::Basic)
+    }
+
+    /// Number of documents of the whole postings list.
+    pub fn doc_freq(&self) -> u32 {
+        self.doc_freq
+    }
+
+    /// Docs of the current block. Empty once the postings are exhausted.
+    #[inline]
+    pub fn docs(&self) -> &[DocId] {
+        self.doc_decoder.output_array()
+    }
+
+    #[inline]
+    pub fn doc(&self, idx: usize) -> DocId {
+        self.doc_decoder.output(idx)
+    }
+
+    /// Term frequencies of the current block, all equal to 1 if the field does
+    /// not record them.
+    #[inline]
+    pub fn freqs(&self) -> &[u32] {
+        self.freq_decoder.output_array()
+    }
+
+    #[inline]
+    pub fn freq(&self, idx: usize) -> u32 {
+        self.freq_decoder.output(idx)
+    }
+
+    pub fn block_len(&self) -> usize {
+        self.doc_decoder.output_array().len()
+    }
+
+    /// Maximum term frequency in the current block.
+    pub fn block_max_term_freq(&self) -> u32 {
+        match self.skip_reader.block_info() {
+            BlockInfo::BitPacked { block_max_tf, .. } => block_max_tf,
+            BlockInfo::VInt { .. } => self.freqs().iter().cloned().max().unwrap_or(0),
+        }
+    }
+
+    /// Number of positions of the term in the documents preceding the current block.
+    pub(crate) fn position_offset(&self) -> u64 {
+        self.skip_reader.position_offset()
+    }
+
+    /// Loads the next block. The current block is empty once the postings are
+    /// exhausted.
+    pub fn advance(&mut self) {
+        self.skip_reader.advance();
+        self.load_block();
+    }
+
+    /// Moves to the block that may contain `target`, skipping blocks without
+    /// decoding them.
+    ///
+    /// Docs of the current block are all lower than `target` only if the
+    /// postings are exhausted.
+    pub fn seek(&mut self, target: DocId) {
+        if self.skip_reader.seek(target) {
+            self.load_block();
+        }
+    }
+
+    fn load_block(&mut self) {
+        let offset = self.skip_reader.last_doc_in_previous_block();
+        let data = &self.data.as_slice()[self.skip_reader.byte_offset()..];
+        match self.skip_reader.block_info() {
+            BlockInfo::BitPacked {
+                doc_num_bits,
+                tf_num_bits,
+                ..
+            } => {
+                let num_bytes =
+                    self.doc_decoder
+                        .uncompress_block_sorted(data, offset, doc_num_bits);
+                if self.record_option.has_freq() {
+                    self.freq_decoder
+                        .uncompress_block_unsorted(&data[num_bytes..], tf_num_bits);
+                } else {
+                    self.freq_decoder.fill(1, COMPRESSION_BLOCK_SIZE);
+                }
+            }
+            BlockInfo::VInt { num_docs } => {
+                let num_docs = num_docs as usize;
+                if num_docs == 0 {
+                    self.doc_decoder.clear();
+                    self.freq_decoder.clear();
+                    return;
+                }
+                let num_bytes = self
+                    .doc_decoder
+                    .uncompress_vint_sorted(data, offset, num_docs);
+                if self.record_option.has_freq() {
+                    self.freq_decoder
+                        .uncompress_vint_unsorted(&data[num_bytes..], num_docs);
+                } else {
+                    self.freq_decoder.fill(1, num_docs);
+                }
+            }
+        }
+    }
+
+    /// Returns the last doc of the current block, or `TERMINATED` if it is the
+    /// VInt tail.
+    pub(crate) fn last_doc_in_block(&self) -> DocId {
+        if self.block_len() == 0 {
+            TERMINATED
+        } else {
+            self.skip_reader.last_doc_in_block()
+        }
+    }
+}
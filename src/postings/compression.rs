@@ -0,0 +1,169 @@
+use bitpacking::{BitPacker, BitPacker4x};
+
+use crate::common::read_u32_vint;
+
+/// Number of documents in a bitpacked block.
+pub const COMPRESSION_BLOCK_SIZE: usize = BitPacker4x::BLOCK_LEN;
+const COMPRESSED_BLOCK_MAX_SIZE: usize = COMPRESSION_BLOCK_SIZE * 4;
+
+/// Size in bytes of a block of `COMPRESSION_BLOCK_SIZE` values bitpacked
+/// with `num_bits` bits each.
+pub(crate) fn compressed_block_size(num_bits: u8) -> usize {
+    BitPacker4x::compressed_block_size(num_bits)
+}
+
+pub(crate) struct BlockEncoder {
+    bitpacker: BitPacker4x,
+    output: [u8; COMPRESSED_BLOCK_MAX_SIZE],
+}
+
+impl BlockEncoder {
+    pub fn new() -> BlockEncoder {
+        BlockEncoder {
+            bitpacker: BitPacker4x::new(),
+            output: [0u8; COMPRESSED_BLOCK_MAX_SIZE],
+        }
+    }
+
+    /// Delta-encodes and bitpacks a block of sorted values, `offset` being the
+    /// value preceding the block.
+    pub fn compress_block_sorted(&mut self, block: &[u32], offset: u32) -> (u8, &[u8]) {
+        let num_bits = self.bitpacker.num_bits_sorted(offset, block);
+        let written_size =
+            self.bitpacker
+                .compress_sorted(offset, block, &mut self.output[..], num_bits);
+        (num_bits, &self.output[..written_size])
+    }
+
+    pub fn compress_block_unsorted(&mut self, block: &[u32]) -> (u8, &[u8]) {
+        let num_bits = self.bitpacker.num_bits(block);
+        let written_size = self
+            .bitpacker
+            .compress(block, &mut self.output[..], num_bits);
+        (num_bits, &self.output[..written_size])
+    }
+}
+
+/// Decodes blocks written by `BlockEncoder`, as well as the VInt-encoded tail
+/// of a postings list.
+pub(crate) struct BlockDecoder {
+    bitpacker: BitPacker4x,
+    output: [u32; COMPRESSION_BLOCK_SIZE],
+    output_len: usize,
+}
+
+impl BlockDecoder {
+    pub fn new() -> BlockDecoder {
+        BlockDecoder {
+            bitpacker: BitPacker4x::new(),
+            output: [0u32; COMPRESSION_BLOCK_SIZE],
+            output_len: 0,
+        }
+    }
+
+    /// Returns the number of bytes read.
+    pub fn uncompress_block_sorted(&mut self, data: &[u8], offset: u32, num_bits: u8) -> usize {
+        self.output_len = COMPRESSION_BLOCK_SIZE;
+        self.bitpacker
+            .decompress_sorted(offset, data, &mut self.output, num_bits)
+    }
+
+    /// Returns the number of bytes read.
+    pub fn uncompress_block_unsorted(&mut self, data: &[u8], num_bits: u8) -> usize {
+        self.output_len = COMPRESSION_BLOCK_SIZE;
+        self.bitpacker.decompress(data, &mut self.output, num_bits)
+    }
+
+    /// Decodes `num_els` VInt deltas, `offset` being the value preceding them.
+    /// Returns the number of bytes read.
+    pub fn uncompress_vint_sorted(&mut self, data: &[u8], offset: u32, num_els: usize) -> usize {
+        let mut cursor = data;
+        let mut value = offset;
+        for output in &mut self.output[..num_els] {
+            value += read_u32_vint(&mut cursor);
+            *output = value;
+        }
+        self.output_len = num_els;
+        data.len() - cursor.len()
+    }
+
+    /// Decodes `num_els` VInts. Returns the number of bytes read.
+    pub fn uncompress_vint_unsorted(&mut self, data: &[u8], num_els: usize) -> usize {
+        let mut cursor = data;
+        for output in &mut self.output[..num_els] {
+            *output = read_u32_vint(&mut cursor);
+        }
+        self.output_len = num_els;
+        data.len() - cursor.len()
+    }
+
+    /// Fills the output with `num_els` copies of `value`.
+    pub fn fill(&mut self, value: u32, num_els: usize) {
+        for output in &mut self.output[..num_els] {
+            *output = value;
+        }
+        self.output_len = num_els;
+    }
+
+    pub fn clear(&mut self) {
+        self.output_len = 0;
+    }
+
+    #[inline]
+    pub fn output_array(&self) -> &[u32] {
+        &self.output[..self.output_len]
+    }
+
+    #[inline]
+    pub fn output(&self, idx: usize) -> u32 {
+        self.output[idx]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlockDecoder, BlockEncoder, COMPRESSION_BLOCK_SIZE};
+    use crate::common::write_u32_vint;
+
+    #[test]
+    fn test_encode_sorted_block() {
+        let vals: Vec<u32> = (0..COMPRESSION_BLOCK_SIZE as u32)
+            .map(|i| 10 + i * 3)
+            .collect();
+        let mut encoder = BlockEncoder::new();
+        let (num_bits, compressed) = encoder.compress_block_sorted(&vals, 7);
+        let compressed = compressed.to_vec();
+        assert_eq!(num_bits, 2);
+        let mut decoder = BlockDecoder::new();
+        let consumed = decoder.uncompress_block_sorted(&compressed, 7, num_bits);
+        assert_eq!(consumed, compressed.len());
+        assert_eq!(decoder.output_array(), &vals[..]);
+    }
+
+    #[test]
+    fn test_encode_unsorted_block() {
+        let vals: Vec<u32> = (0..COMPRESSION_BLOCK_SIZE as u32)
+            .map(|i| (i * 7) % 13)
+            .collect();
+        let mut encoder = BlockEncoder::new();
+        let (num_bits, compressed) = encoder.compress_block_unsorted(&vals);
+        let compressed = compressed.to_vec();
+        let mut decoder = BlockDecoder::new();
+        decoder.uncompress_block_unsorted(&compressed, num_bits);
+        assert_eq!(decoder.output_array(), &vals[..]);
+    }
+
+    #[test]
+    fn test_decode_vint_tail() -> std::io::Result<()> {
+        let mut data = Vec::new();
+        for delta in &[3u32, 1, 300] {
+            write_u32_vint(*delta, &mut data)?;
+        }
+        data.push(0xff);
+        let mut decoder = BlockDecoder::new();
+        let consumed = decoder.uncompress_vint_sorted(&data, 5, 3);
+        assert_eq!(consumed, data.len() - 1);
+        assert_eq!(decoder.output_array(), &[8, 9, 309]);
+        Ok(())
+    }
+}
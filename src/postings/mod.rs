@@ -0,0 +1,19 @@
+mod block_segment_postings;
+mod compression;
+mod position_reader;
+mod postings;
+mod postings_writer;
+mod recorder;
+mod segment_postings;
+mod serializer;
+mod skip;
+pub(crate) mod stacker;
+mod term_info;
+
+pub use block_segment_postings::*;
+pub use compression::COMPRESSION_BLOCK_SIZE;
+pub use postings::*;
+pub use postings_writer::*;
+pub use segment_postings::*;
+pub use serializer::*;
+pub use term_info::*;
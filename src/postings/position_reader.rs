@@ -0,0 +1,83 @@
+use crate::common::read_u32_vint;
+use crate::OwnedBytes;
+
+const STOP_BIT: u8 = 128;
+
+/// Reads the positions of a term.
+///
+/// Positions are VInt-encoded, delta-encoded within each document. They are
+/// addressed by the number of positions preceding them in the postings list,
+/// which the reader expects to mostly increase.
+pub(crate) struct PositionReader {
+    original: OwnedBytes,
+    bytes: OwnedBytes,
+    offset: u64,
+}
+
+impl PositionReader {
+    pub fn new(bytes: OwnedBytes) -> PositionReader {
+        PositionReader {
+            original: bytes.clone(),
+            bytes,
+            offset: 0,
+        }
+    }
+
+    fn skip(&mut self, num_positions: u64) {
+        let data = self.bytes.as_slice();
+        let mut remaining = num_positions;
+        let mut num_bytes = 0;
+        while remaining > 0 {
+            if data[num_bytes] >= STOP_BIT {
+                remaining -= 1;
+            }
+            num_bytes += 1;
+        }
+        self.bytes.advance(num_bytes);
+        self.offset += num_positions;
+    }
+
+    /// Reads the `num_positions` positions starting at position `offset`.
+    pub fn read(&mut self, offset: u64, num_positions: usize, output: &mut Vec<u32>) {
+        if offset < self.offset {
+            self.bytes = self.original.clone();
+            self.offset = 0;
+        }
+        self.skip(offset - self.offset);
+        let mut cursor = self.bytes.as_slice();
+        let mut position = 0u32;
+        output.clear();
+        for _ in 0..num_positions {
+            position += read_u32_vint(&mut cursor);
+            output.push(position);
+        }
+        let num_bytes = self.bytes.len() - cursor.len();
+        self.bytes.advance(num_bytes);
+        self.offset += num_positions as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PositionReader;
+    use crate::common::write_u32_vint;
+    use crate::OwnedBytes;
+
+    #[test]
+    fn test_position_reader() -> std::io::Result<()> {
+        let mut data = Vec::new();
+        // doc 0: [1, 200], doc 1: [0], doc 2: [3, 4, 1000]
+        for delta in &[1u32, 199, 0, 3, 1, 996] {
+            write_u32_vint(*delta, &mut data)?;
+        }
+        let mut reader = PositionReader::new(OwnedBytes::new(data));
+        let mut output = Vec::new();
+        reader.read(3, 3, &mut output);
+        assert_eq!(output, vec![3, 4, 1000]);
+        reader.read(0, 2, &mut output);
+        assert_eq!(output, vec![1, 200]);
+        reader.read(2, 1, &mut output);
+        assert_eq!(output, vec![0]);
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+use crate::DocSet;
+
+/// `DocSet` of the documents containing a term, giving access to the term
+/// frequency and positions of the current document.
+pub trait Postings: DocSet + 'static {
+    /// Number of occurrences of the term in the current document.
+    ///
+    /// Returns 1 if the field does not record term frequencies.
+    fn term_freq(&self) -> u32;
+
+    /// Writes the positions of the term in the current document into `output`,
+    /// shifted by `offset`.
+    ///
+    /// `output` is left empty if the field does not record positions.
+    fn positions_with_offset(&mut self, offset: u32, output: &mut Vec<u32>);
+
+    /// Writes the positions of the term in the current document into `output`.
+    fn positions(&mut self, output: &mut Vec<u32>) {
+        self.positions_with_offset(0u32, output);
+    }
+}
@@ -0,0 +1,198 @@
+use std::io;
+
+use crate::postings::recorder::{
+    NothingRecorder, Recorder, TermFrequencyRecorder, TfAndPositionRecorder,
+};
+use crate::postings::stacker::{MemoryArena, TermHashMap};
+use crate::{
+    BoxTokenStream, DocId, Field, FieldSerializer, FieldType, IndexRecordOption,
+    InvertedIndexSerializer, Schema, Term, FIELD_ID_LEN,
+};
+
+/// Tokens longer than this are ignored by the indexer.
+pub const MAX_TOKEN_LEN: usize = u16::MAX as usize - FIELD_ID_LEN;
+
+/// Keeps track of the positions of a field across the values of a document.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct IndexingPosition {
+    pub num_tokens: u32,
+    pub end_position: u32,
+}
+
+/// Index of the terms of all the fields of the segment being built.
+pub struct MultiFieldPostingsWriter {
+    arena: MemoryArena,
+    schema: Schema,
+    per_field_postings_writers: Vec<Box<dyn PostingsWriter>>,
+}
+
+fn posting_writer_from_field_type(field_type: &FieldType) -> Box<dyn PostingsWriter> {
+    match field_type.get_index_record_option() {
+        Some(IndexRecordOption::WithFreqsAndPositions) => {
+            SpecializedPostingsWriter::<TfAndPositionRecorder>::new_boxed()
+        }
+        Some(IndexRecordOption::WithFreqs) => {
+            SpecializedPostingsWriter::<TermFrequencyRecorder>::new_boxed()
+        }
+        Some(IndexRecordOption::Basic) | None => {
+            SpecializedPostingsWriter::<NothingRecorder>::new_boxed()
+        }
+    }
+}
+
+impl MultiFieldPostingsWriter {
+    pub fn new(schema: &Schema) -> MultiFieldPostingsWriter {
+        let per_field_postings_writers = schema
+            .fields()
+            .map(|(_, field_entry)| posting_writer_from_field_type(field_entry.field_type()))
+            .collect();
+        MultiFieldPostingsWriter {
+            arena: MemoryArena::new(),
+            schema: schema.clone(),
+            per_field_postings_writers,
+        }
+    }
+
+    pub fn mem_usage(&self) -> usize {
+        self.arena.mem_usage()
+            + self
+                .per_field_postings_writers
+                .iter()
+                .map(|postings_writer| postings_writer.mem_usage())
+                .sum::<usize>()
+    }
+
+    /// Records a single occurrence of `term` in `doc`.
+    pub fn subscribe(&mut self, doc: DocId, term: &Term) {
+        let postings_writer =
+            &mut self.per_field_postings_writers[term.field().field_id() as usize];
+        postings_writer.subscribe(doc, 0u32, term.as_slice(), &mut self.arena);
+    }
+
+    /// Records all the tokens of `token_stream` as occurrences in `doc`.
+    ///
+    /// Positions are shifted by `indexing_position.end_position`, so that the values
+    /// of a multi-valued field do not overlap.
+    pub fn index_text(
+        &mut self,
+        doc: DocId,
+        field: Field,
+        token_stream: &mut BoxTokenStream<'_>,
+        term_buffer: &mut Term,
+        indexing_position: &mut IndexingPosition,
+    ) {
+        term_buffer.set_field(field);
+        let postings_writer = &mut self.per_field_postings_writers[field.field_id() as usize];
+        let arena = &mut self.arena;
+        let position_offset = indexing_position.end_position;
+        let mut end_position = position_offset;
+        let mut num_tokens = 0u32;
+        token_stream.process(&mut |token| {
+            if token.text.len() > MAX_TOKEN_LEN {
+                return;
+            }
+            term_buffer.set_text(&token.text);
+            let position = position_offset + token.position as u32;
+            postings_writer.subscribe(doc, position, term_buffer.as_slice(), arena);
+            end_position = end_position.max(position + token.position_length as u32);
+            num_tokens += 1;
+        });
+        indexing_position.num_tokens += num_tokens;
+        // leaves a gap, so that phrases never match across values.
+        indexing_position.end_position = end_position + 1;
+    }
+
+    pub fn serialize(mut self, serializer: &mut InvertedIndexSerializer) -> io::Result<()> {
+        for (field, field_entry) in self.schema.fields() {
+            let postings_writer = &mut self.per_field_postings_writers[field.field_id() as usize];
+            if postings_writer.num_terms() == 0 {
+                continue;
+            }
+            let record_option = field_entry
+                .field_type()
+                .get_index_record_option()
+                .unwrap_or(IndexRecordOption::Basic);
+            let mut field_serializer = serializer.new_field(field, record_option);
+            postings_writer.serialize(&mut field_serializer, &mut self.arena)?;
+            field_serializer.close()?;
+        }
+        Ok(())
+    }
+}
+
+/// Postings writer of a single field.
+pub(crate) trait PostingsWriter {
+    /// Records an occurrence of `term` at `position` in `doc`.
+    ///
+    /// Docs must be subscribed in increasing order.
+    fn subscribe(&mut self, doc: DocId, position: u32, term: &[u8], arena: &mut MemoryArena);
+
+    /// Serializes the terms of the field, sorted by their bytes.
+    fn serialize(
+        &mut self,
+        serializer: &mut FieldSerializer<'_>,
+        arena: &mut MemoryArena,
+    ) -> io::Result<()>;
+
+    fn num_terms(&self) -> usize;
+
+    fn mem_usage(&self) -> usize;
+}
+
+pub(crate) struct SpecializedPostingsWriter<Rec: Recorder> {
+    term_index: TermHashMap,
+    recorders: Vec<Rec>,
+}
+
+impl<Rec: Recorder> SpecializedPostingsWriter<Rec> {
+    pub fn new_boxed() -> Box<dyn PostingsWriter> {
+        Box::new(SpecializedPostingsWriter::<Rec> {
+            term_index: TermHashMap::default(),
+            recorders: Vec::new(),
+        })
+    }
+}
+
+impl<Rec: Recorder> PostingsWriter for SpecializedPostingsWriter<Rec> {
+    fn subscribe(&mut self, doc: DocId, position: u32, term: &[u8], arena: &mut MemoryArena) {
+        let (term_id, created) = self.term_index.get_or_create(term, arena);
+        if created {
+            self.recorders.push(Rec::default());
+        }
+        let recorder = &mut self.recorders[term_id as usize];
+        if recorder.current_doc() != doc {
+            if !created {
+                recorder.close_doc(arena);
+            }
+            recorder.new_doc(doc, arena);
+        }
+        recorder.record_position(position, arena);
+    }
+
+    fn serialize(
+        &mut self,
+        serializer: &mut FieldSerializer<'_>,
+        arena: &mut MemoryArena,
+    ) -> io::Result<()> {
+        for recorder in &mut self.recorders {
+            recorder.close_doc(arena);
+        }
+        let mut term_ids: Vec<(&[u8], u32)> = self.term_index.iter(arena).collect();
+        term_ids.sort_unstable_by_key(|(term_bytes, _)| *term_bytes);
+        let mut buffer = Vec::new();
+        for (term_bytes, term_id) in term_ids {
+            serializer.new_term(term_bytes)?;
+            self.recorders[term_id as usize].serialize(arena, serializer, &mut buffer)?;
+            serializer.close_term()?;
+        }
+        Ok(())
+    }
+
+    fn num_terms(&self) -> usize {
+        self.term_index.len()
+    }
+
+    fn mem_usage(&self) -> usize {
+        self.term_index.mem_usage() + self.recorders.capacity() * std::mem::size_of::<Rec>()
+    }
+}
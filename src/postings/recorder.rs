@@ -0,0 +1,202 @@
+use std::io;
+
+use crate::common::{read_u32_vint, VInt};
+use crate::postings::stacker::{ExpUnrolledLinkedList, MemoryArena};
+use crate::{DocId, FieldSerializer};
+
+const POSITION_END: u32 = 0;
+
+fn write_vint(stack: &mut ExpUnrolledLinkedList, arena: &mut MemoryArena, val: u32) {
+    let mut buffer = [0u8; 10];
+    let num_bytes = VInt(u64::from(val)).serialize_into(&mut buffer);
+    stack.extend_from_slice(arena, &buffer[..num_bytes]);
+}
+
+/// Accumulates the postings of a single term while documents are being indexed.
+///
+/// The recorded information is appended to an `ExpUnrolledLinkedList` living in the
+/// shared `MemoryArena`; the recorder itself is a small `Copy` struct.
+pub(crate) trait Recorder: Copy + Default + 'static {
+    /// Returns the doc currently being recorded, or `u32::MAX` before the first one.
+    fn current_doc(&self) -> DocId;
+
+    fn new_doc(&mut self, doc: DocId, arena: &mut MemoryArena);
+
+    fn record_position(&mut self, position: u32, arena: &mut MemoryArena);
+
+    fn close_doc(&mut self, arena: &mut MemoryArena);
+
+    /// Sends the recorded postings to the serializer.
+    ///
+    /// The last document must have been closed beforehand.
+    fn serialize(
+        &self,
+        arena: &MemoryArena,
+        serializer: &mut FieldSerializer<'_>,
+        buffer: &mut Vec<u8>,
+    ) -> io::Result<()>;
+}
+
+/// Only records the doc ids.
+#[derive(Clone, Copy)]
+pub(crate) struct NothingRecorder {
+    stack: ExpUnrolledLinkedList,
+    current_doc: DocId,
+}
+
+impl Default for NothingRecorder {
+    fn default() -> Self {
+        NothingRecorder {
+            stack: ExpUnrolledLinkedList::default(),
+            current_doc: u32::MAX,
+        }
+    }
+}
+
+impl Recorder for NothingRecorder {
+    fn current_doc(&self) -> DocId {
+        self.current_doc
+    }
+
+    fn new_doc(&mut self, doc: DocId, arena: &mut MemoryArena) {
+        self.current_doc = doc;
+        write_vint(&mut self.stack, arena, doc);
+    }
+
+    fn record_position(&mut self, _position: u32, _arena: &mut MemoryArena) {}
+
+    fn close_doc(&mut self, _arena: &mut MemoryArena) {}
+
+    fn serialize(
+        &self,
+        arena: &MemoryArena,
+        serializer: &mut FieldSerializer<'_>,
+        buffer: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        buffer.clear();
+        self.stack.read_to_end(arena, buffer);
+        let mut data = &buffer[..];
+        while !data.is_empty() {
+            let doc = read_u32_vint(&mut data);
+            serializer.write_doc(doc, 0u32, &[])?;
+        }
+        Ok(())
+    }
+}
+
+/// Records the doc ids and the term frequencies.
+#[derive(Clone, Copy)]
+pub(crate) struct TermFrequencyRecorder {
+    stack: ExpUnrolledLinkedList,
+    current_doc: DocId,
+    current_tf: u32,
+}
+
+impl Default for TermFrequencyRecorder {
+    fn default() -> Self {
+        TermFrequencyRecorder {
+            stack: ExpUnrolledLinkedList::default(),
+            current_doc: u32::MAX,
+            current_tf: 0,
+        }
+    }
+}
+
+impl Recorder for TermFrequencyRecorder {
+    fn current_doc(&self) -> DocId {
+        self.current_doc
+    }
+
+    fn new_doc(&mut self, doc: DocId, arena: &mut MemoryArena) {
+        self.current_doc = doc;
+        write_vint(&mut self.stack, arena, doc);
+    }
+
+    fn record_position(&mut self, _position: u32, _arena: &mut MemoryArena) {
+        self.current_tf += 1;
+    }
+
+    fn close_doc(&mut self, arena: &mut MemoryArena) {
+        debug_assert!(self.current_tf > 0);
+        write_vint(&mut self.stack, arena, self.current_tf);
+        self.current_tf = 0;
+    }
+
+    fn serialize(
+        &self,
+        arena: &MemoryArena,
+        serializer: &mut FieldSerializer<'_>,
+        buffer: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        buffer.clear();
+        self.stack.read_to_end(arena, buffer);
+        let mut data = &buffer[..];
+        while !data.is_empty() {
+            let doc = read_u32_vint(&mut data);
+            let term_freq = read_u32_vint(&mut data);
+            serializer.write_doc(doc, term_freq, &[])?;
+        }
+        Ok(())
+    }
+}
+
+/// Records the doc ids, the term frequencies and the positions.
+#[derive(Clone, Copy)]
+pub(crate) struct TfAndPositionRecorder {
+    stack: ExpUnrolledLinkedList,
+    current_doc: DocId,
+}
+
+impl Default for TfAndPositionRecorder {
+    fn default() -> Self {
+        TfAndPositionRecorder {
+            stack: ExpUnrolledLinkedList::default(),
+            current_doc: u32::MAX,
+        }
+    }
+}
+
+impl Recorder for TfAndPositionRecorder {
+    fn current_doc(&self) -> DocId {
+        self.current_doc
+    }
+
+    fn new_doc(&mut self, doc: DocId, arena: &mut MemoryArena) {
+        self.current_doc = doc;
+        write_vint(&mut self.stack, arena, doc);
+    }
+
+    fn record_position(&mut self, position: u32, arena: &mut MemoryArena) {
+        // positions are shifted by one, 0 marks the end of the document.
+        write_vint(&mut self.stack, arena, position + 1);
+    }
+
+    fn close_doc(&mut self, arena: &mut MemoryArena) {
+        write_vint(&mut self.stack, arena, POSITION_END);
+    }
+
+    fn serialize(
+        &self,
+        arena: &MemoryArena,
+        serializer: &mut FieldSerializer<'_>,
+        buffer: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        buffer.clear();
+        self.stack.read_to_end(arena, buffer);
+        let mut data = &buffer[..];
+        let mut positions = Vec::new();
+        while !data.is_empty() {
+            let doc = read_u32_vint(&mut data);
+            positions.clear();
+            loop {
+                let position_plus_one = read_u32_vint(&mut data);
+                if position_plus_one == POSITION_END {
+                    break;
+                }
+                positions.push(position_plus_one - 1);
+            }
+            serializer.write_doc(doc, positions.len() as u32, &positions)?;
+        }
+        Ok(())
+    }
+}
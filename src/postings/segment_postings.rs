@@ -0,0 +1,253 @@
+use crate::postings::position_reader::PositionReader;
+use crate::{
+    BlockSegmentPostings, DocId, DocSet, IndexRecordOption, OwnedBytes, Postings, TERMINATED,
+};
+
+/// Postings of a term within a segment, read from the postings and positions
+/// files.
+pub struct SegmentPostings {
+    block_cursor: BlockSegmentPostings,
+    cur: usize,
+    position_reader: Option<PositionReader>,
+}
+
+impl SegmentPostings {
+    /// Opens the postings of a term.
+    ///
+    /// `postings_data` and `positions_data` start respectively at the postings and
+    /// positions offsets of the term's `TermInfo`. Positions are only read if the
+    /// field records them.
+    pub fn open(
+        doc_freq: u32,
+        postings_data: OwnedBytes,
+        record_option: IndexRecordOption,
+        positions_data: Option<OwnedBytes>,
+    ) -> SegmentPostings {
+        let block_cursor = BlockSegmentPostings::open(doc_freq, postings_data, record_option);
+        let position_reader = if record_option.has_positions() {
+            positions_data.map(PositionReader::new)
+        } else {
+            None
+        };
+        SegmentPostings {
+            block_cursor,
+            cur: 0,
+            position_reader,
+        }
+    }
+
+    /// Returns postings without any document.
+    pub fn empty() -> SegmentPostings {
+        SegmentPostings {
+            block_cursor: BlockSegmentPostings::empty(),
+            cur: 0,
+            position_reader: None,
+        }
+    }
+
+    /// Number of documents containing the term.
+    pub fn doc_freq(&self) -> u32 {
+        self.block_cursor.doc_freq()
+    }
+
+    /// Gives access to the underlying blocks.
+    pub fn block_cursor(&self) -> &BlockSegmentPostings {
+        &self.block_cursor
+    }
+}
+
+impl DocSet for SegmentPostings {
+    fn advance(&mut self) -> DocId {
+        if self.cur >= self.block_cursor.block_len() {
+            return TERMINATED;
+        }
+        self.cur += 1;
+        if self.cur >= self.block_cursor.block_len() {
+            self.cur = 0;
+            self.block_cursor.advance();
+        }
+        self.doc()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        if self.doc() >= target {
+            return self.doc();
+        }
+        if self.block_cursor.last_doc_in_block() < target {
+            self.block_cursor.seek(target);
+            self.cur = 0;
+        }
+        let docs = self.block_cursor.docs();
+        // docs within the block are sorted and the last one is >= target.
+        self.cur += docs[self.cur..]
+            .iter()
+            .take_while(|&&doc| doc < target)
+            .count();
+        self.doc()
+    }
+
+    #[inline]
+    fn doc(&self) -> DocId {
+        if self.cur < self.block_cursor.block_len() {
+            self.block_cursor.doc(self.cur)
+        } else {
+            TERMINATED
+        }
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.doc_freq()
+    }
+}
+
+impl Postings for SegmentPostings {
+    fn term_freq(&self) -> u32 {
+        debug_assert!(self.cur < self.block_cursor.block_len());
+        self.block_cursor.freq(self.cur)
+    }
+
+    fn positions_with_offset(&mut self, offset: u32, output: &mut Vec<u32>) {
+        output.clear();
+        if self.cur >= self.block_cursor.block_len() {
+            return;
+        }
+        let term_freq = self.term_freq() as usize;
+        if let Some(position_reader) = self.position_reader.as_mut() {
+            let freqs_before: u32 = self.block_cursor.freqs()[..self.cur].iter().sum();
+            let position_offset = self.block_cursor.position_offset() + u64::from(freqs_before);
+            position_reader.read(position_offset, term_freq, output);
+            for position in output.iter_mut() {
+                *position += offset;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        DocSet, Field, Index, IndexRecordOption, InvertedIndexSerializer, Postings, Schema,
+        Segment, SegmentComponent, SegmentPostings, Term, TermDictionary, TERMINATED, TEXT,
+    };
+
+    /// Writes a single term whose postings are `docs`, doc `d` having the
+    /// term at positions `0..(d % 3) + 1`.
+    fn write_term(docs: &[u32], record_option: IndexRecordOption) -> crate::Result<Segment> {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let segment = index.new_segment();
+        let mut serializer = InvertedIndexSerializer::open(&segment)?;
+        let field = Field::from_field_id(0);
+        {
+            let mut field_serializer = serializer.new_field(field, record_option);
+            field_serializer.new_term(Term::from_field_text(field, "a").as_slice())?;
+            for &doc in docs {
+                let positions: Vec<u32> = (0..doc % 3 + 1).collect();
+                field_serializer.write_doc(doc, positions.len() as u32, &positions)?;
+            }
+            field_serializer.close()?;
+        }
+        serializer.close()?;
+        Ok(segment)
+    }
+
+    fn open_postings(
+        segment: &Segment,
+        record_option: IndexRecordOption,
+    ) -> crate::Result<SegmentPostings> {
+        let term_dictionary = TermDictionary::open(segment.open_read(SegmentComponent::Terms)?)?;
+        let term_info = term_dictionary
+            .get(Term::from_field_text(Field::from_field_id(0), "a"))
+            .unwrap();
+        let postings = segment
+            .open_read(SegmentComponent::Postings)?
+            .read_bytes()?;
+        let positions = segment
+            .open_read(SegmentComponent::Positions)?
+            .read_bytes()?;
+        Ok(SegmentPostings::open(
+            term_info.doc_freq,
+            postings.slice(term_info.postings_offset as usize..postings.len()),
+            record_option,
+            Some(positions.slice(term_info.positions_offset as usize..positions.len())),
+        ))
+    }
+
+    #[test]
+    fn test_empty_postings() {
+        let mut postings = SegmentPostings::empty();
+        assert_eq!(postings.doc(), TERMINATED);
+        assert_eq!(postings.advance(), TERMINATED);
+        assert_eq!(postings.seek(10), TERMINATED);
+        assert_eq!(postings.size_hint(), 0);
+    }
+
+    #[test]
+    fn test_postings_advance_across_blocks() -> crate::Result<()> {
+        let record_option = IndexRecordOption::WithFreqsAndPositions;
+        for &num_docs in &[1u32, 127, 128, 129, 256, 1000] {
+            let docs: Vec<u32> = (0..num_docs).map(|i| i * 3 + i % 2).collect();
+            let segment = write_term(&docs, record_option)?;
+            let mut postings = open_postings(&segment, record_option)?;
+            assert_eq!(postings.doc_freq(), num_docs);
+            let mut positions = Vec::new();
+            for &doc in &docs {
+                assert_eq!(postings.doc(), doc);
+                assert_eq!(postings.term_freq(), doc % 3 + 1);
+                postings.positions_with_offset(10, &mut positions);
+                let expected: Vec<u32> = (10..doc % 3 + 11).collect();
+                assert_eq!(positions, expected);
+                postings.advance();
+            }
+            assert_eq!(postings.doc(), TERMINATED);
+            assert_eq!(postings.advance(), TERMINATED);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_postings_seek() -> crate::Result<()> {
+        let record_option = IndexRecordOption::WithFreqsAndPositions;
+        let docs: Vec<u32> = (0..1000u32).map(|i| i * 5).collect();
+        let segment = write_term(&docs, record_option)?;
+        let mut postings = open_postings(&segment, record_option)?;
+        assert_eq!(postings.seek(0), 0);
+        assert_eq!(postings.seek(6), 10);
+        assert_eq!(postings.seek(10), 10);
+        // jumps over several blocks without decoding them.
+        assert_eq!(postings.seek(3001), 3005);
+        assert_eq!(postings.term_freq(), 3005 % 3 + 1);
+        let mut positions = Vec::new();
+        postings.positions(&mut positions);
+        assert_eq!(positions.len() as u32, 3005 % 3 + 1);
+        assert_eq!(postings.advance(), 3010);
+        assert_eq!(postings.seek(4995), 4995);
+        assert_eq!(postings.seek(4996), TERMINATED);
+        Ok(())
+    }
+
+    #[test]
+    fn test_postings_without_freqs() -> crate::Result<()> {
+        let record_option = IndexRecordOption::Basic;
+        let docs: Vec<u32> = (0..300u32).map(|i| i * 2).collect();
+        let segment = write_term(&docs, record_option)?;
+        let mut postings = open_postings(&segment, record_option)?;
+        assert_eq!(postings.seek(257), 258);
+        assert_eq!(postings.term_freq(), 1);
+        let mut positions = vec![1];
+        postings.positions(&mut positions);
+        assert!(positions.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_block_max_term_freq() -> crate::Result<()> {
+        let record_option = IndexRecordOption::WithFreqs;
+        let docs: Vec<u32> = (0..200u32).collect();
+        let segment = write_term(&docs, record_option)?;
+        let postings = open_postings(&segment, record_option)?;
+        assert_eq!(postings.block_cursor().block_max_term_freq(), 3);
+        Ok(())
+    }
+}
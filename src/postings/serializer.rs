@@ -0,0 +1,222 @@
+use std::io::{self, Write};
+
+use crate::common::{write_u32_vint, CountingWriter};
+use crate::postings::compression::{BlockEncoder, COMPRESSION_BLOCK_SIZE};
+use crate::postings::skip::SkipSerializer;
+use crate::{
+    BinarySerializable, DocId, Field, IndexRecordOption, Segment, SegmentComponent,
+    TermDictionaryBuilder, TermInfo, TerminatingWrite, VInt, WritePtr, FIELD_ID_LEN,
+};
+
+/// Writes the inverted index of a segment: its term dictionary, postings and
+/// positions files.
+///
+/// Terms must be sent in increasing byte order, field by field. See
+/// `BlockSegmentPostings` for the layout of the postings of a term.
+pub struct InvertedIndexSerializer {
+    terms_write: TermDictionaryBuilder<WritePtr>,
+    postings_write: CountingWriter<WritePtr>,
+    positions_write: CountingWriter<WritePtr>,
+}
+
+impl InvertedIndexSerializer {
+    pub fn open(segment: &Segment) -> crate::Result<InvertedIndexSerializer> {
+        let terms_write =
+            TermDictionaryBuilder::create(segment.open_write(SegmentComponent::Terms)?)?;
+        let postings_write = CountingWriter::wrap(segment.open_write(SegmentComponent::Postings)?);
+        let positions_write =
+            CountingWriter::wrap(segment.open_write(SegmentComponent::Positions)?);
+        Ok(InvertedIndexSerializer {
+            terms_write,
+            postings_write,
+            positions_write,
+        })
+    }
+
+    /// Starts the serialization of the terms of `field`.
+    pub fn new_field(
+        &mut self,
+        field: Field,
+        record_option: IndexRecordOption,
+    ) -> FieldSerializer<'_> {
+        FieldSerializer {
+            terms_write: &mut self.terms_write,
+            postings_serializer: PostingsSerializer::new(&mut self.postings_write, record_option),
+            positions_write: &mut self.positions_write,
+            field,
+            record_option,
+            current_term_info: TermInfo::default(),
+            term_open: false,
+        }
+    }
+
+    pub fn close(self) -> io::Result<()> {
+        self.terms_write.finish()?.terminate()?;
+        self.postings_write.finish()?.terminate()?;
+        self.positions_write.finish()?.terminate()
+    }
+}
+
+/// Serializes the postings of the terms of a single field.
+pub struct FieldSerializer<'a> {
+    terms_write: &'a mut TermDictionaryBuilder<WritePtr>,
+    postings_serializer: PostingsSerializer<'a>,
+    positions_write: &'a mut CountingWriter<WritePtr>,
+    field: Field,
+    record_option: IndexRecordOption,
+    current_term_info: TermInfo,
+    term_open: bool,
+}
+
+impl<'a> FieldSerializer<'a> {
+    /// Starts a new term. `term` is the complete term, field prefix included.
+    pub fn new_term(&mut self, term: &[u8]) -> io::Result<()> {
+        assert!(
+            !self.term_open,
+            "Called new_term, while the previous term was not closed."
+        );
+        debug_assert_eq!(&term[..FIELD_ID_LEN], &self.field.field_id().to_be_bytes());
+        self.term_open = true;
+        self.postings_serializer.new_term();
+        self.current_term_info = TermInfo {
+            doc_freq: 0,
+            postings_offset: self.postings_serializer.written_bytes(),
+            positions_offset: self.positions_write.written_bytes(),
+        };
+        self.terms_write.insert_key(term)
+    }
+
+    /// Appends a document to the postings of the current term.
+    ///
+    /// Docs must be added in increasing order. `term_freq` and `positions` are
+    /// ignored if the field does not record them.
+    pub fn write_doc(
+        &mut self,
+        doc_id: DocId,
+        term_freq: u32,
+        positions: &[u32],
+    ) -> io::Result<()> {
+        self.postings_serializer.write_doc(doc_id, term_freq);
+        if self.record_option.has_positions() {
+            let mut last_position = 0u32;
+            for &position in positions {
+                write_u32_vint(position - last_position, self.positions_write)?;
+                last_position = position;
+            }
+        }
+        self.current_term_info.doc_freq += 1;
+        Ok(())
+    }
+
+    pub fn close_term(&mut self) -> io::Result<()> {
+        if self.term_open {
+            self.postings_serializer.close_term()?;
+            self.terms_write.insert_value(&self.current_term_info)?;
+            self.term_open = false;
+        }
+        Ok(())
+    }
+
+    pub fn close(mut self) -> io::Result<()> {
+        self.close_term()
+    }
+}
+
+/// Encodes the postings of a term into bitpacked blocks, a VInt tail and a
+/// skip list.
+///
+/// Blocks are buffered until the term is closed, since the skip list is
+/// written before them.
+struct PostingsSerializer<'a> {
+    output_write: &'a mut CountingWriter<WritePtr>,
+    record_option: IndexRecordOption,
+    block_encoder: BlockEncoder,
+    docs: Vec<DocId>,
+    term_freqs: Vec<u32>,
+    last_doc_in_previous_block: DocId,
+    skip_write: SkipSerializer,
+    block_data: Vec<u8>,
+}
+
+impl<'a> PostingsSerializer<'a> {
+    fn new(
+        output_write: &'a mut CountingWriter<WritePtr>,
+        record_option: IndexRecordOption,
+    ) -> PostingsSerializer<'a> {
+        PostingsSerializer {
+            output_write,
+            record_option,
+            block_encoder: BlockEncoder::new(),
+            docs: Vec::with_capacity(COMPRESSION_BLOCK_SIZE),
+            term_freqs: Vec::with_capacity(COMPRESSION_BLOCK_SIZE),
+            last_doc_in_previous_block: 0,
+            skip_write: SkipSerializer::new(),
+            block_data: Vec::new(),
+        }
+    }
+
+    fn written_bytes(&self) -> u64 {
+        self.output_write.written_bytes()
+    }
+
+    fn new_term(&mut self) {
+        self.docs.clear();
+        self.term_freqs.clear();
+        self.last_doc_in_previous_block = 0;
+        self.skip_write.clear();
+        self.block_data.clear();
+    }
+
+    fn write_doc(&mut self, doc_id: DocId, term_freq: u32) {
+        debug_assert!(self
+            .docs
+            .last()
+            .map(|&last_doc| doc_id > last_doc)
+            .unwrap_or(true));
+        self.docs.push(doc_id);
+        if self.record_option.has_freq() {
+            self.term_freqs.push(term_freq);
+        }
+        if self.docs.len() == COMPRESSION_BLOCK_SIZE {
+            self.write_block();
+        }
+    }
+
+    fn write_block(&mut self) {
+        let last_doc = self.docs[COMPRESSION_BLOCK_SIZE - 1];
+        {
+            let (num_bits, compressed_docs) = self
+                .block_encoder
+                .compress_block_sorted(&self.docs, self.last_doc_in_previous_block);
+            self.block_data.extend_from_slice(compressed_docs);
+            self.skip_write.write_doc(last_doc, num_bits);
+        }
+        if self.record_option.has_freq() {
+            let tf_sum = self.term_freqs.iter().sum();
+            let block_max_tf = self.term_freqs.iter().cloned().max().unwrap_or(0);
+            let (num_bits, compressed_freqs) =
+                self.block_encoder.compress_block_unsorted(&self.term_freqs);
+            self.block_data.extend_from_slice(compressed_freqs);
+            self.skip_write
+                .write_term_freqs(num_bits, tf_sum, block_max_tf);
+        }
+        self.last_doc_in_previous_block = last_doc;
+        self.docs.clear();
+        self.term_freqs.clear();
+    }
+
+    fn close_term(&mut self) -> io::Result<()> {
+        let mut last_doc = self.last_doc_in_previous_block;
+        for &doc in &self.docs {
+            VInt(u64::from(doc - last_doc)).serialize_into_vec(&mut self.block_data);
+            last_doc = doc;
+        }
+        for &term_freq in &self.term_freqs {
+            VInt(u64::from(term_freq)).serialize_into_vec(&mut self.block_data);
+        }
+        VInt(self.skip_write.data().len() as u64).serialize(self.output_write)?;
+        self.output_write.write_all(self.skip_write.data())?;
+        self.output_write.write_all(&self.block_data)?;
+        Ok(())
+    }
+}
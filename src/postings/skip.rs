@@ -0,0 +1,227 @@
+use std::convert::TryInto;
+
+use crate::postings::compression::{compressed_block_size, COMPRESSION_BLOCK_SIZE};
+use crate::{DocId, IndexRecordOption, OwnedBytes, TERMINATED};
+
+/// Writes the skip list of a postings list: one entry per bitpacked block.
+///
+/// An entry holds the last doc of the block and the number of bits used to
+/// pack it. If term frequencies are recorded, it also holds the number of bits
+/// of the term frequency block, the sum of the term frequencies of the block
+/// (to locate positions) and their maximum.
+pub(crate) struct SkipSerializer {
+    buffer: Vec<u8>,
+}
+
+impl SkipSerializer {
+    pub fn new() -> SkipSerializer {
+        SkipSerializer { buffer: Vec::new() }
+    }
+
+    pub fn write_doc(&mut self, last_doc: DocId, doc_num_bits: u8) {
+        self.buffer.extend_from_slice(&last_doc.to_le_bytes());
+        self.buffer.push(doc_num_bits);
+    }
+
+    pub fn write_term_freqs(&mut self, tf_num_bits: u8, tf_sum: u32, block_max_tf: u32) {
+        self.buffer.push(tf_num_bits);
+        self.buffer.extend_from_slice(&tf_sum.to_le_bytes());
+        self.buffer.extend_from_slice(&block_max_tf.to_le_bytes());
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.buffer[..]
+    }
+
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum BlockInfo {
+    BitPacked {
+        doc_num_bits: u8,
+        tf_num_bits: u8,
+        tf_sum: u32,
+        block_max_tf: u32,
+    },
+    VInt {
+        num_docs: u32,
+    },
+}
+
+/// Walks the skip list of a postings list, block by block.
+#[derive(Clone)]
+pub(crate) struct SkipReader {
+    data: OwnedBytes,
+    has_freq: bool,
+    remaining_docs: u32,
+    last_doc_in_previous_block: DocId,
+    last_doc_in_block: DocId,
+    byte_offset: usize,
+    position_offset: u64,
+    block_info: BlockInfo,
+}
+
+fn read_u32(data: &mut OwnedBytes) -> u32 {
+    let value = u32::from_le_bytes(data.as_slice()[..4].try_into().unwrap());
+    data.advance(4);
+    value
+}
+
+impl SkipReader {
+    pub fn new(data: OwnedBytes, doc_freq: u32, record_option: IndexRecordOption) -> SkipReader {
+        let mut skip_reader = SkipReader {
+            data,
+            has_freq: record_option.has_freq(),
+            remaining_docs: doc_freq,
+            last_doc_in_previous_block: 0,
+            last_doc_in_block: 0,
+            byte_offset: 0,
+            position_offset: 0,
+            block_info: BlockInfo::VInt { num_docs: 0 },
+        };
+        skip_reader.read_block_info();
+        skip_reader
+    }
+
+    fn read_block_info(&mut self) {
+        if self.remaining_docs >= COMPRESSION_BLOCK_SIZE as u32 {
+            self.last_doc_in_block = read_u32(&mut self.data);
+            let doc_num_bits = self.data.read_u8();
+            let (tf_num_bits, tf_sum, block_max_tf) = if self.has_freq {
+                let tf_num_bits = self.data.read_u8();
+                let tf_sum = read_u32(&mut self.data);
+                let block_max_tf = read_u32(&mut self.data);
+                (tf_num_bits, tf_sum, block_max_tf)
+            } else {
+                (0, COMPRESSION_BLOCK_SIZE as u32, 1)
+            };
+            self.block_info = BlockInfo::BitPacked {
+                doc_num_bits,
+                tf_num_bits,
+                tf_sum,
+                block_max_tf,
+            };
+        } else {
+            self.last_doc_in_block = TERMINATED;
+            self.block_info = BlockInfo::VInt {
+                num_docs: self.remaining_docs,
+            };
+        }
+    }
+
+    /// Moves to the next block. Returns `false` if the current block was the
+    /// last one.
+    pub fn advance(&mut self) -> bool {
+        match self.block_info {
+            BlockInfo::BitPacked {
+                doc_num_bits,
+                tf_num_bits,
+                tf_sum,
+                ..
+            } => {
+                self.byte_offset +=
+                    compressed_block_size(doc_num_bits) + compressed_block_size(tf_num_bits);
+                self.position_offset += u64::from(tf_sum);
+                self.last_doc_in_previous_block = self.last_doc_in_block;
+                self.remaining_docs -= COMPRESSION_BLOCK_SIZE as u32;
+                self.read_block_info();
+                true
+            }
+            BlockInfo::VInt { .. } => {
+                self.remaining_docs = 0;
+                self.block_info = BlockInfo::VInt { num_docs: 0 };
+                false
+            }
+        }
+    }
+
+    /// Skips the blocks that cannot contain `target`. Returns `true` if the
+    /// current block changed.
+    pub fn seek(&mut self, target: DocId) -> bool {
+        let mut moved = false;
+        while self.last_doc_in_block < target {
+            moved = true;
+            if !self.advance() {
+                break;
+            }
+        }
+        moved
+    }
+
+    pub fn block_info(&self) -> BlockInfo {
+        self.block_info
+    }
+
+    /// Doc preceding the current block, used as the base of its deltas.
+    pub fn last_doc_in_previous_block(&self) -> DocId {
+        self.last_doc_in_previous_block
+    }
+
+    /// Last doc of the current block, or `TERMINATED` for the VInt tail.
+    pub fn last_doc_in_block(&self) -> DocId {
+        self.last_doc_in_block
+    }
+
+    /// Start of the current block in the block data.
+    pub fn byte_offset(&self) -> usize {
+        self.byte_offset
+    }
+
+    /// Number of positions recorded before the current block.
+    pub fn position_offset(&self) -> u64 {
+        self.position_offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlockInfo, SkipReader, SkipSerializer};
+    use crate::{IndexRecordOption, OwnedBytes, TERMINATED};
+
+    #[test]
+    fn test_skip_reader() {
+        let mut skip_serializer = SkipSerializer::new();
+        skip_serializer.write_doc(200, 3);
+        skip_serializer.write_term_freqs(2, 300, 7);
+        skip_serializer.write_doc(500, 4);
+        skip_serializer.write_term_freqs(1, 150, 2);
+        let data = OwnedBytes::new(skip_serializer.data().to_vec());
+        let mut skip_reader = SkipReader::new(data, 300, IndexRecordOption::WithFreqsAndPositions);
+        assert_eq!(skip_reader.last_doc_in_block(), 200);
+        assert_eq!(
+            skip_reader.block_info(),
+            BlockInfo::BitPacked {
+                doc_num_bits: 3,
+                tf_num_bits: 2,
+                tf_sum: 300,
+                block_max_tf: 7
+            }
+        );
+        assert!(!skip_reader.seek(150));
+        assert!(skip_reader.seek(201));
+        assert_eq!(skip_reader.last_doc_in_previous_block(), 200);
+        assert_eq!(skip_reader.last_doc_in_block(), 500);
+        assert_eq!(skip_reader.byte_offset(), 3 * 16 + 2 * 16);
+        assert_eq!(skip_reader.position_offset(), 300);
+        assert!(skip_reader.advance());
+        assert_eq!(skip_reader.last_doc_in_block(), TERMINATED);
+        assert_eq!(skip_reader.block_info(), BlockInfo::VInt { num_docs: 44 });
+        assert!(!skip_reader.advance());
+        assert_eq!(skip_reader.block_info(), BlockInfo::VInt { num_docs: 0 });
+    }
+
+    #[test]
+    fn test_skip_reader_without_freqs() {
+        let mut skip_serializer = SkipSerializer::new();
+        skip_serializer.write_doc(127, 1);
+        let data = OwnedBytes::new(skip_serializer.data().to_vec());
+        let mut skip_reader = SkipReader::new(data, 128, IndexRecordOption::Basic);
+        assert_eq!(skip_reader.last_doc_in_block(), 127);
+        assert!(skip_reader.seek(128));
+        assert_eq!(skip_reader.byte_offset(), 16);
+        assert_eq!(skip_reader.block_info(), BlockInfo::VInt { num_docs: 0 });
+    }
+}
@@ -0,0 +1,124 @@
+use std::cmp;
+
+use super::{Addr, MemoryArena};
+
+const FIRST_BLOCK_LEN: usize = 16;
+const MAX_BLOCK_LEN: usize = 1 << 15;
+
+fn block_len(block_id: u16) -> usize {
+    cmp::min(FIRST_BLOCK_LEN << cmp::min(block_id, 11), MAX_BLOCK_LEN)
+}
+
+/// Growable byte list stored in a `MemoryArena`.
+///
+/// Bytes are appended to blocks of exponentially growing size (capped at 32KB),
+/// each block ending with the address of the next one. Most terms only appear in a
+/// handful of documents, so their list stays in a single small block.
+#[derive(Clone, Copy)]
+pub(crate) struct ExpUnrolledLinkedList {
+    len: u32,
+    num_blocks: u16,
+    remaining_cap: u16,
+    head: Addr,
+    tail: Addr,
+}
+
+impl Default for ExpUnrolledLinkedList {
+    fn default() -> Self {
+        ExpUnrolledLinkedList {
+            len: 0,
+            num_blocks: 0,
+            remaining_cap: 0,
+            head: Addr::null(),
+            tail: Addr::null(),
+        }
+    }
+}
+
+impl ExpUnrolledLinkedList {
+    pub fn extend_from_slice(&mut self, arena: &mut MemoryArena, mut buf: &[u8]) {
+        while !buf.is_empty() {
+            if self.remaining_cap == 0 {
+                self.allocate_block(arena);
+            }
+            let num_bytes = cmp::min(self.remaining_cap as usize, buf.len());
+            arena
+                .slice_mut(self.tail, num_bytes)
+                .copy_from_slice(&buf[..num_bytes]);
+            self.tail = self.tail.offset(num_bytes as u32);
+            self.remaining_cap -= num_bytes as u16;
+            self.len += num_bytes as u32;
+            buf = &buf[num_bytes..];
+        }
+    }
+
+    fn allocate_block(&mut self, arena: &mut MemoryArena) {
+        let block_len = block_len(self.num_blocks);
+        // each block is followed by the address of the next one.
+        let new_block = arena.allocate_space(block_len + 4);
+        if self.head.is_null() {
+            self.head = new_block;
+        } else {
+            // the tail of a full block points to its next-block slot.
+            arena.write_addr(self.tail, new_block);
+        }
+        self.tail = new_block;
+        self.remaining_cap = block_len as u16;
+        self.num_blocks += 1;
+    }
+
+    pub fn read_to_end(&self, arena: &MemoryArena, output: &mut Vec<u8>) {
+        let mut remaining = self.len as usize;
+        let mut addr = self.head;
+        let mut block_id = 0u16;
+        while remaining > 0 {
+            let block_len = block_len(block_id);
+            let num_bytes = cmp::min(block_len, remaining);
+            output.extend_from_slice(arena.slice(addr, num_bytes));
+            remaining -= num_bytes;
+            if remaining > 0 {
+                addr = arena.read_addr(addr.offset(block_len as u32));
+            }
+            block_id += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExpUnrolledLinkedList;
+    use crate::postings::stacker::MemoryArena;
+
+    #[test]
+    fn test_expull_spans_several_blocks() {
+        let mut arena = MemoryArena::new();
+        let mut first = ExpUnrolledLinkedList::default();
+        let mut second = ExpUnrolledLinkedList::default();
+        let mut expected_first = Vec::new();
+        let mut expected_second = Vec::new();
+        for i in 0..10_000u32 {
+            let bytes = i.to_le_bytes();
+            first.extend_from_slice(&mut arena, &bytes[..(i % 4) as usize + 1]);
+            expected_first.extend_from_slice(&bytes[..(i % 4) as usize + 1]);
+            if i % 3 == 0 {
+                second.extend_from_slice(&mut arena, &bytes);
+                expected_second.extend_from_slice(&bytes);
+            }
+        }
+        let mut output = Vec::new();
+        first.read_to_end(&arena, &mut output);
+        assert_eq!(output, expected_first);
+        output.clear();
+        second.read_to_end(&arena, &mut output);
+        assert_eq!(output, expected_second);
+    }
+
+    #[test]
+    fn test_expull_empty() {
+        let arena = MemoryArena::new();
+        let list = ExpUnrolledLinkedList::default();
+        let mut output = Vec::new();
+        list.read_to_end(&arena, &mut output);
+        assert!(output.is_empty());
+    }
+}
@@ -0,0 +1,106 @@
+use std::convert::TryInto;
+
+/// Position of an allocation within a `MemoryArena`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Addr(u32);
+
+impl Addr {
+    pub fn null() -> Addr {
+        Addr(u32::MAX)
+    }
+
+    pub fn is_null(self) -> bool {
+        self.0 == u32::MAX
+    }
+
+    pub fn offset(self, offset: u32) -> Addr {
+        Addr(self.0 + offset)
+    }
+}
+
+/// Append-only byte arena.
+///
+/// Objects are referenced by their `Addr` rather than by pointers, which keeps
+/// the many small allocations of the indexer compact and cheap to account for.
+#[derive(Default)]
+pub(crate) struct MemoryArena {
+    data: Vec<u8>,
+}
+
+impl MemoryArena {
+    pub fn new() -> MemoryArena {
+        MemoryArena::default()
+    }
+
+    pub fn mem_usage(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Allocates `len` zeroed bytes.
+    pub fn allocate_space(&mut self, len: usize) -> Addr {
+        let addr = self.data.len();
+        assert!(
+            addr + len < u32::MAX as usize,
+            "the memory arena cannot grow over 4GB"
+        );
+        self.data.resize(addr + len, 0u8);
+        Addr(addr as u32)
+    }
+
+    pub fn slice(&self, addr: Addr, len: usize) -> &[u8] {
+        let start = addr.0 as usize;
+        &self.data[start..start + len]
+    }
+
+    pub fn slice_mut(&mut self, addr: Addr, len: usize) -> &mut [u8] {
+        let start = addr.0 as usize;
+        &mut self.data[start..start + len]
+    }
+
+    pub fn read_u32(&self, addr: Addr) -> u32 {
+        u32::from_le_bytes(self.slice(addr, 4).try_into().unwrap())
+    }
+
+    pub fn write_u32(&mut self, addr: Addr, val: u32) {
+        self.slice_mut(addr, 4).copy_from_slice(&val.to_le_bytes());
+    }
+
+    pub fn read_addr(&self, addr: Addr) -> Addr {
+        Addr(self.read_u32(addr))
+    }
+
+    pub fn write_addr(&mut self, addr: Addr, target: Addr) {
+        self.write_u32(addr, target.0);
+    }
+
+    /// Stores `bytes` prefixed by their length.
+    pub fn store_bytes(&mut self, bytes: &[u8]) -> Addr {
+        let addr = self.allocate_space(4 + bytes.len());
+        self.write_u32(addr, bytes.len() as u32);
+        self.slice_mut(addr.offset(4), bytes.len())
+            .copy_from_slice(bytes);
+        addr
+    }
+
+    /// Reads back bytes stored with `store_bytes`.
+    pub fn read_bytes(&self, addr: Addr) -> &[u8] {
+        let len = self.read_u32(addr) as usize;
+        self.slice(addr.offset(4), len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MemoryArena;
+
+    #[test]
+    fn test_arena_store_bytes() {
+        let mut arena = MemoryArena::new();
+        let first = arena.store_bytes(b"hello");
+        let second = arena.store_bytes(b"");
+        let third = arena.store_bytes(b"world!");
+        assert_eq!(arena.read_bytes(first), b"hello");
+        assert_eq!(arena.read_bytes(second), b"");
+        assert_eq!(arena.read_bytes(third), b"world!");
+    }
+}
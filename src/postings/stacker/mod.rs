@@ -0,0 +1,7 @@
+mod expull;
+mod memory_arena;
+mod term_hashmap;
+
+pub(crate) use expull::*;
+pub(crate) use memory_arena::*;
+pub(crate) use term_hashmap::*;
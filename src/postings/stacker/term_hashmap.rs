@@ -0,0 +1,160 @@
+use super::{Addr, MemoryArena};
+
+/// Unordered identifier of a term, assigned in order of first appearance.
+pub(crate) type UnorderedTermId = u32;
+
+#[derive(Clone, Copy)]
+struct KeyValue {
+    key_addr: Addr,
+    hash: u32,
+    unordered_term_id: UnorderedTermId,
+}
+
+impl Default for KeyValue {
+    fn default() -> Self {
+        KeyValue {
+            key_addr: Addr::null(),
+            hash: 0,
+            unordered_term_id: 0,
+        }
+    }
+}
+
+impl KeyValue {
+    fn is_empty(&self) -> bool {
+        self.key_addr.is_null()
+    }
+}
+
+/// Open-addressing hash table mapping term bytes to an `UnorderedTermId`.
+///
+/// Keys live in a `MemoryArena` shared with the postings lists, the table itself
+/// only holds fixed-size slots.
+pub(crate) struct TermHashMap {
+    table: Box<[KeyValue]>,
+    mask: usize,
+    occupied: Vec<usize>,
+}
+
+impl Default for TermHashMap {
+    fn default() -> Self {
+        TermHashMap::new(1 << 10)
+    }
+}
+
+impl TermHashMap {
+    pub fn new(table_size: usize) -> TermHashMap {
+        assert!(table_size.is_power_of_two());
+        TermHashMap {
+            table: vec![KeyValue::default(); table_size].into_boxed_slice(),
+            mask: table_size - 1,
+            occupied: Vec::with_capacity(table_size / 2),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.occupied.len()
+    }
+
+    pub fn mem_usage(&self) -> usize {
+        self.table.len() * std::mem::size_of::<KeyValue>()
+            + self.occupied.capacity() * std::mem::size_of::<usize>()
+    }
+
+    /// Returns the id of `key`, registering it if it was not there yet.
+    ///
+    /// The boolean is true if the key was just inserted.
+    pub fn get_or_create(
+        &mut self,
+        key: &[u8],
+        arena: &mut MemoryArena,
+    ) -> (UnorderedTermId, bool) {
+        if self.occupied.len() * 2 >= self.table.len() {
+            self.resize();
+        }
+        let hash = murmurhash32::murmurhash2(key);
+        let mut bucket = hash as usize & self.mask;
+        loop {
+            let kv = self.table[bucket];
+            if kv.is_empty() {
+                let unordered_term_id = self.occupied.len() as UnorderedTermId;
+                self.table[bucket] = KeyValue {
+                    key_addr: arena.store_bytes(key),
+                    hash,
+                    unordered_term_id,
+                };
+                self.occupied.push(bucket);
+                return (unordered_term_id, true);
+            }
+            if kv.hash == hash && arena.read_bytes(kv.key_addr) == key {
+                return (kv.unordered_term_id, false);
+            }
+            bucket = (bucket + 1) & self.mask;
+        }
+    }
+
+    /// Iterates over the registered keys, in insertion order.
+    pub fn iter<'a>(
+        &'a self,
+        arena: &'a MemoryArena,
+    ) -> impl Iterator<Item = (&'a [u8], UnorderedTermId)> + 'a {
+        self.occupied.iter().map(move |&bucket| {
+            let kv = self.table[bucket];
+            (arena.read_bytes(kv.key_addr), kv.unordered_term_id)
+        })
+    }
+
+    fn resize(&mut self) {
+        let new_len = self.table.len() * 2;
+        let mask = new_len - 1;
+        let mut new_table = vec![KeyValue::default(); new_len].into_boxed_slice();
+        let mut new_occupied = Vec::with_capacity(new_len / 2);
+        for &bucket in &self.occupied {
+            let kv = self.table[bucket];
+            let mut new_bucket = kv.hash as usize & mask;
+            while !new_table[new_bucket].is_empty() {
+                new_bucket = (new_bucket + 1) & mask;
+            }
+            new_table[new_bucket] = kv;
+            new_occupied.push(new_bucket);
+        }
+        self.table = new_table;
+        self.mask = mask;
+        self.occupied = new_occupied;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TermHashMap;
+    use crate::postings::stacker::MemoryArena;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_term_hashmap() {
+        let mut arena = MemoryArena::new();
+        let mut hash_map = TermHashMap::new(4);
+        assert_eq!(hash_map.get_or_create(b"abc", &mut arena), (0, true));
+        assert_eq!(hash_map.get_or_create(b"abcd", &mut arena), (1, true));
+        assert_eq!(hash_map.get_or_create(b"abc", &mut arena), (0, false));
+        assert_eq!(hash_map.get_or_create(b"", &mut arena), (2, true));
+        let keys: Vec<(&[u8], u32)> = hash_map.iter(&arena).collect();
+        assert_eq!(
+            keys,
+            vec![(&b"abc"[..], 0), (&b"abcd"[..], 1), (&b""[..], 2)]
+        );
+    }
+
+    #[test]
+    fn test_term_hashmap_resize() {
+        let mut arena = MemoryArena::new();
+        let mut hash_map = TermHashMap::new(2);
+        let mut expected = HashMap::new();
+        for i in 0..10_000u32 {
+            let key = format!("term{}", i % 3_000);
+            let (term_id, _) = hash_map.get_or_create(key.as_bytes(), &mut arena);
+            assert_eq!(*expected.entry(key).or_insert(term_id), term_id);
+        }
+        assert_eq!(hash_map.len(), 3_000);
+    }
+}
@@ -0,0 +1,33 @@
+use std::io::{self, Read, Write};
+
+use crate::BinarySerializable;
+
+/// Information associated with a term in the term dictionary.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct TermInfo {
+    /// Number of documents containing the term.
+    pub doc_freq: u32,
+    /// Start of the term postings in the postings file.
+    pub postings_offset: u64,
+    /// Start of the term positions in the positions file.
+    pub positions_offset: u64,
+}
+
+impl BinarySerializable for TermInfo {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.doc_freq.serialize(writer)?;
+        self.postings_offset.serialize(writer)?;
+        self.positions_offset.serialize(writer)
+    }
+
+    fn deserialize<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let doc_freq = u32::deserialize(reader)?;
+        let postings_offset = u64::deserialize(reader)?;
+        let positions_offset = u64::deserialize(reader)?;
+        Ok(TermInfo {
+            doc_freq,
+            postings_offset,
+            positions_offset,
+        })
+    }
+}
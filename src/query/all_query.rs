@@ -0,0 +1,122 @@
+use crate::{
+    seek_to_matching_doc, DocId, DocSet, Explanation, Query, Score, Scorer, Searcher,
+    SegmentReader, Weight, TERMINATED,
+};
+
+/// Query matching all the documents, all with a score of 1.
+#[derive(Clone, Debug)]
+pub struct AllQuery;
+
+impl Query for AllQuery {
+    fn weight(
+        &self,
+        _searcher: &Searcher,
+        _scoring_enabled: bool,
+    ) -> crate::Result<Box<dyn Weight>> {
+        Ok(Box::new(AllWeight))
+    }
+}
+
+/// Weight of an `AllQuery`.
+pub struct AllWeight;
+
+impl Weight for AllWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> crate::Result<Box<dyn Scorer>> {
+        Ok(Box::new(AllScorer::new(reader.max_doc(), boost)))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let mut scorer = self.scorer(reader, 1.0)?;
+        seek_to_matching_doc(scorer.as_mut(), doc)?;
+        Ok(Explanation::new("AllQuery", scorer.score()))
+    }
+
+    fn count(&self, reader: &SegmentReader) -> crate::Result<u32> {
+        Ok(reader.num_docs())
+    }
+}
+
+/// Scorer iterating over all the documents of a segment.
+pub struct AllScorer {
+    doc: DocId,
+    max_doc: DocId,
+    score: Score,
+}
+
+impl AllScorer {
+    pub fn new(max_doc: DocId, score: Score) -> AllScorer {
+        AllScorer {
+            doc: if max_doc == 0 { TERMINATED } else { 0 },
+            max_doc,
+            score,
+        }
+    }
+}
+
+impl DocSet for AllScorer {
+    fn advance(&mut self) -> DocId {
+        if self.doc + 1 >= self.max_doc {
+            self.doc = TERMINATED;
+        } else {
+            self.doc += 1;
+        }
+        self.doc
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.doc = if target >= self.max_doc {
+            TERMINATED
+        } else {
+            target
+        };
+        self.doc
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.max_doc
+    }
+}
+
+impl Scorer for AllScorer {
+    fn score(&mut self) -> Score {
+        self.score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AllQuery, AllScorer, DocAddress, DocSet, Index, Query, Schema, TERMINATED, TEXT};
+
+    #[test]
+    fn test_all_scorer() {
+        let mut scorer = AllScorer::new(3, 1.0);
+        assert_eq!(scorer.doc(), 0);
+        assert_eq!(scorer.advance(), 1);
+        assert_eq!(scorer.seek(2), 2);
+        assert_eq!(scorer.advance(), TERMINATED);
+        assert_eq!(AllScorer::new(0, 1.0).doc(), TERMINATED);
+    }
+
+    #[test]
+    fn test_all_query() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 10_000_000)?;
+        index_writer.add_document(doc!(title => "a"))?;
+        index_writer.add_document(doc!(title => "b"))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(title => "c"))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        assert_eq!(AllQuery.count(&searcher)?, 3);
+        let explanation = AllQuery.explain(&searcher, DocAddress::new(0, 1))?;
+        assert_eq!(explanation.value(), 1.0);
+        assert!(AllQuery.explain(&searcher, DocAddress::new(0, 5)).is_err());
+        Ok(())
+    }
+}
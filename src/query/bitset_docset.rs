@@ -0,0 +1,62 @@
+use crate::{BitSet, DocId, DocSet, TERMINATED};
+
+/// `DocSet` over the documents of a `BitSet`.
+pub struct BitSetDocSet {
+    bitset: BitSet,
+    doc: DocId,
+}
+
+impl From<BitSet> for BitSetDocSet {
+    fn from(bitset: BitSet) -> BitSetDocSet {
+        let doc = bitset.next_from(0).unwrap_or(TERMINATED);
+        BitSetDocSet { bitset, doc }
+    }
+}
+
+impl DocSet for BitSetDocSet {
+    fn advance(&mut self) -> DocId {
+        self.seek(self.doc + 1)
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        if target <= self.doc {
+            return self.doc;
+        }
+        self.doc = self.bitset.next_from(target).unwrap_or(TERMINATED);
+        self.doc
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.bitset.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::intersection::tests::collect_docs;
+    use crate::{BitSet, BitSetDocSet, DocSet, TERMINATED};
+
+    #[test]
+    fn test_bitset_docset() {
+        let mut bitset = BitSet::with_max_value(300);
+        for doc in &[2, 63, 64, 250] {
+            bitset.insert(*doc);
+        }
+        assert_eq!(
+            collect_docs(BitSetDocSet::from(bitset.clone())),
+            vec![2, 63, 64, 250]
+        );
+        let mut docset = BitSetDocSet::from(bitset);
+        assert_eq!(docset.seek(64), 64);
+        assert_eq!(docset.seek(65), 250);
+        assert_eq!(docset.advance(), TERMINATED);
+        assert_eq!(
+            BitSetDocSet::from(BitSet::with_max_value(10)).doc(),
+            TERMINATED
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use crate::{id_to_fieldnorm, Explanation, Score, Searcher, Term};
+
+const K1: Score = 1.2;
+const B: Score = 0.75;
+
+/// Inverse document frequency of a term appearing in `doc_freq` of `doc_count`
+/// documents.
+pub fn idf(doc_freq: u64, doc_count: u64) -> Score {
+    assert!(doc_count >= doc_freq, "{} >= {}", doc_count, doc_freq);
+    let x = ((doc_count - doc_freq) as Score + 0.5) / (doc_freq as Score + 0.5);
+    (1.0 + x).ln()
+}
+
+fn cached_tf_component(fieldnorm: u32, average_fieldnorm: Score) -> Score {
+    K1 * (1.0 - B + B * fieldnorm as Score / average_fieldnorm)
+}
+
+fn compute_tf_cache(average_fieldnorm: Score) -> [Score; 256] {
+    let mut cache: [Score; 256] = [0.0; 256];
+    for (fieldnorm_id, cache_mut) in cache.iter_mut().enumerate() {
+        let fieldnorm = id_to_fieldnorm(fieldnorm_id as u8);
+        *cache_mut = cached_tf_component(fieldnorm, average_fieldnorm);
+    }
+    cache
+}
+
+/// BM25 weight of a set of terms of the same field.
+///
+/// The collection statistics (number of documents, average fieldnorm, document
+/// frequencies) are computed once over all the segments of a `Searcher`, so that
+/// a document scores the same whatever segment it lives in.
+#[derive(Clone)]
+pub struct Bm25Weight {
+    idf_explain: Explanation,
+    weight: Score,
+    cache: [Score; 256],
+    average_fieldnorm: Score,
+}
+
+impl Bm25Weight {
+    /// Creates the weight of `terms`, summing their idfs.
+    ///
+    /// All the terms must belong to the same field.
+    pub fn for_terms(searcher: &Searcher, terms: &[Term]) -> crate::Result<Bm25Weight> {
+        assert!(!terms.is_empty(), "Bm25Weight requires at least one term");
+        let field = terms[0].field();
+        for term in &terms[1..] {
+            assert_eq!(
+                term.field(),
+                field,
+                "all terms must belong to the same field"
+            );
+        }
+
+        let total_num_tokens = searcher.total_num_tokens(field)?;
+        // doc freqs and token counts include the deleted documents, so must the
+        // number of documents.
+        let total_num_docs = searcher.max_doc();
+        let average_fieldnorm = if total_num_docs == 0 {
+            1.0
+        } else {
+            total_num_tokens as Score / total_num_docs as Score
+        };
+
+        if terms.len() == 1 {
+            let term_doc_freq = searcher.doc_freq(&terms[0]);
+            return Ok(Bm25Weight::for_one_term(
+                term_doc_freq,
+                total_num_docs,
+                average_fieldnorm,
+            ));
+        }
+
+        let mut idf_sum: Score = 0.0;
+        for term in terms {
+            idf_sum += idf(searcher.doc_freq(term), total_num_docs);
+        }
+        let idf_explain = Explanation::new("idf, sum of the idf of each term", idf_sum);
+        Ok(Bm25Weight::new(idf_explain, average_fieldnorm))
+    }
+
+    pub fn for_one_term(
+        term_doc_freq: u64,
+        total_num_docs: u64,
+        average_fieldnorm: Score,
+    ) -> Bm25Weight {
+        let idf = idf(term_doc_freq, total_num_docs);
+        let mut idf_explain =
+            Explanation::new("idf, computed as log(1 + (N - n + 0.5) / (n + 0.5))", idf);
+        idf_explain.add_const(
+            "n, number of docs containing this term",
+            term_doc_freq as Score,
+        );
+        idf_explain.add_const("N, total number of docs", total_num_docs as Score);
+        Bm25Weight::new(idf_explain, average_fieldnorm)
+    }
+
+    fn new(idf_explain: Explanation, average_fieldnorm: Score) -> Bm25Weight {
+        let weight = idf_explain.value() * (1.0 + K1);
+        Bm25Weight {
+            idf_explain,
+            weight,
+            cache: compute_tf_cache(average_fieldnorm),
+            average_fieldnorm,
+        }
+    }
+
+    /// Returns the same weight, with all its scores multiplied by `factor`.
+    pub fn boost_by(&self, factor: Score) -> Bm25Weight {
+        Bm25Weight {
+            idf_explain: self.idf_explain.clone(),
+            weight: self.weight * factor,
+            cache: self.cache,
+            average_fieldnorm: self.average_fieldnorm,
+        }
+    }
+
+    #[inline]
+    pub fn score(&self, fieldnorm_id: u8, term_freq: u32) -> Score {
+        self.weight * self.tf_factor(fieldnorm_id, term_freq)
+    }
+
+    /// Highest score a document can get, reached with the highest term frequency
+    /// in the shortest field.
+    pub fn max_score(&self) -> Score {
+        self.score(0u8, 2_013_265_944)
+    }
+
+    #[inline]
+    pub(crate) fn tf_factor(&self, fieldnorm_id: u8, term_freq: u32) -> Score {
+        let term_freq = term_freq as Score;
+        let norm = self.cache[fieldnorm_id as usize];
+        term_freq / (term_freq + norm)
+    }
+
+    pub fn explain(&self, fieldnorm_id: u8, term_freq: u32) -> Explanation {
+        let score = self.score(fieldnorm_id, term_freq);
+
+        let norm = self.cache[fieldnorm_id as usize];
+        let term_freq = term_freq as Score;
+        let right_factor = term_freq / (term_freq + norm);
+
+        let mut tf_explanation = Explanation::new(
+            "freq / (freq + k1 * (1 - b + b * dl / avgdl))",
+            right_factor,
+        );
+        tf_explanation.add_const("freq, occurrences of term within document", term_freq);
+        tf_explanation.add_const("k1, term saturation parameter", K1);
+        tf_explanation.add_const("b, length normalization parameter", B);
+        tf_explanation.add_const(
+            "dl, length of field",
+            id_to_fieldnorm(fieldnorm_id) as Score,
+        );
+        tf_explanation.add_const("avgdl, average length of field", self.average_fieldnorm);
+
+        let mut explanation = Explanation::new("TermQuery, product of...", score);
+        explanation.add_detail(Explanation::new("(K1+1)", K1 + 1.0));
+        explanation.add_detail(self.idf_explain.clone());
+        explanation.add_detail(tf_explanation);
+        explanation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fieldnorm_to_id, idf, Bm25Weight, Index, Score, Term, TEXT};
+
+    fn assert_nearly_equals(left: Score, right: Score) {
+        assert!((left - right).abs() < 1e-5, "{} != {}", left, right);
+    }
+
+    #[test]
+    fn test_idf() {
+        assert_nearly_equals(idf(1, 2), (1.0f32 + 1.5 / 1.5).ln());
+        assert!(idf(1, 100) > idf(10, 100));
+        assert!(idf(100, 100) > 0.0);
+    }
+
+    #[test]
+    fn test_bm25_favors_frequent_terms_in_short_fields() {
+        let weight = Bm25Weight::for_one_term(10, 100, 10.0);
+        let short = fieldnorm_to_id(5);
+        let long = fieldnorm_to_id(20);
+        assert!(weight.score(short, 1) > weight.score(long, 1));
+        assert!(weight.score(short, 2) > weight.score(short, 1));
+        assert!(weight.score(short, 1) <= weight.max_score());
+        assert_nearly_equals(
+            weight.boost_by(2.0).score(short, 1),
+            2.0 * weight.score(short, 1),
+        );
+        assert_nearly_equals(weight.explain(short, 3).value(), weight.score(short, 3));
+    }
+
+    #[test]
+    fn test_bm25_collection_statistics() -> crate::Result<()> {
+        let mut schema_builder = crate::Schema::builder();
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 10_000_000)?;
+        index_writer.add_document(doc!(body => "a b c"))?;
+        index_writer.add_document(doc!(body => "a b"))?;
+        index_writer.commit()?;
+        index_writer.add_document(doc!(body => "a c d e f g"))?;
+        index_writer.commit()?;
+
+        let searcher = index.reader()?.searcher();
+        assert_eq!(searcher.segment_readers().len(), 2);
+        assert_eq!(searcher.total_num_tokens(body)?, 11);
+        let fieldnorm_reader = searcher.segment_reader(1).get_fieldnorms_reader(body)?;
+        assert_eq!(fieldnorm_reader.fieldnorm(0), 6);
+
+        let term_b = Term::from_field_text(body, "b");
+        let weight = Bm25Weight::for_terms(&searcher, &[term_b])?;
+        let explanation = weight.explain(fieldnorm_to_id(2), 1);
+        assert_nearly_equals(explanation.details()[1].value(), idf(2, 3));
+        assert_nearly_equals(explanation.details()[2].details()[4].value(), 11.0 / 3.0);
+        Ok(())
+    }
+}
@@ -0,0 +1,255 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    intersect_scorers, seek_to_matching_doc, DocId, EmptyScorer, Exclude, Explanation,
+    IndexRecordOption, Occur, Query, RequiredOptionalScorer, Score, Scorer, Searcher,
+    SegmentReader, Term, TermQuery, Union, Weight,
+};
+
+/// Combination of queries.
+///
+/// A document matches if it matches all the `Must` clauses, none of the
+/// `MustNot` clauses, and at least one `Should` clause when there are no
+/// `Must` clauses. Its score is the sum of the scores of the `Must` and `Should`
+/// clauses it matches.
+///
+/// A query made only of `MustNot` clauses matches nothing.
+#[derive(Clone, Debug)]
+pub struct BooleanQuery {
+    subqueries: Vec<(Occur, Box<dyn Query>)>,
+}
+
+impl From<Vec<(Occur, Box<dyn Query>)>> for BooleanQuery {
+    fn from(subqueries: Vec<(Occur, Box<dyn Query>)>) -> BooleanQuery {
+        BooleanQuery::new(subqueries)
+    }
+}
+
+impl BooleanQuery {
+    pub fn new(subqueries: Vec<(Occur, Box<dyn Query>)>) -> BooleanQuery {
+        BooleanQuery { subqueries }
+    }
+
+    /// Matches the documents containing any of `terms`.
+    pub fn new_multiterms_query(terms: Vec<Term>) -> BooleanQuery {
+        let subqueries = terms
+            .into_iter()
+            .map(|term| {
+                let term_query: Box<dyn Query> =
+                    Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs));
+                (Occur::Should, term_query)
+            })
+            .collect();
+        BooleanQuery::new(subqueries)
+    }
+
+    pub fn clauses(&self) -> &[(Occur, Box<dyn Query>)] {
+        &self.subqueries
+    }
+}
+
+impl Query for BooleanQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        let weights = self
+            .subqueries
+            .iter()
+            .map(|(occur, subquery)| Ok((*occur, subquery.weight(searcher, scoring_enabled)?)))
+            .collect::<crate::Result<Vec<_>>>()?;
+        Ok(Box::new(BooleanWeight::new(weights, scoring_enabled)))
+    }
+
+    fn query_terms(&self, terms: &mut BTreeSet<Term>) {
+        for (occur, subquery) in &self.subqueries {
+            if *occur != Occur::MustNot {
+                subquery.query_terms(terms);
+            }
+        }
+    }
+}
+
+/// Weight of a `BooleanQuery`.
+pub struct BooleanWeight {
+    weights: Vec<(Occur, Box<dyn Weight>)>,
+    scoring_enabled: bool,
+}
+
+impl BooleanWeight {
+    pub fn new(weights: Vec<(Occur, Box<dyn Weight>)>, scoring_enabled: bool) -> BooleanWeight {
+        BooleanWeight {
+            weights,
+            scoring_enabled,
+        }
+    }
+
+    fn scorers(
+        &self,
+        occur: Occur,
+        reader: &SegmentReader,
+        boost: Score,
+    ) -> crate::Result<Vec<Box<dyn Scorer>>> {
+        self.weights
+            .iter()
+            .filter(|(weight_occur, _)| *weight_occur == occur)
+            .map(|(_, weight)| weight.scorer(reader, boost))
+            .collect()
+    }
+}
+
+fn union_scorers(mut scorers: Vec<Box<dyn Scorer>>) -> Option<Box<dyn Scorer>> {
+    match scorers.len() {
+        0 => None,
+        1 => scorers.pop(),
+        _ => Some(Box::new(Union::new(scorers))),
+    }
+}
+
+impl Weight for BooleanWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> crate::Result<Box<dyn Scorer>> {
+        let should_scorer = union_scorers(self.scorers(Occur::Should, reader, boost)?);
+        let must_scorers = self.scorers(Occur::Must, reader, boost)?;
+        let must_scorer = if must_scorers.is_empty() {
+            None
+        } else {
+            Some(intersect_scorers(must_scorers))
+        };
+        let positive_scorer: Box<dyn Scorer> = match (should_scorer, must_scorer) {
+            (None, None) => return Ok(Box::new(EmptyScorer)),
+            (Some(should_scorer), None) => should_scorer,
+            (None, Some(must_scorer)) => must_scorer,
+            (Some(should_scorer), Some(must_scorer)) => {
+                if self.scoring_enabled {
+                    Box::new(RequiredOptionalScorer::new(must_scorer, should_scorer))
+                } else {
+                    must_scorer
+                }
+            }
+        };
+        match union_scorers(self.scorers(Occur::MustNot, reader, 1.0)?) {
+            Some(excluded_scorer) => Ok(Box::new(Exclude::new(positive_scorer, excluded_scorer))),
+            None => Ok(positive_scorer),
+        }
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let mut scorer = self.scorer(reader, 1.0)?;
+        seek_to_matching_doc(scorer.as_mut(), doc)?;
+        let mut explanation = Explanation::new("BooleanClause. Sum of ...", scorer.score());
+        for (occur, weight) in &self.weights {
+            if *occur == Occur::MustNot {
+                continue;
+            }
+            if let Ok(child_explanation) = weight.explain(reader, doc) {
+                explanation.add_detail(child_explanation);
+            }
+        }
+        Ok(explanation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::tests::search_all;
+    use crate::{
+        AllQuery, BooleanQuery, DocAddress, DocId, Field, Index, IndexRecordOption, Occur, Query,
+        Schema, Searcher, Term, TermQuery, TEXT,
+    };
+
+    fn term_query(field: Field, text: &str) -> Box<dyn Query> {
+        Box::new(TermQuery::new(
+            Term::from_field_text(field, text),
+            IndexRecordOption::WithFreqs,
+        ))
+    }
+
+    fn matching_docs(query: &dyn Query, searcher: &Searcher) -> Vec<DocId> {
+        let mut docs: Vec<DocId> = search_all(query, searcher)
+            .unwrap()
+            .into_iter()
+            .map(|(_, doc_address)| doc_address.doc_id)
+            .collect();
+        docs.sort_unstable();
+        docs
+    }
+
+    #[test]
+    fn test_boolean_query() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 10_000_000)?;
+        index_writer.add_document(doc!(text => "a b c"))?;
+        index_writer.add_document(doc!(text => "a c"))?;
+        index_writer.add_document(doc!(text => "b c"))?;
+        index_writer.add_document(doc!(text => "a b"))?;
+        index_writer.add_document(doc!(text => "d"))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        let query = BooleanQuery::new(vec![
+            (Occur::Must, term_query(text, "a")),
+            (Occur::Must, term_query(text, "b")),
+        ]);
+        assert_eq!(matching_docs(&query, &searcher), vec![0, 3]);
+        assert_eq!(query.count(&searcher)?, 2);
+
+        let query = BooleanQuery::new(vec![
+            (Occur::Should, term_query(text, "a")),
+            (Occur::Should, term_query(text, "d")),
+        ]);
+        assert_eq!(matching_docs(&query, &searcher), vec![0, 1, 3, 4]);
+
+        let query = BooleanQuery::new(vec![
+            (Occur::Should, term_query(text, "a")),
+            (Occur::MustNot, term_query(text, "b")),
+        ]);
+        assert_eq!(matching_docs(&query, &searcher), vec![1]);
+
+        let query = BooleanQuery::new(vec![
+            (Occur::Must, Box::new(AllQuery)),
+            (Occur::MustNot, term_query(text, "c")),
+        ]);
+        assert_eq!(matching_docs(&query, &searcher), vec![3, 4]);
+
+        let query = BooleanQuery::new(vec![(Occur::MustNot, term_query(text, "c"))]);
+        assert!(matching_docs(&query, &searcher).is_empty());
+
+        let query = BooleanQuery::new(vec![
+            (Occur::Must, term_query(text, "c")),
+            (Occur::Should, term_query(text, "a")),
+        ]);
+        let results = search_all(&query, &searcher)?;
+        assert_eq!(results.len(), 3);
+        // the optional clause only changes the ranking.
+        assert_eq!(results[2].1, DocAddress::new(0, 2));
+        let explanation = query.explain(&searcher, results[0].1)?;
+        assert_eq!(explanation.details().len(), 2);
+        assert!((explanation.value() - results[0].0).abs() < 1e-5);
+        assert!(query.explain(&searcher, DocAddress::new(0, 4)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_boolean_query_terms() {
+        let text = Field::from_field_id(0);
+        let query = BooleanQuery::new(vec![
+            (Occur::Should, term_query(text, "a")),
+            (Occur::MustNot, term_query(text, "b")),
+            (
+                Occur::Must,
+                Box::new(BooleanQuery::new_multiterms_query(vec![
+                    Term::from_field_text(text, "c"),
+                ])),
+            ),
+        ]);
+        let mut terms = std::collections::BTreeSet::new();
+        query.query_terms(&mut terms);
+        let terms: Vec<Term> = terms.into_iter().collect();
+        assert_eq!(
+            terms,
+            vec![
+                Term::from_field_text(text, "a"),
+                Term::from_field_text(text, "c")
+            ]
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::{DocId, Explanation, Query, Score, Scorer, Searcher, SegmentReader, Term, Weight};
+
+/// Multiplies the scores of a query by a constant.
+#[derive(Clone)]
+pub struct BoostQuery {
+    query: Box<dyn Query>,
+    boost: Score,
+}
+
+impl BoostQuery {
+    pub fn new(query: Box<dyn Query>, boost: Score) -> BoostQuery {
+        BoostQuery { query, boost }
+    }
+
+    pub fn boost(&self) -> Score {
+        self.boost
+    }
+}
+
+impl fmt::Debug for BoostQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Boost(query={:?}, boost={})", self.query, self.boost)
+    }
+}
+
+impl Query for BoostQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        let weight = self.query.weight(searcher, scoring_enabled)?;
+        if scoring_enabled {
+            Ok(Box::new(BoostWeight::new(weight, self.boost)))
+        } else {
+            Ok(weight)
+        }
+    }
+
+    fn query_terms(&self, terms: &mut BTreeSet<Term>) {
+        self.query.query_terms(terms);
+    }
+}
+
+/// Weight of a `BoostQuery`.
+pub struct BoostWeight {
+    weight: Box<dyn Weight>,
+    boost: Score,
+}
+
+impl BoostWeight {
+    pub fn new(weight: Box<dyn Weight>, boost: Score) -> BoostWeight {
+        BoostWeight { weight, boost }
+    }
+}
+
+impl Weight for BoostWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> crate::Result<Box<dyn Scorer>> {
+        self.weight.scorer(reader, boost * self.boost)
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let underlying_explanation = self.weight.explain(reader, doc)?;
+        let score = underlying_explanation.value() * self.boost;
+        let mut explanation = Explanation::new(format!("Boost x{} of ...", self.boost), score);
+        explanation.add_detail(underlying_explanation);
+        Ok(explanation)
+    }
+
+    fn count(&self, reader: &SegmentReader) -> crate::Result<u32> {
+        self.weight.count(reader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::tests::search_all;
+    use crate::{
+        BoostQuery, DocAddress, Index, IndexRecordOption, Query, Schema, Term, TermQuery, TEXT,
+    };
+
+    #[test]
+    fn test_boost_query() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 10_000_000)?;
+        index_writer.add_document(doc!(text => "a b"))?;
+        index_writer.add_document(doc!(text => "c"))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        let term_query: Box<dyn Query> = Box::new(TermQuery::new(
+            Term::from_field_text(text, "a"),
+            IndexRecordOption::WithFreqs,
+        ));
+        let score = search_all(&term_query, &searcher)?[0].0;
+        let boost_query = BoostQuery::new(term_query, 3.0);
+        let results = search_all(&boost_query, &searcher)?;
+        assert_eq!(results.len(), 1);
+        assert!((results[0].0 - 3.0 * score).abs() < 1e-5);
+        let explanation = boost_query.explain(&searcher, DocAddress::new(0, 0))?;
+        assert!((explanation.value() - results[0].0).abs() < 1e-5);
+        assert_eq!(boost_query.count(&searcher)?, 1);
+        Ok(())
+    }
+}
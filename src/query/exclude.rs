@@ -0,0 +1,89 @@
+use crate::{DocId, DocSet, Score, Scorer, TERMINATED};
+
+/// Documents of `underlying` that are not in `excluding`.
+pub struct Exclude<TDocSet, TDocSetExclude> {
+    underlying: TDocSet,
+    excluding: TDocSetExclude,
+}
+
+impl<TDocSet: DocSet, TDocSetExclude: DocSet> Exclude<TDocSet, TDocSetExclude> {
+    pub fn new(underlying: TDocSet, excluding: TDocSetExclude) -> Self {
+        let mut exclude = Exclude {
+            underlying,
+            excluding,
+        };
+        exclude.skip_excluded();
+        exclude
+    }
+
+    fn is_excluded(&mut self, doc: DocId) -> bool {
+        self.excluding.doc() <= doc && self.excluding.seek(doc) == doc
+    }
+
+    /// Advances `underlying` until its current document is not excluded.
+    fn skip_excluded(&mut self) -> DocId {
+        let mut doc = self.underlying.doc();
+        while doc != TERMINATED && self.is_excluded(doc) {
+            doc = self.underlying.advance();
+        }
+        doc
+    }
+}
+
+impl<TDocSet: DocSet, TDocSetExclude: DocSet> DocSet for Exclude<TDocSet, TDocSetExclude> {
+    fn advance(&mut self) -> DocId {
+        self.underlying.advance();
+        self.skip_excluded()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        if target <= self.doc() {
+            return self.doc();
+        }
+        self.underlying.seek(target);
+        self.skip_excluded()
+    }
+
+    fn doc(&self) -> DocId {
+        self.underlying.doc()
+    }
+
+    /// The excluded documents are ignored, so this may overestimate the size.
+    fn size_hint(&self) -> u32 {
+        self.underlying.size_hint()
+    }
+}
+
+impl<TScorer: Scorer, TDocSetExclude: DocSet + 'static> Scorer
+    for Exclude<TScorer, TDocSetExclude>
+{
+    fn score(&mut self) -> Score {
+        self.underlying.score()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::intersection::tests::{collect_docs, VecDocSet};
+    use crate::{DocSet, Exclude};
+
+    #[test]
+    fn test_exclude() {
+        let exclude = Exclude::new(
+            VecDocSet::new(vec![1, 2, 5, 8, 10, 15, 24]),
+            VecDocSet::new(vec![1, 2, 3, 10, 16, 24]),
+        );
+        assert_eq!(collect_docs(exclude), vec![5, 8, 15]);
+    }
+
+    #[test]
+    fn test_exclude_seek() {
+        let mut exclude = Exclude::new(
+            VecDocSet::new(vec![1, 2, 5, 8, 10, 15, 24]),
+            VecDocSet::new(vec![1, 10, 16, 24]),
+        );
+        assert_eq!(exclude.doc(), 2);
+        assert_eq!(exclude.seek(9), 15);
+        assert_eq!(collect_docs(exclude), vec![15]);
+    }
+}
@@ -0,0 +1,102 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::Score;
+
+/// Tree describing how a score was computed.
+///
+/// Each node holds a value, a description of what it is, and the values it was
+/// computed from.
+#[derive(Clone, Serialize)]
+pub struct Explanation {
+    value: Score,
+    description: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    details: Vec<Explanation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    context: Vec<String>,
+}
+
+impl fmt::Debug for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Explanation({})", self.to_pretty_json())
+    }
+}
+
+impl Explanation {
+    pub fn new<T: ToString>(description: T, value: Score) -> Explanation {
+        Explanation {
+            value,
+            description: description.to_string(),
+            details: Vec::new(),
+            context: Vec::new(),
+        }
+    }
+
+    pub fn value(&self) -> Score {
+        self.value
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn details(&self) -> &[Explanation] {
+        &self.details
+    }
+
+    /// Adds an explanation of one of the values this value was computed from.
+    pub fn add_detail(&mut self, child_explanation: Explanation) {
+        self.details.push(child_explanation);
+    }
+
+    /// Adds a leaf detail holding a constant.
+    pub fn add_const<T: ToString>(&mut self, description: T, value: Score) {
+        self.details.push(Explanation::new(description, value));
+    }
+
+    /// Adds a free-form note, e.g. the term a value is about.
+    pub fn add_context(&mut self, context: String) {
+        self.context.push(context);
+    }
+
+    pub fn to_pretty_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("an explanation can always be serialized")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Explanation;
+
+    #[test]
+    fn test_explanation_json() {
+        let mut explanation = Explanation::new("product", 6.0);
+        explanation.add_const("a", 2.0);
+        let mut detail = Explanation::new("b", 3.0);
+        detail.add_context("computed elsewhere".to_string());
+        explanation.add_detail(detail);
+        assert_eq!(explanation.details().len(), 2);
+        assert_eq!(
+            explanation.to_pretty_json(),
+            r#"{
+  "value": 6.0,
+  "description": "product",
+  "details": [
+    {
+      "value": 2.0,
+      "description": "a"
+    },
+    {
+      "value": 3.0,
+      "description": "b",
+      "context": [
+        "computed elsewhere"
+      ]
+    }
+  ]
+}"#
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{DocId, DocSet, EmptyScorer, Score, Scorer, TERMINATED};
+
+/// Returns the intersection of `scorers`, summing their scores.
+pub fn intersect_scorers(mut scorers: Vec<Box<dyn Scorer>>) -> Box<dyn Scorer> {
+    if scorers.is_empty() || scorers.iter().any(|scorer| scorer.doc() == TERMINATED) {
+        return Box::new(EmptyScorer);
+    }
+    if scorers.len() == 1 {
+        return scorers.pop().unwrap();
+    }
+    Box::new(Intersection::new(scorers))
+}
+
+/// Moves all the docsets to their first common document.
+fn go_to_first_doc<TDocSet: DocSet>(docsets: &mut [TDocSet]) -> DocId {
+    let mut candidate = docsets.iter().map(TDocSet::doc).max().unwrap();
+    'outer: loop {
+        for docset in docsets.iter_mut() {
+            let seek_doc = docset.seek(candidate);
+            if seek_doc > candidate {
+                candidate = seek_doc;
+                continue 'outer;
+            }
+        }
+        return candidate;
+    }
+}
+
+/// Intersection of two or more docsets.
+///
+/// The two smallest docsets leapfrog each other through `seek` until they agree
+/// on a candidate, which the other docsets then confirm or push forward.
+pub struct Intersection<TDocSet: DocSet> {
+    left: TDocSet,
+    right: TDocSet,
+    others: Vec<TDocSet>,
+}
+
+impl<TDocSet: DocSet> Intersection<TDocSet> {
+    /// Panics if there are less than two docsets.
+    pub fn new(mut docsets: Vec<TDocSet>) -> Intersection<TDocSet> {
+        assert!(docsets.len() >= 2, "an intersection needs two docsets");
+        docsets.sort_by_key(|docset| docset.size_hint());
+        go_to_first_doc(&mut docsets);
+        let left = docsets.remove(0);
+        let right = docsets.remove(0);
+        Intersection {
+            left,
+            right,
+            others: docsets,
+        }
+    }
+
+    /// Number of docsets in the intersection.
+    pub fn num_docsets(&self) -> usize {
+        self.others.len() + 2
+    }
+
+    /// Returns one of the docsets of the intersection, in no particular order.
+    pub fn docset_mut(&mut self, ord: usize) -> &mut TDocSet {
+        match ord {
+            0 => &mut self.left,
+            1 => &mut self.right,
+            n => &mut self.others[n - 2],
+        }
+    }
+}
+
+impl<TDocSet: DocSet> DocSet for Intersection<TDocSet> {
+    fn advance(&mut self) -> DocId {
+        let candidate = self.left.advance();
+        self.go_to_doc(candidate)
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        if target <= self.doc() {
+            return self.doc();
+        }
+        let candidate = self.left.seek(target);
+        self.go_to_doc(candidate)
+    }
+
+    fn doc(&self) -> DocId {
+        self.left.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.left.size_hint()
+    }
+}
+
+impl<TDocSet: DocSet> Intersection<TDocSet> {
+    /// Finds the first common document, starting from the current document of
+    /// `left`, `candidate`. All the other docsets are behind `candidate`.
+    fn go_to_doc(&mut self, mut candidate: DocId) -> DocId {
+        'outer: loop {
+            loop {
+                let right_doc = self.right.seek(candidate);
+                candidate = self.left.seek(right_doc);
+                if candidate == right_doc {
+                    break;
+                }
+            }
+            for other in &mut self.others {
+                let seek_doc = other.seek(candidate);
+                if seek_doc > candidate {
+                    candidate = self.left.seek(seek_doc);
+                    continue 'outer;
+                }
+            }
+            return candidate;
+        }
+    }
+}
+
+impl<TScorer: Scorer> Scorer for Intersection<TScorer> {
+    fn score(&mut self) -> Score {
+        self.left.score()
+            + self.right.score()
+            + self.others.iter_mut().map(Scorer::score).sum::<Score>()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::{ConstScorer, DocId, DocSet, Intersection, TERMINATED};
+
+    /// `DocSet` over a sorted list of documents.
+    pub struct VecDocSet {
+        docs: Vec<DocId>,
+        cursor: usize,
+    }
+
+    impl VecDocSet {
+        pub fn new(docs: Vec<DocId>) -> VecDocSet {
+            VecDocSet { docs, cursor: 0 }
+        }
+    }
+
+    impl DocSet for VecDocSet {
+        fn advance(&mut self) -> DocId {
+            self.cursor += 1;
+            self.doc()
+        }
+
+        fn doc(&self) -> DocId {
+            self.docs.get(self.cursor).cloned().unwrap_or(TERMINATED)
+        }
+
+        fn size_hint(&self) -> u32 {
+            self.docs.len() as u32
+        }
+    }
+
+    pub fn collect_docs<TDocSet: DocSet>(mut docset: TDocSet) -> Vec<DocId> {
+        let mut docs = Vec::new();
+        while docset.doc() != TERMINATED {
+            docs.push(docset.doc());
+            docset.advance();
+        }
+        docs
+    }
+
+    #[test]
+    fn test_intersection() {
+        let intersection = Intersection::new(vec![
+            VecDocSet::new(vec![1, 3, 9]),
+            VecDocSet::new(vec![3, 4, 9, 18]),
+        ]);
+        assert_eq!(collect_docs(intersection), vec![3, 9]);
+
+        let intersection = Intersection::new(vec![
+            VecDocSet::new(vec![1, 4, 5, 6, 8, 20]),
+            VecDocSet::new(vec![2, 4, 6, 8, 20]),
+            VecDocSet::new(vec![3, 4, 6, 20, 21]),
+        ]);
+        assert_eq!(collect_docs(intersection), vec![4, 6, 20]);
+
+        let intersection =
+            Intersection::new(vec![VecDocSet::new(vec![1, 2]), VecDocSet::new(vec![3, 4])]);
+        assert_eq!(collect_docs(intersection), Vec::<DocId>::new());
+    }
+
+    #[test]
+    fn test_intersection_seek() {
+        let mut intersection = Intersection::new(vec![
+            VecDocSet::new(vec![1, 4, 5, 6, 8, 20, 30]),
+            VecDocSet::new(vec![1, 4, 6, 8, 20, 30]),
+            VecDocSet::new(vec![1, 4, 6, 20, 21, 30]),
+        ]);
+        assert_eq!(intersection.doc(), 1);
+        assert_eq!(intersection.seek(5), 6);
+        assert_eq!(intersection.seek(6), 6);
+        assert_eq!(intersection.seek(21), 30);
+        assert_eq!(intersection.advance(), TERMINATED);
+    }
+
+    #[test]
+    fn test_intersection_scores() {
+        use crate::Scorer;
+        let mut intersection = Intersection::new(vec![
+            ConstScorer::new(VecDocSet::new(vec![1, 2]), 1.0),
+            ConstScorer::new(VecDocSet::new(vec![2, 3]), 2.5),
+        ]);
+        assert_eq!(intersection.doc(), 2);
+        assert_eq!(intersection.score(), 3.5);
+    }
+}
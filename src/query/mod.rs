@@ -0,0 +1,68 @@
+//! Queries and their scoring.
+//!
+//! A `Query` describes what to look for. Given a `Searcher`, it creates a
+//! `Weight` holding the statistics of the whole snapshot, which in turn creates
+//! one `Scorer` per segment to iterate over the matching documents.
+mod all_query;
+mod bitset_docset;
+mod bm25;
+mod boolean_query;
+mod boost_query;
+mod exclude;
+mod explanation;
+mod intersection;
+mod occur;
+mod phrase_query;
+mod phrase_scorer;
+mod query;
+mod range_query;
+mod required_optional_scorer;
+mod scorer;
+mod term_query;
+mod term_scorer;
+mod union;
+mod weight;
+
+pub use all_query::*;
+pub use bitset_docset::*;
+pub use bm25::*;
+pub use boolean_query::*;
+pub use boost_query::*;
+pub use exclude::*;
+pub use explanation::*;
+pub use intersection::*;
+pub use occur::*;
+pub use phrase_query::*;
+pub use phrase_scorer::*;
+pub use query::*;
+pub use range_query::*;
+pub use required_optional_scorer::*;
+pub use scorer::*;
+pub use term_query::*;
+pub use term_scorer::*;
+pub use union::*;
+pub use weight::*;
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::{DocAddress, DocSet, Query, Score, Searcher, TERMINATED};
+
+    /// Returns all the documents matching `query`, best scores first.
+    pub fn search_all(
+        query: &dyn Query,
+        searcher: &Searcher,
+    ) -> crate::Result<Vec<(Score, DocAddress)>> {
+        let weight = query.weight(searcher, true)?;
+        let mut results = Vec::new();
+        for (segment_ord, segment_reader) in searcher.segment_readers().iter().enumerate() {
+            let mut scorer = weight.scorer(segment_reader, 1.0)?;
+            while scorer.doc() != TERMINATED {
+                let doc_address = DocAddress::new(segment_ord as u32, scorer.doc());
+                results.push((scorer.score(), doc_address));
+                scorer.advance();
+            }
+        }
+        results.sort_by(|left, right| right.0.partial_cmp(&left.0).unwrap());
+        Ok(results)
+    }
+}
@@ -0,0 +1,21 @@
+/// How a clause of a `BooleanQuery` constrains the matching documents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Occur {
+    /// The clause may match. Matching it increases the score.
+    Should,
+    /// The clause must match.
+    Must,
+    /// The clause must not match. It does not contribute to the score.
+    MustNot,
+}
+
+impl Occur {
+    /// Returns the character used for the occur in query strings.
+    pub fn to_char(self) -> char {
+        match self {
+            Occur::Should => ' ',
+            Occur::Must => '+',
+            Occur::MustNot => '-',
+        }
+    }
+}
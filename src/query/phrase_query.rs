@@ -0,0 +1,243 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::{
+    Bm25Weight, DocId, DocSet, EmptyScorer, Explanation, Field, FieldType, IndexRecordOption,
+    MySearchError, PhraseScorer, Query, Score, Scorer, Searcher, SegmentReader, Term, Weight,
+};
+
+/// Query matching the documents containing a sequence of terms at consecutive
+/// positions.
+///
+/// The field must be indexed with positions.
+#[derive(Clone)]
+pub struct PhraseQuery {
+    field: Field,
+    phrase_terms: Vec<(usize, Term)>,
+}
+
+impl fmt::Debug for PhraseQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PhraseQuery({:?})", self.phrase_terms)
+    }
+}
+
+impl PhraseQuery {
+    /// Creates a phrase query from terms following each other.
+    ///
+    /// Panics if there are less than two terms or if they belong to different
+    /// fields.
+    pub fn new(terms: Vec<Term>) -> PhraseQuery {
+        PhraseQuery::new_with_offset(terms.into_iter().enumerate().collect())
+    }
+
+    /// Creates a phrase query from terms and their positions in the phrase.
+    ///
+    /// Gaps between the offsets let the phrase skip tokens, e.g. stop words the
+    /// analyzer removed.
+    pub fn new_with_offset(mut terms: Vec<(usize, Term)>) -> PhraseQuery {
+        assert!(
+            terms.len() > 1,
+            "a phrase query needs at least two terms, use a TermQuery instead"
+        );
+        terms.sort_by_key(|&(offset, _)| offset);
+        let field = terms[0].1.field();
+        assert!(
+            terms.iter().all(|(_, term)| term.field() == field),
+            "all the terms of a phrase query must belong to the same field"
+        );
+        PhraseQuery {
+            field,
+            phrase_terms: terms,
+        }
+    }
+
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    /// Returns the terms of the phrase, in order.
+    pub fn phrase_terms(&self) -> Vec<Term> {
+        self.phrase_terms
+            .iter()
+            .map(|(_, term)| term.clone())
+            .collect()
+    }
+
+    pub fn specialized_weight(
+        &self,
+        searcher: &Searcher,
+        scoring_enabled: bool,
+    ) -> crate::Result<PhraseWeight> {
+        let field_entry = searcher.schema().get_field_entry(self.field);
+        let has_positions = match field_entry.field_type() {
+            FieldType::Str(text_options) => text_options
+                .get_indexing_options()
+                .map(|indexing_options| indexing_options.index_option().has_positions())
+                .unwrap_or(false),
+            _ => false,
+        };
+        if !has_positions {
+            return Err(MySearchError::SchemaError(format!(
+                "phrase query on field '{}', which does not have positions indexed",
+                field_entry.name()
+            )));
+        }
+        let similarity_weight = Bm25Weight::for_terms(searcher, &self.phrase_terms())?;
+        Ok(PhraseWeight {
+            phrase_terms: self.phrase_terms.clone(),
+            similarity_weight,
+            scoring_enabled,
+        })
+    }
+}
+
+impl Query for PhraseQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        Ok(Box::new(
+            self.specialized_weight(searcher, scoring_enabled)?,
+        ))
+    }
+
+    fn query_terms(&self, terms: &mut BTreeSet<Term>) {
+        for (_, term) in &self.phrase_terms {
+            terms.insert(term.clone());
+        }
+    }
+}
+
+/// Weight of a `PhraseQuery`.
+pub struct PhraseWeight {
+    phrase_terms: Vec<(usize, Term)>,
+    similarity_weight: Bm25Weight,
+    scoring_enabled: bool,
+}
+
+impl PhraseWeight {
+    /// Returns the scorer of the phrase, or `None` if the segment lacks one of
+    /// its terms.
+    pub fn phrase_scorer(
+        &self,
+        reader: &SegmentReader,
+        boost: Score,
+    ) -> crate::Result<Option<PhraseScorer>> {
+        let field = self.phrase_terms[0].1.field();
+        let fieldnorm_reader = reader.get_fieldnorms_reader(field)?;
+        let inverted_index = reader.inverted_index(field);
+        let mut term_postings = Vec::with_capacity(self.phrase_terms.len());
+        for (offset, term) in &self.phrase_terms {
+            match inverted_index.read_postings(term, IndexRecordOption::WithFreqsAndPositions) {
+                Some(postings) => term_postings.push((*offset, postings)),
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(PhraseScorer::new(
+            term_postings,
+            self.similarity_weight.boost_by(boost),
+            fieldnorm_reader,
+            self.scoring_enabled,
+        )))
+    }
+}
+
+impl Weight for PhraseWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> crate::Result<Box<dyn Scorer>> {
+        Ok(match self.phrase_scorer(reader, boost)? {
+            Some(phrase_scorer) => Box::new(phrase_scorer),
+            None => Box::new(EmptyScorer),
+        })
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let not_matching =
+            || MySearchError::InvalidArgument(format!("document #{} does not match", doc));
+        let mut scorer = self.phrase_scorer(reader, 1.0)?.ok_or_else(not_matching)?;
+        if scorer.doc() > doc || scorer.seek(doc) != doc {
+            return Err(not_matching());
+        }
+        Ok(scorer.explain())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::tests::search_all;
+    use crate::{
+        DocAddress, DocId, Index, MySearchError, PhraseQuery, Query, Schema, Searcher, Term,
+        STRING, TEXT,
+    };
+
+    fn matching_docs(query: &dyn Query, searcher: &Searcher) -> Vec<DocId> {
+        let mut docs: Vec<DocId> = search_all(query, searcher)
+            .unwrap()
+            .into_iter()
+            .map(|(_, doc_address)| doc_address.doc_id)
+            .collect();
+        docs.sort_unstable();
+        docs
+    }
+
+    #[test]
+    fn test_phrase_query() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 10_000_000)?;
+        index_writer.add_document(doc!(text => "a b c"))?;
+        index_writer.add_document(doc!(text => "a c b"))?;
+        index_writer.add_document(doc!(text => "b c a b c"))?;
+        index_writer.add_document(doc!(text => "a", text => "b c"))?;
+        index_writer.add_document(doc!(text => "c a d b c"))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        let phrase = |words: &[&str]| {
+            PhraseQuery::new(
+                words
+                    .iter()
+                    .map(|word| Term::from_field_text(text, word))
+                    .collect(),
+            )
+        };
+        assert_eq!(matching_docs(&phrase(&["a", "b"]), &searcher), vec![0, 2]);
+        assert_eq!(
+            matching_docs(&phrase(&["b", "c"]), &searcher),
+            vec![0, 2, 3, 4]
+        );
+        assert_eq!(
+            matching_docs(&phrase(&["a", "b", "c"]), &searcher),
+            vec![0, 2]
+        );
+        assert!(matching_docs(&phrase(&["c", "missing"]), &searcher).is_empty());
+        assert_eq!(phrase(&["b", "c"]).count(&searcher)?, 4);
+
+        let with_gap = PhraseQuery::new_with_offset(vec![
+            (0, Term::from_field_text(text, "a")),
+            (2, Term::from_field_text(text, "b")),
+        ]);
+        // values of a multi-valued field are separated by a position gap.
+        assert_eq!(matching_docs(&with_gap, &searcher), vec![1, 3, 4]);
+
+        // "b c" appears twice in doc 2.
+        let results = search_all(&phrase(&["b", "c"]), &searcher)?;
+        let explanation = phrase(&["b", "c"]).explain(&searcher, DocAddress::new(0, 2))?;
+        assert_eq!(explanation.details()[2].details()[0].value(), 2.0);
+        assert!(results.iter().any(|(score, doc_address)| {
+            doc_address.doc_id == 2 && (*score - explanation.value()).abs() < 1e-5
+        }));
+        assert!(phrase(&["a", "b"])
+            .explain(&searcher, DocAddress::new(0, 1))
+            .is_err());
+
+        let on_tag = PhraseQuery::new(vec![
+            Term::from_field_text(tag, "a"),
+            Term::from_field_text(tag, "b"),
+        ]);
+        assert!(matches!(
+            on_tag.count(&searcher),
+            Err(MySearchError::SchemaError(_))
+        ));
+        Ok(())
+    }
+}
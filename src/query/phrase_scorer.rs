@@ -0,0 +1,199 @@
+use crate::{
+    Bm25Weight, DocId, DocSet, Explanation, FieldNormReader, Intersection, Postings, Score, Scorer,
+    SegmentPostings, TERMINATED,
+};
+
+/// Postings whose positions are shifted so that the terms of a phrase all report
+/// the same position when they appear in order.
+pub struct PostingsWithOffset {
+    offset: u32,
+    postings: SegmentPostings,
+}
+
+impl PostingsWithOffset {
+    pub fn new(postings: SegmentPostings, offset: u32) -> PostingsWithOffset {
+        PostingsWithOffset { offset, postings }
+    }
+
+    pub fn positions(&mut self, output: &mut Vec<u32>) {
+        self.postings.positions_with_offset(self.offset, output);
+    }
+}
+
+impl DocSet for PostingsWithOffset {
+    fn advance(&mut self) -> DocId {
+        self.postings.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.postings.seek(target)
+    }
+
+    fn doc(&self) -> DocId {
+        self.postings.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.postings.size_hint()
+    }
+}
+
+/// Keeps the values of `left` that are also in `right`, both being sorted.
+/// Returns the number of values kept.
+fn intersection(left: &mut [u32], right: &[u32]) -> usize {
+    let mut left_i = 0;
+    let mut right_i = 0;
+    let mut count = 0;
+    while left_i < left.len() && right_i < right.len() {
+        let left_val = left[left_i];
+        let right_val = right[right_i];
+        if left_val < right_val {
+            left_i += 1;
+        } else if right_val < left_val {
+            right_i += 1;
+        } else {
+            left[count] = left_val;
+            count += 1;
+            left_i += 1;
+            right_i += 1;
+        }
+    }
+    count
+}
+
+/// Matches the documents containing the terms of a phrase at consecutive
+/// positions.
+///
+/// The documents containing all the terms are found by intersecting their
+/// postings, then their positions are compared. The number of occurrences of the
+/// phrase is used as the term frequency in BM25.
+pub struct PhraseScorer {
+    intersection_docset: Intersection<PostingsWithOffset>,
+    left: Vec<u32>,
+    right: Vec<u32>,
+    phrase_count: u32,
+    fieldnorm_reader: FieldNormReader,
+    similarity_weight: Bm25Weight,
+    scoring_enabled: bool,
+}
+
+impl PhraseScorer {
+    /// `term_postings` holds the postings of each term of the phrase, with the
+    /// offset of the term in the phrase.
+    pub fn new(
+        term_postings: Vec<(usize, SegmentPostings)>,
+        similarity_weight: Bm25Weight,
+        fieldnorm_reader: FieldNormReader,
+        scoring_enabled: bool,
+    ) -> PhraseScorer {
+        let max_offset = term_postings
+            .iter()
+            .map(|&(offset, _)| offset)
+            .max()
+            .unwrap_or(0);
+        let postings_with_offsets = term_postings
+            .into_iter()
+            .map(|(offset, postings)| {
+                PostingsWithOffset::new(postings, (max_offset - offset) as u32)
+            })
+            .collect();
+        let mut scorer = PhraseScorer {
+            intersection_docset: Intersection::new(postings_with_offsets),
+            left: Vec::with_capacity(100),
+            right: Vec::with_capacity(100),
+            phrase_count: 0,
+            fieldnorm_reader,
+            similarity_weight,
+            scoring_enabled,
+        };
+        if scorer.doc() != TERMINATED && !scorer.phrase_match() {
+            scorer.advance();
+        }
+        scorer
+    }
+
+    /// Number of occurrences of the phrase in the current document.
+    pub fn phrase_count(&self) -> u32 {
+        self.phrase_count
+    }
+
+    pub fn explain(&self) -> Explanation {
+        let fieldnorm_id = self.fieldnorm_reader.fieldnorm_id(self.doc());
+        self.similarity_weight
+            .explain(fieldnorm_id, self.phrase_count)
+    }
+
+    fn phrase_match(&mut self) -> bool {
+        self.intersection_docset
+            .docset_mut(0)
+            .positions(&mut self.left);
+        let mut intersection_len = self.left.len();
+        for i in 1..self.intersection_docset.num_docsets() {
+            self.intersection_docset
+                .docset_mut(i)
+                .positions(&mut self.right);
+            intersection_len = intersection(&mut self.left[..intersection_len], &self.right);
+            if intersection_len == 0 {
+                return false;
+            }
+            // without scoring, one occurrence of the phrase is enough.
+            if !self.scoring_enabled && i + 1 == self.intersection_docset.num_docsets() {
+                break;
+            }
+        }
+        self.phrase_count = intersection_len as u32;
+        true
+    }
+}
+
+impl DocSet for PhraseScorer {
+    fn advance(&mut self) -> DocId {
+        loop {
+            let doc = self.intersection_docset.advance();
+            if doc == TERMINATED || self.phrase_match() {
+                return doc;
+            }
+        }
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        if target <= self.doc() {
+            return self.doc();
+        }
+        let doc = self.intersection_docset.seek(target);
+        if doc == TERMINATED || self.phrase_match() {
+            return doc;
+        }
+        self.advance()
+    }
+
+    fn doc(&self) -> DocId {
+        self.intersection_docset.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.intersection_docset.size_hint()
+    }
+}
+
+impl Scorer for PhraseScorer {
+    fn score(&mut self) -> Score {
+        let fieldnorm_id = self.fieldnorm_reader.fieldnorm_id(self.doc());
+        self.similarity_weight
+            .score(fieldnorm_id, self.phrase_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::intersection;
+
+    #[test]
+    fn test_positions_intersection() {
+        let mut left = vec![1, 3, 4, 9];
+        assert_eq!(intersection(&mut left, &[2, 3, 9, 10]), 2);
+        assert_eq!(&left[..2], &[3, 9]);
+        let mut left = vec![1, 2];
+        assert_eq!(intersection(&mut left, &[3]), 0);
+    }
+}
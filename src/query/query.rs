@@ -0,0 +1,74 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::{DocAddress, Explanation, Searcher, Term, Weight};
+
+/// A query, independent of any index.
+///
+/// Running a query goes through two steps: `Query::weight` computes the
+/// statistics the query needs over a whole `Searcher`, then `Weight::scorer`
+/// iterates over the matching documents of each segment.
+pub trait Query: QueryClone + Send + Sync + fmt::Debug {
+    /// Creates the `Weight` of the query for `searcher`.
+    ///
+    /// If `scoring_enabled` is false, the scores of the matching documents are
+    /// not needed and the query may skip reading what they require.
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>>;
+
+    /// Explains how the document at `doc_address` is scored.
+    ///
+    /// Fails if the document does not match the query.
+    fn explain(&self, searcher: &Searcher, doc_address: DocAddress) -> crate::Result<Explanation> {
+        let segment_reader = searcher.segment_reader(doc_address.segment_ord);
+        let weight = self.weight(searcher, true)?;
+        weight.explain(segment_reader, doc_address.doc_id)
+    }
+
+    /// Number of documents matching the query.
+    fn count(&self, searcher: &Searcher) -> crate::Result<usize> {
+        let weight = self.weight(searcher, false)?;
+        let mut result = 0;
+        for segment_reader in searcher.segment_readers() {
+            result += weight.count(segment_reader)? as usize;
+        }
+        Ok(result)
+    }
+
+    /// Adds the terms the query looks for to `terms`, e.g. to highlight them.
+    fn query_terms(&self, _terms: &mut BTreeSet<Term>) {}
+}
+
+/// Makes `Box<dyn Query>` cloneable.
+pub trait QueryClone {
+    fn box_clone(&self) -> Box<dyn Query>;
+}
+
+impl<T: 'static + Query + Clone> QueryClone for T {
+    fn box_clone(&self) -> Box<dyn Query> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn Query> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+impl Query for Box<dyn Query> {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        self.as_ref().weight(searcher, scoring_enabled)
+    }
+
+    fn explain(&self, searcher: &Searcher, doc_address: DocAddress) -> crate::Result<Explanation> {
+        self.as_ref().explain(searcher, doc_address)
+    }
+
+    fn count(&self, searcher: &Searcher) -> crate::Result<usize> {
+        self.as_ref().count(searcher)
+    }
+
+    fn query_terms(&self, terms: &mut BTreeSet<Term>) {
+        self.as_ref().query_terms(terms);
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::Bound;
+use std::fmt;
+use std::ops::Range;
+
+use crate::{
+    seek_to_matching_doc, BitSet, BitSetDocSet, ConstScorer, DocId, DocSet, Explanation, Field,
+    IndexRecordOption, Query, Score, Scorer, Searcher, SegmentReader, Term, Weight, TERMINATED,
+};
+
+fn map_bound<TFrom, TTo, F: Fn(&TFrom) -> TTo>(bound: &Bound<TFrom>, transform: F) -> Bound<TTo> {
+    match bound {
+        Bound::Included(value) => Bound::Included(transform(value)),
+        Bound::Excluded(value) => Bound::Excluded(transform(value)),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+/// Query matching the documents having a term of a field within a range.
+///
+/// Terms are compared on their encoded bytes, which preserves the order of
+/// numbers and dates. All the matching documents get a score of 1.
+#[derive(Clone)]
+pub struct RangeQuery {
+    field: Field,
+    left_bound: Bound<Term>,
+    right_bound: Bound<Term>,
+}
+
+impl fmt::Debug for RangeQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "RangeQuery({:?}, {:?})",
+            self.left_bound, self.right_bound
+        )
+    }
+}
+
+impl RangeQuery {
+    /// Creates a range query from bounds on terms of `field`.
+    ///
+    /// Panics if a bound belongs to another field.
+    pub fn new_term_bounds(
+        field: Field,
+        left_bound: Bound<Term>,
+        right_bound: Bound<Term>,
+    ) -> RangeQuery {
+        for bound in &[&left_bound, &right_bound] {
+            if let Bound::Included(term) | Bound::Excluded(term) = bound {
+                assert_eq!(term.field(), field, "range bounds must belong to the field");
+            }
+        }
+        RangeQuery {
+            field,
+            left_bound,
+            right_bound,
+        }
+    }
+
+    /// Matches the `u64` values within `range`.
+    pub fn new_u64(field: Field, range: Range<u64>) -> RangeQuery {
+        RangeQuery::new_term_bounds(
+            field,
+            Bound::Included(Term::from_field_u64(field, range.start)),
+            Bound::Excluded(Term::from_field_u64(field, range.end)),
+        )
+    }
+
+    /// Matches the `i64` values within `range`.
+    pub fn new_i64(field: Field, range: Range<i64>) -> RangeQuery {
+        RangeQuery::new_term_bounds(
+            field,
+            Bound::Included(Term::from_field_i64(field, range.start)),
+            Bound::Excluded(Term::from_field_i64(field, range.end)),
+        )
+    }
+
+    /// Matches the `f64` values within `range`.
+    pub fn new_f64(field: Field, range: Range<f64>) -> RangeQuery {
+        RangeQuery::new_term_bounds(
+            field,
+            Bound::Included(Term::from_field_f64(field, range.start)),
+            Bound::Excluded(Term::from_field_f64(field, range.end)),
+        )
+    }
+
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    pub fn left_bound(&self) -> &Bound<Term> {
+        &self.left_bound
+    }
+
+    pub fn right_bound(&self) -> &Bound<Term> {
+        &self.right_bound
+    }
+}
+
+impl Query for RangeQuery {
+    fn weight(
+        &self,
+        _searcher: &Searcher,
+        _scoring_enabled: bool,
+    ) -> crate::Result<Box<dyn Weight>> {
+        Ok(Box::new(RangeWeight {
+            field: self.field,
+            left_bound: map_bound(&self.left_bound, |term| term.as_slice().to_vec()),
+            right_bound: map_bound(&self.right_bound, |term| term.as_slice().to_vec()),
+        }))
+    }
+}
+
+/// Weight of a `RangeQuery`.
+pub struct RangeWeight {
+    field: Field,
+    left_bound: Bound<Vec<u8>>,
+    right_bound: Bound<Vec<u8>>,
+}
+
+impl Weight for RangeWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> crate::Result<Box<dyn Scorer>> {
+        let inverted_index = reader.inverted_index(self.field);
+        let mut term_range = inverted_index.terms().range();
+        term_range = match &self.left_bound {
+            Bound::Included(term_bytes) => term_range.ge(term_bytes),
+            Bound::Excluded(term_bytes) => term_range.gt(term_bytes),
+            Bound::Unbounded => term_range.ge(Term::for_field(self.field).as_slice()),
+        };
+        term_range = match &self.right_bound {
+            Bound::Included(term_bytes) => term_range.le(term_bytes),
+            Bound::Excluded(term_bytes) => term_range.lt(term_bytes),
+            // the terms of the next field start right after the terms of this field.
+            Bound::Unbounded => {
+                let next_field = Field::from_field_id(self.field.field_id() + 1);
+                term_range.lt(Term::for_field(next_field).as_slice())
+            }
+        };
+        let mut doc_bitset = BitSet::with_max_value(reader.max_doc());
+        let mut term_stream = term_range.into_stream();
+        while term_stream.advance() {
+            let mut postings = inverted_index
+                .read_postings_from_terminfo(term_stream.value(), IndexRecordOption::Basic);
+            while postings.doc() != TERMINATED {
+                doc_bitset.insert(postings.doc());
+                postings.advance();
+            }
+        }
+        Ok(Box::new(ConstScorer::new(
+            BitSetDocSet::from(doc_bitset),
+            boost,
+        )))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let mut scorer = self.scorer(reader, 1.0)?;
+        seek_to_matching_doc(scorer.as_mut(), doc)?;
+        Ok(Explanation::new("RangeQuery", 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::Bound;
+
+    use crate::query::tests::search_all;
+    use crate::{DocId, Index, Query, RangeQuery, Schema, Searcher, Term, INDEXED, STRING};
+
+    fn matching_docs(query: &dyn Query, searcher: &Searcher) -> Vec<DocId> {
+        let mut docs: Vec<DocId> = search_all(query, searcher)
+            .unwrap()
+            .into_iter()
+            .map(|(_, doc_address)| doc_address.doc_id)
+            .collect();
+        docs.sort_unstable();
+        docs
+    }
+
+    #[test]
+    fn test_range_query() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let year = schema_builder.add_u64_field("year", INDEXED);
+        let temperature = schema_builder.add_i64_field("temperature", INDEXED);
+        let name = schema_builder.add_text_field("name", STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 10_000_000)?;
+        for (i, &(year_val, temperature_val)) in [
+            (1990u64, -5i64),
+            (2000, 10),
+            (2005, 3),
+            (2010, -20),
+            (2020, 30),
+        ]
+        .iter()
+        .enumerate()
+        {
+            let name_val = format!("name{}", i);
+            index_writer.add_document(doc!(
+                year => year_val,
+                temperature => temperature_val,
+                name => name_val.as_str()
+            ))?;
+        }
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        let query = RangeQuery::new_u64(year, 2000..2010);
+        assert_eq!(matching_docs(&query, &searcher), vec![1, 2]);
+        assert_eq!(query.count(&searcher)?, 2);
+        let query = RangeQuery::new_i64(temperature, -10..10);
+        assert_eq!(matching_docs(&query, &searcher), vec![0, 2]);
+
+        let query = RangeQuery::new_term_bounds(
+            year,
+            Bound::Excluded(Term::from_field_u64(year, 2005)),
+            Bound::Unbounded,
+        );
+        assert_eq!(matching_docs(&query, &searcher), vec![3, 4]);
+        let query = RangeQuery::new_term_bounds(
+            temperature,
+            Bound::Unbounded,
+            Bound::Included(Term::from_field_i64(temperature, -5)),
+        );
+        assert_eq!(matching_docs(&query, &searcher), vec![0, 3]);
+        let query = RangeQuery::new_term_bounds(
+            name,
+            Bound::Included(Term::from_field_text(name, "name1")),
+            Bound::Included(Term::from_field_text(name, "name3")),
+        );
+        assert_eq!(matching_docs(&query, &searcher), vec![1, 2, 3]);
+        let query = RangeQuery::new_term_bounds(name, Bound::Unbounded, Bound::Unbounded);
+        assert_eq!(query.count(&searcher)?, 5);
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{DocId, DocSet, Score, Scorer};
+
+/// Matches the documents of `req_scorer`, adding the score of `opt_scorer`
+/// when it matches them too.
+pub struct RequiredOptionalScorer<TReqScorer, TOptScorer> {
+    req_scorer: TReqScorer,
+    opt_scorer: TOptScorer,
+}
+
+impl<TReqScorer: Scorer, TOptScorer: Scorer> RequiredOptionalScorer<TReqScorer, TOptScorer> {
+    pub fn new(req_scorer: TReqScorer, opt_scorer: TOptScorer) -> Self {
+        RequiredOptionalScorer {
+            req_scorer,
+            opt_scorer,
+        }
+    }
+}
+
+impl<TReqScorer: Scorer, TOptScorer: Scorer> DocSet
+    for RequiredOptionalScorer<TReqScorer, TOptScorer>
+{
+    fn advance(&mut self) -> DocId {
+        self.req_scorer.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.req_scorer.seek(target)
+    }
+
+    fn doc(&self) -> DocId {
+        self.req_scorer.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.req_scorer.size_hint()
+    }
+}
+
+impl<TReqScorer: Scorer, TOptScorer: Scorer> Scorer
+    for RequiredOptionalScorer<TReqScorer, TOptScorer>
+{
+    fn score(&mut self) -> Score {
+        let doc = self.doc();
+        let mut score = self.req_scorer.score();
+        // the optional scorer only moves when asked for a score.
+        if self.opt_scorer.doc() <= doc && self.opt_scorer.seek(doc) == doc {
+            score += self.opt_scorer.score();
+        }
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::intersection::tests::{collect_docs, VecDocSet};
+    use crate::{ConstScorer, DocSet, RequiredOptionalScorer, Scorer};
+
+    #[test]
+    fn test_required_optional_scorer() {
+        let mut scorer = RequiredOptionalScorer::new(
+            ConstScorer::new(VecDocSet::new(vec![1, 3, 7]), 1.0),
+            ConstScorer::new(VecDocSet::new(vec![2, 3, 4, 7]), 0.5),
+        );
+        assert_eq!(scorer.score(), 1.0);
+        assert_eq!(scorer.advance(), 3);
+        assert_eq!(scorer.score(), 1.5);
+        assert_eq!(scorer.advance(), 7);
+        assert_eq!(scorer.score(), 1.5);
+        assert_eq!(
+            collect_docs(RequiredOptionalScorer::new(
+                ConstScorer::new(VecDocSet::new(vec![1, 3, 7]), 1.0),
+                ConstScorer::new(VecDocSet::new(vec![2]), 1.0),
+            )),
+            vec![1, 3, 7]
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{DocId, DocSet, MySearchError, Score, TERMINATED};
+
+/// `DocSet` of the documents matching a query in a segment, with their scores.
+pub trait Scorer: DocSet + 'static {
+    /// Returns the score of the current document.
+    fn score(&mut self) -> Score;
+}
+
+impl Scorer for Box<dyn Scorer> {
+    fn score(&mut self) -> Score {
+        self.as_mut().score()
+    }
+}
+
+/// Moves `scorer` to `doc` and checks that it matches it.
+///
+/// Used to implement `Weight::explain`.
+pub(crate) fn seek_to_matching_doc(scorer: &mut dyn Scorer, doc: DocId) -> crate::Result<()> {
+    if scorer.doc() > doc || scorer.seek(doc) != doc {
+        return Err(MySearchError::InvalidArgument(format!(
+            "document #{} does not match the query",
+            doc
+        )));
+    }
+    Ok(())
+}
+
+/// Scorer matching no document.
+pub struct EmptyScorer;
+
+impl DocSet for EmptyScorer {
+    fn advance(&mut self) -> DocId {
+        TERMINATED
+    }
+
+    fn doc(&self) -> DocId {
+        TERMINATED
+    }
+
+    fn size_hint(&self) -> u32 {
+        0
+    }
+}
+
+impl Scorer for EmptyScorer {
+    fn score(&mut self) -> Score {
+        0.0
+    }
+}
+
+/// Gives the same score to all the documents of a `DocSet`.
+pub struct ConstScorer<TDocSet: DocSet> {
+    docset: TDocSet,
+    score: Score,
+}
+
+impl<TDocSet: DocSet> ConstScorer<TDocSet> {
+    pub fn new(docset: TDocSet, score: Score) -> ConstScorer<TDocSet> {
+        ConstScorer { docset, score }
+    }
+}
+
+impl<TDocSet: DocSet> DocSet for ConstScorer<TDocSet> {
+    fn advance(&mut self) -> DocId {
+        self.docset.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.docset.seek(target)
+    }
+
+    fn doc(&self) -> DocId {
+        self.docset.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.docset.size_hint()
+    }
+}
+
+impl<TDocSet: DocSet + 'static> Scorer for ConstScorer<TDocSet> {
+    fn score(&mut self) -> Score {
+        self.score
+    }
+}
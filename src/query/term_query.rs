@@ -0,0 +1,184 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::{
+    Bm25Weight, DocId, DocSet, EmptyScorer, Explanation, FieldNormReader, IndexRecordOption,
+    MySearchError, Query, Score, Scorer, Searcher, SegmentReader, Term, TermScorer, Weight,
+};
+
+/// Query matching the documents containing a term, scored with BM25.
+#[derive(Clone)]
+pub struct TermQuery {
+    term: Term,
+    index_record_option: IndexRecordOption,
+}
+
+impl fmt::Debug for TermQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TermQuery({:?})", self.term)
+    }
+}
+
+impl TermQuery {
+    /// Creates a query for `term`.
+    ///
+    /// Scoring uses term frequencies only if `index_record_option` asks for them.
+    pub fn new(term: Term, index_record_option: IndexRecordOption) -> TermQuery {
+        TermQuery {
+            term,
+            index_record_option,
+        }
+    }
+
+    pub fn term(&self) -> &Term {
+        &self.term
+    }
+
+    pub fn specialized_weight(
+        &self,
+        searcher: &Searcher,
+        scoring_enabled: bool,
+    ) -> crate::Result<TermWeight> {
+        let similarity_weight = Bm25Weight::for_terms(searcher, std::slice::from_ref(&self.term))?;
+        let index_record_option = if scoring_enabled {
+            self.index_record_option
+        } else {
+            IndexRecordOption::Basic
+        };
+        Ok(TermWeight {
+            term: self.term.clone(),
+            index_record_option,
+            similarity_weight,
+        })
+    }
+}
+
+impl Query for TermQuery {
+    fn weight(&self, searcher: &Searcher, scoring_enabled: bool) -> crate::Result<Box<dyn Weight>> {
+        Ok(Box::new(
+            self.specialized_weight(searcher, scoring_enabled)?,
+        ))
+    }
+
+    fn query_terms(&self, terms: &mut BTreeSet<Term>) {
+        terms.insert(self.term.clone());
+    }
+}
+
+/// Weight of a `TermQuery`.
+pub struct TermWeight {
+    term: Term,
+    index_record_option: IndexRecordOption,
+    similarity_weight: Bm25Weight,
+}
+
+impl TermWeight {
+    /// Returns the scorer of the term, or `None` if the segment does not contain it.
+    pub fn specialized_scorer(
+        &self,
+        reader: &SegmentReader,
+        boost: Score,
+    ) -> crate::Result<Option<TermScorer>> {
+        let field = self.term.field();
+        // fields that are not tokenized have one token per document.
+        let fieldnorm_reader = reader
+            .fieldnorm_readers()
+            .get_field(field)?
+            .unwrap_or_else(|| FieldNormReader::constant(reader.max_doc(), 1));
+        let postings = reader
+            .inverted_index(field)
+            .read_postings(&self.term, self.index_record_option);
+        Ok(postings.map(|postings| {
+            TermScorer::new(
+                postings,
+                fieldnorm_reader,
+                self.similarity_weight.boost_by(boost),
+            )
+        }))
+    }
+}
+
+impl Weight for TermWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> crate::Result<Box<dyn Scorer>> {
+        Ok(match self.specialized_scorer(reader, boost)? {
+            Some(term_scorer) => Box::new(term_scorer),
+            None => Box::new(EmptyScorer),
+        })
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation> {
+        let not_matching =
+            || MySearchError::InvalidArgument(format!("document #{} does not match", doc));
+        let mut scorer = self
+            .specialized_scorer(reader, 1.0)?
+            .ok_or_else(not_matching)?;
+        if scorer.seek(doc) != doc {
+            return Err(not_matching());
+        }
+        Ok(scorer.explain())
+    }
+
+    fn count(&self, reader: &SegmentReader) -> crate::Result<u32> {
+        if reader.alive_bitset().is_some() {
+            // the doc freq includes the deleted documents.
+            let mut count = 0u32;
+            self.for_each(reader, &mut |doc, _| {
+                if !reader.is_deleted(doc) {
+                    count += 1;
+                }
+            })?;
+            return Ok(count);
+        }
+        Ok(reader
+            .inverted_index(self.term.field())
+            .doc_freq(&self.term))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::tests::search_all;
+    use crate::{
+        DocAddress, Index, IndexRecordOption, Query, Schema, Term, TermQuery, INDEXED, TEXT,
+    };
+
+    #[test]
+    fn test_term_query() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let body = schema_builder.add_text_field("body", TEXT);
+        let count = schema_builder.add_u64_field("count", INDEXED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, 10_000_000)?;
+        index_writer.add_document(doc!(body => "a b c d e f", count => 1u64))?;
+        index_writer.add_document(doc!(body => "b", count => 2u64))?;
+        index_writer.add_document(doc!(body => "c d"))?;
+        index_writer.add_document(doc!(body => "b b c", count => 1u64))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        let query = TermQuery::new(
+            Term::from_field_text(body, "b"),
+            IndexRecordOption::WithFreqs,
+        );
+        assert_eq!(query.count(&searcher)?, 3);
+        let results = search_all(&query, &searcher)?;
+        let docs: Vec<u32> = results.iter().map(|(_, doc)| doc.doc_id).collect();
+        // the short field and the repeated term score higher than the long field.
+        assert_eq!(docs, vec![1, 3, 0]);
+        let explanation = query.explain(&searcher, DocAddress::new(0, 3))?;
+        assert_eq!(explanation.value(), results[1].0);
+        assert!(query.explain(&searcher, DocAddress::new(0, 2)).is_err());
+
+        let query = TermQuery::new(Term::from_field_u64(count, 1), IndexRecordOption::Basic);
+        assert_eq!(query.count(&searcher)?, 2);
+        assert_eq!(search_all(&query, &searcher)?.len(), 2);
+
+        let query = TermQuery::new(
+            Term::from_field_text(body, "missing"),
+            IndexRecordOption::Basic,
+        );
+        assert_eq!(query.count(&searcher)?, 0);
+        assert!(search_all(&query, &searcher)?.is_empty());
+        Ok(())
+    }
+}
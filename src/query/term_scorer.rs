@@ -0,0 +1,63 @@
+use crate::{
+    Bm25Weight, DocId, DocSet, Explanation, FieldNormReader, Postings, Score, Scorer,
+    SegmentPostings,
+};
+
+/// Scores the documents containing a term with BM25.
+pub struct TermScorer {
+    postings: SegmentPostings,
+    fieldnorm_reader: FieldNormReader,
+    similarity_weight: Bm25Weight,
+}
+
+impl TermScorer {
+    pub fn new(
+        postings: SegmentPostings,
+        fieldnorm_reader: FieldNormReader,
+        similarity_weight: Bm25Weight,
+    ) -> TermScorer {
+        TermScorer {
+            postings,
+            fieldnorm_reader,
+            similarity_weight,
+        }
+    }
+
+    pub fn term_freq(&self) -> u32 {
+        self.postings.term_freq()
+    }
+
+    pub fn fieldnorm_id(&self) -> u8 {
+        self.fieldnorm_reader.fieldnorm_id(self.doc())
+    }
+
+    pub fn explain(&self) -> Explanation {
+        self.similarity_weight
+            .explain(self.fieldnorm_id(), self.term_freq())
+    }
+}
+
+impl DocSet for TermScorer {
+    fn advance(&mut self) -> DocId {
+        self.postings.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.postings.seek(target)
+    }
+
+    fn doc(&self) -> DocId {
+        self.postings.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.postings.size_hint()
+    }
+}
+
+impl Scorer for TermScorer {
+    fn score(&mut self) -> Score {
+        self.similarity_weight
+            .score(self.fieldnorm_id(), self.term_freq())
+    }
+}
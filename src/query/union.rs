@@ -0,0 +1,110 @@
+use crate::{DocId, DocSet, Score, Scorer, TERMINATED};
+
+/// Union of docsets, summing the scores of the scorers matching each document.
+pub struct Union<TDocSet: DocSet> {
+    docsets: Vec<TDocSet>,
+    doc: DocId,
+}
+
+impl<TDocSet: DocSet> Union<TDocSet> {
+    pub fn new(docsets: Vec<TDocSet>) -> Union<TDocSet> {
+        let mut union = Union {
+            docsets,
+            doc: TERMINATED,
+        };
+        union.refresh();
+        union
+    }
+
+    /// Drops the exhausted docsets and moves to the smallest current document.
+    fn refresh(&mut self) -> DocId {
+        self.docsets.retain(|docset| docset.doc() != TERMINATED);
+        self.doc = self
+            .docsets
+            .iter()
+            .map(DocSet::doc)
+            .min()
+            .unwrap_or(TERMINATED);
+        self.doc
+    }
+}
+
+impl<TDocSet: DocSet> DocSet for Union<TDocSet> {
+    fn advance(&mut self) -> DocId {
+        let doc = self.doc;
+        for docset in &mut self.docsets {
+            if docset.doc() == doc {
+                docset.advance();
+            }
+        }
+        self.refresh()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        if target <= self.doc {
+            return self.doc;
+        }
+        for docset in &mut self.docsets {
+            if docset.doc() < target {
+                docset.seek(target);
+            }
+        }
+        self.refresh()
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.docsets
+            .iter()
+            .map(DocSet::size_hint)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl<TScorer: Scorer> Scorer for Union<TScorer> {
+    fn score(&mut self) -> Score {
+        let doc = self.doc;
+        self.docsets
+            .iter_mut()
+            .filter(|scorer| scorer.doc() == doc)
+            .map(Scorer::score)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::intersection::tests::{collect_docs, VecDocSet};
+    use crate::{ConstScorer, DocId, DocSet, Scorer, Union, TERMINATED};
+
+    #[test]
+    fn test_union() {
+        let union = Union::new(vec![
+            VecDocSet::new(vec![1, 3, 9]),
+            VecDocSet::new(vec![3, 4, 9, 18]),
+            VecDocSet::new(vec![]),
+        ]);
+        assert_eq!(collect_docs(union), vec![1, 3, 4, 9, 18]);
+        let empty: Union<VecDocSet> = Union::new(vec![]);
+        assert_eq!(collect_docs(empty), Vec::<DocId>::new());
+    }
+
+    #[test]
+    fn test_union_seek_and_score() {
+        let mut union = Union::new(vec![
+            ConstScorer::new(VecDocSet::new(vec![1, 3, 9]), 1.0),
+            ConstScorer::new(VecDocSet::new(vec![3, 4, 9, 18]), 2.0),
+        ]);
+        assert_eq!(union.score(), 1.0);
+        assert_eq!(union.seek(3), 3);
+        assert_eq!(union.score(), 3.0);
+        assert_eq!(union.seek(5), 9);
+        assert_eq!(union.advance(), 18);
+        assert_eq!(union.score(), 2.0);
+        assert_eq!(union.seek(19), TERMINATED);
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{DocId, DocSet, Explanation, Score, Scorer, SegmentReader, TERMINATED};
+
+/// A query bound to the statistics of a `Searcher`.
+///
+/// It creates the `Scorer` of each segment.
+pub trait Weight: Send + Sync + 'static {
+    /// Returns the documents of `reader` matching the query, with their scores
+    /// multiplied by `boost`.
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> crate::Result<Box<dyn Scorer>>;
+
+    /// Explains how `doc` is scored.
+    ///
+    /// Fails if `doc` does not match the query.
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> crate::Result<Explanation>;
+
+    /// Calls `callback` with each matching document of `reader` and its score.
+    fn for_each(
+        &self,
+        reader: &SegmentReader,
+        callback: &mut dyn FnMut(DocId, Score),
+    ) -> crate::Result<()> {
+        let mut scorer = self.scorer(reader, 1.0)?;
+        while scorer.doc() != TERMINATED {
+            callback(scorer.doc(), scorer.score());
+            scorer.advance();
+        }
+        Ok(())
+    }
+
+    /// Number of documents of `reader` matching the query, deleted documents
+    /// excluded.
+    fn count(&self, reader: &SegmentReader) -> crate::Result<u32> {
+        let mut scorer = self.scorer(reader, 1.0)?;
+        let mut count = 0u32;
+        while scorer.doc() != TERMINATED {
+            if !reader.is_deleted(scorer.doc()) {
+                count += 1;
+            }
+            scorer.advance();
+        }
+        Ok(count)
+    }
+}
@@ -0,0 +1,11 @@
+//! Parsing of the queries typed by users.
+//!
+//! `query_grammar` turns the query string into a `UserInputAst`, which
+//! `QueryParser` then resolves against the schema into a `Query`.
+mod query_grammar;
+mod query_parser;
+mod user_input_ast;
+
+pub use query_grammar::parse_query_string;
+pub use query_parser::*;
+pub use user_input_ast::*;
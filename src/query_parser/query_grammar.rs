@@ -0,0 +1,466 @@
+use crate::{Occur, QueryParserError, UserInputAst, UserInputBound, UserInputLeaf};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Operator {
+    And,
+    Or,
+}
+
+/// Parses a query string.
+///
+/// Clauses separated by whitespace only are combined with `AND` if
+/// `conjunction_by_default` is true, with `OR` otherwise. `AND` binds tighter
+/// than `OR`.
+pub fn parse_query_string(
+    query: &str,
+    conjunction_by_default: bool,
+) -> Result<UserInputAst, QueryParserError> {
+    let mut parser = Parser {
+        query,
+        pos: 0,
+        default_operator: if conjunction_by_default {
+            Operator::And
+        } else {
+            Operator::Or
+        },
+    };
+    parser.parse_clauses(0)
+}
+
+fn is_word_end(c: char, allow_colon: bool) -> bool {
+    c.is_whitespace()
+        || matches!(c, '(' | ')' | '"' | '^' | '[' | ']' | '{' | '}')
+        || (c == ':' && !allow_colon)
+}
+
+/// Groups the clauses by `OR`, each group being a conjunction.
+fn aggregate(
+    clauses: Vec<(Option<Occur>, UserInputAst)>,
+    operators: Vec<Operator>,
+) -> UserInputAst {
+    let mut groups: Vec<Vec<(Option<Occur>, UserInputAst)>> = Vec::new();
+    let mut clauses_it = clauses.into_iter();
+    if let Some(first_clause) = clauses_it.next() {
+        groups.push(vec![first_clause]);
+    }
+    for (operator, clause) in operators.into_iter().zip(clauses_it) {
+        match operator {
+            Operator::And => groups.last_mut().unwrap().push(clause),
+            Operator::Or => groups.push(vec![clause]),
+        }
+    }
+    let mut clauses: Vec<(Option<Occur>, UserInputAst)> = groups
+        .into_iter()
+        .map(|mut group| {
+            if group.len() == 1 {
+                group.pop().unwrap()
+            } else {
+                let conjunction = group
+                    .into_iter()
+                    .map(|(occur, ast)| (Some(occur.unwrap_or(Occur::Must)), ast))
+                    .collect();
+                (None, UserInputAst::Clause(conjunction))
+            }
+        })
+        .collect();
+    if clauses.len() == 1 && clauses[0].0.is_none() {
+        return clauses.pop().unwrap().1;
+    }
+    UserInputAst::Clause(clauses)
+}
+
+struct Parser<'a> {
+    query: &'a str,
+    pos: usize,
+    default_operator: Operator,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.query[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+            self.bump();
+        }
+    }
+
+    fn syntax_error<T>(&self, position: usize, message: &str) -> Result<T, QueryParserError> {
+        Err(QueryParserError::SyntaxError {
+            position,
+            message: message.to_string(),
+        })
+    }
+
+    /// Consumes `keyword` if it is followed by whitespace, a parenthesis or a
+    /// phrase.
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let rest = &self.query[self.pos..];
+        let is_keyword = rest.starts_with(keyword)
+            && rest[keyword.len()..]
+                .chars()
+                .next()
+                .map(|c| c.is_whitespace() || c == '(' || c == '"')
+                .unwrap_or(false);
+        if is_keyword {
+            self.pos += keyword.len();
+        }
+        is_keyword
+    }
+
+    fn parse_clauses(&mut self, depth: usize) -> Result<UserInputAst, QueryParserError> {
+        let mut clauses = Vec::new();
+        let mut operators = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => break,
+                Some(')') if depth > 0 => break,
+                Some(')') => return self.syntax_error(self.pos, "unmatched ')'"),
+                _ => {}
+            }
+            let operator_pos = self.pos;
+            let operator = if self.eat_keyword("AND") {
+                Some(Operator::And)
+            } else if self.eat_keyword("OR") {
+                Some(Operator::Or)
+            } else {
+                None
+            };
+            match operator {
+                Some(operator) => {
+                    if clauses.is_empty() {
+                        return self
+                            .syntax_error(operator_pos, "expected a clause before operator");
+                    }
+                    self.skip_whitespace();
+                    if matches!(self.peek(), None | Some(')')) {
+                        return self.syntax_error(self.pos, "expected a clause after operator");
+                    }
+                    operators.push(operator);
+                }
+                None if !clauses.is_empty() => operators.push(self.default_operator),
+                None => {}
+            }
+            clauses.push(self.parse_clause(depth)?);
+        }
+        Ok(aggregate(clauses, operators))
+    }
+
+    fn parse_clause(
+        &mut self,
+        depth: usize,
+    ) -> Result<(Option<Occur>, UserInputAst), QueryParserError> {
+        let occur = match self.peek() {
+            Some('+') | Some('-') => {
+                let occur = if self.bump() == Some('+') {
+                    Occur::Must
+                } else {
+                    Occur::MustNot
+                };
+                if matches!(self.peek(), None | Some(')')) || self.peek().unwrap().is_whitespace() {
+                    return self.syntax_error(self.pos, "expected a clause after '+' or '-'");
+                }
+                Some(occur)
+            }
+            _ if self.eat_keyword("NOT") => {
+                self.skip_whitespace();
+                Some(Occur::MustNot)
+            }
+            _ => None,
+        };
+        let mut ast = self.parse_leaf(depth)?;
+        if self.peek() == Some('^') {
+            self.bump();
+            let boost_start = self.pos;
+            while matches!(self.peek(), Some(c) if c.is_ascii_digit() || c == '.') {
+                self.bump();
+            }
+            let boost = match self.query[boost_start..self.pos].parse::<f64>() {
+                Ok(boost) => boost,
+                Err(_) => return self.syntax_error(boost_start, "expected a number after '^'"),
+            };
+            ast = UserInputAst::Boost(Box::new(ast), boost);
+        }
+        Ok((occur, ast))
+    }
+
+    fn parse_leaf(&mut self, depth: usize) -> Result<UserInputAst, QueryParserError> {
+        let start = self.pos;
+        let leaf = match self.peek() {
+            None => return self.syntax_error(start, "unexpected end of query"),
+            Some('(') => {
+                self.bump();
+                let ast = self.parse_clauses(depth + 1)?;
+                if self.bump() != Some(')') {
+                    return self.syntax_error(start, "missing closing ')'");
+                }
+                return Ok(ast);
+            }
+            Some('"') => UserInputLeaf::Literal {
+                field_name: None,
+                text: self.parse_phrase()?,
+                position: start,
+            },
+            Some('[') | Some('{') => self.parse_range(None, start)?,
+            Some('*')
+                if self.query[start + 1..]
+                    .chars()
+                    .next()
+                    .map(|c| c.is_whitespace() || c == ')' || c == '^')
+                    .unwrap_or(true) =>
+            {
+                self.bump();
+                UserInputLeaf::All { position: start }
+            }
+            Some(c) => {
+                let word = self.parse_word(false);
+                if word.is_empty() {
+                    return self.syntax_error(start, &format!("unexpected character '{}'", c));
+                }
+                if self.peek() == Some(':') {
+                    self.bump();
+                    self.parse_field_value(word, start)?
+                } else {
+                    UserInputLeaf::Literal {
+                        field_name: None,
+                        text: word,
+                        position: start,
+                    }
+                }
+            }
+        };
+        Ok(UserInputAst::Leaf(Box::new(leaf)))
+    }
+
+    fn parse_field_value(
+        &mut self,
+        field_name: String,
+        start: usize,
+    ) -> Result<UserInputLeaf, QueryParserError> {
+        match self.peek() {
+            Some('"') => Ok(UserInputLeaf::Literal {
+                field_name: Some(field_name),
+                text: self.parse_phrase()?,
+                position: start,
+            }),
+            Some('[') | Some('{') => self.parse_range(Some(field_name), start),
+            _ => {
+                let value_pos = self.pos;
+                let text = self.parse_word(true);
+                if text.is_empty() {
+                    return self.syntax_error(value_pos, "expected a value after ':'");
+                }
+                Ok(UserInputLeaf::Literal {
+                    field_name: Some(field_name),
+                    text,
+                    position: start,
+                })
+            }
+        }
+    }
+
+    fn parse_word(&mut self, allow_colon: bool) -> String {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if !is_word_end(c, allow_colon)) {
+            self.bump();
+        }
+        self.query[start..self.pos].to_string()
+    }
+
+    /// Parses a quoted phrase. `\` escapes the next character.
+    fn parse_phrase(&mut self) -> Result<String, QueryParserError> {
+        let start = self.pos;
+        self.bump();
+        let mut phrase = String::new();
+        loop {
+            match self.bump() {
+                None => return self.syntax_error(start, "unterminated phrase"),
+                Some('"') => return Ok(phrase),
+                Some('\\') => match self.bump() {
+                    Some(c) => phrase.push(c),
+                    None => return self.syntax_error(start, "unterminated phrase"),
+                },
+                Some(c) => phrase.push(c),
+            }
+        }
+    }
+
+    fn parse_range(
+        &mut self,
+        field_name: Option<String>,
+        start: usize,
+    ) -> Result<UserInputLeaf, QueryParserError> {
+        let lower_inclusive = self.bump() == Some('[');
+        self.skip_whitespace();
+        let lower = self.parse_bound_value()?;
+        self.skip_whitespace();
+        if !self.eat_keyword("TO") {
+            return self.syntax_error(self.pos, "expected 'TO' in range");
+        }
+        self.skip_whitespace();
+        let upper = self.parse_bound_value()?;
+        self.skip_whitespace();
+        let upper_inclusive = match self.peek() {
+            Some(']') => true,
+            Some('}') => false,
+            _ => return self.syntax_error(self.pos, "expected ']' or '}' to close the range"),
+        };
+        self.bump();
+        let to_bound = |value: Option<String>, inclusive: bool| match value {
+            None => UserInputBound::Unbounded,
+            Some(value) if inclusive => UserInputBound::Inclusive(value),
+            Some(value) => UserInputBound::Exclusive(value),
+        };
+        Ok(UserInputLeaf::Range {
+            field_name,
+            lower: to_bound(lower, lower_inclusive),
+            upper: to_bound(upper, upper_inclusive),
+            position: start,
+        })
+    }
+
+    /// Parses a range bound. Returns `None` for `*`.
+    fn parse_bound_value(&mut self) -> Result<Option<String>, QueryParserError> {
+        if self.peek() == Some('"') {
+            return self.parse_phrase().map(Some);
+        }
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if !c.is_whitespace() && c != ']' && c != '}') {
+            self.bump();
+        }
+        match &self.query[start..self.pos] {
+            "" => self.syntax_error(start, "expected a range bound"),
+            "*" => Ok(None),
+            value => Ok(Some(value.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_query_string, QueryParserError};
+
+    fn assert_parse(query: &str, expected: &str) {
+        let ast = parse_query_string(query, false).unwrap();
+        assert_eq!(format!("{:?}", ast), expected);
+    }
+
+    fn literal(field_name: Option<&str>, text: &str, position: usize) -> String {
+        format!(
+            "Leaf(Literal {{ field_name: {:?}, text: {:?}, position: {} }})",
+            field_name.map(str::to_string),
+            text,
+            position
+        )
+    }
+
+    fn syntax_error_position(query: &str) -> usize {
+        match parse_query_string(query, false) {
+            Err(QueryParserError::SyntaxError { position, .. }) => position,
+            other => panic!("expected a syntax error for {:?}, got {:?}", query, other),
+        }
+    }
+
+    #[test]
+    fn test_parse_leaves() {
+        assert_parse("foo", &literal(None, "foo", 0));
+        assert_parse("  title:foo ", &literal(Some("title"), "foo", 2));
+        assert_parse(
+            r#"title:"a \"quoted\" phrase""#,
+            &literal(Some("title"), r#"a "quoted" phrase"#, 0),
+        );
+        assert_parse(
+            "date:2020-01-01T00:00:00Z",
+            &literal(Some("date"), "2020-01-01T00:00:00Z", 0),
+        );
+        assert_parse("*", "Leaf(All { position: 0 })");
+        assert_parse(
+            "price:[10 TO *}",
+            "Leaf(Range { field_name: Some(\"price\"), lower: Inclusive(\"10\"), \
+             upper: Unbounded, position: 0 })",
+        );
+        assert_parse(
+            "{a TO \"b c\"]",
+            "Leaf(Range { field_name: None, lower: Exclusive(\"a\"), \
+             upper: Inclusive(\"b c\"), position: 0 })",
+        );
+        assert_parse(
+            "foo^2.5",
+            &format!("Boost({}, 2.5)", literal(None, "foo", 0)),
+        );
+    }
+
+    #[test]
+    fn test_parse_operators() {
+        assert_parse(
+            "a b",
+            &format!(
+                "Clause([(None, {}), (None, {})])",
+                literal(None, "a", 0),
+                literal(None, "b", 2)
+            ),
+        );
+        assert_parse(
+            "+a -b NOT c",
+            &format!(
+                "Clause([(Some(Must), {}), (Some(MustNot), {}), (Some(MustNot), {})])",
+                literal(None, "a", 1),
+                literal(None, "b", 4),
+                literal(None, "c", 10)
+            ),
+        );
+        assert_parse(
+            "a AND b OR c",
+            &format!(
+                "Clause([(None, Clause([(Some(Must), {}), (Some(Must), {})])), (None, {})])",
+                literal(None, "a", 0),
+                literal(None, "b", 6),
+                literal(None, "c", 11)
+            ),
+        );
+        assert_parse(
+            r#"title:foo AND (bar OR "exact phrase") -baz"#,
+            &format!(
+                "Clause([(None, Clause([(Some(Must), {}), (Some(Must), Clause([(None, {}), \
+                 (None, {})]))])), (Some(MustNot), {})])",
+                literal(Some("title"), "foo", 0),
+                literal(None, "bar", 15),
+                literal(None, "exact phrase", 22),
+                literal(None, "baz", 39)
+            ),
+        );
+        assert_parse("(a)", &literal(None, "a", 1));
+        assert_parse("", "Clause([])");
+        let ast = parse_query_string("a b", true).unwrap();
+        assert_eq!(
+            format!("{:?}", ast),
+            format!(
+                "Clause([(Some(Must), {}), (Some(Must), {})])",
+                literal(None, "a", 0),
+                literal(None, "b", 2)
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(syntax_error_position("a )"), 2);
+        assert_eq!(syntax_error_position("a (b c"), 2);
+        assert_eq!(syntax_error_position("title:\"abc"), 6);
+        assert_eq!(syntax_error_position("title: foo"), 6);
+        assert_eq!(syntax_error_position("foo^x"), 4);
+        assert_eq!(syntax_error_position("AND foo"), 0);
+        assert_eq!(syntax_error_position("foo AND "), 8);
+        assert_eq!(syntax_error_position("a - b"), 3);
+        assert_eq!(syntax_error_position("price:[10 20]"), 10);
+        assert_eq!(syntax_error_position("price:[10 TO 20"), 15);
+    }
+}
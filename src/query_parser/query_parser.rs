@@ -0,0 +1,550 @@
+use std::collections::Bound;
+
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+use crate::{
+    parse_query_string, AllQuery, BooleanQuery, BoostQuery, Facet, Field, FieldType, Index,
+    IndexRecordOption, Occur, PhraseQuery, Query, RangeQuery, Schema, Score, Term, TermQuery,
+    TokenizerManager, UserInputAst, UserInputBound, UserInputLeaf,
+};
+
+/// Error returned by `QueryParser::parse_query`.
+///
+/// Positions are byte offsets in the query string.
+#[derive(Debug, Error, PartialEq)]
+pub enum QueryParserError {
+    #[error("Syntax error at position {position}: {message}")]
+    SyntaxError { position: usize, message: String },
+    #[error("The field '{field_name}' at position {position} does not exist in the schema")]
+    FieldDoesNotExist { position: usize, field_name: String },
+    #[error("The field '{field_name}' at position {position} is not indexed")]
+    FieldNotIndexed { position: usize, field_name: String },
+    #[error(
+        "The field '{field_name}' at position {position} does not record positions, \
+         phrase queries are not possible"
+    )]
+    FieldDoesNotHavePositionsIndexed { position: usize, field_name: String },
+    #[error("Expected an integer at position {position}, got '{value}'")]
+    ExpectedInt { position: usize, value: String },
+    #[error("Expected a float at position {position}, got '{value}'")]
+    ExpectedFloat { position: usize, value: String },
+    #[error("Expected an RFC 3339 date at position {position}, got '{value}'")]
+    ExpectedDate { position: usize, value: String },
+    #[error("Expected a facet at position {position}, got '{value}'")]
+    ExpectedFacet { position: usize, value: String },
+    #[error("Expected base64 bytes at position {position}, got '{value}'")]
+    ExpectedBase64 { position: usize, value: String },
+    #[error("Range bound '{value}' at position {position} is not a single term")]
+    RangeBoundNotSingleTerm { position: usize, value: String },
+    #[error("The query at position {position} only excludes documents")]
+    AllButQueryForbidden { position: usize },
+    #[error("No field is given at position {position} and no default field is declared")]
+    NoDefaultFieldDeclared { position: usize },
+    #[error("The tokenizer '{tokenizer_name}' of field '{field_name}' is not registered")]
+    UnknownTokenizer {
+        field_name: String,
+        tokenizer_name: String,
+    },
+}
+
+/// Parses the queries typed by users into `Query` objects.
+///
+/// The syntax is close to Lucene's:
+/// - `title:foo` looks for `foo` in `title`, `foo` alone looks in the default
+///   fields.
+/// - `"exact phrase"` matches consecutive terms.
+/// - `a AND b`, `a OR b`, `+a`, `-a` and `NOT a` combine clauses, grouped with
+///   parentheses. Clauses without operator are combined with `OR`, or with `AND`
+///   after `set_conjunction_by_default`.
+/// - `foo^2` multiplies the score of a clause.
+/// - `price:[10 TO 20}` matches a range, `[` and `]` being inclusive, `{` and `}`
+///   exclusive and `*` unbounded.
+/// - `*` matches all the documents.
+///
+/// Text goes through the `TextAnalyzer` of its field, so that the query matches
+/// the terms produced at indexing.
+#[derive(Clone)]
+pub struct QueryParser {
+    schema: Schema,
+    default_fields: Vec<Field>,
+    conjunction_by_default: bool,
+    tokenizer_manager: TokenizerManager,
+}
+
+impl QueryParser {
+    pub fn new(
+        schema: Schema,
+        default_fields: Vec<Field>,
+        tokenizer_manager: TokenizerManager,
+    ) -> QueryParser {
+        QueryParser {
+            schema,
+            default_fields,
+            conjunction_by_default: false,
+            tokenizer_manager,
+        }
+    }
+
+    /// Creates a query parser using the schema and the tokenizers of `index`.
+    pub fn for_index(index: &Index, default_fields: Vec<Field>) -> QueryParser {
+        QueryParser::new(index.schema(), default_fields, index.tokenizers().clone())
+    }
+
+    /// Combines the clauses without operator with `AND` instead of `OR`.
+    pub fn set_conjunction_by_default(&mut self) {
+        self.conjunction_by_default = true;
+    }
+
+    /// Parses `query`.
+    ///
+    /// A query whose text produces no token at all matches no document.
+    pub fn parse_query(&self, query: &str) -> Result<Box<dyn Query>, QueryParserError> {
+        let user_input_ast = parse_query_string(query, self.conjunction_by_default)?;
+        Ok(self
+            .compute_query(&user_input_ast)?
+            .unwrap_or_else(|| Box::new(BooleanQuery::new(Vec::new()))))
+    }
+
+    fn compute_query(
+        &self,
+        ast: &UserInputAst,
+    ) -> Result<Option<Box<dyn Query>>, QueryParserError> {
+        match ast {
+            UserInputAst::Clause(clauses) => {
+                let mut subqueries = Vec::new();
+                for (occur, clause_ast) in clauses {
+                    if let Some(subquery) = self.compute_query(clause_ast)? {
+                        subqueries.push((occur.unwrap_or(Occur::Should), subquery));
+                    }
+                }
+                if subqueries.is_empty() {
+                    return Ok(None);
+                }
+                if subqueries.iter().all(|(occur, _)| *occur == Occur::MustNot) {
+                    return Err(QueryParserError::AllButQueryForbidden {
+                        position: ast.position().unwrap_or(0),
+                    });
+                }
+                if subqueries.len() == 1 {
+                    return Ok(subqueries.pop().map(|(_, subquery)| subquery));
+                }
+                Ok(Some(Box::new(BooleanQuery::new(subqueries))))
+            }
+            UserInputAst::Boost(ast, boost) => Ok(self
+                .compute_query(ast)?
+                .map(|query| Box::new(BoostQuery::new(query, *boost as Score)) as Box<dyn Query>)),
+            UserInputAst::Leaf(leaf) => self.compute_leaf_query(leaf),
+        }
+    }
+
+    fn compute_leaf_query(
+        &self,
+        leaf: &UserInputLeaf,
+    ) -> Result<Option<Box<dyn Query>>, QueryParserError> {
+        let mut field_queries = Vec::new();
+        match leaf {
+            UserInputLeaf::All { .. } => return Ok(Some(Box::new(AllQuery))),
+            UserInputLeaf::Literal {
+                field_name,
+                text,
+                position,
+            } => {
+                for field in self.resolve_fields(field_name.as_deref(), *position)? {
+                    if let Some(query) = self.compute_literal_query(field, text, *position)? {
+                        field_queries.push((Occur::Should, query));
+                    }
+                }
+            }
+            UserInputLeaf::Range {
+                field_name,
+                lower,
+                upper,
+                position,
+            } => {
+                for field in self.resolve_fields(field_name.as_deref(), *position)? {
+                    let range_query = RangeQuery::new_term_bounds(
+                        field,
+                        self.resolve_bound(field, lower, *position)?,
+                        self.resolve_bound(field, upper, *position)?,
+                    );
+                    field_queries.push((Occur::Should, Box::new(range_query) as Box<dyn Query>));
+                }
+            }
+        }
+        if field_queries.len() <= 1 {
+            return Ok(field_queries.pop().map(|(_, query)| query));
+        }
+        Ok(Some(Box::new(BooleanQuery::new(field_queries))))
+    }
+
+    fn resolve_fields(
+        &self,
+        field_name: Option<&str>,
+        position: usize,
+    ) -> Result<Vec<Field>, QueryParserError> {
+        match field_name {
+            Some(field_name) => {
+                let field = self.schema.get_field(field_name).ok_or_else(|| {
+                    QueryParserError::FieldDoesNotExist {
+                        position,
+                        field_name: field_name.to_string(),
+                    }
+                })?;
+                Ok(vec![field])
+            }
+            None if self.default_fields.is_empty() => {
+                Err(QueryParserError::NoDefaultFieldDeclared { position })
+            }
+            None => Ok(self.default_fields.clone()),
+        }
+    }
+
+    fn resolve_bound(
+        &self,
+        field: Field,
+        bound: &UserInputBound,
+        position: usize,
+    ) -> Result<Bound<Term>, QueryParserError> {
+        let value = match bound {
+            UserInputBound::Inclusive(value) | UserInputBound::Exclusive(value) => value,
+            UserInputBound::Unbounded => return Ok(Bound::Unbounded),
+        };
+        let mut terms = self.compute_terms(field, value, position)?;
+        if terms.len() != 1 {
+            return Err(QueryParserError::RangeBoundNotSingleTerm {
+                position,
+                value: value.clone(),
+            });
+        }
+        let (_, term) = terms.pop().unwrap();
+        Ok(match bound {
+            UserInputBound::Inclusive(_) => Bound::Included(term),
+            _ => Bound::Excluded(term),
+        })
+    }
+
+    fn compute_literal_query(
+        &self,
+        field: Field,
+        text: &str,
+        position: usize,
+    ) -> Result<Option<Box<dyn Query>>, QueryParserError> {
+        let mut terms = self.compute_terms(field, text, position)?;
+        let index_record_option = match self.schema.get_field_entry(field).field_type() {
+            FieldType::Str(text_options) => text_options
+                .get_indexing_options()
+                .map(|indexing_options| indexing_options.index_option())
+                .unwrap_or(IndexRecordOption::Basic),
+            _ => IndexRecordOption::Basic,
+        };
+        match terms.len() {
+            0 => Ok(None),
+            1 => {
+                let (_, term) = terms.pop().unwrap();
+                Ok(Some(Box::new(TermQuery::new(term, index_record_option))))
+            }
+            _ if index_record_option.has_positions() => {
+                Ok(Some(Box::new(PhraseQuery::new_with_offset(terms))))
+            }
+            _ => Err(QueryParserError::FieldDoesNotHavePositionsIndexed {
+                position,
+                field_name: self.schema.get_field_name(field).to_string(),
+            }),
+        }
+    }
+
+    /// Converts `text` into the terms of `field`, with their positions.
+    fn compute_terms(
+        &self,
+        field: Field,
+        text: &str,
+        position: usize,
+    ) -> Result<Vec<(usize, Term)>, QueryParserError> {
+        let field_entry = self.schema.get_field_entry(field);
+        if !field_entry.is_indexed() {
+            return Err(QueryParserError::FieldNotIndexed {
+                position,
+                field_name: field_entry.name().to_string(),
+            });
+        }
+        let term = match field_entry.field_type() {
+            FieldType::Str(text_options) => {
+                let tokenizer_name = text_options
+                    .get_indexing_options()
+                    .map(|indexing_options| indexing_options.tokenizer())
+                    .unwrap_or_default();
+                let text_analyzer =
+                    self.tokenizer_manager.get(tokenizer_name).ok_or_else(|| {
+                        QueryParserError::UnknownTokenizer {
+                            field_name: field_entry.name().to_string(),
+                            tokenizer_name: tokenizer_name.to_string(),
+                        }
+                    })?;
+                let mut terms = Vec::new();
+                let mut token_stream = text_analyzer.token_stream(text);
+                token_stream.process(&mut |token| {
+                    terms.push((token.position, Term::from_field_text(field, &token.text)));
+                });
+                return Ok(terms);
+            }
+            FieldType::U64(_) => {
+                let val = text
+                    .parse::<u64>()
+                    .map_err(|_| QueryParserError::ExpectedInt {
+                        position,
+                        value: text.to_string(),
+                    })?;
+                Term::from_field_u64(field, val)
+            }
+            FieldType::I64(_) => {
+                let val = text
+                    .parse::<i64>()
+                    .map_err(|_| QueryParserError::ExpectedInt {
+                        position,
+                        value: text.to_string(),
+                    })?;
+                Term::from_field_i64(field, val)
+            }
+            FieldType::F64(_) => {
+                let val = text
+                    .parse::<f64>()
+                    .map_err(|_| QueryParserError::ExpectedFloat {
+                        position,
+                        value: text.to_string(),
+                    })?;
+                Term::from_field_f64(field, val)
+            }
+            FieldType::Date(_) => {
+                let date = DateTime::parse_from_rfc3339(text).map_err(|_| {
+                    QueryParserError::ExpectedDate {
+                        position,
+                        value: text.to_string(),
+                    }
+                })?;
+                Term::from_field_date(field, &date.with_timezone(&Utc))
+            }
+            FieldType::HierarchicalFacet(_) => {
+                let facet =
+                    Facet::from_text(text).ok_or_else(|| QueryParserError::ExpectedFacet {
+                        position,
+                        value: text.to_string(),
+                    })?;
+                Term::from_facet(field, &facet)
+            }
+            FieldType::Bytes(_) => {
+                let bytes = base64::decode(text).map_err(|_| QueryParserError::ExpectedBase64 {
+                    position,
+                    value: text.to_string(),
+                })?;
+                Term::from_field_bytes(field, &bytes)
+            }
+        };
+        Ok(vec![(0, term)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::tests::search_all;
+    use crate::{
+        FacetOptions, Field, Index, IndexRecordOption, QueryParser, QueryParserError, Schema, Term,
+        TextFieldIndexing, TextOptions, FAST, INDEXED, STORED, STRING, TEXT,
+    };
+
+    fn test_index() -> Index {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", TEXT);
+        schema_builder.add_text_field("body", TEXT | STORED);
+        schema_builder.add_text_field("tag", STRING);
+        schema_builder.add_text_field(
+            "nopos",
+            TextOptions::default().set_indexing_options(
+                TextFieldIndexing::default().set_index_option(IndexRecordOption::WithFreqs),
+            ),
+        );
+        schema_builder.add_u64_field("price", INDEXED | FAST);
+        schema_builder.add_i64_field("delta", INDEXED);
+        schema_builder.add_f64_field("ratio", INDEXED);
+        schema_builder.add_date_field("date", INDEXED);
+        schema_builder.add_facet_field("category", FacetOptions::default().set_indexed());
+        schema_builder.add_u64_field("stored_only", STORED);
+        Index::create_in_ram(schema_builder.build())
+    }
+
+    fn parse(query: &str) -> Result<String, QueryParserError> {
+        let index = test_index();
+        let title = index.schema().get_field("title").unwrap();
+        let body = index.schema().get_field("body").unwrap();
+        let query_parser = QueryParser::for_index(&index, vec![title, body]);
+        query_parser
+            .parse_query(query)
+            .map(|query| format!("{:?}", query))
+    }
+
+    #[test]
+    fn test_parse_terms() {
+        assert_eq!(
+            parse("title:Foo").unwrap(),
+            r#"TermQuery(Term(field=0, "Foo"))"#
+        );
+        assert_eq!(
+            parse("foo").unwrap(),
+            r#"BooleanQuery { subqueries: [(Should, TermQuery(Term(field=0, "foo"))), (Should, TermQuery(Term(field=1, "foo")))] }"#
+        );
+        let price = Field::from_field_id(4);
+        assert_eq!(
+            parse("price:10").unwrap(),
+            format!("TermQuery({:?})", Term::from_field_u64(price, 10))
+        );
+        assert_eq!(
+            parse("title:\"a b\"").unwrap(),
+            r#"PhraseQuery([(0, Term(field=0, "a")), (1, Term(field=0, "b"))])"#
+        );
+        // the analyzer splits the word.
+        assert_eq!(
+            parse("title:a-b").unwrap(),
+            r#"PhraseQuery([(0, Term(field=0, "a")), (1, Term(field=0, "b"))])"#
+        );
+        assert_eq!(
+            parse("tag:\"a b\"").unwrap(),
+            r#"TermQuery(Term(field=2, "a b"))"#
+        );
+        assert_eq!(
+            parse("title:foo^2").unwrap(),
+            r#"Boost(query=TermQuery(Term(field=0, "foo")), boost=2)"#
+        );
+        assert_eq!(parse("*").unwrap(), "AllQuery");
+        assert_eq!(
+            parse("title:\"!\"").unwrap(),
+            "BooleanQuery { subqueries: [] }"
+        );
+        assert!(parse("delta:-3").is_ok());
+        assert!(parse("ratio:1.5").is_ok());
+        assert!(parse("date:\"2020-01-01T00:00:00Z\"").is_ok());
+        assert!(parse("category:/a/b").is_ok());
+    }
+
+    #[test]
+    fn test_parse_ranges() {
+        let price = Field::from_field_id(4);
+        assert_eq!(
+            parse("price:[10 TO 20}").unwrap(),
+            format!(
+                "RangeQuery(Included({:?}), Excluded({:?}))",
+                Term::from_field_u64(price, 10),
+                Term::from_field_u64(price, 20)
+            )
+        );
+        assert_eq!(
+            parse("title:{a TO *]").unwrap(),
+            r#"RangeQuery(Excluded(Term(field=0, "a")), Unbounded)"#
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse("title:a unknown:b"),
+            Err(QueryParserError::FieldDoesNotExist {
+                position: 8,
+                field_name: "unknown".to_string()
+            })
+        );
+        assert_eq!(
+            parse("price:abc"),
+            Err(QueryParserError::ExpectedInt {
+                position: 0,
+                value: "abc".to_string()
+            })
+        );
+        assert_eq!(
+            parse("a OR ratio:x"),
+            Err(QueryParserError::ExpectedFloat {
+                position: 5,
+                value: "x".to_string()
+            })
+        );
+        assert!(matches!(
+            parse("date:yesterday"),
+            Err(QueryParserError::ExpectedDate { position: 0, .. })
+        ));
+        assert!(matches!(
+            parse("category:a"),
+            Err(QueryParserError::ExpectedFacet { position: 0, .. })
+        ));
+        assert!(matches!(
+            parse("stored_only:1"),
+            Err(QueryParserError::FieldNotIndexed { position: 0, .. })
+        ));
+        assert!(matches!(
+            parse("nopos:\"a b\""),
+            Err(QueryParserError::FieldDoesNotHavePositionsIndexed { position: 0, .. })
+        ));
+        assert!(matches!(
+            parse("title:[\"a b\" TO c]"),
+            Err(QueryParserError::RangeBoundNotSingleTerm { position: 0, .. })
+        ));
+        assert_eq!(
+            parse("  -a -b"),
+            Err(QueryParserError::AllButQueryForbidden { position: 3 })
+        );
+        assert!(matches!(
+            parse("title:(a"),
+            Err(QueryParserError::SyntaxError { position: 6, .. })
+        ));
+
+        let index = test_index();
+        let query_parser = QueryParser::for_index(&index, Vec::new());
+        assert!(matches!(
+            query_parser.parse_query("title:a b"),
+            Err(QueryParserError::NoDefaultFieldDeclared { position: 8 })
+        ));
+    }
+
+    #[test]
+    fn test_query_parser_search() -> crate::Result<()> {
+        let index = test_index();
+        let schema = index.schema();
+        let title = schema.get_field("title").unwrap();
+        let body = schema.get_field("body").unwrap();
+        let price = schema.get_field("price").unwrap();
+        let mut index_writer = index.writer_with_num_threads(1, 10_000_000)?;
+        index_writer
+            .add_document(doc!(title => "foo", body => "an exact phrase", price => 10u64))?;
+        index_writer.add_document(doc!(title => "foo", body => "bar baz", price => 15u64))?;
+        index_writer.add_document(doc!(title => "foo", body => "phrase exact", price => 30u64))?;
+        index_writer.add_document(doc!(title => "qux", body => "bar", price => 20u64))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+
+        let mut query_parser = QueryParser::for_index(&index, vec![title, body]);
+        let matching_docs = |query_parser: &QueryParser, query: &str| {
+            let query = query_parser.parse_query(query).unwrap();
+            let mut docs: Vec<u32> = search_all(&query, &searcher)
+                .unwrap()
+                .into_iter()
+                .map(|(_, doc_address)| doc_address.doc_id)
+                .collect();
+            docs.sort_unstable();
+            docs
+        };
+        assert_eq!(
+            matching_docs(
+                &query_parser,
+                r#"title:foo AND (bar OR "exact phrase") -baz"#
+            ),
+            vec![0]
+        );
+        assert_eq!(matching_docs(&query_parser, "price:[10 TO 20}"), vec![0, 1]);
+        assert_eq!(
+            matching_docs(&query_parser, "price:{10 TO *]"),
+            vec![1, 2, 3]
+        );
+        assert_eq!(matching_docs(&query_parser, "bar qux"), vec![1, 3]);
+        assert_eq!(matching_docs(&query_parser, "* -foo"), vec![3]);
+        query_parser.set_conjunction_by_default();
+        assert_eq!(matching_docs(&query_parser, "bar qux"), vec![3]);
+        Ok(())
+    }
+}
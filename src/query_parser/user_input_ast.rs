@@ -0,0 +1,54 @@
+use crate::Occur;
+
+/// Bound of a range, as typed by the user.
+#[derive(Clone, Debug, PartialEq)]
+pub enum UserInputBound {
+    Inclusive(String),
+    Exclusive(String),
+    Unbounded,
+}
+
+/// Leaf of a parsed query. Positions are byte offsets in the query string.
+#[derive(Clone, Debug, PartialEq)]
+pub enum UserInputLeaf {
+    /// A word or a quoted phrase, optionally prefixed by a field name.
+    Literal {
+        field_name: Option<String>,
+        text: String,
+        position: usize,
+    },
+    /// `*`, matching all the documents.
+    All { position: usize },
+    /// `[lower TO upper]`, optionally prefixed by a field name.
+    Range {
+        field_name: Option<String>,
+        lower: UserInputBound,
+        upper: UserInputBound,
+        position: usize,
+    },
+}
+
+/// Query string parsed, before its fields and terms are resolved.
+#[derive(Clone, Debug, PartialEq)]
+pub enum UserInputAst {
+    /// Boolean combination of clauses. A clause without an explicit occur is
+    /// optional.
+    Clause(Vec<(Option<Occur>, UserInputAst)>),
+    Leaf(Box<UserInputLeaf>),
+    Boost(Box<UserInputAst>, f64),
+}
+
+impl UserInputAst {
+    /// Byte offset of the start of the query part this node was parsed from.
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            UserInputAst::Clause(clauses) => clauses.first().and_then(|(_, ast)| ast.position()),
+            UserInputAst::Leaf(leaf) => Some(match leaf.as_ref() {
+                UserInputLeaf::Literal { position, .. }
+                | UserInputLeaf::All { position }
+                | UserInputLeaf::Range { position, .. } => *position,
+            }),
+            UserInputAst::Boost(ast, _) => ast.position(),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use std::sync::{Arc, RwLock, Weak};
+
+use crate::{Index, Searcher, SegmentReader, WatchHandle, META_LOCK};
+
+/// Defines when an `IndexReader` picks up new commits.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReloadPolicy {
+    /// The reader is only reloaded by explicit calls to `IndexReader::reload`.
+    Manual,
+    /// The reader reloads itself whenever a commit is published.
+    ///
+    /// With an `MmapDirectory`, `meta.json` is polled, so commits become visible
+    /// after a short delay.
+    OnCommit,
+}
+
+/// Configures an `IndexReader`. Obtained through `Index::reader_builder`.
+#[derive(Clone)]
+pub struct IndexReaderBuilder {
+    reload_policy: ReloadPolicy,
+    index: Index,
+}
+
+impl IndexReaderBuilder {
+    pub(crate) fn new(index: Index) -> IndexReaderBuilder {
+        IndexReaderBuilder {
+            reload_policy: ReloadPolicy::OnCommit,
+            index,
+        }
+    }
+
+    /// Sets the reload policy. Defaults to `ReloadPolicy::OnCommit`.
+    pub fn reload_policy(mut self, reload_policy: ReloadPolicy) -> IndexReaderBuilder {
+        self.reload_policy = reload_policy;
+        self
+    }
+
+    /// Opens the segments of the last commit and builds the `IndexReader`.
+    pub fn try_into(self) -> crate::Result<IndexReader> {
+        let searcher = load_searcher(&self.index)?;
+        let inner = Arc::new(InnerIndexReader {
+            index: self.index,
+            searcher: RwLock::new(searcher),
+        });
+        let watch_handle = match self.reload_policy {
+            ReloadPolicy::Manual => None,
+            ReloadPolicy::OnCommit => {
+                let inner_weak: Weak<InnerIndexReader> = Arc::downgrade(&inner);
+                let watch_handle = inner.index.directory().watch(Box::new(move || {
+                    if let Some(inner) = inner_weak.upgrade() {
+                        // a failed reload keeps serving the previous snapshot.
+                        let _ = inner.reload();
+                    }
+                }))?;
+                Some(watch_handle)
+            }
+        };
+        Ok(IndexReader {
+            inner,
+            _watch_handle: watch_handle,
+        })
+    }
+}
+
+fn load_searcher(index: &Index) -> crate::Result<Searcher> {
+    // keeps the files of the commit from being garbage collected before the
+    // segment readers hold on to them.
+    let _meta_lock = index.directory().acquire_lock(&META_LOCK)?;
+    let segment_readers = index
+        .searchable_segment_metas()?
+        .into_iter()
+        .map(|segment_meta| SegmentReader::open(&index.segment(segment_meta)))
+        .collect::<crate::Result<Vec<_>>>()?;
+    Searcher::new(index.schema(), index.clone(), segment_readers)
+}
+
+struct InnerIndexReader {
+    index: Index,
+    searcher: RwLock<Searcher>,
+}
+
+impl InnerIndexReader {
+    fn reload(&self) -> crate::Result<()> {
+        let searcher = load_searcher(&self.index)?;
+        *self.searcher.write().unwrap() = searcher;
+        Ok(())
+    }
+}
+
+/// Hands out `Searcher`s over the last loaded commit of an index.
+///
+/// Cloning an `IndexReader` is cheap: clones share the same snapshot.
+#[derive(Clone)]
+pub struct IndexReader {
+    inner: Arc<InnerIndexReader>,
+    _watch_handle: Option<WatchHandle>,
+}
+
+impl IndexReader {
+    /// Loads the last commit. Searchers obtained afterwards see it.
+    pub fn reload(&self) -> crate::Result<()> {
+        self.inner.reload()
+    }
+
+    /// Returns a snapshot of the index as of the last reload.
+    ///
+    /// Searchers are cheap to create and stay valid after subsequent reloads.
+    pub fn searcher(&self) -> Searcher {
+        self.inner.searcher.read().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        DocSet, Index, IndexMeta, IndexRecordOption, Postings, ReloadPolicy, Schema, Term,
+        MEMORY_ARENA_NUM_BYTES_MIN, TEXT,
+    };
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn test_manual_reload() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        assert_eq!(reader.searcher().num_docs(), 0);
+
+        let mut index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+        index_writer.add_document(doc!(text => "hello world"))?;
+        index_writer.add_document(doc!(text => "hello"))?;
+        index_writer.commit()?;
+        let previous_searcher = reader.searcher();
+        assert_eq!(previous_searcher.num_docs(), 0);
+
+        reader.reload()?;
+        let searcher = reader.searcher();
+        assert_eq!(searcher.num_docs(), 2);
+        assert_eq!(searcher.segment_readers().len(), 1);
+        assert_eq!(searcher.doc_freq(&Term::from_field_text(text, "hello")), 2);
+        assert_eq!(searcher.doc_freq(&Term::from_field_text(text, "world")), 1);
+        assert_eq!(previous_searcher.num_docs(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reload_on_commit_in_ram() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let reader = index.reader()?;
+        let mut index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+        index_writer.add_document(doc!(text => "hello"))?;
+        index_writer.commit()?;
+        assert_eq!(reader.searcher().num_docs(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reload_on_commit_on_disk() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let tempdir = tempfile::TempDir::new()?;
+        let index = Index::create_in_dir(tempdir.path(), schema_builder.build())?;
+        let reader = index.reader()?;
+        {
+            // the writer works on its own instance of the index, like another process would.
+            let writer_index = Index::open_in_dir(tempdir.path())?;
+            let mut index_writer = writer_index.writer(MEMORY_ARENA_NUM_BYTES_MIN)?;
+            index_writer.add_document(doc!(text => "hello"))?;
+            index_writer.commit()?;
+        }
+        for _ in 0..50 {
+            if reader.searcher().num_docs() > 0 {
+                break;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        assert_eq!(reader.searcher().num_docs(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_searcher_survives_segment_deletion() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let text = schema_builder.add_text_field("text", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer_with_num_threads(1, MEMORY_ARENA_NUM_BYTES_MIN)?;
+        index_writer.add_document(doc!(text => "a b a"))?;
+        index_writer.commit()?;
+        let reader = index.reader()?;
+        let searcher = reader.searcher();
+
+        // a commit dropping the segment, as a merge would.
+        IndexMeta::with_schema(index.schema()).save(index.directory())?;
+        let gc_result = index.garbage_collect_files()?;
+        assert!(gc_result.deleted_files.is_empty());
+        assert_eq!(reader.searcher().num_docs(), 0);
+
+        let term = Term::from_field_text(text, "a");
+        let mut postings = searcher
+            .segment_reader(0)
+            .inverted_index(text)
+            .read_postings(&term, IndexRecordOption::WithFreqsAndPositions)
+            .unwrap();
+        assert_eq!(postings.doc(), 0);
+        assert_eq!(postings.term_freq(), 2);
+        let mut positions = Vec::new();
+        postings.positions(&mut positions);
+        assert_eq!(positions, vec![0, 2]);
+
+        drop(postings);
+        drop(searcher);
+        let gc_result = index.garbage_collect_files()?;
+        assert!(!gc_result.deleted_files.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,3 @@
+mod index_reader;
+
+pub use index_reader::*;
@@ -0,0 +1,53 @@
+use std::ops::BitOr;
+
+use serde::{Deserialize, Serialize};
+
+/// Options of a bytes field.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BytesOptions {
+    indexed: bool,
+    fast: bool,
+    stored: bool,
+}
+
+impl BytesOptions {
+    pub fn is_indexed(&self) -> bool {
+        self.indexed
+    }
+
+    pub fn is_fast(&self) -> bool {
+        self.fast
+    }
+
+    pub fn is_stored(&self) -> bool {
+        self.stored
+    }
+
+    pub fn set_indexed(mut self) -> BytesOptions {
+        self.indexed = true;
+        self
+    }
+
+    pub fn set_fast(mut self) -> BytesOptions {
+        self.fast = true;
+        self
+    }
+
+    pub fn set_stored(mut self) -> BytesOptions {
+        self.stored = true;
+        self
+    }
+}
+
+impl<T: Into<BytesOptions>> BitOr<T> for BytesOptions {
+    type Output = BytesOptions;
+
+    fn bitor(self, other: T) -> BytesOptions {
+        let other = other.into();
+        BytesOptions {
+            indexed: self.indexed || other.indexed,
+            fast: self.fast || other.fast,
+            stored: self.stored || other.stored,
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use std::io::{self, Read, Write};
+
+use chrono::{DateTime, Utc};
+
+use crate::{BinarySerializable, Facet, Field, FieldValue, VInt, Value};
+
+/// A document is a list of `(Field, Value)` pairs.
+///
+/// A field may appear several times: all of its values are indexed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Document {
+    field_values: Vec<FieldValue>,
+}
+
+impl From<Vec<FieldValue>> for Document {
+    fn from(field_values: Vec<FieldValue>) -> Self {
+        Document { field_values }
+    }
+}
+
+impl Document {
+    pub fn new() -> Document {
+        Document::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.field_values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.field_values.is_empty()
+    }
+
+    pub fn add(&mut self, field_value: FieldValue) {
+        self.field_values.push(field_value);
+    }
+
+    pub fn add_text<S: ToString>(&mut self, field: Field, text: S) {
+        self.add(FieldValue::new(field, Value::Str(text.to_string())));
+    }
+
+    pub fn add_u64(&mut self, field: Field, value: u64) {
+        self.add(FieldValue::new(field, Value::U64(value)));
+    }
+
+    pub fn add_i64(&mut self, field: Field, value: i64) {
+        self.add(FieldValue::new(field, Value::I64(value)));
+    }
+
+    pub fn add_f64(&mut self, field: Field, value: f64) {
+        self.add(FieldValue::new(field, Value::F64(value)));
+    }
+
+    pub fn add_date(&mut self, field: Field, value: DateTime<Utc>) {
+        self.add(FieldValue::new(field, Value::Date(value)));
+    }
+
+    pub fn add_facet(&mut self, field: Field, facet: Facet) {
+        self.add(FieldValue::new(field, Value::Facet(facet)));
+    }
+
+    pub fn add_bytes<B: Into<Vec<u8>>>(&mut self, field: Field, bytes: B) {
+        self.add(FieldValue::new(field, Value::Bytes(bytes.into())));
+    }
+
+    pub fn field_values(&self) -> &[FieldValue] {
+        &self.field_values
+    }
+
+    /// Keeps only the values of the fields accepted by `predicate`.
+    pub fn filter_fields<P: Fn(Field) -> bool>(&mut self, predicate: P) {
+        self.field_values
+            .retain(|field_value| predicate(field_value.field()));
+    }
+
+    /// Returns all the values associated with `field`, in insertion order.
+    pub fn get_all(&self, field: Field) -> impl Iterator<Item = &Value> {
+        self.field_values
+            .iter()
+            .filter(move |field_value| field_value.field() == field)
+            .map(FieldValue::value)
+    }
+
+    pub fn get_first(&self, field: Field) -> Option<&Value> {
+        self.get_all(field).next()
+    }
+
+    /// Groups the values by field, keeping the insertion order within a field.
+    pub fn get_sorted_field_values(&self) -> Vec<(Field, Vec<&Value>)> {
+        let mut field_values: Vec<&FieldValue> = self.field_values.iter().collect();
+        field_values.sort_by_key(|field_value| field_value.field());
+        let mut grouped: Vec<(Field, Vec<&Value>)> = Vec::new();
+        for field_value in field_values {
+            match grouped.last_mut() {
+                Some((field, values)) if *field == field_value.field() => {
+                    values.push(field_value.value())
+                }
+                _ => grouped.push((field_value.field(), vec![field_value.value()])),
+            }
+        }
+        grouped
+    }
+}
+
+impl BinarySerializable for Document {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        VInt(self.field_values.len() as u64).serialize(writer)?;
+        for field_value in &self.field_values {
+            field_value.serialize(writer)?;
+        }
+        Ok(())
+    }
+
+    fn deserialize<R: Read>(reader: &mut R) -> io::Result<Document> {
+        let num_field_values = VInt::deserialize(reader)?.val() as usize;
+        let field_values = (0..num_field_values)
+            .map(|_| FieldValue::deserialize(reader))
+            .collect::<io::Result<Vec<_>>>()?;
+        Ok(Document::from(field_values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use crate::{BinarySerializable, Document, Facet, Field};
+
+    #[test]
+    fn test_document_serialization() {
+        let field = Field::from_field_id;
+        let mut doc = Document::new();
+        doc.add_text(field(0), "hello");
+        doc.add_u64(field(1), 4);
+        doc.add_i64(field(2), -4);
+        doc.add_f64(field(3), 0.25);
+        doc.add_date(field(4), Utc.timestamp(1_600_000_000, 123));
+        doc.add_facet(field(5), Facet::from_text("/a/b").unwrap());
+        doc.add_bytes(field(6), vec![0u8, 255]);
+        doc.add_text(field(0), "world");
+
+        let mut buffer = Vec::new();
+        doc.serialize(&mut buffer).unwrap();
+        assert_eq!(Document::deserialize(&mut &buffer[..]).unwrap(), doc);
+    }
+
+    #[test]
+    fn test_document_filter_fields() {
+        let mut doc = doc!(Field::from_field_id(0) => "a", Field::from_field_id(1) => 3u64);
+        doc.filter_fields(|field| field.field_id() == 1);
+        assert_eq!(doc.field_values().len(), 1);
+        assert_eq!(doc.get_first(Field::from_field_id(1)), Some(&3u64.into()));
+    }
+}
@@ -0,0 +1,115 @@
+use std::fmt;
+use std::fmt::Formatter;
+
+use serde::{Serialize, Serializer};
+
+/// Byte separating the segments of an encoded facet.
+pub const FACET_SEP_BYTE: u8 = 0u8;
+
+const FACET_SEP_CHAR: char = '\u{0}';
+
+/// Path in a hierarchy of categories, such as `/category/electronics/phones`.
+///
+/// Internally, the path segments are joined with `FACET_SEP_BYTE`, so that a
+/// facet sorts right before its children.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Facet(String);
+
+impl Facet {
+    /// Returns the root facet `/`.
+    pub fn root() -> Facet {
+        Facet(String::new())
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Parses a facet from its textual representation, e.g. `/a/b`.
+    ///
+    /// Returns `None` if the text does not start with `/`.
+    pub fn from_text(path: &str) -> Option<Facet> {
+        let path = path.strip_prefix('/')?;
+        if path.is_empty() {
+            return Some(Facet::root());
+        }
+        Some(Facet::from_path(path.split('/')))
+    }
+
+    pub fn from_path<Path>(path: Path) -> Facet
+    where
+        Path: IntoIterator,
+        Path::Item: ToString,
+    {
+        let segments: Vec<String> = path.into_iter().map(|s| s.to_string()).collect();
+        Facet(segments.join(&FACET_SEP_CHAR.to_string()))
+    }
+
+    pub fn from_encoded(encoded: String) -> Facet {
+        Facet(encoded)
+    }
+
+    pub fn encoded_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns true if `other` is a strict descendant of this facet.
+    pub fn is_prefix_of(&self, other: &Facet) -> bool {
+        if self.is_root() {
+            return !other.is_root();
+        }
+        other.0.len() > self.0.len()
+            && other.0.starts_with(&self.0)
+            && other.0.as_bytes()[self.0.len()] == FACET_SEP_BYTE
+    }
+
+    pub fn to_path(&self) -> Vec<&str> {
+        if self.is_root() {
+            return Vec::new();
+        }
+        self.0.split(FACET_SEP_CHAR).collect()
+    }
+}
+
+impl fmt::Display for Facet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.is_root() {
+            return write!(f, "/");
+        }
+        for segment in self.to_path() {
+            write!(f, "/{}", segment)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Facet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Facet;
+
+    #[test]
+    fn test_facet_from_text() {
+        let facet = Facet::from_text("/category/electronics").unwrap();
+        assert_eq!(facet.to_path(), vec!["category", "electronics"]);
+        assert_eq!(facet.encoded_str(), "category\u{0}electronics");
+        assert_eq!(facet.to_string(), "/category/electronics");
+        assert_eq!(Facet::from_text("/").unwrap(), Facet::root());
+        assert_eq!(Facet::root().to_string(), "/");
+        assert!(Facet::from_text("category").is_none());
+    }
+
+    #[test]
+    fn test_facet_is_prefix_of() {
+        let parent = Facet::from_text("/a/b").unwrap();
+        assert!(parent.is_prefix_of(&Facet::from_text("/a/b/c").unwrap()));
+        assert!(!parent.is_prefix_of(&Facet::from_text("/a/bc").unwrap()));
+        assert!(!parent.is_prefix_of(&parent));
+        assert!(Facet::root().is_prefix_of(&parent));
+    }
+}
@@ -0,0 +1,42 @@
+use std::ops::BitOr;
+
+use serde::{Deserialize, Serialize};
+
+/// Options of a hierarchical facet field.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FacetOptions {
+    indexed: bool,
+    stored: bool,
+}
+
+impl FacetOptions {
+    pub fn is_indexed(&self) -> bool {
+        self.indexed
+    }
+
+    pub fn is_stored(&self) -> bool {
+        self.stored
+    }
+
+    pub fn set_indexed(mut self) -> FacetOptions {
+        self.indexed = true;
+        self
+    }
+
+    pub fn set_stored(mut self) -> FacetOptions {
+        self.stored = true;
+        self
+    }
+}
+
+impl<T: Into<FacetOptions>> BitOr<T> for FacetOptions {
+    type Output = FacetOptions;
+
+    fn bitor(self, other: T) -> FacetOptions {
+        let other = other.into();
+        FacetOptions {
+            indexed: self.indexed || other.indexed,
+            stored: self.stored || other.stored,
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use std::io::{self, Read, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::BinarySerializable;
+
+/// Identifier of a field within a `Schema`.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Hash, Serialize, Deserialize)]
+pub struct Field(u32);
+
+impl Field {
+    pub const fn from_field_id(field_id: u32) -> Field {
+        Field(field_id)
+    }
+
+    pub const fn field_id(self) -> u32 {
+        self.0
+    }
+}
+
+impl BinarySerializable for Field {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        BinarySerializable::serialize(&self.0, writer)
+    }
+
+    fn deserialize<R: Read>(reader: &mut R) -> io::Result<Field> {
+        <u32 as BinarySerializable>::deserialize(reader).map(Field)
+    }
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{BytesOptions, FacetOptions, FieldType, IntOptions, TextOptions};
+
+/// Name and type of a field of the `Schema`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldEntry {
+    name: String,
+    #[serde(flatten)]
+    field_type: FieldType,
+}
+
+impl FieldEntry {
+    pub fn new(name: String, field_type: FieldType) -> FieldEntry {
+        FieldEntry { name, field_type }
+    }
+
+    pub fn new_text(name: String, text_options: TextOptions) -> FieldEntry {
+        FieldEntry::new(name, FieldType::Str(text_options))
+    }
+
+    pub fn new_u64(name: String, int_options: IntOptions) -> FieldEntry {
+        FieldEntry::new(name, FieldType::U64(int_options))
+    }
+
+    pub fn new_i64(name: String, int_options: IntOptions) -> FieldEntry {
+        FieldEntry::new(name, FieldType::I64(int_options))
+    }
+
+    pub fn new_f64(name: String, int_options: IntOptions) -> FieldEntry {
+        FieldEntry::new(name, FieldType::F64(int_options))
+    }
+
+    pub fn new_date(name: String, int_options: IntOptions) -> FieldEntry {
+        FieldEntry::new(name, FieldType::Date(int_options))
+    }
+
+    pub fn new_bytes(name: String, bytes_options: BytesOptions) -> FieldEntry {
+        FieldEntry::new(name, FieldType::Bytes(bytes_options))
+    }
+
+    pub fn new_facet(name: String, facet_options: FacetOptions) -> FieldEntry {
+        FieldEntry::new(name, FieldType::HierarchicalFacet(facet_options))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn field_type(&self) -> &FieldType {
+        &self.field_type
+    }
+
+    pub fn is_indexed(&self) -> bool {
+        self.field_type.is_indexed()
+    }
+
+    pub fn is_stored(&self) -> bool {
+        self.field_type.is_stored()
+    }
+
+    pub fn is_fast(&self) -> bool {
+        self.field_type.is_fast()
+    }
+}
@@ -0,0 +1,168 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use thiserror::Error;
+
+use crate::{BytesOptions, Facet, FacetOptions, IndexRecordOption, IntOptions, TextOptions, Value};
+
+/// Reason why a json value could not be converted into a field `Value`.
+#[derive(Debug, Error, PartialEq)]
+pub enum ValueParsingError {
+    #[error("Overflow error. Expected {expected}, got {json}")]
+    OverflowError {
+        expected: &'static str,
+        json: String,
+    },
+    #[error("Type error. Expected {expected}, got {json}")]
+    TypeError {
+        expected: &'static str,
+        json: String,
+    },
+    #[error("Invalid base64: {0}")]
+    InvalidBase64(String),
+    #[error("Invalid date: {0}")]
+    InvalidDate(String),
+    #[error("Invalid facet: {0}")]
+    InvalidFacet(String),
+}
+
+/// Type of a field, along with its type-specific options.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "options")]
+pub enum FieldType {
+    #[serde(rename = "text")]
+    Str(TextOptions),
+    #[serde(rename = "u64")]
+    U64(IntOptions),
+    #[serde(rename = "i64")]
+    I64(IntOptions),
+    #[serde(rename = "f64")]
+    F64(IntOptions),
+    #[serde(rename = "date")]
+    Date(IntOptions),
+    #[serde(rename = "bytes")]
+    Bytes(BytesOptions),
+    #[serde(rename = "facet")]
+    HierarchicalFacet(FacetOptions),
+}
+
+impl FieldType {
+    /// Name of the type, as it appears in the serialized schema.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            FieldType::Str(_) => "text",
+            FieldType::U64(_) => "u64",
+            FieldType::I64(_) => "i64",
+            FieldType::F64(_) => "f64",
+            FieldType::Date(_) => "date",
+            FieldType::Bytes(_) => "bytes",
+            FieldType::HierarchicalFacet(_) => "facet",
+        }
+    }
+
+    pub fn is_indexed(&self) -> bool {
+        match self {
+            FieldType::Str(text_options) => text_options.get_indexing_options().is_some(),
+            FieldType::U64(int_options)
+            | FieldType::I64(int_options)
+            | FieldType::F64(int_options)
+            | FieldType::Date(int_options) => int_options.is_indexed(),
+            FieldType::Bytes(bytes_options) => bytes_options.is_indexed(),
+            FieldType::HierarchicalFacet(facet_options) => facet_options.is_indexed(),
+        }
+    }
+
+    pub fn is_stored(&self) -> bool {
+        match self {
+            FieldType::Str(text_options) => text_options.is_stored(),
+            FieldType::U64(int_options)
+            | FieldType::I64(int_options)
+            | FieldType::F64(int_options)
+            | FieldType::Date(int_options) => int_options.is_stored(),
+            FieldType::Bytes(bytes_options) => bytes_options.is_stored(),
+            FieldType::HierarchicalFacet(facet_options) => facet_options.is_stored(),
+        }
+    }
+
+    pub fn is_fast(&self) -> bool {
+        match self {
+            FieldType::U64(int_options)
+            | FieldType::I64(int_options)
+            | FieldType::F64(int_options)
+            | FieldType::Date(int_options) => int_options.is_fast(),
+            FieldType::Bytes(bytes_options) => bytes_options.is_fast(),
+            FieldType::Str(_) | FieldType::HierarchicalFacet(_) => false,
+        }
+    }
+
+    /// Converts a json value into a `Value` of this field type.
+    pub fn value_from_json(&self, json: &JsonValue) -> Result<Value, ValueParsingError> {
+        match self {
+            FieldType::Str(_) => match json {
+                JsonValue::String(text) => Ok(Value::Str(text.clone())),
+                _ => Err(type_error("a string", json)),
+            },
+            FieldType::U64(_) => match json {
+                JsonValue::Number(number) => number.as_u64().map(Value::U64).ok_or_else(|| {
+                    ValueParsingError::OverflowError {
+                        expected: "an unsigned 64-bit integer",
+                        json: json.to_string(),
+                    }
+                }),
+                _ => Err(type_error("an unsigned 64-bit integer", json)),
+            },
+            FieldType::I64(_) => match json {
+                JsonValue::Number(number) => number.as_i64().map(Value::I64).ok_or_else(|| {
+                    ValueParsingError::OverflowError {
+                        expected: "a signed 64-bit integer",
+                        json: json.to_string(),
+                    }
+                }),
+                _ => Err(type_error("a signed 64-bit integer", json)),
+            },
+            FieldType::F64(_) => match json {
+                JsonValue::Number(number) => number
+                    .as_f64()
+                    .map(Value::F64)
+                    .ok_or_else(|| type_error("a 64-bit float", json)),
+                _ => Err(type_error("a 64-bit float", json)),
+            },
+            FieldType::Date(_) => match json {
+                JsonValue::String(text) => DateTime::parse_from_rfc3339(text)
+                    .map(|date| Value::Date(date.with_timezone(&Utc)))
+                    .map_err(|err| ValueParsingError::InvalidDate(format!("{}: {}", text, err))),
+                _ => Err(type_error("an RFC 3339 date string", json)),
+            },
+            FieldType::Bytes(_) => match json {
+                JsonValue::String(text) => base64::decode(text)
+                    .map(Value::Bytes)
+                    .map_err(|_| ValueParsingError::InvalidBase64(text.clone())),
+                _ => Err(type_error("a base64 string", json)),
+            },
+            FieldType::HierarchicalFacet(_) => match json {
+                JsonValue::String(text) => Facet::from_text(text)
+                    .map(Value::Facet)
+                    .ok_or_else(|| ValueParsingError::InvalidFacet(text.clone())),
+                _ => Err(type_error("a facet path string", json)),
+            },
+        }
+    }
+
+    /// Returns what the postings of the field record, or `None` if it is not indexed.
+    pub fn get_index_record_option(&self) -> Option<IndexRecordOption> {
+        match self {
+            FieldType::Str(text_options) => text_options
+                .get_indexing_options()
+                .map(|indexing_options| indexing_options.index_option()),
+            _ if self.is_indexed() => Some(IndexRecordOption::Basic),
+            _ => None,
+        }
+    }
+}
+
+fn type_error(expected: &'static str, json: &JsonValue) -> ValueParsingError {
+    ValueParsingError::TypeError {
+        expected,
+        json: json.to_string(),
+    }
+}
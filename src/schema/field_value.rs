@@ -0,0 +1,37 @@
+use std::io::{self, Read, Write};
+
+use crate::{BinarySerializable, Field, Value};
+
+/// A value associated with the field it belongs to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldValue {
+    field: Field,
+    value: Value,
+}
+
+impl FieldValue {
+    pub fn new(field: Field, value: Value) -> FieldValue {
+        FieldValue { field, value }
+    }
+
+    pub fn field(&self) -> Field {
+        self.field
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+}
+
+impl BinarySerializable for FieldValue {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.field.serialize(writer)?;
+        self.value.serialize(writer)
+    }
+
+    fn deserialize<R: Read>(reader: &mut R) -> io::Result<FieldValue> {
+        let field = Field::deserialize(reader)?;
+        let value = Value::deserialize(reader)?;
+        Ok(FieldValue::new(field, value))
+    }
+}
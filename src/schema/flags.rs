@@ -0,0 +1,144 @@
+use std::ops::BitOr;
+
+use crate::{BytesOptions, Cardinality, FacetOptions, IntOptions, TextOptions};
+
+#[derive(Clone)]
+pub struct StoredFlag;
+
+#[derive(Clone)]
+pub struct IndexedFlag;
+
+#[derive(Clone)]
+pub struct FastFlag;
+
+/// Heterogeneous list of flags, built by combining the flag constants with `|`.
+#[derive(Clone)]
+pub struct SchemaFlagList<Head: Clone, Tail: Clone> {
+    pub head: Head,
+    pub tail: Tail,
+}
+
+/// The field value will be kept in the doc store.
+pub const STORED: SchemaFlagList<StoredFlag, ()> = SchemaFlagList {
+    head: StoredFlag,
+    tail: (),
+};
+
+/// The field will be searchable.
+pub const INDEXED: SchemaFlagList<IndexedFlag, ()> = SchemaFlagList {
+    head: IndexedFlag,
+    tail: (),
+};
+
+/// The field values will be available as a column for fast random access.
+pub const FAST: SchemaFlagList<FastFlag, ()> = SchemaFlagList {
+    head: FastFlag,
+    tail: (),
+};
+
+impl<Head, OldHead, OldTail> BitOr<SchemaFlagList<Head, ()>> for SchemaFlagList<OldHead, OldTail>
+where
+    Head: Clone,
+    OldHead: Clone,
+    OldTail: Clone,
+{
+    type Output = SchemaFlagList<Head, SchemaFlagList<OldHead, OldTail>>;
+
+    fn bitor(self, head: SchemaFlagList<Head, ()>) -> Self::Output {
+        SchemaFlagList {
+            head: head.head,
+            tail: self,
+        }
+    }
+}
+
+impl<Head: Clone, Tail: Clone> BitOr<TextOptions> for SchemaFlagList<Head, Tail>
+where
+    TextOptions: From<SchemaFlagList<Head, Tail>>,
+{
+    type Output = TextOptions;
+
+    fn bitor(self, rhs: TextOptions) -> TextOptions {
+        TextOptions::from(self) | rhs
+    }
+}
+
+macro_rules! impl_from_flags {
+    ($options:ty) => {
+        impl From<()> for $options {
+            fn from(_: ()) -> $options {
+                <$options>::default()
+            }
+        }
+
+        impl<Head, Tail> From<SchemaFlagList<Head, Tail>> for $options
+        where
+            Head: Clone,
+            Tail: Clone,
+            $options: From<Head> + From<Tail>,
+        {
+            fn from(flags: SchemaFlagList<Head, Tail>) -> $options {
+                <$options>::from(flags.head) | <$options>::from(flags.tail)
+            }
+        }
+    };
+}
+
+impl_from_flags!(TextOptions);
+impl_from_flags!(IntOptions);
+impl_from_flags!(BytesOptions);
+impl_from_flags!(FacetOptions);
+
+impl From<StoredFlag> for TextOptions {
+    fn from(_: StoredFlag) -> TextOptions {
+        TextOptions::default().set_stored()
+    }
+}
+
+impl From<StoredFlag> for IntOptions {
+    fn from(_: StoredFlag) -> IntOptions {
+        IntOptions::default().set_stored()
+    }
+}
+
+impl From<IndexedFlag> for IntOptions {
+    fn from(_: IndexedFlag) -> IntOptions {
+        IntOptions::default().set_indexed()
+    }
+}
+
+impl From<FastFlag> for IntOptions {
+    fn from(_: FastFlag) -> IntOptions {
+        IntOptions::default().set_fast(Cardinality::SingleValue)
+    }
+}
+
+impl From<StoredFlag> for BytesOptions {
+    fn from(_: StoredFlag) -> BytesOptions {
+        BytesOptions::default().set_stored()
+    }
+}
+
+impl From<IndexedFlag> for BytesOptions {
+    fn from(_: IndexedFlag) -> BytesOptions {
+        BytesOptions::default().set_indexed()
+    }
+}
+
+impl From<FastFlag> for BytesOptions {
+    fn from(_: FastFlag) -> BytesOptions {
+        BytesOptions::default().set_fast()
+    }
+}
+
+impl From<StoredFlag> for FacetOptions {
+    fn from(_: StoredFlag) -> FacetOptions {
+        FacetOptions::default().set_stored()
+    }
+}
+
+impl From<IndexedFlag> for FacetOptions {
+    fn from(_: IndexedFlag) -> FacetOptions {
+        FacetOptions::default().set_indexed()
+    }
+}
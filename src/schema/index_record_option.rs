@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// Amount of information recorded in the postings of an indexed field.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Ord, Eq, Hash, Serialize, Deserialize)]
+pub enum IndexRecordOption {
+    /// Only the doc ids are recorded.
+    #[serde(rename = "basic")]
+    Basic,
+    /// Doc ids and term frequencies are recorded.
+    #[serde(rename = "freq")]
+    WithFreqs,
+    /// Doc ids, term frequencies and term positions are recorded.
+    #[serde(rename = "position")]
+    WithFreqsAndPositions,
+}
+
+impl IndexRecordOption {
+    pub fn has_freq(self) -> bool {
+        match self {
+            IndexRecordOption::Basic => false,
+            IndexRecordOption::WithFreqs | IndexRecordOption::WithFreqsAndPositions => true,
+        }
+    }
+
+    pub fn has_positions(self) -> bool {
+        match self {
+            IndexRecordOption::Basic | IndexRecordOption::WithFreqs => false,
+            IndexRecordOption::WithFreqsAndPositions => true,
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::ops::BitOr;
+
+use serde::{Deserialize, Serialize};
+
+/// Number of values a document may have for a fast field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Cardinality {
+    #[serde(rename = "single")]
+    SingleValue,
+    #[serde(rename = "multi")]
+    MultiValues,
+}
+
+/// Options of the numeric (`u64`, `i64`, `f64`) and date fields.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntOptions {
+    indexed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fast: Option<Cardinality>,
+    stored: bool,
+}
+
+impl IntOptions {
+    pub fn is_indexed(&self) -> bool {
+        self.indexed
+    }
+
+    pub fn is_stored(&self) -> bool {
+        self.stored
+    }
+
+    pub fn is_fast(&self) -> bool {
+        self.fast.is_some()
+    }
+
+    pub fn get_fastfield_cardinality(&self) -> Option<Cardinality> {
+        self.fast
+    }
+
+    pub fn set_indexed(mut self) -> IntOptions {
+        self.indexed = true;
+        self
+    }
+
+    pub fn set_stored(mut self) -> IntOptions {
+        self.stored = true;
+        self
+    }
+
+    pub fn set_fast(mut self, cardinality: Cardinality) -> IntOptions {
+        self.fast = Some(cardinality);
+        self
+    }
+}
+
+impl<T: Into<IntOptions>> BitOr<T> for IntOptions {
+    type Output = IntOptions;
+
+    fn bitor(self, other: T) -> IntOptions {
+        let other = other.into();
+        IntOptions {
+            indexed: self.indexed || other.indexed,
+            fast: self.fast.or(other.fast),
+            stored: self.stored || other.stored,
+        }
+    }
+}
@@ -0,0 +1,31 @@
+mod bytes_options;
+mod document;
+mod facet;
+mod facet_options;
+mod field;
+mod field_entry;
+mod field_type;
+mod field_value;
+mod flags;
+mod index_record_option;
+mod int_options;
+mod schema;
+mod term;
+mod text_options;
+mod value;
+
+pub use bytes_options::*;
+pub use document::*;
+pub use facet::*;
+pub use facet_options::*;
+pub use field::*;
+pub use field_entry::*;
+pub use field_type::*;
+pub use field_value::*;
+pub use flags::*;
+pub use index_record_option::*;
+pub use int_options::*;
+pub use schema::*;
+pub use term::*;
+pub use text_options::*;
+pub use value::*;
@@ -0,0 +1,544 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
+
+use serde::de::{SeqAccess, Visitor};
+use serde::ser::SerializeSeq;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map as JsonObject, Value as JsonValue};
+use thiserror::Error;
+
+use crate::{
+    BytesOptions, Document, FacetOptions, Field, FieldEntry, FieldValue, IntOptions, TextOptions,
+    Value, ValueParsingError,
+};
+
+/// Error returned by `Schema::parse_document`.
+#[derive(Debug, Error, PartialEq)]
+pub enum DocParsingError {
+    #[error("Failed to parse json document: {0}")]
+    NotJson(String),
+    #[error("The field '{0}' could not be parsed: {1}")]
+    ValueError(String, ValueParsingError),
+    #[error("The field '{0}' does not exist in the schema")]
+    NoSuchFieldInSchema(String),
+}
+
+/// Document whose values are keyed by field name, as produced by `Schema::to_named_doc`.
+#[derive(Debug, Serialize)]
+pub struct NamedFieldDocument(pub BTreeMap<String, Vec<Value>>);
+
+/// Builds a `Schema` by declaring its fields one after the other.
+#[derive(Default)]
+pub struct SchemaBuilder {
+    fields: Vec<FieldEntry>,
+    fields_map: HashMap<String, Field>,
+}
+
+impl SchemaBuilder {
+    pub fn new() -> SchemaBuilder {
+        SchemaBuilder::default()
+    }
+
+    pub fn add_text_field<T: Into<TextOptions>>(&mut self, field_name: &str, options: T) -> Field {
+        self.add_field(FieldEntry::new_text(field_name.to_string(), options.into()))
+    }
+
+    pub fn add_u64_field<T: Into<IntOptions>>(&mut self, field_name: &str, options: T) -> Field {
+        self.add_field(FieldEntry::new_u64(field_name.to_string(), options.into()))
+    }
+
+    pub fn add_i64_field<T: Into<IntOptions>>(&mut self, field_name: &str, options: T) -> Field {
+        self.add_field(FieldEntry::new_i64(field_name.to_string(), options.into()))
+    }
+
+    pub fn add_f64_field<T: Into<IntOptions>>(&mut self, field_name: &str, options: T) -> Field {
+        self.add_field(FieldEntry::new_f64(field_name.to_string(), options.into()))
+    }
+
+    pub fn add_date_field<T: Into<IntOptions>>(&mut self, field_name: &str, options: T) -> Field {
+        self.add_field(FieldEntry::new_date(field_name.to_string(), options.into()))
+    }
+
+    pub fn add_bytes_field<T: Into<BytesOptions>>(
+        &mut self,
+        field_name: &str,
+        options: T,
+    ) -> Field {
+        self.add_field(FieldEntry::new_bytes(
+            field_name.to_string(),
+            options.into(),
+        ))
+    }
+
+    pub fn add_facet_field<T: Into<FacetOptions>>(
+        &mut self,
+        field_name: &str,
+        options: T,
+    ) -> Field {
+        self.add_field(FieldEntry::new_facet(
+            field_name.to_string(),
+            options.into(),
+        ))
+    }
+
+    /// Adds a field entry to the schema.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a field with the same name was already added.
+    pub fn add_field(&mut self, field_entry: FieldEntry) -> Field {
+        let field = Field::from_field_id(self.fields.len() as u32);
+        let field_name = field_entry.name().to_string();
+        if self.fields_map.contains_key(&field_name) {
+            panic!("Field '{}' is declared twice in the schema", field_name);
+        }
+        self.fields.push(field_entry);
+        self.fields_map.insert(field_name, field);
+        field
+    }
+
+    pub fn build(self) -> Schema {
+        Schema(Arc::new(InnerSchema {
+            fields: self.fields,
+            fields_map: self.fields_map,
+        }))
+    }
+}
+
+struct InnerSchema {
+    fields: Vec<FieldEntry>,
+    fields_map: HashMap<String, Field>,
+}
+
+impl PartialEq for InnerSchema {
+    fn eq(&self, other: &InnerSchema) -> bool {
+        self.fields == other.fields
+    }
+}
+
+impl Eq for InnerSchema {}
+
+/// Describes the fields of the documents of an index.
+///
+/// Cloning a `Schema` is cheap: the field entries are shared.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Schema(Arc<InnerSchema>);
+
+impl Schema {
+    pub fn builder() -> SchemaBuilder {
+        SchemaBuilder::default()
+    }
+
+    pub fn get_field_entry(&self, field: Field) -> &FieldEntry {
+        &self.0.fields[field.field_id() as usize]
+    }
+
+    pub fn get_field_name(&self, field: Field) -> &str {
+        self.get_field_entry(field).name()
+    }
+
+    pub fn get_field(&self, field_name: &str) -> Option<Field> {
+        self.0.fields_map.get(field_name).cloned()
+    }
+
+    pub fn num_fields(&self) -> usize {
+        self.0.fields.len()
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item = (Field, &FieldEntry)> {
+        self.0
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(field_id, field_entry)| (Field::from_field_id(field_id as u32), field_entry))
+    }
+
+    /// Builds a `Document` from a json object mapping field names to a value or to
+    /// an array of values.
+    pub fn parse_document(&self, doc_json: &str) -> Result<Document, DocParsingError> {
+        let json_obj: JsonObject<String, JsonValue> = serde_json::from_str(doc_json)
+            .map_err(|err| DocParsingError::NotJson(format!("{}: {:?}", err, doc_json)))?;
+        let mut doc = Document::default();
+        for (field_name, json_value) in json_obj {
+            let field = self
+                .get_field(&field_name)
+                .ok_or_else(|| DocParsingError::NoSuchFieldInSchema(field_name.clone()))?;
+            let field_type = self.get_field_entry(field).field_type();
+            let json_values = match json_value {
+                JsonValue::Array(json_items) => json_items,
+                json_value => vec![json_value],
+            };
+            for json_item in &json_values {
+                let value = field_type
+                    .value_from_json(json_item)
+                    .map_err(|err| DocParsingError::ValueError(field_name.clone(), err))?;
+                doc.add(FieldValue::new(field, value));
+            }
+        }
+        Ok(doc)
+    }
+
+    /// Groups the values of a document by field name.
+    pub fn to_named_doc(&self, doc: &Document) -> NamedFieldDocument {
+        let mut field_map = BTreeMap::new();
+        for (field, field_values) in doc.get_sorted_field_values() {
+            let field_name = self.get_field_name(field);
+            let values: Vec<Value> = field_values.into_iter().cloned().collect();
+            field_map.insert(field_name.to_string(), values);
+        }
+        NamedFieldDocument(field_map)
+    }
+
+    /// Encodes a document as a json object keyed by field name.
+    pub fn to_json(&self, doc: &Document) -> String {
+        serde_json::to_string(&self.to_named_doc(doc)).expect("doc encoding failed. This is a bug")
+    }
+}
+
+impl fmt::Debug for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.0.fields.iter()).finish()
+    }
+}
+
+impl Serialize for Schema {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.fields.len()))?;
+        for field_entry in &self.0.fields {
+            seq.serialize_element(field_entry)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Schema {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SchemaVisitor;
+
+        impl<'de> Visitor<'de> for SchemaVisitor {
+            type Value = Schema;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a list of field entries")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Schema, A::Error> {
+                let mut schema_builder = SchemaBuilder::new();
+                while let Some(field_entry) = seq.next_element::<FieldEntry>()? {
+                    if schema_builder.fields_map.contains_key(field_entry.name()) {
+                        return Err(serde::de::Error::custom(format!(
+                            "field '{}' is declared twice",
+                            field_entry.name()
+                        )));
+                    }
+                    schema_builder.add_field(field_entry);
+                }
+                Ok(schema_builder.build())
+            }
+        }
+
+        deserializer.deserialize_seq(SchemaVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Cardinality, DocParsingError, Facet, FieldType, IndexRecordOption, IntOptions, Schema,
+        TextFieldIndexing, TextOptions, Value, ValueParsingError, FAST, INDEXED, STORED, STRING,
+        TEXT,
+    };
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_schema_fields() {
+        let mut schema_builder = Schema::builder();
+        let title = schema_builder.add_text_field("title", TEXT | STORED);
+        let price = schema_builder.add_u64_field("price", INDEXED | FAST);
+        let schema = schema_builder.build();
+        assert_eq!(schema.num_fields(), 2);
+        assert_eq!(schema.get_field("title"), Some(title));
+        assert_eq!(schema.get_field("price"), Some(price));
+        assert_eq!(schema.get_field("missing"), None);
+        assert_eq!(schema.get_field_name(price), "price");
+
+        let title_entry = schema.get_field_entry(title);
+        assert!(title_entry.is_indexed());
+        assert!(title_entry.is_stored());
+        assert_eq!(
+            title_entry.field_type().get_index_record_option(),
+            Some(IndexRecordOption::WithFreqsAndPositions)
+        );
+        let price_entry = schema.get_field_entry(price);
+        assert!(price_entry.is_indexed());
+        assert!(price_entry.is_fast());
+        assert!(!price_entry.is_stored());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_schema_duplicate_field() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", TEXT);
+        schema_builder.add_u64_field("title", INDEXED);
+    }
+
+    #[test]
+    fn test_schema_serialization() {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", TEXT | STORED);
+        schema_builder.add_text_field("id", STRING);
+        schema_builder.add_text_field(
+            "body",
+            TextOptions::default().set_indexing_options(
+                TextFieldIndexing::default()
+                    .set_tokenizer("en_stem")
+                    .set_index_option(IndexRecordOption::WithFreqs),
+            ),
+        );
+        schema_builder.add_u64_field("count", INDEXED | STORED | FAST);
+        schema_builder.add_i64_field("delta", STORED);
+        schema_builder.add_f64_field(
+            "ratio",
+            IntOptions::default().set_fast(Cardinality::MultiValues),
+        );
+        schema_builder.add_date_field("published", INDEXED);
+        schema_builder.add_bytes_field("payload", STORED | FAST);
+        schema_builder.add_facet_field("category", INDEXED | STORED);
+        let schema = schema_builder.build();
+
+        let schema_json = serde_json::to_string_pretty(&schema).unwrap();
+        let expected = r#"[
+  {
+    "name": "title",
+    "type": "text",
+    "options": {
+      "indexing": {
+        "record": "position",
+        "tokenizer": "default"
+      },
+      "stored": true
+    }
+  },
+  {
+    "name": "id",
+    "type": "text",
+    "options": {
+      "indexing": {
+        "record": "basic",
+        "tokenizer": "raw"
+      },
+      "stored": false
+    }
+  },
+  {
+    "name": "body",
+    "type": "text",
+    "options": {
+      "indexing": {
+        "record": "freq",
+        "tokenizer": "en_stem"
+      },
+      "stored": false
+    }
+  },
+  {
+    "name": "count",
+    "type": "u64",
+    "options": {
+      "indexed": true,
+      "fast": "single",
+      "stored": true
+    }
+  },
+  {
+    "name": "delta",
+    "type": "i64",
+    "options": {
+      "indexed": false,
+      "stored": true
+    }
+  },
+  {
+    "name": "ratio",
+    "type": "f64",
+    "options": {
+      "indexed": false,
+      "fast": "multi",
+      "stored": false
+    }
+  },
+  {
+    "name": "published",
+    "type": "date",
+    "options": {
+      "indexed": true,
+      "stored": false
+    }
+  },
+  {
+    "name": "payload",
+    "type": "bytes",
+    "options": {
+      "indexed": false,
+      "fast": true,
+      "stored": true
+    }
+  },
+  {
+    "name": "category",
+    "type": "facet",
+    "options": {
+      "indexed": true,
+      "stored": true
+    }
+  }
+]"#;
+        assert_eq!(schema_json, expected);
+
+        let deserialized: Schema = serde_json::from_str(&schema_json).unwrap();
+        assert_eq!(deserialized, schema);
+        let category = deserialized.get_field("category").unwrap();
+        assert!(matches!(
+            deserialized.get_field_entry(category).field_type(),
+            FieldType::HierarchicalFacet(_)
+        ));
+    }
+
+    #[test]
+    fn test_schema_deserialize_duplicate_field_fails() {
+        let schema_json = r#"[
+            {"name": "a", "type": "u64", "options": {"indexed": true, "stored": false}},
+            {"name": "a", "type": "i64", "options": {"indexed": true, "stored": false}}
+        ]"#;
+        assert!(serde_json::from_str::<Schema>(schema_json).is_err());
+    }
+
+    fn parsing_schema() -> Schema {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", TEXT | STORED);
+        schema_builder.add_u64_field("count", INDEXED);
+        schema_builder.add_i64_field("delta", INDEXED);
+        schema_builder.add_f64_field("ratio", INDEXED);
+        schema_builder.add_date_field("published", INDEXED);
+        schema_builder.add_bytes_field("payload", STORED);
+        schema_builder.add_facet_field("category", INDEXED);
+        schema_builder.build()
+    }
+
+    #[test]
+    fn test_parse_document() {
+        let schema = parsing_schema();
+        let doc = schema
+            .parse_document(
+                r#"{
+                    "title": ["my title", "another title"],
+                    "count": 4,
+                    "delta": -5,
+                    "ratio": 2,
+                    "published": "2021-04-01T10:00:00+02:00",
+                    "payload": "aGVsbG8=",
+                    "category": "/electronics/phones"
+                }"#,
+            )
+            .unwrap();
+        let field = |name: &str| schema.get_field(name).unwrap();
+        let titles: Vec<&Value> = doc.get_all(field("title")).collect();
+        assert_eq!(
+            titles,
+            vec![
+                &Value::Str("my title".to_string()),
+                &Value::Str("another title".to_string())
+            ]
+        );
+        assert_eq!(doc.get_first(field("count")), Some(&Value::U64(4)));
+        assert_eq!(doc.get_first(field("delta")), Some(&Value::I64(-5)));
+        assert_eq!(doc.get_first(field("ratio")), Some(&Value::F64(2.0)));
+        assert_eq!(
+            doc.get_first(field("published")),
+            Some(&Value::Date(Utc.ymd(2021, 4, 1).and_hms(8, 0, 0)))
+        );
+        assert_eq!(
+            doc.get_first(field("payload")),
+            Some(&Value::Bytes(b"hello".to_vec()))
+        );
+        assert_eq!(
+            doc.get_first(field("category")),
+            Some(&Value::Facet(
+                Facet::from_text("/electronics/phones").unwrap()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_document_errors() {
+        let schema = parsing_schema();
+        assert_eq!(
+            schema.parse_document(r#"{"unknown": "value"}"#),
+            Err(DocParsingError::NoSuchFieldInSchema("unknown".to_string()))
+        );
+        assert!(matches!(
+            schema.parse_document(r#"{"title": 3}"#),
+            Err(DocParsingError::ValueError(field_name, ValueParsingError::TypeError { .. }))
+                if field_name == "title"
+        ));
+        assert!(matches!(
+            schema.parse_document(r#"{"count": -1}"#),
+            Err(DocParsingError::ValueError(
+                _,
+                ValueParsingError::OverflowError { .. }
+            ))
+        ));
+        assert!(matches!(
+            schema.parse_document(r#"{"count": "12"}"#),
+            Err(DocParsingError::ValueError(
+                _,
+                ValueParsingError::TypeError { .. }
+            ))
+        ));
+        assert!(matches!(
+            schema.parse_document(r#"{"published": "yesterday"}"#),
+            Err(DocParsingError::ValueError(
+                _,
+                ValueParsingError::InvalidDate(_)
+            ))
+        ));
+        assert!(matches!(
+            schema.parse_document(r#"{"payload": "%%%"}"#),
+            Err(DocParsingError::ValueError(
+                _,
+                ValueParsingError::InvalidBase64(_)
+            ))
+        ));
+        assert!(matches!(
+            schema.parse_document(r#"{"category": "no-slash"}"#),
+            Err(DocParsingError::ValueError(
+                _,
+                ValueParsingError::InvalidFacet(_)
+            ))
+        ));
+        assert!(matches!(
+            schema.parse_document("[1, 2]"),
+            Err(DocParsingError::NotJson(_))
+        ));
+    }
+
+    #[test]
+    fn test_doc_macro_and_to_json() {
+        let schema = parsing_schema();
+        let title = schema.get_field("title").unwrap();
+        let count = schema.get_field("count").unwrap();
+        let doc = doc!(
+            title => "the title",
+            count => 3u64,
+            title => "second title",
+        );
+        assert_eq!(doc.len(), 3);
+        assert_eq!(
+            schema.to_json(&doc),
+            r#"{"count":[3],"title":["the title","second title"]}"#
+        );
+        assert!(doc!().is_empty());
+    }
+}
@@ -0,0 +1,179 @@
+use std::convert::TryInto;
+use std::fmt;
+use std::str;
+
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::common::{f64_to_u64, i64_to_u64, u64_to_f64, u64_to_i64};
+use crate::{Facet, Field};
+
+/// Size of the field prefix of a term.
+pub const FIELD_ID_LEN: usize = 4;
+
+/// A term is the unit of search: the field it belongs to, followed by the
+/// order-preserving byte encoding of its value.
+///
+/// Numeric values are encoded big-endian, so that terms sort like their values.
+#[derive(Clone, PartialEq, PartialOrd, Ord, Eq, Hash)]
+pub struct Term(Vec<u8>);
+
+impl Term {
+    /// Creates a term with an empty value for `field`.
+    ///
+    /// Its bytes are a prefix of all the terms of `field`.
+    pub fn for_field(field: Field) -> Term {
+        let mut term = Term(Vec::with_capacity(100));
+        term.0.extend_from_slice(&field.field_id().to_be_bytes());
+        term
+    }
+
+    pub fn from_field_text(field: Field, text: &str) -> Term {
+        let mut term = Term::for_field(field);
+        term.set_text(text);
+        term
+    }
+
+    pub fn from_field_u64(field: Field, val: u64) -> Term {
+        let mut term = Term::for_field(field);
+        term.set_u64(val);
+        term
+    }
+
+    pub fn from_field_i64(field: Field, val: i64) -> Term {
+        Term::from_field_u64(field, i64_to_u64(val))
+    }
+
+    pub fn from_field_f64(field: Field, val: f64) -> Term {
+        Term::from_field_u64(field, f64_to_u64(val))
+    }
+
+    pub fn from_field_date(field: Field, val: &DateTime<Utc>) -> Term {
+        Term::from_field_i64(field, val.timestamp())
+    }
+
+    pub fn from_field_bytes(field: Field, bytes: &[u8]) -> Term {
+        let mut term = Term::for_field(field);
+        term.set_bytes(bytes);
+        term
+    }
+
+    pub fn from_facet(field: Field, facet: &Facet) -> Term {
+        Term::from_field_text(field, facet.encoded_str())
+    }
+
+    /// Wraps the raw bytes of a term, as stored in the term dictionary.
+    pub fn wrap(bytes: Vec<u8>) -> Term {
+        assert!(bytes.len() >= FIELD_ID_LEN);
+        Term(bytes)
+    }
+
+    pub fn field(&self) -> Field {
+        let field_id = u32::from_be_bytes(self.0[..FIELD_ID_LEN].try_into().unwrap());
+        Field::from_field_id(field_id)
+    }
+
+    pub fn set_field(&mut self, field: Field) {
+        self.0.clear();
+        self.0.extend_from_slice(&field.field_id().to_be_bytes());
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.set_bytes(text.as_bytes());
+    }
+
+    pub fn set_u64(&mut self, val: u64) {
+        self.set_bytes(&val.to_be_bytes());
+    }
+
+    pub fn set_i64(&mut self, val: i64) {
+        self.set_u64(i64_to_u64(val));
+    }
+
+    pub fn set_f64(&mut self, val: f64) {
+        self.set_u64(f64_to_u64(val));
+    }
+
+    pub fn set_bytes(&mut self, bytes: &[u8]) {
+        self.0.truncate(FIELD_ID_LEN);
+        self.0.extend_from_slice(bytes);
+    }
+
+    /// Returns the bytes of the value, without the field prefix.
+    pub fn value_bytes(&self) -> &[u8] {
+        &self.0[FIELD_ID_LEN..]
+    }
+
+    /// Returns the text of the term, if its value is valid UTF-8.
+    pub fn as_str(&self) -> Option<&str> {
+        str::from_utf8(self.value_bytes()).ok()
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        let bytes: [u8; 8] = self.value_bytes().try_into().ok()?;
+        Some(u64::from_be_bytes(bytes))
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_u64().map(u64_to_i64)
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_u64().map(u64_to_f64)
+    }
+
+    pub fn as_date(&self) -> Option<DateTime<Utc>> {
+        self.as_i64().map(|timestamp| Utc.timestamp(timestamp, 0))
+    }
+
+    /// Returns the complete encoded term, field prefix included.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Term {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl fmt::Debug for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.as_str() {
+            Some(text) => write!(f, "Term(field={}, {:?})", self.field().field_id(), text),
+            None => write!(
+                f,
+                "Term(field={}, {:?})",
+                self.field().field_id(),
+                self.value_bytes()
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Field, Term};
+
+    #[test]
+    fn test_term_text() {
+        let field = Field::from_field_id(1);
+        let term = Term::from_field_text(field, "hello");
+        assert_eq!(term.field(), field);
+        assert_eq!(term.as_str(), Some("hello"));
+        assert_eq!(term.as_slice(), b"\0\0\0\x01hello");
+    }
+
+    #[test]
+    fn test_term_numeric_order() {
+        let field = Field::from_field_id(0);
+        assert!(Term::from_field_u64(field, 3) < Term::from_field_u64(field, 256));
+        assert!(Term::from_field_i64(field, -3) < Term::from_field_i64(field, 2));
+        assert!(Term::from_field_f64(field, -0.5) < Term::from_field_f64(field, 0.25));
+        assert_eq!(Term::from_field_i64(field, -42).as_i64(), Some(-42));
+        assert!(
+            Term::from_field_u64(Field::from_field_id(0), u64::MAX)
+                < Term::from_field_u64(Field::from_field_id(1), 0)
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use std::borrow::Cow;
+use std::ops::BitOr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::IndexRecordOption;
+
+/// Options of a text field.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextOptions {
+    indexing: Option<TextFieldIndexing>,
+    stored: bool,
+}
+
+impl TextOptions {
+    pub fn get_indexing_options(&self) -> Option<&TextFieldIndexing> {
+        self.indexing.as_ref()
+    }
+
+    pub fn is_stored(&self) -> bool {
+        self.stored
+    }
+
+    pub fn set_stored(mut self) -> TextOptions {
+        self.stored = true;
+        self
+    }
+
+    pub fn set_indexing_options(mut self, indexing: TextFieldIndexing) -> TextOptions {
+        self.indexing = Some(indexing);
+        self
+    }
+}
+
+/// Describes how a text field is tokenized and what its postings record.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextFieldIndexing {
+    record: IndexRecordOption,
+    tokenizer: Cow<'static, str>,
+}
+
+impl Default for TextFieldIndexing {
+    fn default() -> Self {
+        TextFieldIndexing {
+            record: IndexRecordOption::Basic,
+            tokenizer: Cow::Borrowed("default"),
+        }
+    }
+}
+
+impl TextFieldIndexing {
+    /// Sets the name of the `TextAnalyzer`, as registered in the `TokenizerManager`.
+    pub fn set_tokenizer(mut self, tokenizer_name: &str) -> TextFieldIndexing {
+        self.tokenizer = Cow::Owned(tokenizer_name.to_string());
+        self
+    }
+
+    pub fn tokenizer(&self) -> &str {
+        &self.tokenizer
+    }
+
+    pub fn set_index_option(mut self, index_option: IndexRecordOption) -> TextFieldIndexing {
+        self.record = index_option;
+        self
+    }
+
+    pub fn index_option(&self) -> IndexRecordOption {
+        self.record
+    }
+}
+
+/// The field will be untokenized and indexed as a single term.
+pub const STRING: TextOptions = TextOptions {
+    indexing: Some(TextFieldIndexing {
+        record: IndexRecordOption::Basic,
+        tokenizer: Cow::Borrowed("raw"),
+    }),
+    stored: false,
+};
+
+/// The field will be tokenized with the default analyzer, recording positions.
+pub const TEXT: TextOptions = TextOptions {
+    indexing: Some(TextFieldIndexing {
+        record: IndexRecordOption::WithFreqsAndPositions,
+        tokenizer: Cow::Borrowed("default"),
+    }),
+    stored: false,
+};
+
+impl<T: Into<TextOptions>> BitOr<T> for TextOptions {
+    type Output = TextOptions;
+
+    fn bitor(self, other: T) -> TextOptions {
+        let other = other.into();
+        TextOptions {
+            indexing: self.indexing.or(other.indexing),
+            stored: self.stored || other.stored,
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use serde::Serializer;
+
+use crate::{BinarySerializable, Facet};
+
+/// Value of a field within a `Document`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Str(String),
+    U64(u64),
+    I64(i64),
+    F64(f64),
+    Date(DateTime<Utc>),
+    Facet(Facet),
+    Bytes(Vec<u8>),
+}
+
+impl Value {
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Value::Str(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn u64_value(&self) -> Option<u64> {
+        match self {
+            Value::U64(val) => Some(*val),
+            _ => None,
+        }
+    }
+
+    pub fn i64_value(&self) -> Option<i64> {
+        match self {
+            Value::I64(val) => Some(*val),
+            _ => None,
+        }
+    }
+
+    pub fn f64_value(&self) -> Option<f64> {
+        match self {
+            Value::F64(val) => Some(*val),
+            _ => None,
+        }
+    }
+
+    pub fn date_value(&self) -> Option<&DateTime<Utc>> {
+        match self {
+            Value::Date(date) => Some(date),
+            _ => None,
+        }
+    }
+
+    pub fn facet(&self) -> Option<&Facet> {
+        match self {
+            Value::Facet(facet) => Some(facet),
+            _ => None,
+        }
+    }
+
+    pub fn bytes_value(&self) -> Option<&[u8]> {
+        match self {
+            Value::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+}
+
+impl serde::Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Str(text) => serializer.serialize_str(text),
+            Value::U64(val) => serializer.serialize_u64(*val),
+            Value::I64(val) => serializer.serialize_i64(*val),
+            Value::F64(val) => serializer.serialize_f64(*val),
+            Value::Date(date) => {
+                serializer.serialize_str(&date.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            }
+            Value::Facet(facet) => serde::Serialize::serialize(facet, serializer),
+            Value::Bytes(bytes) => serializer.serialize_str(&base64::encode(bytes)),
+        }
+    }
+}
+
+mod type_codes {
+    pub const TEXT_CODE: u8 = 0;
+    pub const U64_CODE: u8 = 1;
+    pub const I64_CODE: u8 = 2;
+    pub const F64_CODE: u8 = 3;
+    pub const DATE_CODE: u8 = 4;
+    pub const FACET_CODE: u8 = 5;
+    pub const BYTES_CODE: u8 = 6;
+}
+
+/// Binary encoding used by the doc store: a type code followed by the value.
+impl BinarySerializable for Value {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        match self {
+            Value::Str(text) => {
+                type_codes::TEXT_CODE.serialize(writer)?;
+                text.serialize(writer)
+            }
+            Value::U64(val) => {
+                type_codes::U64_CODE.serialize(writer)?;
+                val.serialize(writer)
+            }
+            Value::I64(val) => {
+                type_codes::I64_CODE.serialize(writer)?;
+                val.serialize(writer)
+            }
+            Value::F64(val) => {
+                type_codes::F64_CODE.serialize(writer)?;
+                val.serialize(writer)
+            }
+            Value::Date(date) => {
+                type_codes::DATE_CODE.serialize(writer)?;
+                date.timestamp().serialize(writer)?;
+                date.timestamp_subsec_nanos().serialize(writer)
+            }
+            Value::Facet(facet) => {
+                type_codes::FACET_CODE.serialize(writer)?;
+                facet.encoded_str().to_string().serialize(writer)
+            }
+            Value::Bytes(bytes) => {
+                type_codes::BYTES_CODE.serialize(writer)?;
+                bytes.serialize(writer)
+            }
+        }
+    }
+
+    fn deserialize<R: Read>(reader: &mut R) -> io::Result<Value> {
+        let type_code = u8::deserialize(reader)?;
+        let value = match type_code {
+            type_codes::TEXT_CODE => Value::Str(String::deserialize(reader)?),
+            type_codes::U64_CODE => Value::U64(u64::deserialize(reader)?),
+            type_codes::I64_CODE => Value::I64(i64::deserialize(reader)?),
+            type_codes::F64_CODE => Value::F64(f64::deserialize(reader)?),
+            type_codes::DATE_CODE => {
+                let secs = i64::deserialize(reader)?;
+                let nanos = u32::deserialize(reader)?;
+                Value::Date(Utc.timestamp(secs, nanos))
+            }
+            type_codes::FACET_CODE => {
+                Value::Facet(Facet::from_encoded(String::deserialize(reader)?))
+            }
+            type_codes::BYTES_CODE => Value::Bytes(Vec::<u8>::deserialize(reader)?),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unknown value type code {}", type_code),
+                ))
+            }
+        };
+        Ok(value)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Str(text) => write!(f, "{}", text),
+            Value::U64(val) => write!(f, "{}", val),
+            Value::I64(val) => write!(f, "{}", val),
+            Value::F64(val) => write!(f, "{}", val),
+            Value::Date(date) => write!(f, "{}", date.to_rfc3339()),
+            Value::Facet(facet) => write!(f, "{}", facet),
+            Value::Bytes(bytes) => write!(f, "{}", base64::encode(bytes)),
+        }
+    }
+}
+
+impl From<String> for Value {
+    fn from(text: String) -> Value {
+        Value::Str(text)
+    }
+}
+
+impl<'a> From<&'a str> for Value {
+    fn from(text: &'a str) -> Value {
+        Value::Str(text.to_string())
+    }
+}
+
+impl From<u64> for Value {
+    fn from(val: u64) -> Value {
+        Value::U64(val)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(val: i64) -> Value {
+        Value::I64(val)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(val: f64) -> Value {
+        Value::F64(val)
+    }
+}
+
+impl From<DateTime<Utc>> for Value {
+    fn from(date: DateTime<Utc>) -> Value {
+        Value::Date(date)
+    }
+}
+
+impl From<Facet> for Value {
+    fn from(facet: Facet) -> Value {
+        Value::Facet(facet)
+    }
+}
+
+impl From<Vec<u8>> for Value {
+    fn from(bytes: Vec<u8>) -> Value {
+        Value::Bytes(bytes)
+    }
+}
+
+impl<'a> From<&'a [u8]> for Value {
+    fn from(bytes: &'a [u8]) -> Value {
+        Value::Bytes(bytes.to_vec())
+    }
+}
@@ -0,0 +1,117 @@
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+/// Codec used to compress the blocks of the doc store.
+///
+/// The codec is recorded in every store file, so changing it only affects the
+/// segments written afterwards.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Compressor {
+    #[serde(rename = "none")]
+    None,
+    #[default]
+    #[serde(rename = "lz4")]
+    Lz4,
+    #[serde(rename = "snappy")]
+    Snappy,
+}
+
+impl Compressor {
+    pub(crate) fn to_id(self) -> u8 {
+        match self {
+            Compressor::None => 0,
+            Compressor::Lz4 => 1,
+            Compressor::Snappy => 2,
+        }
+    }
+
+    pub(crate) fn from_id(id: u8) -> io::Result<Compressor> {
+        match id {
+            0 => Ok(Compressor::None),
+            1 => Ok(Compressor::Lz4),
+            2 => Ok(Compressor::Snappy),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown doc store compressor id {}", id),
+            )),
+        }
+    }
+
+    /// Replaces the content of `compressed` with the compressed `uncompressed`.
+    pub fn compress_into(self, uncompressed: &[u8], compressed: &mut Vec<u8>) -> io::Result<()> {
+        compressed.clear();
+        match self {
+            Compressor::None => compressed.extend_from_slice(uncompressed),
+            Compressor::Lz4 => {
+                compressed.extend_from_slice(&lz4_flex::compress_prepend_size(uncompressed))
+            }
+            Compressor::Snappy => {
+                let max_len = snap::raw::max_compress_len(uncompressed.len());
+                compressed.resize(max_len, 0);
+                let compressed_len = snap::raw::Encoder::new()
+                    .compress(uncompressed, compressed)
+                    .map_err(io::Error::other)?;
+                compressed.truncate(compressed_len);
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces the content of `decompressed` with the decompressed `compressed`.
+    pub fn decompress_into(self, compressed: &[u8], decompressed: &mut Vec<u8>) -> io::Result<()> {
+        decompressed.clear();
+        match self {
+            Compressor::None => decompressed.extend_from_slice(compressed),
+            Compressor::Lz4 => {
+                let data = lz4_flex::decompress_size_prepended(compressed)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+                decompressed.extend_from_slice(&data);
+            }
+            Compressor::Snappy => {
+                let data = snap::raw::Decoder::new()
+                    .decompress_vec(compressed)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+                decompressed.extend_from_slice(&data);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Compressor;
+
+    #[test]
+    fn test_compressors_roundtrip() {
+        let data: Vec<u8> = b"hello happy tax payer "
+            .iter()
+            .cycle()
+            .take(1_000)
+            .cloned()
+            .collect();
+        for &compressor in &[Compressor::None, Compressor::Lz4, Compressor::Snappy] {
+            let mut compressed = Vec::new();
+            compressor.compress_into(&data, &mut compressed).unwrap();
+            if compressor != Compressor::None {
+                assert!(compressed.len() < data.len());
+            }
+            let mut decompressed = Vec::new();
+            compressor
+                .decompress_into(&compressed, &mut decompressed)
+                .unwrap();
+            assert_eq!(decompressed, data);
+            assert_eq!(Compressor::from_id(compressor.to_id()).unwrap(), compressor);
+        }
+        assert!(Compressor::from_id(42).is_err());
+    }
+
+    #[test]
+    fn test_lz4_rejects_corrupted_block() {
+        let mut decompressed = Vec::new();
+        assert!(Compressor::Lz4
+            .decompress_into(&[255, 255, 255, 0, 1], &mut decompressed)
+            .is_err());
+    }
+}
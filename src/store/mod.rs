@@ -0,0 +1,22 @@
+//! Doc store: the stored fields of the documents of a segment.
+//!
+//! Documents are serialized one after the other into blocks of about
+//! `BLOCK_SIZE` bytes, each compressed on its own with the `Compressor` of the
+//! index. A skip index maps doc ids to the block holding them, so fetching a
+//! document only decompresses one block.
+//!
+//! ```text
+//! [block 0][block 1]...[skip index][skip index offset: u64][compressor id: u8]
+//! ```
+mod compressors;
+mod reader;
+mod skip_index;
+mod writer;
+
+pub use compressors::*;
+pub use reader::*;
+pub(crate) use skip_index::*;
+pub use writer::*;
+
+/// Number of serialized bytes above which a block gets compressed and written.
+pub const BLOCK_SIZE: usize = 16_384;
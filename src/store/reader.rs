@@ -0,0 +1,276 @@
+use std::fmt;
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use lru::LruCache;
+
+use crate::store::{Checkpoint, SkipIndex};
+use crate::{
+    BinarySerializable, Compressor, DocId, Document, FileSlice, HasLen, MySearchError, OwnedBytes,
+    VInt,
+};
+
+/// Number of decompressed blocks kept in memory by a `StoreReader`.
+const LRU_CACHE_CAPACITY: usize = 100;
+
+/// Size of the footer: the skip index offset and the compressor id.
+const FOOTER_NUM_BYTES: usize = 8 + 1;
+
+/// Hit and miss counters of the block cache of a `StoreReader`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub num_entries: usize,
+    pub cache_hits: usize,
+    pub cache_misses: usize,
+}
+
+/// Reads the documents of a doc store.
+///
+/// Cloning a `StoreReader` is cheap, and the clones share their block cache.
+#[derive(Clone)]
+pub struct StoreReader {
+    compressor: Compressor,
+    data: FileSlice,
+    skip_index: Arc<SkipIndex>,
+    cache: Arc<Mutex<LruCache<u64, OwnedBytes>>>,
+    cache_hits: Arc<AtomicUsize>,
+    cache_misses: Arc<AtomicUsize>,
+}
+
+impl StoreReader {
+    pub fn open(store_file: FileSlice) -> io::Result<StoreReader> {
+        if store_file.len() < FOOTER_NUM_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "doc store file is too short",
+            ));
+        }
+        let (data_and_skip_index, footer) = store_file.split_from_end(FOOTER_NUM_BYTES);
+        let mut footer = footer.read_bytes()?;
+        let skip_index_offset = footer.read_u64() as usize;
+        let compressor = Compressor::from_id(footer.read_u8())?;
+        if skip_index_offset > data_and_skip_index.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "doc store skip index offset is out of bounds",
+            ));
+        }
+        let (data, skip_index_data) = data_and_skip_index.split(skip_index_offset);
+        let skip_index = SkipIndex::open(skip_index_data.read_bytes()?.as_slice())?;
+        Ok(StoreReader {
+            compressor,
+            data,
+            skip_index: Arc::new(skip_index),
+            cache: Arc::new(Mutex::new(LruCache::new(LRU_CACHE_CAPACITY))),
+            cache_hits: Arc::new(AtomicUsize::new(0)),
+            cache_misses: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    pub fn compressor(&self) -> Compressor {
+        self.compressor
+    }
+
+    /// Number of documents of the store.
+    pub fn num_docs(&self) -> DocId {
+        self.skip_index.num_docs()
+    }
+
+    pub fn cache_stats(&self) -> CacheStats {
+        CacheStats {
+            num_entries: self.cache.lock().unwrap().len(),
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+            cache_misses: self.cache_misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns the decompressed block starting at `checkpoint`, from the cache
+    /// if possible.
+    fn read_block(&self, checkpoint: &Checkpoint) -> io::Result<OwnedBytes> {
+        if let Some(block) = self.cache.lock().unwrap().get(&checkpoint.start_offset) {
+            self.cache_hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(block.clone());
+        }
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
+        let compressed_block = self.data.read_bytes_slice(checkpoint.byte_range())?;
+        let mut decompressed_block = Vec::new();
+        self.compressor
+            .decompress_into(compressed_block.as_slice(), &mut decompressed_block)?;
+        let block = OwnedBytes::new(decompressed_block);
+        self.cache
+            .lock()
+            .unwrap()
+            .put(checkpoint.start_offset, block.clone());
+        Ok(block)
+    }
+
+    /// Returns the stored fields of `doc`.
+    pub fn get(&self, doc: DocId) -> crate::Result<Document> {
+        let checkpoint = self.skip_index.seek(doc).ok_or_else(|| {
+            MySearchError::InvalidArgument(format!(
+                "doc {} is out of the doc store range [0, {})",
+                doc,
+                self.num_docs()
+            ))
+        })?;
+        let block = self.read_block(&checkpoint)?;
+        let mut cursor = block.as_slice();
+        for _ in checkpoint.start_doc..doc {
+            let doc_len = VInt::deserialize(&mut cursor)?.val() as usize;
+            cursor = cursor.get(doc_len..).ok_or_else(|| {
+                MySearchError::DataCorruption("doc store block is truncated".to_string())
+            })?;
+        }
+        let _doc_len = VInt::deserialize(&mut cursor)?;
+        Ok(Document::deserialize(&mut cursor)?)
+    }
+
+    /// Iterates over all the documents of the store, in doc id order.
+    ///
+    /// Blocks are decompressed one after the other, bypassing the cache.
+    pub fn iter(&self) -> impl Iterator<Item = crate::Result<Document>> + '_ {
+        self.skip_index
+            .checkpoints()
+            .iter()
+            .flat_map(move |checkpoint| {
+                let block = self
+                    .data
+                    .read_bytes_slice(checkpoint.byte_range())
+                    .and_then(|compressed_block| {
+                        let mut block = Vec::new();
+                        self.compressor
+                            .decompress_into(compressed_block.as_slice(), &mut block)?;
+                        Ok(block)
+                    });
+                let num_docs = (checkpoint.end_doc - checkpoint.start_doc) as usize;
+                let mut cursor = 0;
+                (0..num_docs).map(move |_| {
+                    let block = block.as_ref().map_err(|err| {
+                        MySearchError::IoError(io::Error::new(err.kind(), err.to_string()))
+                    })?;
+                    let mut doc_data = &block[cursor..];
+                    let doc_len = VInt::deserialize(&mut doc_data)?.val() as usize;
+                    cursor = block.len() - doc_data.len() + doc_len;
+                    Ok(Document::deserialize(&mut doc_data)?)
+                })
+            })
+    }
+}
+
+impl fmt::Debug for StoreReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "StoreReader(compressor={:?}, num_docs={})",
+            self.compressor,
+            self.num_docs()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::store::BLOCK_SIZE;
+    use crate::{
+        Compressor, Directory, Document, Field, MySearchError, RAMDirectory, StoreReader,
+        StoreWriter,
+    };
+
+    fn write_store(directory: &RAMDirectory, path: &Path, compressor: Compressor, num_docs: u64) {
+        let mut store_writer = StoreWriter::new(directory.open_write(path).unwrap(), compressor);
+        for i in 0..num_docs {
+            let mut doc = Document::new();
+            doc.add_u64(Field::from_field_id(0), i);
+            doc.add_text(Field::from_field_id(1), format!("document number {}", i));
+            store_writer.store(&doc).unwrap();
+        }
+        store_writer.close().unwrap();
+    }
+
+    #[test]
+    fn test_store_roundtrip() -> crate::Result<()> {
+        for &compressor in &[Compressor::None, Compressor::Lz4, Compressor::Snappy] {
+            let directory = RAMDirectory::create();
+            let path = Path::new("store");
+            write_store(&directory, path, compressor, 1_000);
+            let store_reader = StoreReader::open(directory.open_read(path)?)?;
+            assert_eq!(store_reader.compressor(), compressor);
+            assert_eq!(store_reader.num_docs(), 1_000);
+            // several blocks were written.
+            assert!(store_reader.skip_index.checkpoints().len() > 1);
+            let all_docs = store_reader.iter().collect::<crate::Result<Vec<_>>>()?;
+            assert_eq!(all_docs.len(), 1_000);
+            assert_eq!(all_docs[500], store_reader.get(500)?);
+            for &doc in &[0u32, 1, 499, 998, 999] {
+                let document = store_reader.get(doc)?;
+                assert_eq!(
+                    document.get_first(Field::from_field_id(0)),
+                    Some(&u64::from(doc).into())
+                );
+                assert_eq!(
+                    document
+                        .get_first(Field::from_field_id(1))
+                        .and_then(|value| value.text()),
+                    Some(format!("document number {}", doc).as_str())
+                );
+            }
+            assert!(matches!(
+                store_reader.get(1_000),
+                Err(MySearchError::InvalidArgument(_))
+            ));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_block_cache() -> crate::Result<()> {
+        let directory = RAMDirectory::create();
+        let path = Path::new("store");
+        write_store(&directory, path, Compressor::Lz4, 1_000);
+        let store_reader = StoreReader::open(directory.open_read(path)?)?;
+        store_reader.get(0)?;
+        store_reader.get(1)?;
+        let clone = store_reader.clone();
+        clone.get(2)?;
+        let stats = store_reader.cache_stats();
+        assert_eq!(stats.num_entries, 1);
+        assert_eq!(stats.cache_misses, 1);
+        assert_eq!(stats.cache_hits, 2);
+        store_reader.get(999)?;
+        assert_eq!(store_reader.cache_stats().num_entries, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_large_document() -> crate::Result<()> {
+        let directory = RAMDirectory::create();
+        let path = Path::new("store");
+        let large_text = "a".repeat(3 * BLOCK_SIZE);
+        let mut store_writer = StoreWriter::new(directory.open_write(path)?, Compressor::default());
+        let mut doc = Document::new();
+        doc.add_text(Field::from_field_id(0), &large_text);
+        store_writer.store(&Document::new())?;
+        store_writer.store(&doc)?;
+        store_writer.store(&Document::new())?;
+        store_writer.close()?;
+        let store_reader = StoreReader::open(directory.open_read(path)?)?;
+        assert_eq!(store_reader.num_docs(), 3);
+        assert_eq!(store_reader.get(1)?, doc);
+        assert!(store_reader.get(2)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_empty() -> crate::Result<()> {
+        let directory = RAMDirectory::create();
+        let path = Path::new("store");
+        write_store(&directory, path, Compressor::Lz4, 0);
+        let store_reader = StoreReader::open(directory.open_read(path)?)?;
+        assert_eq!(store_reader.num_docs(), 0);
+        assert!(store_reader.get(0).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use std::io::{self, Write};
+use std::ops::Range;
+
+use crate::{BinarySerializable, DocId, VInt};
+
+/// Location of a block of the doc store: the docs `[start_doc, end_doc)` are
+/// stored in the bytes `[start_offset, end_offset)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Checkpoint {
+    pub start_doc: DocId,
+    pub end_doc: DocId,
+    pub start_offset: u64,
+    pub end_offset: u64,
+}
+
+impl Checkpoint {
+    pub fn byte_range(&self) -> Range<usize> {
+        self.start_offset as usize..self.end_offset as usize
+    }
+}
+
+/// Accumulates the checkpoints of the blocks, in doc id order.
+#[derive(Default)]
+pub(crate) struct SkipIndexBuilder {
+    checkpoints: Vec<Checkpoint>,
+}
+
+impl SkipIndexBuilder {
+    pub fn insert(&mut self, checkpoint: Checkpoint) {
+        if let Some(last_checkpoint) = self.checkpoints.last() {
+            debug_assert_eq!(last_checkpoint.end_doc, checkpoint.start_doc);
+            debug_assert_eq!(last_checkpoint.end_offset, checkpoint.start_offset);
+        }
+        self.checkpoints.push(checkpoint);
+    }
+
+    /// Checkpoints are contiguous, so only the number of docs and the number of
+    /// bytes of each block are written.
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        VInt(self.checkpoints.len() as u64).serialize(writer)?;
+        for checkpoint in &self.checkpoints {
+            VInt(u64::from(checkpoint.end_doc - checkpoint.start_doc)).serialize(writer)?;
+            VInt(checkpoint.end_offset - checkpoint.start_offset).serialize(writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Maps a doc id to the checkpoint of the block holding it.
+pub(crate) struct SkipIndex {
+    checkpoints: Vec<Checkpoint>,
+}
+
+impl SkipIndex {
+    pub fn open(mut data: &[u8]) -> io::Result<SkipIndex> {
+        let num_checkpoints = VInt::deserialize(&mut data)?.val() as usize;
+        let mut checkpoints = Vec::with_capacity(num_checkpoints);
+        let mut start_doc = 0;
+        let mut start_offset = 0;
+        for _ in 0..num_checkpoints {
+            let num_docs = VInt::deserialize(&mut data)?.val() as DocId;
+            let num_bytes = VInt::deserialize(&mut data)?.val();
+            let checkpoint = Checkpoint {
+                start_doc,
+                end_doc: start_doc + num_docs,
+                start_offset,
+                end_offset: start_offset + num_bytes,
+            };
+            start_doc = checkpoint.end_doc;
+            start_offset = checkpoint.end_offset;
+            checkpoints.push(checkpoint);
+        }
+        Ok(SkipIndex { checkpoints })
+    }
+
+    /// Number of documents covered by the index.
+    pub fn num_docs(&self) -> DocId {
+        self.checkpoints
+            .last()
+            .map(|checkpoint| checkpoint.end_doc)
+            .unwrap_or(0)
+    }
+
+    pub fn checkpoints(&self) -> &[Checkpoint] {
+        &self.checkpoints
+    }
+
+    pub fn seek(&self, doc: DocId) -> Option<Checkpoint> {
+        let pos = self
+            .checkpoints
+            .partition_point(|checkpoint| checkpoint.end_doc <= doc);
+        self.checkpoints.get(pos).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Checkpoint, SkipIndex, SkipIndexBuilder};
+
+    #[test]
+    fn test_skip_index() {
+        let mut builder = SkipIndexBuilder::default();
+        let mut start_offset = 0;
+        for (start_doc, end_doc) in [(0, 3), (3, 4), (4, 10)].iter().cloned() {
+            let checkpoint = Checkpoint {
+                start_doc,
+                end_doc,
+                start_offset,
+                end_offset: start_offset + 100 * u64::from(end_doc - start_doc),
+            };
+            start_offset = checkpoint.end_offset;
+            builder.insert(checkpoint);
+        }
+        let mut buffer = Vec::new();
+        builder.write(&mut buffer).unwrap();
+        let skip_index = SkipIndex::open(&buffer).unwrap();
+        assert_eq!(skip_index.checkpoints(), &builder.checkpoints[..]);
+        assert_eq!(skip_index.num_docs(), 10);
+        assert_eq!(skip_index.seek(0).map(|c| c.start_doc), Some(0));
+        assert_eq!(skip_index.seek(2).map(|c| c.start_doc), Some(0));
+        assert_eq!(skip_index.seek(3).map(|c| c.byte_range()), Some(300..400));
+        assert_eq!(skip_index.seek(9).map(|c| c.start_doc), Some(4));
+        assert_eq!(skip_index.seek(10), None);
+    }
+
+    #[test]
+    fn test_empty_skip_index() {
+        let mut buffer = Vec::new();
+        SkipIndexBuilder::default().write(&mut buffer).unwrap();
+        let skip_index = SkipIndex::open(&buffer).unwrap();
+        assert_eq!(skip_index.num_docs(), 0);
+        assert_eq!(skip_index.seek(0), None);
+    }
+}
@@ -0,0 +1,76 @@
+use std::io::{self, Write};
+
+use crate::common::CountingWriter;
+use crate::store::{Checkpoint, SkipIndexBuilder, BLOCK_SIZE};
+use crate::{BinarySerializable, Compressor, DocId, Document, TerminatingWrite, VInt, WritePtr};
+
+/// Writes the doc store of a segment.
+///
+/// Documents must be stored in doc id order. Nothing is readable before
+/// `close` writes the skip index.
+pub struct StoreWriter {
+    compressor: Compressor,
+    doc: DocId,
+    first_doc_in_block: DocId,
+    skip_index_builder: SkipIndexBuilder,
+    current_block: Vec<u8>,
+    doc_buffer: Vec<u8>,
+    compressed_buffer: Vec<u8>,
+    writer: CountingWriter<WritePtr>,
+}
+
+impl StoreWriter {
+    pub fn new(writer: WritePtr, compressor: Compressor) -> StoreWriter {
+        StoreWriter {
+            compressor,
+            doc: 0,
+            first_doc_in_block: 0,
+            skip_index_builder: SkipIndexBuilder::default(),
+            current_block: Vec::new(),
+            doc_buffer: Vec::new(),
+            compressed_buffer: Vec::new(),
+            writer: CountingWriter::wrap(writer),
+        }
+    }
+
+    /// Appends `document` as the next doc id.
+    pub fn store(&mut self, document: &Document) -> io::Result<()> {
+        self.doc_buffer.clear();
+        document.serialize(&mut self.doc_buffer)?;
+        VInt(self.doc_buffer.len() as u64).serialize(&mut self.current_block)?;
+        self.current_block.write_all(&self.doc_buffer)?;
+        self.doc += 1;
+        if self.current_block.len() > BLOCK_SIZE {
+            self.write_and_compress_block()?;
+        }
+        Ok(())
+    }
+
+    fn write_and_compress_block(&mut self) -> io::Result<()> {
+        self.compressor
+            .compress_into(&self.current_block, &mut self.compressed_buffer)?;
+        let start_offset = self.writer.written_bytes();
+        self.writer.write_all(&self.compressed_buffer)?;
+        self.skip_index_builder.insert(Checkpoint {
+            start_doc: self.first_doc_in_block,
+            end_doc: self.doc,
+            start_offset,
+            end_offset: self.writer.written_bytes(),
+        });
+        self.first_doc_in_block = self.doc;
+        self.current_block.clear();
+        Ok(())
+    }
+
+    /// Flushes the last block and writes the skip index and the footer.
+    pub fn close(mut self) -> io::Result<()> {
+        if !self.current_block.is_empty() {
+            self.write_and_compress_block()?;
+        }
+        let skip_index_offset = self.writer.written_bytes();
+        self.skip_index_builder.write(&mut self.writer)?;
+        skip_index_offset.serialize(&mut self.writer)?;
+        self.compressor.to_id().serialize(&mut self.writer)?;
+        self.writer.finish()?.terminate()
+    }
+}
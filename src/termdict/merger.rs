@@ -0,0 +1,146 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::{TermInfo, TermStreamer};
+
+struct HeapItem<'a> {
+    streamer: TermStreamer<'a>,
+    segment_ord: usize,
+}
+
+impl<'a> PartialEq for HeapItem<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.segment_ord == other.segment_ord
+    }
+}
+
+impl<'a> Eq for HeapItem<'a> {}
+
+impl<'a> PartialOrd for HeapItem<'a> {
+    fn partial_cmp(&self, other: &HeapItem<'a>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a> Ord for HeapItem<'a> {
+    // reversed, so that the max-heap pops the smallest term, then the
+    // smallest segment ordinal.
+    fn cmp(&self, other: &HeapItem<'a>) -> Ordering {
+        (other.streamer.key(), other.segment_ord).cmp(&(self.streamer.key(), self.segment_ord))
+    }
+}
+
+/// Iterates over the union of the terms of several term streamers, in
+/// increasing order.
+///
+/// Each term is returned once, with the `TermInfo`s of the streamers having
+/// it. Used to merge the term dictionaries of segments.
+pub struct TermMerger<'a> {
+    heap: BinaryHeap<HeapItem<'a>>,
+    current_streamers: Vec<HeapItem<'a>>,
+}
+
+impl<'a> TermMerger<'a> {
+    /// Creates a merger over `streams`, the ordinal of a stream in the vector
+    /// being its segment ordinal.
+    pub fn new(streams: Vec<TermStreamer<'a>>) -> TermMerger<'a> {
+        TermMerger {
+            heap: BinaryHeap::new(),
+            current_streamers: streams
+                .into_iter()
+                .enumerate()
+                .map(|(segment_ord, streamer)| HeapItem {
+                    streamer,
+                    segment_ord,
+                })
+                .collect(),
+        }
+    }
+
+    /// Moves to the next term. Returns `false` once all the streams are
+    /// exhausted.
+    pub fn advance(&mut self) -> bool {
+        for mut heap_item in self.current_streamers.drain(..) {
+            if heap_item.streamer.advance() {
+                self.heap.push(heap_item);
+            }
+        }
+        let head = match self.heap.pop() {
+            Some(head) => head,
+            None => return false,
+        };
+        while let Some(next) = self.heap.peek() {
+            if next.streamer.key() != head.streamer.key() {
+                break;
+            }
+            let next = self.heap.pop().expect("the heap was just peeked");
+            self.current_streamers.push(next);
+        }
+        self.current_streamers.insert(0, head);
+        true
+    }
+
+    /// Bytes of the current term.
+    ///
+    /// Only valid after a call to `advance` returned `true`.
+    pub fn key(&self) -> &[u8] {
+        self.current_streamers[0].streamer.key()
+    }
+
+    /// Segment ordinals and `TermInfo`s of the streams having the current
+    /// term, by increasing segment ordinal.
+    pub fn current_segment_ords_and_term_infos(
+        &self,
+    ) -> impl Iterator<Item = (usize, TermInfo)> + '_ {
+        self.current_streamers
+            .iter()
+            .map(|heap_item| (heap_item.segment_ord, *heap_item.streamer.value()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FileSlice, TermDictionary, TermDictionaryBuilder, TermInfo, TermMerger};
+
+    fn term_dictionary(keys: &[&str]) -> crate::Result<TermDictionary> {
+        let mut builder = TermDictionaryBuilder::create(Vec::new())?;
+        for (ord, key) in keys.iter().enumerate() {
+            let term_info = TermInfo {
+                doc_freq: ord as u32 + 1,
+                postings_offset: 0,
+                positions_offset: 0,
+            };
+            builder.insert(key, &term_info)?;
+        }
+        let buffer = builder.finish()?;
+        Ok(TermDictionary::open(FileSlice::from(buffer))?)
+    }
+
+    #[test]
+    fn test_term_merger() -> crate::Result<()> {
+        let left = term_dictionary(&["a", "c", "d"])?;
+        let right = term_dictionary(&["b", "c"])?;
+        let mut merger = TermMerger::new(vec![left.stream(), right.stream()]);
+        let mut merged = Vec::new();
+        while merger.advance() {
+            let term_infos: Vec<(usize, u32)> = merger
+                .current_segment_ords_and_term_infos()
+                .map(|(segment_ord, term_info)| (segment_ord, term_info.doc_freq))
+                .collect();
+            merged.push((
+                String::from_utf8(merger.key().to_vec()).unwrap(),
+                term_infos,
+            ));
+        }
+        assert_eq!(
+            merged,
+            vec![
+                ("a".to_string(), vec![(0, 1)]),
+                ("b".to_string(), vec![(1, 1)]),
+                ("c".to_string(), vec![(0, 2), (1, 2)]),
+                ("d".to_string(), vec![(0, 3)]),
+            ]
+        );
+        Ok(())
+    }
+}
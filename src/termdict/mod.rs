@@ -0,0 +1,20 @@
+//! Term dictionary of a segment.
+//!
+//! The dictionary file is made of a finite-state transducer mapping every term
+//! to its ordinal, followed by the `TermInfo`s of the terms stored by ordinal:
+//!
+//! ```text
+//! [fst][term infos][fst length: u64]
+//! ```
+mod merger;
+mod streamer;
+mod term_dictionary;
+mod term_info_store;
+
+pub use merger::*;
+pub use streamer::*;
+pub use term_dictionary::*;
+pub(crate) use term_info_store::*;
+
+/// Position of a term in the sorted list of the terms of a segment.
+pub type TermOrdinal = u64;
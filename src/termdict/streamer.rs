@@ -0,0 +1,115 @@
+use tantivy_fst::automaton::AlwaysMatch;
+use tantivy_fst::map::{Stream, StreamBuilder};
+use tantivy_fst::{Automaton, IntoStreamer, Streamer};
+
+use crate::{TermDictionary, TermInfo, TermOrdinal};
+
+/// Configures the bounds of a `TermStreamer`.
+///
+/// Bounds are given on the complete term bytes.
+pub struct TermStreamerBuilder<'a, A = AlwaysMatch>
+where
+    A: Automaton,
+    A::State: Clone,
+{
+    term_dictionary: &'a TermDictionary,
+    stream_builder: StreamBuilder<'a, A>,
+}
+
+impl<'a, A> TermStreamerBuilder<'a, A>
+where
+    A: Automaton,
+    A::State: Clone,
+{
+    pub(crate) fn new(
+        term_dictionary: &'a TermDictionary,
+        stream_builder: StreamBuilder<'a, A>,
+    ) -> Self {
+        TermStreamerBuilder {
+            term_dictionary,
+            stream_builder,
+        }
+    }
+
+    /// Limits the stream to terms greater than or equal to `bound`.
+    pub fn ge<T: AsRef<[u8]>>(mut self, bound: T) -> Self {
+        self.stream_builder = self.stream_builder.ge(bound);
+        self
+    }
+
+    /// Limits the stream to terms strictly greater than `bound`.
+    pub fn gt<T: AsRef<[u8]>>(mut self, bound: T) -> Self {
+        self.stream_builder = self.stream_builder.gt(bound);
+        self
+    }
+
+    /// Limits the stream to terms lesser than or equal to `bound`.
+    pub fn le<T: AsRef<[u8]>>(mut self, bound: T) -> Self {
+        self.stream_builder = self.stream_builder.le(bound);
+        self
+    }
+
+    /// Limits the stream to terms strictly lesser than `bound`.
+    pub fn lt<T: AsRef<[u8]>>(mut self, bound: T) -> Self {
+        self.stream_builder = self.stream_builder.lt(bound);
+        self
+    }
+
+    pub fn into_stream(self) -> TermStreamer<'a, A> {
+        TermStreamer {
+            term_dictionary: self.term_dictionary,
+            stream: self.stream_builder.into_stream(),
+            term_ord: 0,
+            current_key: Vec::with_capacity(100),
+            current_value: TermInfo::default(),
+        }
+    }
+}
+
+/// Iterates over the terms of a `TermDictionary` in increasing order.
+pub struct TermStreamer<'a, A = AlwaysMatch>
+where
+    A: Automaton,
+{
+    term_dictionary: &'a TermDictionary,
+    stream: Stream<'a, A>,
+    term_ord: TermOrdinal,
+    current_key: Vec<u8>,
+    current_value: TermInfo,
+}
+
+impl<'a, A: Automaton> TermStreamer<'a, A> {
+    /// Moves to the next term. Returns `false` once the stream is exhausted.
+    pub fn advance(&mut self) -> bool {
+        if let Some((term, term_ord)) = self.stream.next() {
+            self.current_key.clear();
+            self.current_key.extend_from_slice(term);
+            self.term_ord = term_ord;
+            self.current_value = self.term_dictionary.term_info_from_ord(term_ord);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Ordinal of the current term.
+    ///
+    /// Only valid after a call to `advance` returned `true`.
+    pub fn term_ord(&self) -> TermOrdinal {
+        self.term_ord
+    }
+
+    /// Bytes of the current term.
+    ///
+    /// Only valid after a call to `advance` returned `true`.
+    pub fn key(&self) -> &[u8] {
+        &self.current_key
+    }
+
+    /// `TermInfo` of the current term.
+    ///
+    /// Only valid after a call to `advance` returned `true`.
+    pub fn value(&self) -> &TermInfo {
+        &self.current_value
+    }
+}
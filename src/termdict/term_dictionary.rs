@@ -0,0 +1,352 @@
+use std::io::{self, Write};
+
+use tantivy_fst::raw::Fst;
+use tantivy_fst::{Automaton, Map, MapBuilder};
+
+use crate::common::CountingWriter;
+use crate::termdict::{TermInfoStore, TermInfoStoreWriter};
+use crate::{
+    BinarySerializable, FileSlice, HasLen, OwnedBytes, TermInfo, TermOrdinal, TermStreamer,
+    TermStreamerBuilder,
+};
+
+fn convert_fst_error(error: tantivy_fst::Error) -> io::Error {
+    io::Error::other(error)
+}
+
+/// Writes a term dictionary: the sorted list of the terms of a segment, each
+/// associated with its `TermInfo`.
+pub struct TermDictionaryBuilder<W: Write> {
+    fst_builder: MapBuilder<CountingWriter<W>>,
+    term_info_store_writer: TermInfoStoreWriter,
+    term_ord: TermOrdinal,
+    key_pending: bool,
+}
+
+impl<W: Write> TermDictionaryBuilder<W> {
+    pub fn create(write: W) -> io::Result<TermDictionaryBuilder<W>> {
+        let fst_builder =
+            MapBuilder::new(CountingWriter::wrap(write)).map_err(convert_fst_error)?;
+        Ok(TermDictionaryBuilder {
+            fst_builder,
+            term_info_store_writer: TermInfoStoreWriter::default(),
+            term_ord: 0,
+            key_pending: false,
+        })
+    }
+
+    /// Inserts a term and its `TermInfo`. Terms must be inserted in increasing order.
+    pub fn insert<K: AsRef<[u8]>>(&mut self, key: K, term_info: &TermInfo) -> io::Result<()> {
+        self.insert_key(key.as_ref())?;
+        self.insert_value(term_info)
+    }
+
+    /// Inserts a term whose `TermInfo` will be given by the next call to `insert_value`.
+    ///
+    /// # Panics
+    ///
+    /// If `key` is not greater than the previously inserted key.
+    pub fn insert_key(&mut self, key: &[u8]) -> io::Result<()> {
+        assert!(!self.key_pending, "the previous key has no value");
+        match self.fst_builder.insert(key, self.term_ord) {
+            Ok(()) => {}
+            Err(tantivy_fst::Error::Fst(_)) => {
+                panic!("terms must be inserted in increasing order")
+            }
+            Err(err) => return Err(convert_fst_error(err)),
+        }
+        self.key_pending = true;
+        Ok(())
+    }
+
+    pub fn insert_value(&mut self, term_info: &TermInfo) -> io::Result<()> {
+        assert!(self.key_pending, "insert_value called without a key");
+        self.term_info_store_writer.write_term_info(term_info);
+        self.term_ord += 1;
+        self.key_pending = false;
+        Ok(())
+    }
+
+    /// Writes the term infos and the footer, and returns the underlying writer.
+    pub fn finish(self) -> io::Result<W> {
+        let mut write = self.fst_builder.into_inner().map_err(convert_fst_error)?;
+        let fst_len = write.written_bytes();
+        self.term_info_store_writer.serialize(&mut write)?;
+        fst_len.serialize(&mut write)?;
+        write.finish()
+    }
+}
+
+/// Read-only view of a term dictionary written by `TermDictionaryBuilder`.
+///
+/// The dictionary reads its `FileSlice` in place: opening it does not copy the
+/// terms.
+pub struct TermDictionary {
+    fst_index: Map<OwnedBytes>,
+    term_info_store: TermInfoStore,
+}
+
+impl TermDictionary {
+    pub fn open(file: FileSlice) -> io::Result<TermDictionary> {
+        if file.len() < 8 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "term dictionary file is too short",
+            ));
+        }
+        let (main_slice, footer_slice) = file.split_from_end(8);
+        let fst_len = u64::deserialize(&mut footer_slice.read_bytes()?)? as usize;
+        if fst_len > main_slice.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "fst length {} exceeds the term dictionary length {}",
+                    fst_len,
+                    main_slice.len()
+                ),
+            ));
+        }
+        let (fst_slice, term_info_slice) = main_slice.split(fst_len);
+        let fst = Fst::new(fst_slice.read_bytes()?).map_err(convert_fst_error)?;
+        let fst_index = Map::from(fst);
+        let term_info_store = TermInfoStore::open(term_info_slice)?;
+        if fst_index.len() != term_info_store.num_terms() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "the fst holds {} terms but the term info store holds {}",
+                    fst_index.len(),
+                    term_info_store.num_terms()
+                ),
+            ));
+        }
+        Ok(TermDictionary {
+            fst_index,
+            term_info_store,
+        })
+    }
+
+    /// Returns a dictionary without any term.
+    pub fn empty() -> TermDictionary {
+        let buffer = TermDictionaryBuilder::create(Vec::new())
+            .and_then(TermDictionaryBuilder::finish)
+            .expect("writing an empty term dictionary in memory cannot fail");
+        TermDictionary::open(FileSlice::from(buffer))
+            .expect("an empty term dictionary is always valid")
+    }
+
+    pub fn num_terms(&self) -> usize {
+        self.term_info_store.num_terms()
+    }
+
+    /// Returns the ordinal of `key`, i.e. its rank in the dictionary.
+    pub fn term_ord<K: AsRef<[u8]>>(&self, key: K) -> Option<TermOrdinal> {
+        self.fst_index.get(key)
+    }
+
+    pub fn get<K: AsRef<[u8]>>(&self, key: K) -> Option<TermInfo> {
+        self.term_ord(key)
+            .map(|term_ord| self.term_info_from_ord(term_ord))
+    }
+
+    pub fn term_info_from_ord(&self, term_ord: TermOrdinal) -> TermInfo {
+        self.term_info_store.get(term_ord)
+    }
+
+    /// Writes the bytes of the term with the given ordinal into `bytes`.
+    ///
+    /// Returns `false` if there is no such term. The lookup walks the transducer,
+    /// picking at each node the last transition whose output does not exceed the
+    /// remaining ordinal.
+    pub fn ord_to_term(&self, mut term_ord: TermOrdinal, bytes: &mut Vec<u8>) -> bool {
+        bytes.clear();
+        let fst = self.fst_index.as_fst();
+        let mut node = fst.root();
+        while term_ord != 0 || !node.is_final() {
+            let transition = node
+                .transitions()
+                .take_while(|transition| transition.out.value() <= term_ord)
+                .last();
+            match transition {
+                Some(transition) => {
+                    term_ord -= transition.out.value();
+                    bytes.push(transition.inp);
+                    node = fst.node(transition.addr);
+                }
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// Returns a builder streaming over a range of the terms, in order.
+    pub fn range(&self) -> TermStreamerBuilder<'_> {
+        TermStreamerBuilder::new(self, self.fst_index.range())
+    }
+
+    /// Streams over all the terms, in order.
+    pub fn stream(&self) -> TermStreamer<'_> {
+        self.range().into_stream()
+    }
+
+    /// Returns a builder streaming over the terms starting with `prefix`.
+    pub fn prefix_range<K: AsRef<[u8]>>(&self, prefix: K) -> TermStreamerBuilder<'_> {
+        let prefix = prefix.as_ref();
+        let builder = self.range().ge(prefix);
+        match prefix_upper_bound(prefix) {
+            Some(upper_bound) => builder.lt(upper_bound),
+            None => builder,
+        }
+    }
+
+    /// Returns a builder streaming over the terms accepted by `automaton`.
+    pub fn search<A: Automaton>(&self, automaton: A) -> TermStreamerBuilder<'_, A>
+    where
+        A::State: Clone,
+    {
+        TermStreamerBuilder::new(self, self.fst_index.search(automaton))
+    }
+}
+
+/// Returns the smallest key greater than all the keys starting with `prefix`,
+/// or `None` if there is no such key.
+fn prefix_upper_bound(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut upper_bound = prefix.to_vec();
+    while let Some(last_byte) = upper_bound.pop() {
+        if last_byte != u8::MAX {
+            upper_bound.push(last_byte + 1);
+            return Some(upper_bound);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::prefix_upper_bound;
+    use crate::{FileSlice, TermDictionary, TermDictionaryBuilder, TermInfo};
+    use std::io;
+
+    fn term_info(doc_freq: u32) -> TermInfo {
+        TermInfo {
+            doc_freq,
+            postings_offset: u64::from(doc_freq) * 10,
+            positions_offset: u64::from(doc_freq) * 100,
+        }
+    }
+
+    fn build_term_dictionary(keys: &[&[u8]]) -> io::Result<TermDictionary> {
+        let mut builder = TermDictionaryBuilder::create(Vec::new())?;
+        for (i, key) in keys.iter().enumerate() {
+            builder.insert(key, &term_info(i as u32 + 1))?;
+        }
+        let buffer = builder.finish()?;
+        TermDictionary::open(FileSlice::from(buffer))
+    }
+
+    fn collect_keys(mut streamer: crate::TermStreamer<'_>) -> Vec<Vec<u8>> {
+        let mut keys = Vec::new();
+        while streamer.advance() {
+            keys.push(streamer.key().to_vec());
+        }
+        keys
+    }
+
+    #[test]
+    fn test_term_dictionary() -> io::Result<()> {
+        let term_dictionary = build_term_dictionary(&[b"abc", b"abd", b"b"])?;
+        assert_eq!(term_dictionary.num_terms(), 3);
+        assert_eq!(term_dictionary.get(b"abd"), Some(term_info(2)));
+        assert_eq!(term_dictionary.get(b"ab"), None);
+        assert_eq!(term_dictionary.term_ord(b"b"), Some(2));
+        let mut term = Vec::new();
+        for (term_ord, expected) in [&b"abc"[..], b"abd", b"b"].iter().enumerate() {
+            assert!(term_dictionary.ord_to_term(term_ord as u64, &mut term));
+            assert_eq!(&term[..], *expected);
+        }
+        assert!(!term_dictionary.ord_to_term(3, &mut term));
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_term_dictionary() {
+        let term_dictionary = TermDictionary::empty();
+        assert_eq!(term_dictionary.num_terms(), 0);
+        assert_eq!(term_dictionary.get(b"a"), None);
+        assert!(collect_keys(term_dictionary.stream()).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_term_dictionary_unsorted_insert() {
+        let mut builder = TermDictionaryBuilder::create(Vec::new()).unwrap();
+        builder.insert(b"b", &term_info(1)).unwrap();
+        builder.insert(b"a", &term_info(2)).unwrap();
+    }
+
+    #[test]
+    fn test_stream_range_and_prefix() -> io::Result<()> {
+        let keys: Vec<&[u8]> = vec![b"a", b"ab", b"abc", b"abd", b"b", b"b\xff", b"c"];
+        let term_dictionary = build_term_dictionary(&keys)?;
+
+        let mut streamer = term_dictionary.stream();
+        for (term_ord, key) in keys.iter().enumerate() {
+            assert!(streamer.advance());
+            assert_eq!(streamer.key(), *key);
+            assert_eq!(streamer.term_ord(), term_ord as u64);
+            assert_eq!(streamer.value(), &term_info(term_ord as u32 + 1));
+        }
+        assert!(!streamer.advance());
+
+        let range = term_dictionary.range().ge(b"ab").lt(b"b").into_stream();
+        assert_eq!(
+            collect_keys(range),
+            vec![b"ab".to_vec(), b"abc".to_vec(), b"abd".to_vec()]
+        );
+        let range = term_dictionary.range().gt(b"abd").le(b"c").into_stream();
+        assert_eq!(
+            collect_keys(range),
+            vec![b"b".to_vec(), b"b\xff".to_vec(), b"c".to_vec()]
+        );
+
+        let prefixed = term_dictionary.prefix_range(b"ab").into_stream();
+        assert_eq!(
+            collect_keys(prefixed),
+            vec![b"ab".to_vec(), b"abc".to_vec(), b"abd".to_vec()]
+        );
+        let prefixed = term_dictionary.prefix_range(b"b").into_stream();
+        assert_eq!(
+            collect_keys(prefixed),
+            vec![b"b".to_vec(), b"b\xff".to_vec()]
+        );
+        assert_eq!(
+            collect_keys(term_dictionary.prefix_range(b"").into_stream()).len(),
+            keys.len()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_prefix_upper_bound() {
+        assert_eq!(prefix_upper_bound(b"ab"), Some(b"ac".to_vec()));
+        assert_eq!(prefix_upper_bound(b"a\xff\xff"), Some(b"b".to_vec()));
+        assert_eq!(prefix_upper_bound(b"\xff"), None);
+        assert_eq!(prefix_upper_bound(b""), None);
+    }
+
+    #[test]
+    fn test_open_truncated_term_dictionary_fails() -> io::Result<()> {
+        let mut builder = TermDictionaryBuilder::create(Vec::new())?;
+        builder.insert(b"abc", &term_info(1))?;
+        let mut buffer = builder.finish()?;
+        buffer.drain(..4);
+        assert!(TermDictionary::open(FileSlice::from(buffer)).is_err());
+        for len in 0..8 {
+            let error = TermDictionary::open(FileSlice::from(vec![0u8; len]))
+                .err()
+                .unwrap();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use std::io::{self, Write};
+
+use crate::{BinarySerializable, FileSlice, OwnedBytes, TermInfo, TermOrdinal};
+
+/// Number of bytes of a serialized `TermInfo`.
+pub(crate) const TERM_INFO_NUM_BYTES: usize = 4 + 8 + 8;
+
+/// `TermInfo`s of a term dictionary, stored as fixed-size records indexed by
+/// term ordinal.
+pub(crate) struct TermInfoStore {
+    data: OwnedBytes,
+}
+
+impl TermInfoStore {
+    pub fn open(file: FileSlice) -> io::Result<TermInfoStore> {
+        let data = file.read_bytes()?;
+        if data.len() % TERM_INFO_NUM_BYTES != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "term info store length {} is not a multiple of {}",
+                    data.len(),
+                    TERM_INFO_NUM_BYTES
+                ),
+            ));
+        }
+        Ok(TermInfoStore { data })
+    }
+
+    pub fn num_terms(&self) -> usize {
+        self.data.len() / TERM_INFO_NUM_BYTES
+    }
+
+    pub fn get(&self, term_ord: TermOrdinal) -> TermInfo {
+        let start = term_ord as usize * TERM_INFO_NUM_BYTES;
+        let mut record = &self.data.as_slice()[start..start + TERM_INFO_NUM_BYTES];
+        TermInfo::deserialize(&mut record).expect("a term info record cannot be truncated")
+    }
+}
+
+/// Buffers the `TermInfo`s of a term dictionary until it is serialized.
+#[derive(Default)]
+pub(crate) struct TermInfoStoreWriter {
+    buffer: Vec<u8>,
+}
+
+impl TermInfoStoreWriter {
+    pub fn write_term_info(&mut self, term_info: &TermInfo) {
+        term_info
+            .serialize(&mut self.buffer)
+            .expect("writing to a Vec cannot fail");
+    }
+
+    pub fn serialize<W: Write>(&self, write: &mut W) -> io::Result<()> {
+        write.write_all(&self.buffer)
+    }
+}
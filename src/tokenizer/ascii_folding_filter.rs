@@ -0,0 +1,161 @@
+use std::mem;
+
+use crate::{BoxTokenStream, Token, TokenFilter, TokenStream};
+
+/// Replaces the accented letters and ligatures of the Latin alphabets, as well
+/// as typographic punctuation, with their closest ASCII equivalent.
+///
+/// `"Crème brûlée"` becomes `"Creme brulee"` and `"Œuvre"` becomes `"OEuvre"`.
+/// Characters without an ASCII equivalent are kept as is.
+#[derive(Clone)]
+pub struct AsciiFoldingFilter;
+
+impl TokenFilter for AsciiFoldingFilter {
+    fn transform<'a>(&self, token_stream: BoxTokenStream<'a>) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(AsciiFoldingFilterTokenStream {
+            tail: token_stream,
+            buffer: String::with_capacity(100),
+        })
+    }
+}
+
+pub struct AsciiFoldingFilterTokenStream<'a> {
+    tail: BoxTokenStream<'a>,
+    buffer: String,
+}
+
+impl<'a> TokenStream for AsciiFoldingFilterTokenStream<'a> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+        let token = self.tail.token_mut();
+        if !token.text.is_ascii() {
+            to_ascii(&token.text, &mut self.buffer);
+            mem::swap(&mut token.text, &mut self.buffer);
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+/// Writes the folded `text` into `output`, replacing its content.
+fn to_ascii(text: &str, output: &mut String) {
+    output.clear();
+    for c in text.chars() {
+        match fold_non_ascii_char(c) {
+            Some(folded) => output.push_str(folded),
+            None => output.push(c),
+        }
+    }
+}
+
+fn fold_non_ascii_char(c: char) -> Option<&'static str> {
+    let folded = match c {
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' | 'Ā' | 'Ă' | 'Ą' => "A",
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' | 'ª' => "a",
+        'Æ' => "AE",
+        'æ' => "ae",
+        'Ç' | 'Ć' | 'Ĉ' | 'Ċ' | 'Č' => "C",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'Ð' | 'Ď' | 'Đ' => "D",
+        'ð' | 'ď' | 'đ' => "d",
+        'È' | 'É' | 'Ê' | 'Ë' | 'Ē' | 'Ĕ' | 'Ė' | 'Ę' | 'Ě' => "E",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'Ĝ' | 'Ğ' | 'Ġ' | 'Ģ' => "G",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'Ĥ' | 'Ħ' => "H",
+        'ĥ' | 'ħ' => "h",
+        'Ì' | 'Í' | 'Î' | 'Ï' | 'Ĩ' | 'Ī' | 'Ĭ' | 'Į' | 'İ' => "I",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'Ĳ' => "IJ",
+        'ĳ' => "ij",
+        'Ĵ' => "J",
+        'ĵ' => "j",
+        'Ķ' => "K",
+        'ķ' | 'ĸ' => "k",
+        'Ĺ' | 'Ļ' | 'Ľ' | 'Ŀ' | 'Ł' => "L",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'Ñ' | 'Ń' | 'Ņ' | 'Ň' | 'Ŋ' => "N",
+        'ñ' | 'ń' | 'ņ' | 'ň' | 'ŉ' | 'ŋ' => "n",
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' | 'Ō' | 'Ŏ' | 'Ő' => "O",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' | 'º' => "o",
+        'Œ' => "OE",
+        'œ' => "oe",
+        'Ŕ' | 'Ŗ' | 'Ř' => "R",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'Ś' | 'Ŝ' | 'Ş' | 'Š' => "S",
+        'ś' | 'ŝ' | 'ş' | 'š' | 'ſ' => "s",
+        'ß' => "ss",
+        'Ţ' | 'Ť' | 'Ŧ' => "T",
+        'ţ' | 'ť' | 'ŧ' => "t",
+        'Þ' => "TH",
+        'þ' => "th",
+        'Ù' | 'Ú' | 'Û' | 'Ü' | 'Ũ' | 'Ū' | 'Ŭ' | 'Ů' | 'Ű' | 'Ų' => "U",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'Ŵ' => "W",
+        'ŵ' => "w",
+        'Ý' | 'Ŷ' | 'Ÿ' => "Y",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'Ź' | 'Ż' | 'Ž' => "Z",
+        'ź' | 'ż' | 'ž' => "z",
+        'ﬀ' => "ff",
+        'ﬁ' => "fi",
+        'ﬂ' => "fl",
+        'ﬃ' => "ffi",
+        'ﬄ' => "ffl",
+        'ﬅ' | 'ﬆ' => "st",
+        '‘' | '’' | '‚' | '‛' | '′' => "'",
+        '“' | '”' | '„' | '‟' | '″' | '«' | '»' => "\"",
+        '‐' | '‑' | '‒' | '–' | '—' | '―' => "-",
+        '…' => "...",
+        '\u{a0}' | '\u{2002}' | '\u{2003}' | '\u{2009}' => " ",
+        _ => return None,
+    };
+    Some(folded)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AsciiFoldingFilter, RawTokenizer, SimpleTokenizer, TextAnalyzer};
+
+    fn folded_tokens<T: crate::Tokenizer>(tokenizer: T, text: &str) -> Vec<String> {
+        let analyzer = TextAnalyzer::from(tokenizer).filter(AsciiFoldingFilter);
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = Vec::new();
+        token_stream.process(&mut |token| tokens.push(token.text.clone()));
+        tokens
+    }
+
+    #[test]
+    fn test_ascii_folding() {
+        assert_eq!(
+            folded_tokens(SimpleTokenizer, "Crème brûlée à Łódź"),
+            vec!["Creme", "brulee", "a", "Lodz"]
+        );
+        assert_eq!(
+            folded_tokens(SimpleTokenizer, "Œuvre Straße æon"),
+            vec!["OEuvre", "Strasse", "aeon"]
+        );
+        assert_eq!(
+            folded_tokens(RawTokenizer, "l’été — 東京"),
+            vec!["l'ete - 東京"]
+        );
+    }
+
+    #[test]
+    fn test_ascii_folding_keeps_offsets() {
+        let analyzer = TextAnalyzer::from(SimpleTokenizer).filter(AsciiFoldingFilter);
+        let mut token_stream = analyzer.token_stream("déjà vu");
+        let token = token_stream.next().unwrap();
+        assert_eq!(token.text, "deja");
+        assert_eq!((token.offset_from, token.offset_to), (0, 6));
+    }
+}
@@ -0,0 +1,156 @@
+use std::vec;
+
+use crate::{BoxTokenStream, Token, TokenStream, Tokenizer};
+
+/// Returns true for the characters of the Chinese, Japanese and Korean
+/// scripts, which are written without spaces between words.
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{1100}'..='\u{11FF}' // Hangul Jamo
+        | '\u{3005}'..='\u{3007}' // ideographic iteration and closing marks, zero
+        | '\u{3040}'..='\u{309F}' // Hiragana
+        | '\u{30A0}'..='\u{30FF}' // Katakana
+        | '\u{3130}'..='\u{318F}' // Hangul Compatibility Jamo
+        | '\u{31F0}'..='\u{31FF}' // Katakana Phonetic Extensions
+        | '\u{3400}'..='\u{4DBF}' // CJK Unified Ideographs Extension A
+        | '\u{4E00}'..='\u{9FFF}' // CJK Unified Ideographs
+        | '\u{AC00}'..='\u{D7AF}' // Hangul Syllables
+        | '\u{F900}'..='\u{FAFF}' // CJK Compatibility Ideographs
+        | '\u{FF66}'..='\u{FF9F}' // Halfwidth Katakana
+        | '\u{20000}'..='\u{2FA1F}' // CJK Unified Ideographs Extension B and beyond
+    )
+}
+
+/// Dictionary-free tokenizer for Chinese, Japanese and Korean text.
+///
+/// Text is split on non-alphanumeric characters like `SimpleTokenizer` does.
+/// Runs of CJK characters are then indexed as overlapping bigrams, "東京都"
+/// giving "東京" and "京都", while the other words are kept whole. A CJK
+/// character standing alone is emitted as a unigram.
+///
+/// Bigrams match any word of two characters or more without a dictionary, at
+/// the cost of a bigger index and of some false positives.
+#[derive(Clone, Debug)]
+pub struct CjkBigramTokenizer;
+
+impl Tokenizer for CjkBigramTokenizer {
+    fn token_stream<'a>(&self, text: &'a str) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(CjkBigramTokenStream {
+            text,
+            token_offsets: bigram_offsets(text).into_iter(),
+            token: Token::default(),
+        })
+    }
+}
+
+/// Computes the byte ranges of the tokens of `text`.
+fn bigram_offsets(text: &str) -> Vec<(usize, usize)> {
+    let mut token_offsets = Vec::new();
+    // byte offsets of the CJK characters of the current run.
+    let mut cjk_run: Vec<usize> = Vec::new();
+    let mut word_start: Option<usize> = None;
+    for (offset, c) in text.char_indices().chain(Some((text.len(), ' '))) {
+        if is_cjk(c) {
+            if let Some(start) = word_start.take() {
+                token_offsets.push((start, offset));
+            }
+            cjk_run.push(offset);
+            continue;
+        }
+        if !cjk_run.is_empty() {
+            push_bigrams(&cjk_run, offset, &mut token_offsets);
+            cjk_run.clear();
+        }
+        if c.is_alphanumeric() {
+            word_start.get_or_insert(offset);
+        } else if let Some(start) = word_start.take() {
+            token_offsets.push((start, offset));
+        }
+    }
+    token_offsets
+}
+
+/// Pushes the bigrams of a run of CJK characters, starting at the byte
+/// offsets `char_offsets` and ending at `run_end`.
+fn push_bigrams(char_offsets: &[usize], run_end: usize, token_offsets: &mut Vec<(usize, usize)>) {
+    if char_offsets.len() == 1 {
+        token_offsets.push((char_offsets[0], run_end));
+        return;
+    }
+    for (i, &start) in char_offsets[..char_offsets.len() - 1].iter().enumerate() {
+        let end = char_offsets.get(i + 2).copied().unwrap_or(run_end);
+        token_offsets.push((start, end));
+    }
+}
+
+pub struct CjkBigramTokenStream<'a> {
+    text: &'a str,
+    token_offsets: vec::IntoIter<(usize, usize)>,
+    token: Token,
+}
+
+impl<'a> TokenStream for CjkBigramTokenStream<'a> {
+    fn advance(&mut self) -> bool {
+        let (offset_from, offset_to) = match self.token_offsets.next() {
+            Some(offsets) => offsets,
+            None => return false,
+        };
+        self.token.position = self.token.position.wrapping_add(1);
+        self.token.offset_from = offset_from;
+        self.token.offset_to = offset_to;
+        self.token.text.clear();
+        self.token.text.push_str(&self.text[offset_from..offset_to]);
+        true
+    }
+
+    fn token(&self) -> &Token {
+        &self.token
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.token
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CjkBigramTokenizer, Tokenizer};
+
+    fn tokens(text: &str) -> Vec<(String, usize, usize, usize)> {
+        let mut token_stream = CjkBigramTokenizer.token_stream(text);
+        let mut tokens = Vec::new();
+        token_stream.process(&mut |token| {
+            tokens.push((
+                token.text.clone(),
+                token.offset_from,
+                token.offset_to,
+                token.position,
+            ))
+        });
+        tokens
+    }
+
+    #[test]
+    fn test_cjk_bigrams() {
+        assert_eq!(
+            tokens("東京都"),
+            vec![("東京".to_string(), 0, 6, 0), ("京都".to_string(), 3, 9, 1)]
+        );
+    }
+
+    #[test]
+    fn test_mixed_scripts() {
+        let text = "Rustで全文検索、 한국어 and 日!";
+        let tokens = tokens(text);
+        let texts: Vec<&str> = tokens.iter().map(|token| token.0.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec!["Rust", "で全", "全文", "文検", "検索", "한국", "국어", "and", "日"]
+        );
+        for (token_text, offset_from, offset_to, _) in &tokens {
+            assert_eq!(&text[*offset_from..*offset_to], token_text);
+        }
+        let positions: Vec<usize> = tokens.iter().map(|token| token.3).collect();
+        assert_eq!(positions, (0..9).collect::<Vec<usize>>());
+    }
+}
@@ -0,0 +1,580 @@
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::MySearchError;
+
+/// Word of the lexicon, or unknown word template of `unk.def`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct WordEntry {
+    /// Context id of the word when it follows another word.
+    pub left_id: u16,
+    /// Context id of the word when another word follows it.
+    pub right_id: u16,
+    pub cost: i16,
+}
+
+/// Files of the dictionary embedded in the crate.
+const EMBEDDED_LEXICON: &str = include_str!("ipadic_dictionary/lexicon.csv");
+const EMBEDDED_MATRIX_DEF: &str = include_str!("ipadic_dictionary/matrix.def");
+const EMBEDDED_CHAR_DEF: &str = include_str!("ipadic_dictionary/char.def");
+const EMBEDDED_UNK_DEF: &str = include_str!("ipadic_dictionary/unk.def");
+
+/// Maximum number of character categories, so that the compatible categories
+/// of a character fit in a `u32` mask.
+const MAX_NUM_CATEGORIES: usize = 32;
+
+/// Character category of `char.def`, driving the detection of unknown words.
+#[derive(Clone, Debug)]
+pub(crate) struct CharCategory {
+    /// Whether unknown words are looked for even when a word of the lexicon
+    /// starts with the character.
+    pub invoke: bool,
+    /// Whether a run of characters of the category makes up an unknown word.
+    pub group: bool,
+    /// Unknown words of 1 to `length` characters of the category are looked
+    /// for as well.
+    pub length: usize,
+}
+
+/// Categories of a character.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct CharInfo {
+    /// Category used to build the unknown words starting with the character.
+    pub category: usize,
+    /// Mask of the categories the character can be grouped with.
+    compatible_categories: u32,
+}
+
+impl CharInfo {
+    /// Whether the character can be part of an unknown word of `category`.
+    pub fn is_compatible_with(self, category: usize) -> bool {
+        self.compatible_categories & (1 << category) != 0
+    }
+}
+
+/// Returns an error reporting that `line` of `file` is malformed.
+fn invalid_line(file: &Path, line_ord: usize, line: &str) -> MySearchError {
+    MySearchError::DataCorruption(format!(
+        "invalid line {} of {:?}: '{}'",
+        line_ord + 1,
+        file,
+        line
+    ))
+}
+
+/// Reads a file of the dictionary, which must be encoded in UTF-8.
+fn read_file(path: &Path) -> crate::Result<String> {
+    fs::read_to_string(path).map_err(|error| {
+        if error.kind() == io::ErrorKind::InvalidData {
+            MySearchError::DataCorruption(format!(
+                "{:?} is not valid UTF-8, IPADIC is distributed in EUC-JP and must be \
+                 converted first",
+                path
+            ))
+        } else {
+            MySearchError::from(error)
+        }
+    })
+}
+
+/// Parses a line of a lexicon CSV or of `unk.def`, returning its surface and
+/// entry. The columns following the cost are ignored.
+fn parse_entry(line: &str) -> Option<(String, WordEntry)> {
+    let (surface, rest) = if let Some(quoted) = line.strip_prefix('"') {
+        // a quoted surface may contain commas, and escapes quotes by doubling them.
+        let mut surface = String::new();
+        let mut chars = quoted.char_indices();
+        loop {
+            match chars.next()? {
+                (offset, '"') => {
+                    if quoted[offset + 1..].starts_with('"') {
+                        surface.push('"');
+                        chars.next();
+                    } else {
+                        break (surface, quoted[offset + 1..].strip_prefix(',')?);
+                    }
+                }
+                (_, c) => surface.push(c),
+            }
+        }
+    } else {
+        let (surface, rest) = line.split_at(line.find(',')?);
+        (surface.to_string(), &rest[1..])
+    };
+    let mut columns = rest.split(',');
+    let left_id = columns.next()?.trim().parse().ok()?;
+    let right_id = columns.next()?.trim().parse().ok()?;
+    let cost = columns.next()?.trim().parse().ok()?;
+    if surface.is_empty() {
+        return None;
+    }
+    Some((
+        surface,
+        WordEntry {
+            left_id,
+            right_id,
+            cost,
+        },
+    ))
+}
+
+/// Parses a code point of `char.def`, written as `0x3042`.
+fn parse_code_point(code_point: &str) -> Option<u32> {
+    u32::from_str_radix(code_point.strip_prefix("0x")?, 16).ok()
+}
+
+/// Character categories of `char.def`.
+struct CharDefinition {
+    categories: Vec<CharCategory>,
+    category_ids: HashMap<String, usize>,
+    /// Categories of the characters of the Basic Multilingual Plane, the other
+    /// characters falling in the `DEFAULT` category.
+    char_infos: Vec<CharInfo>,
+    default_info: CharInfo,
+}
+
+impl CharDefinition {
+    fn parse(path: &Path, char_def: &str) -> crate::Result<CharDefinition> {
+        let mut categories = Vec::new();
+        let mut category_ids = HashMap::new();
+        let mut char_infos: Vec<Option<CharInfo>> = vec![None; 0x10000];
+        for (line_ord, line) in char_def.lines().enumerate() {
+            let content = line.split('#').next().unwrap_or_default().trim();
+            if content.is_empty() {
+                continue;
+            }
+            let invalid = || invalid_line(path, line_ord, line);
+            let columns: Vec<&str> = content.split_whitespace().collect();
+            if !content.starts_with("0x") {
+                // category definition: `NAME INVOKE GROUP LENGTH`.
+                let (name, invoke, group, length) = match columns[..] {
+                    [name, invoke, group, length] => (name, invoke, group, length),
+                    _ => return Err(invalid()),
+                };
+                if categories.len() == MAX_NUM_CATEGORIES {
+                    return Err(MySearchError::DataCorruption(format!(
+                        "{:?} defines more than {} character categories",
+                        path, MAX_NUM_CATEGORIES
+                    )));
+                }
+                category_ids.insert(name.to_string(), categories.len());
+                categories.push(CharCategory {
+                    invoke: invoke == "1",
+                    group: group == "1",
+                    length: length.parse().map_err(|_| invalid())?,
+                });
+                continue;
+            }
+            // mapping: `CODE_POINT[..CODE_POINT] CATEGORY [COMPATIBLE_CATEGORY ...]`.
+            if columns.len() < 2 {
+                return Err(invalid());
+            }
+            let mut range = columns[0].split("..");
+            let first = range
+                .next()
+                .and_then(parse_code_point)
+                .ok_or_else(invalid)?;
+            let last = match range.next() {
+                Some(last) => parse_code_point(last).ok_or_else(invalid)?,
+                None => first,
+            };
+            let mut char_info = CharInfo::default();
+            for (category_ord, name) in columns[1..].iter().enumerate() {
+                let category = *category_ids.get(*name).ok_or_else(invalid)?;
+                if category_ord == 0 {
+                    char_info.category = category;
+                }
+                char_info.compatible_categories |= 1 << category;
+            }
+            // later lines override the previous ones.
+            for code_point in first..=last.min(0xFFFF) {
+                char_infos[code_point as usize] = Some(char_info);
+            }
+        }
+        let default_category = *category_ids.get("DEFAULT").ok_or_else(|| {
+            MySearchError::DataCorruption(format!(
+                "{:?} does not define the DEFAULT category",
+                path
+            ))
+        })?;
+        let default_info = CharInfo {
+            category: default_category,
+            compatible_categories: 1 << default_category,
+        };
+        Ok(CharDefinition {
+            categories,
+            category_ids,
+            char_infos: char_infos
+                .into_iter()
+                .map(|char_info| char_info.unwrap_or(default_info))
+                .collect(),
+            default_info,
+        })
+    }
+
+    fn char_info(&self, c: char) -> CharInfo {
+        self.char_infos
+            .get(c as usize)
+            .copied()
+            .unwrap_or(self.default_info)
+    }
+}
+
+/// Connection costs of `matrix.def`, indexed by the right id of a word and
+/// the left id of the word following it.
+struct ConnectionMatrix {
+    num_right_ids: usize,
+    num_left_ids: usize,
+    costs: Vec<i16>,
+}
+
+impl ConnectionMatrix {
+    fn parse(path: &Path, matrix_def: &str) -> crate::Result<ConnectionMatrix> {
+        let mut lines = matrix_def.lines().enumerate();
+        let (num_right_ids, num_left_ids) = lines
+            .next()
+            .and_then(|(_, header)| {
+                let mut sizes = header.split_whitespace().map(str::parse::<usize>);
+                match (sizes.next(), sizes.next(), sizes.next()) {
+                    (Some(Ok(num_right_ids)), Some(Ok(num_left_ids)), None) => {
+                        Some((num_right_ids, num_left_ids))
+                    }
+                    _ => None,
+                }
+            })
+            .ok_or_else(|| invalid_line(path, 0, matrix_def.lines().next().unwrap_or("")))?;
+        let mut costs = vec![0i16; num_right_ids * num_left_ids];
+        for (line_ord, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let invalid = || invalid_line(path, line_ord, line);
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            let (right_id, left_id, cost) = match columns[..] {
+                [right_id, left_id, cost] => (
+                    right_id.parse::<usize>().map_err(|_| invalid())?,
+                    left_id.parse::<usize>().map_err(|_| invalid())?,
+                    cost.parse::<i16>().map_err(|_| invalid())?,
+                ),
+                _ => return Err(invalid()),
+            };
+            if right_id >= num_right_ids || left_id >= num_left_ids {
+                return Err(invalid());
+            }
+            costs[right_id * num_left_ids + left_id] = cost;
+        }
+        Ok(ConnectionMatrix {
+            num_right_ids,
+            num_left_ids,
+            costs,
+        })
+    }
+
+    fn cost(&self, right_id: u16, left_id: u16) -> i32 {
+        i32::from(self.costs[right_id as usize * self.num_left_ids + left_id as usize])
+    }
+
+    fn check_entry(&self, surface: &str, entry: &WordEntry) -> crate::Result<()> {
+        if entry.left_id as usize >= self.num_left_ids
+            || entry.right_id as usize >= self.num_right_ids
+        {
+            return Err(MySearchError::DataCorruption(format!(
+                "the context ids of '{}' are out of the bounds of the connection matrix",
+                surface
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Japanese dictionary in the format of MeCab's IPADIC.
+///
+/// It is made of the files of the IPADIC directory:
+/// - the lexicon, spread over `*.csv` files,
+/// - `matrix.def`, the costs of the connections between words,
+/// - `char.def`, the character categories,
+/// - `unk.def`, the entries of the words missing from the lexicon, by
+///   character category.
+///
+/// The files must be encoded in UTF-8: IPADIC is distributed in EUC-JP, and
+/// can be converted with `iconv`, or downloaded as `mecab-ipadic-utf8`.
+pub struct IpadicDictionary {
+    lexicon: HashMap<String, Vec<WordEntry>>,
+    /// Length in characters of the longest word of the lexicon.
+    max_word_len: usize,
+    matrix: ConnectionMatrix,
+    char_definition: CharDefinition,
+    /// Entries of the unknown words, indexed by character category.
+    unknown_entries: Vec<Vec<WordEntry>>,
+}
+
+impl IpadicDictionary {
+    /// Loads the dictionary stored in `directory`, e.g. a full IPADIC
+    /// installation to use instead of the embedded dictionary.
+    pub fn open<P: AsRef<Path>>(directory: P) -> crate::Result<IpadicDictionary> {
+        let directory = directory.as_ref();
+        let char_def_path = directory.join("char.def");
+        let matrix_path = directory.join("matrix.def");
+        let unk_def_path = directory.join("unk.def");
+        let char_def = read_file(&char_def_path)?;
+        let matrix_def = read_file(&matrix_path)?;
+        let unk_def = read_file(&unk_def_path)?;
+        let mut csv_paths: Vec<PathBuf> = fs::read_dir(directory)?
+            .map(|dir_entry| dir_entry.map(|dir_entry| dir_entry.path()))
+            .collect::<io::Result<_>>()?;
+        csv_paths.retain(|path| path.extension() == Some(OsStr::new("csv")));
+        csv_paths.sort();
+        if csv_paths.is_empty() {
+            return Err(MySearchError::DataCorruption(format!(
+                "no lexicon file found in {:?}",
+                directory
+            )));
+        }
+        let lexicons = csv_paths
+            .iter()
+            .map(|csv_path| read_file(csv_path))
+            .collect::<crate::Result<Vec<String>>>()?;
+        IpadicDictionary::parse(
+            (&char_def_path, &char_def),
+            (&matrix_path, &matrix_def),
+            (&unk_def_path, &unk_def),
+            csv_paths
+                .iter()
+                .map(PathBuf::as_path)
+                .zip(lexicons.iter().map(String::as_str)),
+        )
+    }
+
+    /// Builds a dictionary from the content of its files: the lexicon CSVs,
+    /// `matrix.def`, `char.def` and `unk.def`.
+    pub fn from_sources(
+        lexicons: &[&str],
+        matrix_def: &str,
+        char_def: &str,
+        unk_def: &str,
+    ) -> crate::Result<IpadicDictionary> {
+        let lexicon_paths: Vec<PathBuf> = (0..lexicons.len())
+            .map(|lexicon_ord| PathBuf::from(format!("lexicon{}.csv", lexicon_ord)))
+            .collect();
+        IpadicDictionary::parse(
+            (Path::new("char.def"), char_def),
+            (Path::new("matrix.def"), matrix_def),
+            (Path::new("unk.def"), unk_def),
+            lexicon_paths
+                .iter()
+                .map(PathBuf::as_path)
+                .zip(lexicons.iter().copied()),
+        )
+    }
+
+    /// Returns the trimmed dictionary embedded in the crate.
+    ///
+    /// It follows the format and the part of speech classes of IPADIC, but
+    /// only covers the grammatical words, the conjugations of the common verbs
+    /// and adjectives, and a basic vocabulary: the other words are guessed
+    /// from the character categories. `JapaneseTokenizer::default()` shares a
+    /// single instance of it.
+    pub fn embedded() -> IpadicDictionary {
+        IpadicDictionary::from_sources(
+            &[EMBEDDED_LEXICON],
+            EMBEDDED_MATRIX_DEF,
+            EMBEDDED_CHAR_DEF,
+            EMBEDDED_UNK_DEF,
+        )
+        .expect("the embedded dictionary is valid")
+    }
+
+    /// Parses the files of a dictionary, given as `(path, content)` pairs.
+    fn parse<'a>(
+        char_def: (&Path, &str),
+        matrix_def: (&Path, &str),
+        unk_def: (&Path, &str),
+        lexicons: impl Iterator<Item = (&'a Path, &'a str)>,
+    ) -> crate::Result<IpadicDictionary> {
+        let char_definition = CharDefinition::parse(char_def.0, char_def.1)?;
+        let matrix = ConnectionMatrix::parse(matrix_def.0, matrix_def.1)?;
+
+        let (unk_def_path, unk_def) = unk_def;
+        let mut unknown_entries = vec![Vec::new(); char_definition.categories.len()];
+        for (line_ord, line) in unk_def.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let invalid = || invalid_line(unk_def_path, line_ord, line);
+            let (category_name, entry) = parse_entry(line).ok_or_else(invalid)?;
+            let category = *char_definition
+                .category_ids
+                .get(&category_name)
+                .ok_or_else(invalid)?;
+            matrix.check_entry(&category_name, &entry)?;
+            unknown_entries[category].push(entry);
+        }
+        for (category_name, &category) in &char_definition.category_ids {
+            if unknown_entries[category].is_empty() {
+                return Err(MySearchError::DataCorruption(format!(
+                    "{:?} has no entry for the character category {}",
+                    unk_def_path, category_name
+                )));
+            }
+        }
+
+        let mut lexicon: HashMap<String, Vec<WordEntry>> = HashMap::new();
+        let mut max_word_len = 0;
+        for (csv_path, csv) in lexicons {
+            for (line_ord, line) in csv.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let (surface, entry) =
+                    parse_entry(line).ok_or_else(|| invalid_line(csv_path, line_ord, line))?;
+                matrix.check_entry(&surface, &entry)?;
+                max_word_len = max_word_len.max(surface.chars().count());
+                lexicon.entry(surface).or_default().push(entry);
+            }
+        }
+        Ok(IpadicDictionary {
+            lexicon,
+            max_word_len,
+            matrix,
+            char_definition,
+            unknown_entries,
+        })
+    }
+
+    /// Returns the entries of the lexicon whose surface is `surface`.
+    pub(crate) fn lookup(&self, surface: &str) -> &[WordEntry] {
+        self.lexicon
+            .get(surface)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Number of distinct surfaces of the lexicon.
+    pub fn num_words(&self) -> usize {
+        self.lexicon.len()
+    }
+
+    pub(crate) fn max_word_len(&self) -> usize {
+        self.max_word_len
+    }
+
+    /// Cost of the word with left id `left_id` following the word with right
+    /// id `right_id`, id 0 standing for the beginning and the end of the text.
+    pub(crate) fn connection_cost(&self, right_id: u16, left_id: u16) -> i32 {
+        self.matrix.cost(right_id, left_id)
+    }
+
+    pub(crate) fn char_info(&self, c: char) -> CharInfo {
+        self.char_definition.char_info(c)
+    }
+
+    pub(crate) fn char_category(&self, category: usize) -> &CharCategory {
+        &self.char_definition.categories[category]
+    }
+
+    /// Returns the entries of the unknown words of `category`.
+    pub(crate) fn unknown_entries(&self, category: usize) -> &[WordEntry] {
+        &self.unknown_entries[category]
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{parse_entry, WordEntry};
+    use crate::{IpadicDictionary, MySearchError};
+    use std::fs;
+    use std::path::Path;
+
+    /// Directory of the small dictionary used by the tests.
+    pub(crate) const FIXTURE_DIRECTORY: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/tokenizer/test_data/ipadic"
+    );
+
+    #[test]
+    fn test_parse_entry() {
+        let entry = |left_id, right_id, cost| WordEntry {
+            left_id,
+            right_id,
+            cost,
+        };
+        assert_eq!(
+            parse_entry(
+                "東京,1293,1293,3003,名詞,固有名詞,地域,一般,*,*,東京,トウキョウ,トーキョー"
+            ),
+            Some(("東京".to_string(), entry(1293, 1293, 3003)))
+        );
+        assert_eq!(
+            parse_entry("\"a,\"\"b\",5,6,-7,記号,一般"),
+            Some(("a,\"b".to_string(), entry(5, 6, -7)))
+        );
+        assert_eq!(parse_entry("東京,1293,1293"), None);
+        assert_eq!(parse_entry(",1,1,1"), None);
+        assert_eq!(parse_entry("東京,a,1,1"), None);
+    }
+
+    #[test]
+    fn test_open_fixture() -> crate::Result<()> {
+        let dictionary = IpadicDictionary::open(FIXTURE_DIRECTORY)?;
+        assert!(dictionary.num_words() > 100);
+        assert_eq!(dictionary.max_word_len(), 4);
+        assert_eq!(dictionary.lookup("東京").len(), 1);
+        assert!(dictionary.lookup("東京タワー").is_empty());
+        // `都` is both a noun and a suffix.
+        assert_eq!(dictionary.lookup("都").len(), 2);
+        let katakana = dictionary.char_info('カ');
+        assert!(dictionary.char_category(katakana.category).group);
+        assert!(dictionary
+            .char_info('ー')
+            .is_compatible_with(katakana.category));
+        assert!(!dictionary
+            .char_info('東')
+            .is_compatible_with(katakana.category));
+        assert!(!dictionary.unknown_entries(katakana.category).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_embedded_dictionary() {
+        let dictionary = IpadicDictionary::embedded();
+        assert!(dictionary.num_words() > 1000);
+        // the conjugated forms of the verbs are in the lexicon.
+        for surface in &["食べる", "食べ", "読む", "読み", "読ん", "読ま"] {
+            assert!(!dictionary.lookup(surface).is_empty());
+        }
+        assert!(IpadicDictionary::from_sources(&["東京,1,1,abc,名詞"], "2 2\n", "", "").is_err());
+    }
+
+    #[test]
+    fn test_open_invalid_dictionary() -> crate::Result<()> {
+        let tempdir = tempfile::TempDir::new()?;
+        assert!(matches!(
+            IpadicDictionary::open(tempdir.path()),
+            Err(MySearchError::IoError(_))
+        ));
+        for file_name in &["char.def", "matrix.def", "unk.def", "lexicon.csv"] {
+            fs::copy(
+                Path::new(FIXTURE_DIRECTORY).join(file_name),
+                tempdir.path().join(file_name),
+            )?;
+        }
+        IpadicDictionary::open(tempdir.path())?;
+
+        // context ids out of the bounds of the matrix.
+        fs::write(tempdir.path().join("extra.csv"), "語彙,11,11,100,名詞\n")?;
+        assert!(matches!(
+            IpadicDictionary::open(tempdir.path()),
+            Err(MySearchError::DataCorruption(_))
+        ));
+        // EUC-JP encoded lexicon.
+        fs::write(tempdir.path().join("extra.csv"), b"\xb8\xec,1,1,100\n")?;
+        assert!(matches!(
+            IpadicDictionary::open(tempdir.path()),
+            Err(MySearchError::DataCorruption(_))
+        ));
+        Ok(())
+    }
+}
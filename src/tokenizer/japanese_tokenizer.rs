@@ -0,0 +1,385 @@
+use std::sync::Arc;
+use std::vec;
+
+use once_cell::sync::Lazy;
+
+use crate::tokenizer::ipadic::IpadicDictionary;
+use crate::{BoxTokenStream, Token, TokenStream, Tokenizer};
+
+static EMBEDDED_DICTIONARY: Lazy<Arc<IpadicDictionary>> =
+    Lazy::new(|| Arc::new(IpadicDictionary::embedded()));
+
+/// Node of the lattice: a candidate word spanning the characters
+/// `start..end`.
+struct Node {
+    start: usize,
+    end: usize,
+    left_id: u16,
+    right_id: u16,
+    cost: i32,
+}
+
+/// Builds the lattice of the candidate words of `chars`, indexed by the
+/// position of their end.
+///
+/// Unknown words are added following the categories of `char.def`, as MeCab
+/// does: a word is looked for when no word of the lexicon starts with the
+/// character, or when its category is invoked unconditionally.
+fn build_lattice(
+    dictionary: &IpadicDictionary,
+    chars: &[char],
+    text: &str,
+    char_offsets: &[usize],
+) -> Vec<Vec<Node>> {
+    let num_chars = chars.len();
+    let mut nodes_by_end: Vec<Vec<Node>> = (0..=num_chars).map(|_| Vec::new()).collect();
+    for start in 0..num_chars {
+        let mut has_dictionary_word = false;
+        for len in 1..=dictionary.max_word_len().min(num_chars - start) {
+            let surface = &text[char_offsets[start]..char_offsets[start + len]];
+            for entry in dictionary.lookup(surface) {
+                has_dictionary_word = true;
+                nodes_by_end[start + len].push(Node {
+                    start,
+                    end: start + len,
+                    left_id: entry.left_id,
+                    right_id: entry.right_id,
+                    cost: i32::from(entry.cost),
+                });
+            }
+        }
+        let category_id = dictionary.char_info(chars[start]).category;
+        let category = dictionary.char_category(category_id);
+        if has_dictionary_word && !category.invoke {
+            continue;
+        }
+        let run_len = chars[start..]
+            .iter()
+            .take_while(|&&c| dictionary.char_info(c).is_compatible_with(category_id))
+            .count();
+        let mut unknown_lens = Vec::new();
+        if category.group {
+            unknown_lens.push(run_len);
+        }
+        unknown_lens.extend(1..=category.length.min(run_len));
+        // the lattice must stay connected.
+        if unknown_lens.is_empty() && !has_dictionary_word {
+            unknown_lens.push(1);
+        }
+        unknown_lens.sort_unstable();
+        unknown_lens.dedup();
+        for len in unknown_lens {
+            for entry in dictionary.unknown_entries(category_id) {
+                nodes_by_end[start + len].push(Node {
+                    start,
+                    end: start + len,
+                    left_id: entry.left_id,
+                    right_id: entry.right_id,
+                    cost: i32::from(entry.cost),
+                });
+            }
+        }
+    }
+    nodes_by_end
+}
+
+/// Returns the character ranges of the words of `chars`, found by building
+/// the lattice of all the candidate words and picking its cheapest path with
+/// the Viterbi algorithm.
+fn segment(
+    dictionary: &IpadicDictionary,
+    chars: &[char],
+    text: &str,
+    char_offsets: &[usize],
+) -> Vec<(usize, usize)> {
+    let num_chars = chars.len();
+    let nodes_by_end = build_lattice(dictionary, chars, text, char_offsets);
+    // best[end][i] is the cost of the cheapest path ending with the i-th node
+    // ending at `end`, along with the index of the node before it. The
+    // beginning and the end of the text have the context id 0.
+    let mut best: Vec<Vec<(i32, Option<usize>)>> = Vec::with_capacity(num_chars + 1);
+    best.push(Vec::new());
+    for end in 1..=num_chars {
+        let mut best_at_end = Vec::with_capacity(nodes_by_end[end].len());
+        for node in &nodes_by_end[end] {
+            let candidate = if node.start == 0 {
+                Some((dictionary.connection_cost(0, node.left_id), None))
+            } else {
+                nodes_by_end[node.start]
+                    .iter()
+                    .zip(&best[node.start])
+                    .enumerate()
+                    .map(|(prev_idx, (prev, &(prev_cost, _)))| {
+                        let cost = prev_cost.saturating_add(
+                            dictionary.connection_cost(prev.right_id, node.left_id),
+                        );
+                        (cost, Some(prev_idx))
+                    })
+                    .min_by_key(|&(cost, _)| cost)
+            };
+            best_at_end.push(match candidate {
+                Some((cost, prev_idx)) => (cost.saturating_add(node.cost), prev_idx),
+                // no path reaches the start of the node.
+                None => (i32::MAX, None),
+            });
+        }
+        best.push(best_at_end);
+    }
+
+    let last_idx = nodes_by_end[num_chars]
+        .iter()
+        .zip(&best[num_chars])
+        .enumerate()
+        .min_by_key(|(_, (node, &(cost, _)))| {
+            cost.saturating_add(dictionary.connection_cost(node.right_id, 0))
+        })
+        .map(|(idx, _)| idx);
+    let mut words = Vec::new();
+    let mut cursor = last_idx.map(|idx| (num_chars, idx));
+    while let Some((end, idx)) = cursor {
+        let node = &nodes_by_end[end][idx];
+        words.push((node.start, node.end));
+        cursor = best[end][idx].1.map(|prev_idx| (node.start, prev_idx));
+    }
+    words.reverse();
+    words
+}
+
+/// Morphological tokenizer for Japanese, driven by an `IpadicDictionary`.
+///
+/// Text is first split on non-alphanumeric characters. Each chunk is then
+/// segmented into words by building the lattice of the words of the lexicon
+/// it contains, and by picking the path minimizing the word and connection
+/// costs with the Viterbi algorithm. Words missing from the lexicon are
+/// guessed from the character categories: a run of katakana or of latin
+/// letters makes up a single word, for instance.
+///
+/// `JapaneseTokenizer::default()` relies on the trimmed dictionary embedded in
+/// the crate, and is registered as `ja` in the default `TokenizerManager`. A
+/// full IPADIC recognizes many more words:
+///
+/// ```no_run
+/// use mysearch::{IpadicDictionary, JapaneseTokenizer, LowerCaser, TextAnalyzer, TokenizerManager};
+///
+/// let dictionary = IpadicDictionary::open("/usr/share/mecab/dic/ipadic")?;
+/// let analyzer = TextAnalyzer::from(JapaneseTokenizer::new(dictionary)).filter(LowerCaser);
+/// TokenizerManager::default().register("ja", analyzer);
+/// # Ok::<(), mysearch::MySearchError>(())
+/// ```
+#[derive(Clone)]
+pub struct JapaneseTokenizer {
+    dictionary: Arc<IpadicDictionary>,
+}
+
+impl JapaneseTokenizer {
+    pub fn new(dictionary: IpadicDictionary) -> JapaneseTokenizer {
+        JapaneseTokenizer {
+            dictionary: Arc::new(dictionary),
+        }
+    }
+
+    /// Computes the byte ranges of the words of `text`.
+    fn word_offsets(&self, text: &str) -> Vec<(usize, usize)> {
+        let mut token_offsets = Vec::new();
+        let mut chunk_start: Option<usize> = None;
+        for (offset, c) in text.char_indices().chain(Some((text.len(), ' '))) {
+            if c.is_alphanumeric() {
+                chunk_start.get_or_insert(offset);
+                continue;
+            }
+            if let Some(start) = chunk_start.take() {
+                let chunk = &text[start..offset];
+                let chars: Vec<char> = chunk.chars().collect();
+                let mut char_offsets: Vec<usize> =
+                    chunk.char_indices().map(|(offset, _)| offset).collect();
+                char_offsets.push(chunk.len());
+                for (start_char, end_char) in
+                    segment(&self.dictionary, &chars, chunk, &char_offsets)
+                {
+                    token_offsets.push((
+                        start + char_offsets[start_char],
+                        start + char_offsets[end_char],
+                    ));
+                }
+            }
+        }
+        token_offsets
+    }
+}
+
+impl Default for JapaneseTokenizer {
+    fn default() -> JapaneseTokenizer {
+        JapaneseTokenizer {
+            dictionary: EMBEDDED_DICTIONARY.clone(),
+        }
+    }
+}
+
+impl Tokenizer for JapaneseTokenizer {
+    fn token_stream<'a>(&self, text: &'a str) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(JapaneseTokenStream {
+            text,
+            token_offsets: self.word_offsets(text).into_iter(),
+            token: Token::default(),
+        })
+    }
+}
+
+pub struct JapaneseTokenStream<'a> {
+    text: &'a str,
+    token_offsets: vec::IntoIter<(usize, usize)>,
+    token: Token,
+}
+
+impl<'a> TokenStream for JapaneseTokenStream<'a> {
+    fn advance(&mut self) -> bool {
+        let (offset_from, offset_to) = match self.token_offsets.next() {
+            Some(offsets) => offsets,
+            None => return false,
+        };
+        self.token.position = self.token.position.wrapping_add(1);
+        self.token.offset_from = offset_from;
+        self.token.offset_to = offset_to;
+        self.token.text.clear();
+        self.token.text.push_str(&self.text[offset_from..offset_to]);
+        true
+    }
+
+    fn token(&self) -> &Token {
+        &self.token
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.token
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenizer::ipadic::tests::FIXTURE_DIRECTORY;
+    use crate::{IpadicDictionary, JapaneseTokenizer, Tokenizer};
+
+    fn tokenizer() -> JapaneseTokenizer {
+        JapaneseTokenizer::new(IpadicDictionary::open(FIXTURE_DIRECTORY).unwrap())
+    }
+
+    fn words(text: &str) -> Vec<String> {
+        let mut token_stream = tokenizer().token_stream(text);
+        let mut words = Vec::new();
+        token_stream.process(&mut |token| {
+            assert_eq!(&text[token.offset_from..token.offset_to], token.text);
+            words.push(token.text.clone())
+        });
+        words
+    }
+
+    #[test]
+    fn test_segmentation() {
+        assert_eq!(
+            words("すもももももももものうち"),
+            vec!["すもも", "も", "もも", "も", "もも", "の", "うち"]
+        );
+        assert_eq!(
+            words("東京都に住んでいます"),
+            vec!["東京", "都", "に", "住ん", "で", "い", "ます"]
+        );
+        assert_eq!(
+            words("私は東京大学の学生です。"),
+            vec!["私", "は", "東京", "大学", "の", "学生", "です"]
+        );
+        assert_eq!(
+            words("今日はいい天気ですね"),
+            vec!["今日", "は", "いい", "天気", "です", "ね"]
+        );
+    }
+
+    #[test]
+    fn test_realistic_sentences() {
+        assert_eq!(
+            words("私は大学で自然言語処理を研究しています。"),
+            vec![
+                "私", "は", "大学", "で", "自然", "言語", "処理", "を", "研究", "し", "て", "い",
+                "ます"
+            ]
+        );
+        assert_eq!(
+            words("東京駅から電車で京都へ行きました"),
+            vec![
+                "東京", "駅", "から", "電車", "で", "京都", "へ", "行き", "まし", "た"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_embedded_dictionary() {
+        let words = |text: &str| -> Vec<String> {
+            let mut token_stream = JapaneseTokenizer::default().token_stream(text);
+            let mut words = Vec::new();
+            token_stream.process(&mut |token| words.push(token.text.clone()));
+            words
+        };
+        assert_eq!(
+            words("昨日は友達と新宿で映画を見ました。"),
+            vec![
+                "昨日", "は", "友達", "と", "新宿", "で", "映画", "を", "見", "まし", "た"
+            ]
+        );
+        assert_eq!(
+            words("全文検索エンジンで日本語の文書を検索する"),
+            vec![
+                "全文",
+                "検索",
+                "エンジン",
+                "で",
+                "日本語",
+                "の",
+                "文書",
+                "を",
+                "検索",
+                "する"
+            ]
+        );
+        assert_eq!(
+            words("明日は雨が降るでしょう"),
+            vec!["明日", "は", "雨", "が", "降る", "でしょ", "う"]
+        );
+        assert_eq!(
+            words("静かな部屋で本を読んでいた"),
+            vec!["静か", "な", "部屋", "で", "本", "を", "読ん", "で", "い", "た"]
+        );
+    }
+
+    #[test]
+    fn test_unknown_words() {
+        assert_eq!(
+            words("スカイツリーに行きました"),
+            vec!["スカイツリー", "に", "行き", "まし", "た"]
+        );
+        assert_eq!(
+            words("Rustで検索エンジンを作る"),
+            vec!["Rust", "で", "検索", "エンジン", "を", "作る"]
+        );
+    }
+
+    #[test]
+    fn test_offsets_and_positions() {
+        let mut token_stream = tokenizer().token_stream("日本語、 2021年");
+        let mut tokens = Vec::new();
+        token_stream.process(&mut |token| {
+            tokens.push((
+                token.text.clone(),
+                token.offset_from,
+                token.offset_to,
+                token.position,
+            ))
+        });
+        assert_eq!(
+            tokens,
+            vec![
+                ("日本語".to_string(), 0, 9, 0),
+                ("2021".to_string(), 13, 17, 1),
+                ("年".to_string(), 17, 20, 2),
+            ]
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use std::mem;
+
+use crate::{BoxTokenStream, Token, TokenFilter, TokenStream};
+
+/// Lower-cases the text of every token, using the Unicode case mapping.
+#[derive(Clone)]
+pub struct LowerCaser;
+
+impl TokenFilter for LowerCaser {
+    fn transform<'a>(&self, token_stream: BoxTokenStream<'a>) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(LowerCaserTokenStream {
+            tail: token_stream,
+            buffer: String::with_capacity(100),
+        })
+    }
+}
+
+pub struct LowerCaserTokenStream<'a> {
+    tail: BoxTokenStream<'a>,
+    buffer: String,
+}
+
+/// Writes the lower-cased `text` into `output`, replacing its content.
+fn to_lowercase_unicode(text: &str, output: &mut String) {
+    output.clear();
+    for c in text.chars() {
+        // `char::to_lowercase` misses the final sigma rule, which only
+        // `str::to_lowercase` implements, but is allocation free.
+        output.extend(c.to_lowercase());
+    }
+}
+
+impl<'a> TokenStream for LowerCaserTokenStream<'a> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+        let token = self.tail.token_mut();
+        if token.text.is_ascii() {
+            // fast path, done in place.
+            token.text.make_ascii_lowercase();
+        } else {
+            to_lowercase_unicode(&token.text, &mut self.buffer);
+            mem::swap(&mut token.text, &mut self.buffer);
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{LowerCaser, SimpleTokenizer, TextAnalyzer};
+
+    fn lowercase_tokens(text: &str) -> Vec<String> {
+        let analyzer = TextAnalyzer::from(SimpleTokenizer).filter(LowerCaser);
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = Vec::new();
+        token_stream.process(&mut |token| tokens.push(token.text.clone()));
+        tokens
+    }
+
+    #[test]
+    fn test_lower_caser() {
+        assert_eq!(
+            lowercase_tokens("THE Quick bRoWn"),
+            vec!["the", "quick", "brown"]
+        );
+        assert_eq!(
+            lowercase_tokens("ÉCOLE Straße ΑΘΗΝΑ"),
+            vec!["école", "straße", "αθηνα"]
+        );
+    }
+}
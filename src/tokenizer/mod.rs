@@ -1,7 +1,29 @@
+mod ascii_folding_filter;
+mod cjk_bigram_tokenizer;
+mod ipadic;
+mod japanese_tokenizer;
+mod lower_caser;
+mod ngram_tokenizer;
+mod raw_tokenizer;
+mod remove_long;
 mod simple_tokenizer;
+mod stemmer;
+mod stop_word_filter;
+mod synonym_filter;
 mod tokenizer;
 mod tokenizer_manager;
 
+pub use ascii_folding_filter::*;
+pub use cjk_bigram_tokenizer::*;
+pub use ipadic::IpadicDictionary;
+pub use japanese_tokenizer::*;
+pub use lower_caser::*;
+pub use ngram_tokenizer::*;
+pub use raw_tokenizer::*;
+pub use remove_long::*;
 pub use simple_tokenizer::*;
+pub use stemmer::*;
+pub use stop_word_filter::*;
+pub use synonym_filter::*;
 pub use tokenizer::*;
 pub use tokenizer_manager::*;
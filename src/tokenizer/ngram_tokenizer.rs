@@ -0,0 +1,185 @@
+use crate::{BoxTokenStream, Token, TokenStream, Tokenizer};
+
+/// Splits the text into all its substrings of `min_gram` to `max_gram`
+/// characters, or only into its prefixes in edge mode.
+///
+/// This lets a search for a partial word match: with `min_gram = 2` and
+/// `max_gram = 3`, "hello" is indexed as "he", "hel", "el", "ell", "ll",
+/// "llo" and "lo", and in edge mode as "he" and "hel" only.
+///
+/// N-grams are cut on character boundaries, so their offsets are always valid
+/// byte offsets in the text. The n-grams starting at the `i`-th character all
+/// get the position `i`.
+#[derive(Clone, Debug)]
+pub struct NgramTokenizer {
+    min_gram: usize,
+    max_gram: usize,
+    prefix_only: bool,
+}
+
+impl NgramTokenizer {
+    /// Creates a tokenizer emitting the n-grams of `min_gram` to `max_gram`
+    /// characters, only the ones starting at the beginning of the text if
+    /// `prefix_only` is set.
+    ///
+    /// # Panics
+    ///
+    /// If `min_gram` is 0 or greater than `max_gram`.
+    pub fn new(min_gram: usize, max_gram: usize, prefix_only: bool) -> NgramTokenizer {
+        assert!(min_gram > 0, "min_gram must be greater than 0");
+        assert!(
+            min_gram <= max_gram,
+            "min_gram ({}) must not be greater than max_gram ({})",
+            min_gram,
+            max_gram
+        );
+        NgramTokenizer {
+            min_gram,
+            max_gram,
+            prefix_only,
+        }
+    }
+
+    /// Creates a tokenizer emitting all the n-grams of the text.
+    pub fn all_ngrams(min_gram: usize, max_gram: usize) -> NgramTokenizer {
+        NgramTokenizer::new(min_gram, max_gram, false)
+    }
+
+    /// Creates a tokenizer emitting the prefixes of the text only, also known
+    /// as edge n-grams.
+    pub fn prefix_only(min_gram: usize, max_gram: usize) -> NgramTokenizer {
+        NgramTokenizer::new(min_gram, max_gram, true)
+    }
+}
+
+impl Tokenizer for NgramTokenizer {
+    fn token_stream<'a>(&self, text: &'a str) -> BoxTokenStream<'a> {
+        let mut char_offsets: Vec<usize> = text.char_indices().map(|(offset, _)| offset).collect();
+        char_offsets.push(text.len());
+        BoxTokenStream::from(NgramTokenStream {
+            text,
+            char_offsets,
+            min_gram: self.min_gram,
+            max_gram: self.max_gram,
+            prefix_only: self.prefix_only,
+            start_char: 0,
+            gram_len: self.min_gram,
+            token: Token::default(),
+        })
+    }
+}
+
+pub struct NgramTokenStream<'a> {
+    text: &'a str,
+    /// Byte offset of every character, followed by the length of the text.
+    char_offsets: Vec<usize>,
+    min_gram: usize,
+    max_gram: usize,
+    prefix_only: bool,
+    /// Index of the first character of the next n-gram.
+    start_char: usize,
+    /// Number of characters of the next n-gram.
+    gram_len: usize,
+    token: Token,
+}
+
+impl<'a> NgramTokenStream<'a> {
+    fn num_chars(&self) -> usize {
+        self.char_offsets.len() - 1
+    }
+}
+
+impl<'a> TokenStream for NgramTokenStream<'a> {
+    fn advance(&mut self) -> bool {
+        loop {
+            let end_char = self.start_char + self.gram_len;
+            if self.gram_len <= self.max_gram && end_char <= self.num_chars() {
+                let offset_from = self.char_offsets[self.start_char];
+                let offset_to = self.char_offsets[end_char];
+                self.token.position = self.start_char;
+                self.token.offset_from = offset_from;
+                self.token.offset_to = offset_to;
+                self.token.text.clear();
+                self.token.text.push_str(&self.text[offset_from..offset_to]);
+                self.gram_len += 1;
+                return true;
+            }
+            // no longer n-gram starts at this character, move to the next one.
+            if self.prefix_only || self.start_char + self.min_gram >= self.num_chars() {
+                return false;
+            }
+            self.start_char += 1;
+            self.gram_len = self.min_gram;
+        }
+    }
+
+    fn token(&self) -> &Token {
+        &self.token
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.token
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{NgramTokenizer, Token, Tokenizer};
+
+    fn ngrams(tokenizer: &NgramTokenizer, text: &str) -> Vec<Token> {
+        let mut token_stream = tokenizer.token_stream(text);
+        let mut tokens = Vec::new();
+        token_stream.process(&mut |token| tokens.push(token.clone()));
+        tokens
+    }
+
+    fn texts(tokens: &[Token]) -> Vec<&str> {
+        tokens.iter().map(|token| token.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_all_ngrams() {
+        let tokens = ngrams(&NgramTokenizer::all_ngrams(2, 3), "hello");
+        assert_eq!(
+            texts(&tokens),
+            vec!["he", "hel", "el", "ell", "ll", "llo", "lo"]
+        );
+        let positions: Vec<usize> = tokens.iter().map(|token| token.position).collect();
+        assert_eq!(positions, vec![0, 0, 1, 1, 2, 2, 3]);
+        assert_eq!((tokens[3].offset_from, tokens[3].offset_to), (1, 4));
+    }
+
+    #[test]
+    fn test_prefix_only() {
+        let tokens = ngrams(&NgramTokenizer::prefix_only(1, 3), "hello");
+        assert_eq!(texts(&tokens), vec!["h", "he", "hel"]);
+        assert!(tokens.iter().all(|token| token.position == 0));
+    }
+
+    #[test]
+    fn test_ngrams_on_multibyte_chars() {
+        let text = "café東京";
+        let tokens = ngrams(&NgramTokenizer::all_ngrams(2, 2), text);
+        assert_eq!(texts(&tokens), vec!["ca", "af", "fé", "é東", "東京"]);
+        for token in &tokens {
+            assert_eq!(&text[token.offset_from..token.offset_to], token.text);
+        }
+        assert_eq!((tokens[3].offset_from, tokens[3].offset_to), (3, 8));
+    }
+
+    #[test]
+    fn test_text_shorter_than_min_gram() {
+        assert!(ngrams(&NgramTokenizer::all_ngrams(3, 5), "hi").is_empty());
+        assert!(ngrams(&NgramTokenizer::prefix_only(3, 5), "").is_empty());
+        assert_eq!(
+            texts(&ngrams(&NgramTokenizer::all_ngrams(2, 5), "abc")),
+            vec!["ab", "abc", "bc"]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_gram_range() {
+        NgramTokenizer::all_ngrams(3, 2);
+    }
+}
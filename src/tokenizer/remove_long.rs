@@ -0,0 +1,72 @@
+use crate::{BoxTokenStream, Token, TokenFilter, TokenStream};
+
+/// Removes the tokens whose text is longer than a given number of bytes.
+///
+/// Very long tokens are seldom useful for search and would bloat the term
+/// dictionary.
+#[derive(Clone)]
+pub struct RemoveLongFilter {
+    length_limit: usize,
+}
+
+impl RemoveLongFilter {
+    /// Keeps only the tokens of at most `length_limit` bytes.
+    pub fn limit(length_limit: usize) -> RemoveLongFilter {
+        RemoveLongFilter { length_limit }
+    }
+}
+
+impl TokenFilter for RemoveLongFilter {
+    fn transform<'a>(&self, token_stream: BoxTokenStream<'a>) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(RemoveLongFilterStream {
+            token_length_limit: self.length_limit,
+            tail: token_stream,
+        })
+    }
+}
+
+pub struct RemoveLongFilterStream<'a> {
+    token_length_limit: usize,
+    tail: BoxTokenStream<'a>,
+}
+
+impl<'a> TokenStream for RemoveLongFilterStream<'a> {
+    fn advance(&mut self) -> bool {
+        while self.tail.advance() {
+            if self.tail.token().text.len() <= self.token_length_limit {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RemoveLongFilter, SimpleTokenizer, TextAnalyzer};
+
+    #[test]
+    fn test_remove_long() {
+        let analyzer = TextAnalyzer::from(SimpleTokenizer).filter(RemoveLongFilter::limit(6));
+        let mut token_stream = analyzer.token_stream("short loooooong sixsix seven77 tiny élève");
+        let mut tokens = Vec::new();
+        token_stream.process(&mut |token| tokens.push((token.text.clone(), token.position)));
+        // "élève" is 5 chars but 7 bytes.
+        assert_eq!(
+            tokens,
+            vec![
+                ("short".to_string(), 0),
+                ("sixsix".to_string(), 2),
+                ("tiny".to_string(), 4)
+            ]
+        );
+    }
+}
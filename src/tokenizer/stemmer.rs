@@ -0,0 +1,165 @@
+use std::borrow::Cow;
+
+use rust_stemmers::{self, Algorithm};
+use serde::{Deserialize, Serialize};
+
+use crate::{BoxTokenStream, Token, TokenFilter, TokenStream};
+
+/// Languages supported by the `Stemmer`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Language {
+    Arabic,
+    Danish,
+    Dutch,
+    English,
+    Finnish,
+    French,
+    German,
+    Greek,
+    Hungarian,
+    Italian,
+    Norwegian,
+    Portuguese,
+    Romanian,
+    Russian,
+    Spanish,
+    Swedish,
+    Tamil,
+    Turkish,
+}
+
+impl Language {
+    fn algorithm(self) -> Algorithm {
+        match self {
+            Language::Arabic => Algorithm::Arabic,
+            Language::Danish => Algorithm::Danish,
+            Language::Dutch => Algorithm::Dutch,
+            Language::English => Algorithm::English,
+            Language::Finnish => Algorithm::Finnish,
+            Language::French => Algorithm::French,
+            Language::German => Algorithm::German,
+            Language::Greek => Algorithm::Greek,
+            Language::Hungarian => Algorithm::Hungarian,
+            Language::Italian => Algorithm::Italian,
+            Language::Norwegian => Algorithm::Norwegian,
+            Language::Portuguese => Algorithm::Portuguese,
+            Language::Romanian => Algorithm::Romanian,
+            Language::Russian => Algorithm::Russian,
+            Language::Spanish => Algorithm::Spanish,
+            Language::Swedish => Algorithm::Swedish,
+            Language::Tamil => Algorithm::Tamil,
+            Language::Turkish => Algorithm::Turkish,
+        }
+    }
+}
+
+/// Reduces every token to its stem with the Snowball algorithm of a language,
+/// so that "running" and "runs" both become "run".
+///
+/// Snowball stemmers expect lower-cased input, so this filter usually comes
+/// after a `LowerCaser`.
+#[derive(Clone)]
+pub struct Stemmer {
+    language: Language,
+}
+
+impl Stemmer {
+    pub fn new(language: Language) -> Stemmer {
+        Stemmer { language }
+    }
+}
+
+impl Default for Stemmer {
+    /// Creates an English stemmer.
+    fn default() -> Stemmer {
+        Stemmer::new(Language::English)
+    }
+}
+
+impl TokenFilter for Stemmer {
+    fn transform<'a>(&self, token_stream: BoxTokenStream<'a>) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(StemmerTokenStream {
+            tail: token_stream,
+            stemmer: rust_stemmers::Stemmer::create(self.language.algorithm()),
+        })
+    }
+}
+
+pub struct StemmerTokenStream<'a> {
+    tail: BoxTokenStream<'a>,
+    stemmer: rust_stemmers::Stemmer,
+}
+
+impl<'a> TokenStream for StemmerTokenStream<'a> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+        let token = self.tail.token_mut();
+        // a borrowed result means the token is its own stem.
+        if let Cow::Owned(stemmed) = self.stemmer.stem(&token.text) {
+            token.text.clear();
+            token.text.push_str(&stemmed);
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Language, LowerCaser, SimpleTokenizer, Stemmer, TextAnalyzer};
+
+    fn stemmed_tokens(language: Language, text: &str) -> Vec<(String, usize, usize)> {
+        let analyzer = TextAnalyzer::from(SimpleTokenizer)
+            .filter(LowerCaser)
+            .filter(Stemmer::new(language));
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = Vec::new();
+        token_stream.process(&mut |token| {
+            tokens.push((token.text.clone(), token.offset_from, token.offset_to))
+        });
+        tokens
+    }
+
+    #[test]
+    fn test_english_stemmer() {
+        assert_eq!(
+            stemmed_tokens(Language::English, "Running dogs ran"),
+            vec![
+                ("run".to_string(), 0, 7),
+                ("dog".to_string(), 8, 12),
+                ("ran".to_string(), 13, 16),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_other_languages() {
+        let stems = |language, text| -> Vec<String> {
+            stemmed_tokens(language, text)
+                .into_iter()
+                .map(|(text, _, _)| text)
+                .collect()
+        };
+        assert_eq!(
+            stems(Language::French, "chevaux continuellement"),
+            vec!["cheval", "continuel"]
+        );
+        assert_eq!(
+            stems(Language::German, "Häuser Katzen"),
+            vec!["haus", "katz"]
+        );
+        assert_eq!(
+            stems(Language::Spanish, "corriendo niñas"),
+            vec!["corr", "niñ"]
+        );
+    }
+}
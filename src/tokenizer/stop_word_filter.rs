@@ -0,0 +1,369 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::{BoxTokenStream, Token, TokenFilter, TokenStream};
+
+/// Removes the tokens belonging to a list of stop words.
+///
+/// The comparison is exact, so the filter usually comes after a `LowerCaser`.
+/// The positions of the remaining tokens are left untouched, so that phrase
+/// queries still account for the removed words.
+#[derive(Clone)]
+pub struct StopWordFilter {
+    words: Arc<HashSet<String>>,
+}
+
+impl StopWordFilter {
+    /// Creates a filter removing `words`.
+    pub fn remove<W, S>(words: W) -> StopWordFilter
+    where
+        W: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        StopWordFilter {
+            words: Arc::new(words.into_iter().map(Into::into).collect()),
+        }
+    }
+
+    /// Removes the most common English words.
+    pub fn english() -> StopWordFilter {
+        StopWordFilter::remove(ENGLISH_STOP_WORDS.iter().copied())
+    }
+
+    /// Removes the most common French words.
+    pub fn french() -> StopWordFilter {
+        StopWordFilter::remove(FRENCH_STOP_WORDS.iter().copied())
+    }
+
+    /// Removes the most common German words.
+    pub fn german() -> StopWordFilter {
+        StopWordFilter::remove(GERMAN_STOP_WORDS.iter().copied())
+    }
+}
+
+impl TokenFilter for StopWordFilter {
+    fn transform<'a>(&self, token_stream: BoxTokenStream<'a>) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(StopWordFilterStream {
+            words: self.words.clone(),
+            tail: token_stream,
+        })
+    }
+}
+
+pub struct StopWordFilterStream<'a> {
+    words: Arc<HashSet<String>>,
+    tail: BoxTokenStream<'a>,
+}
+
+impl<'a> TokenStream for StopWordFilterStream<'a> {
+    fn advance(&mut self) -> bool {
+        while self.tail.advance() {
+            if !self.words.contains(&self.tail.token().text) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+/// Stop words of the Lucene `EnglishAnalyzer`.
+const ENGLISH_STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "if", "in", "into", "is", "it",
+    "no", "not", "of", "on", "or", "such", "that", "the", "their", "then", "there", "these",
+    "they", "this", "to", "was", "will", "with",
+];
+
+/// Stop words of the Snowball French stemmer, without the verb forms.
+const FRENCH_STOP_WORDS: &[&str] = &[
+    "au", "aux", "avec", "ce", "ces", "dans", "de", "des", "du", "elle", "en", "et", "eux", "il",
+    "ils", "je", "la", "le", "les", "leur", "lui", "ma", "mais", "me", "même", "mes", "moi", "mon",
+    "ne", "nos", "notre", "nous", "on", "ou", "par", "pas", "pour", "qu", "que", "qui", "sa", "se",
+    "ses", "son", "sur", "ta", "te", "tes", "toi", "ton", "tu", "un", "une", "vos", "votre",
+    "vous", "c", "d", "j", "l", "à", "m", "n", "s", "t", "y", "est", "été", "sont", "ceci", "cela",
+    "celà", "cet", "cette", "ici", "leurs", "quel", "quels", "quelle", "quelles", "sans", "soi",
+];
+
+/// Stop words of the Snowball German stemmer, without the verb forms.
+const GERMAN_STOP_WORDS: &[&str] = &[
+    "aber",
+    "alle",
+    "allem",
+    "allen",
+    "aller",
+    "alles",
+    "als",
+    "also",
+    "am",
+    "an",
+    "ander",
+    "andere",
+    "anderem",
+    "anderen",
+    "anderer",
+    "anderes",
+    "anderm",
+    "andern",
+    "anders",
+    "auch",
+    "auf",
+    "aus",
+    "bei",
+    "bis",
+    "bist",
+    "da",
+    "damit",
+    "dann",
+    "der",
+    "den",
+    "des",
+    "dem",
+    "die",
+    "das",
+    "dass",
+    "daß",
+    "derselbe",
+    "derselben",
+    "denselben",
+    "desselben",
+    "demselben",
+    "dieselbe",
+    "dieselben",
+    "dasselbe",
+    "dazu",
+    "dein",
+    "deine",
+    "deinem",
+    "deinen",
+    "deiner",
+    "deines",
+    "denn",
+    "derer",
+    "dessen",
+    "dich",
+    "dir",
+    "du",
+    "dies",
+    "diese",
+    "diesem",
+    "diesen",
+    "dieser",
+    "dieses",
+    "doch",
+    "dort",
+    "durch",
+    "ein",
+    "eine",
+    "einem",
+    "einen",
+    "einer",
+    "eines",
+    "einig",
+    "einige",
+    "einigem",
+    "einigen",
+    "einiger",
+    "einiges",
+    "einmal",
+    "er",
+    "ihn",
+    "ihm",
+    "es",
+    "etwas",
+    "euer",
+    "eure",
+    "eurem",
+    "euren",
+    "eurer",
+    "eures",
+    "für",
+    "gegen",
+    "gewesen",
+    "hab",
+    "habe",
+    "haben",
+    "hat",
+    "hatte",
+    "hatten",
+    "hier",
+    "hin",
+    "hinter",
+    "ich",
+    "mich",
+    "mir",
+    "ihr",
+    "ihre",
+    "ihrem",
+    "ihren",
+    "ihrer",
+    "ihres",
+    "euch",
+    "im",
+    "in",
+    "indem",
+    "ins",
+    "ist",
+    "jede",
+    "jedem",
+    "jeden",
+    "jeder",
+    "jedes",
+    "jene",
+    "jenem",
+    "jenen",
+    "jener",
+    "jenes",
+    "jetzt",
+    "kann",
+    "kein",
+    "keine",
+    "keinem",
+    "keinen",
+    "keiner",
+    "keines",
+    "können",
+    "könnte",
+    "machen",
+    "man",
+    "manche",
+    "manchem",
+    "manchen",
+    "mancher",
+    "manches",
+    "mein",
+    "meine",
+    "meinem",
+    "meinen",
+    "meiner",
+    "meines",
+    "mit",
+    "muss",
+    "musste",
+    "nach",
+    "nicht",
+    "nichts",
+    "noch",
+    "nun",
+    "nur",
+    "ob",
+    "oder",
+    "ohne",
+    "sehr",
+    "sein",
+    "seine",
+    "seinem",
+    "seinen",
+    "seiner",
+    "seines",
+    "selbst",
+    "sich",
+    "sie",
+    "ihnen",
+    "sind",
+    "so",
+    "solche",
+    "solchem",
+    "solchen",
+    "solcher",
+    "solches",
+    "soll",
+    "sollte",
+    "sondern",
+    "sonst",
+    "über",
+    "um",
+    "und",
+    "uns",
+    "unsere",
+    "unserem",
+    "unseren",
+    "unser",
+    "unseres",
+    "unter",
+    "viel",
+    "vom",
+    "von",
+    "vor",
+    "während",
+    "war",
+    "waren",
+    "warst",
+    "was",
+    "weg",
+    "weil",
+    "weiter",
+    "welche",
+    "welchem",
+    "welchen",
+    "welcher",
+    "welches",
+    "wenn",
+    "werde",
+    "werden",
+    "wie",
+    "wieder",
+    "will",
+    "wir",
+    "wird",
+    "wirst",
+    "wo",
+    "wollen",
+    "wollte",
+    "würde",
+    "würden",
+    "zu",
+    "zum",
+    "zur",
+    "zwar",
+    "zwischen",
+];
+
+#[cfg(test)]
+mod tests {
+    use crate::{LowerCaser, SimpleTokenizer, StopWordFilter, TextAnalyzer};
+
+    fn tokens(analyzer: &TextAnalyzer, text: &str) -> Vec<(String, usize)> {
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = Vec::new();
+        token_stream.process(&mut |token| tokens.push((token.text.clone(), token.position)));
+        tokens
+    }
+
+    #[test]
+    fn test_stop_word_filter_keeps_positions() {
+        let analyzer = TextAnalyzer::from(SimpleTokenizer)
+            .filter(LowerCaser)
+            .filter(StopWordFilter::english());
+        assert_eq!(
+            tokens(&analyzer, "The cat is on the mat"),
+            vec![("cat".to_string(), 1), ("mat".to_string(), 5)]
+        );
+    }
+
+    #[test]
+    fn test_custom_stop_words() {
+        let analyzer =
+            TextAnalyzer::from(SimpleTokenizer).filter(StopWordFilter::remove(vec!["foo"]));
+        assert_eq!(
+            tokens(&analyzer, "foo bar Foo"),
+            vec![("bar".to_string(), 1), ("Foo".to_string(), 2)]
+        );
+        let french = TextAnalyzer::from(SimpleTokenizer).filter(StopWordFilter::french());
+        assert_eq!(
+            tokens(&french, "le chat et la souris"),
+            vec![("chat".to_string(), 1), ("souris".to_string(), 4)]
+        );
+        let german = TextAnalyzer::from(SimpleTokenizer).filter(StopWordFilter::german());
+        assert_eq!(
+            tokens(&german, "der Hund und die Katze"),
+            vec![("Hund".to_string(), 1), ("Katze".to_string(), 4)]
+        );
+    }
+}
@@ -0,0 +1,405 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::{BoxTokenStream, MySearchError, Token, TokenFilter, TokenStream};
+
+/// Sequences of words and the sequences of words they are expanded to.
+struct SynonymMap {
+    /// The expansions of a sequence include the sequence itself when it is kept.
+    rules: HashMap<Vec<String>, Vec<Vec<String>>>,
+    /// Number of words of the longest sequence having synonyms.
+    max_input_len: usize,
+}
+
+impl SynonymMap {
+    fn parse(synonyms: &str) -> crate::Result<SynonymMap> {
+        let mut synonym_map = SynonymMap {
+            rules: HashMap::new(),
+            max_input_len: 0,
+        };
+        for (line_ord, line) in synonyms.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid_rule = || {
+                MySearchError::InvalidArgument(format!(
+                    "invalid synonym rule on line {}: '{}'",
+                    line_ord + 1,
+                    line
+                ))
+            };
+            let sides: Vec<&str> = line.split("=>").collect();
+            match sides[..] {
+                [equivalent] => {
+                    let equivalent = parse_word_sequences(equivalent).ok_or_else(invalid_rule)?;
+                    for input in &equivalent {
+                        synonym_map.add_rule(input, &equivalent);
+                    }
+                }
+                [inputs, outputs] => {
+                    let inputs = parse_word_sequences(inputs).ok_or_else(invalid_rule)?;
+                    let outputs = parse_word_sequences(outputs).ok_or_else(invalid_rule)?;
+                    for input in &inputs {
+                        synonym_map.add_rule(input, &outputs);
+                    }
+                }
+                _ => return Err(invalid_rule()),
+            }
+        }
+        Ok(synonym_map)
+    }
+
+    fn add_rule(&mut self, input: &[String], outputs: &[Vec<String>]) {
+        self.max_input_len = self.max_input_len.max(input.len());
+        let expansions = self.rules.entry(input.to_vec()).or_default();
+        for output in outputs {
+            if !expansions.contains(output) {
+                expansions.push(output.clone());
+            }
+        }
+    }
+
+    /// Returns the number of tokens of the longest sequence of `tokens`, taken
+    /// from the start, having synonyms, along with its expansions.
+    fn longest_match(&self, tokens: &VecDeque<Token>) -> Option<(usize, &[Vec<String>])> {
+        let mut words: Vec<String> = Vec::with_capacity(self.max_input_len);
+        for (token_ord, token) in tokens.iter().enumerate().take(self.max_input_len) {
+            // the words of a sequence must be adjacent, e.g. not separated by
+            // a stop word.
+            if token_ord > 0 && token.position != tokens[token_ord - 1].position + 1 {
+                break;
+            }
+            words.push(token.text.clone());
+        }
+        (1..=words.len()).rev().find_map(|len| {
+            self.rules
+                .get(&words[..len])
+                .map(|expansions| (len, expansions.as_slice()))
+        })
+    }
+}
+
+/// Parses a comma separated list of word sequences, returning `None` if one of
+/// them is empty.
+fn parse_word_sequences(list: &str) -> Option<Vec<Vec<String>>> {
+    list.split(',')
+        .map(|sequence| {
+            let words: Vec<String> = sequence.split_whitespace().map(String::from).collect();
+            if words.is_empty() {
+                None
+            } else {
+                Some(words)
+            }
+        })
+        .collect()
+}
+
+/// Injects the synonyms of the tokens in the token stream.
+///
+/// The rules follow the Solr synonym file format, one per line:
+/// - `a, b, c` makes the words equivalent: each of them is expanded to all of them.
+/// - `a, b => c, d` replaces `a` and `b` by `c` and `d`.
+///
+/// Empty lines and lines starting with `#` are ignored. Synonyms may be made of
+/// several words, separated by whitespace, and are matched against the text of
+/// consecutive tokens, so the filter usually comes after a `LowerCaser`.
+///
+/// Synonyms are emitted at the position of the words they replace. When a
+/// synonym and the words it replaces do not have the same number of tokens, the
+/// last token of the shorter one spans the remaining positions through its
+/// `position_length`, and the following tokens are shifted accordingly. For
+/// instance with `ny, new york`, "ny city" gives `ny` (spanning two positions)
+/// and `new` at position 0, `york` at 1 and `city` at 2. As long as the same
+/// analyzer is used when indexing and when parsing queries, a `PhraseQuery`
+/// matches either form.
+#[derive(Clone)]
+pub struct SynonymFilter {
+    synonyms: Arc<SynonymMap>,
+}
+
+impl SynonymFilter {
+    /// Creates a filter from rules in the Solr synonym format.
+    pub fn from_solr_rules(rules: &str) -> crate::Result<SynonymFilter> {
+        Ok(SynonymFilter {
+            synonyms: Arc::new(SynonymMap::parse(rules)?),
+        })
+    }
+
+    /// Creates a filter from a file in the Solr synonym format.
+    pub fn open<P: AsRef<Path>>(path: P) -> crate::Result<SynonymFilter> {
+        SynonymFilter::from_solr_rules(&fs::read_to_string(path)?)
+    }
+}
+
+impl TokenFilter for SynonymFilter {
+    fn transform<'a>(&self, token_stream: BoxTokenStream<'a>) -> BoxTokenStream<'a> {
+        BoxTokenStream::from(SynonymFilterStream {
+            synonyms: self.synonyms.clone(),
+            tail: token_stream,
+            lookahead: VecDeque::new(),
+            pending: VecDeque::new(),
+            token: Token::default(),
+            position_shift: 0,
+        })
+    }
+}
+
+pub struct SynonymFilterStream<'a> {
+    synonyms: Arc<SynonymMap>,
+    tail: BoxTokenStream<'a>,
+    /// Tokens read from `tail` to look for multi-word synonyms, not emitted yet.
+    lookahead: VecDeque<Token>,
+    /// Tokens ready to be emitted, in position order.
+    pending: VecDeque<Token>,
+    token: Token,
+    /// Number of positions added by the synonyms longer than the words they
+    /// replace.
+    position_shift: usize,
+}
+
+impl<'a> SynonymFilterStream<'a> {
+    /// Moves the next token of `lookahead`, or the tokens of the next sequence
+    /// having synonyms and its expansions, to `pending`.
+    fn expand_next(&mut self) {
+        while self.lookahead.len() < self.synonyms.max_input_len.max(1) && self.tail.advance() {
+            self.lookahead.push_back(self.tail.token().clone());
+        }
+        let synonyms = self.synonyms.clone();
+        let (input_len, expansions) = match synonyms.longest_match(&self.lookahead) {
+            Some(longest_match) => longest_match,
+            None => {
+                if let Some(mut token) = self.lookahead.pop_front() {
+                    token.position += self.position_shift;
+                    self.pending.push_back(token);
+                }
+                return;
+            }
+        };
+        let input: Vec<Token> = self.lookahead.drain(..input_len).collect();
+        let position = input[0].position + self.position_shift;
+        let offset_from = input[0].offset_from;
+        let offset_to = input[input_len - 1].offset_to;
+        let span_len = expansions.iter().map(Vec::len).fold(input_len, usize::max);
+        let is_input = |words: &Vec<String>| words.iter().eq(input.iter().map(|token| &token.text));
+        // the input tokens, if they are kept, come first among the tokens
+        // sharing their positions.
+        let mut paths: Vec<Vec<Token>> = Vec::with_capacity(expansions.len());
+        if expansions.iter().any(is_input) {
+            paths.push(input.clone());
+        }
+        for words in expansions.iter().filter(|words| !is_input(words)) {
+            paths.push(
+                words
+                    .iter()
+                    .map(|word| Token {
+                        offset_from,
+                        offset_to,
+                        position: 0,
+                        text: word.clone(),
+                        position_length: 1,
+                    })
+                    .collect(),
+            );
+        }
+        let mut tokens = Vec::new();
+        for mut path in paths {
+            let path_len = path.len();
+            for (token_ord, token) in path.iter_mut().enumerate() {
+                token.position = position + token_ord;
+                token.position_length = 1;
+            }
+            path[path_len - 1].position_length = span_len - path_len + 1;
+            tokens.extend(path);
+        }
+        tokens.sort_by_key(|token| token.position);
+        self.pending.extend(tokens);
+        self.position_shift += span_len - input_len;
+    }
+}
+
+impl<'a> TokenStream for SynonymFilterStream<'a> {
+    fn advance(&mut self) -> bool {
+        if self.pending.is_empty() {
+            self.expand_next();
+        }
+        match self.pending.pop_front() {
+            Some(token) => {
+                self.token = token;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn token(&self) -> &Token {
+        &self.token
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.token
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        query::tests::search_all, Index, IndexRecordOption, LowerCaser, QueryParser, Schema,
+        SimpleTokenizer, StopWordFilter, SynonymFilter, TextAnalyzer, TextFieldIndexing,
+        TextOptions,
+    };
+
+    const SYNONYMS: &str = "
+        # equivalent words
+        ny, new york
+        couch, sofa
+        # explicit mappings
+        usa => united states
+        big apple => ny
+    ";
+
+    fn analyzer() -> TextAnalyzer {
+        TextAnalyzer::from(SimpleTokenizer)
+            .filter(LowerCaser)
+            .filter(SynonymFilter::from_solr_rules(SYNONYMS).unwrap())
+    }
+
+    /// Returns the text, position and position length of the tokens.
+    fn tokens(analyzer: &TextAnalyzer, text: &str) -> Vec<(String, usize, usize)> {
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = Vec::new();
+        token_stream.process(&mut |token| {
+            tokens.push((token.text.clone(), token.position, token.position_length))
+        });
+        tokens
+    }
+
+    fn token(text: &str, position: usize, position_length: usize) -> (String, usize, usize) {
+        (text.to_string(), position, position_length)
+    }
+
+    #[test]
+    fn test_single_word_synonyms() {
+        assert_eq!(
+            tokens(&analyzer(), "Red sofa"),
+            vec![
+                token("red", 0, 1),
+                token("sofa", 1, 1),
+                token("couch", 1, 1)
+            ]
+        );
+        assert_eq!(
+            tokens(&analyzer(), "no synonym"),
+            vec![token("no", 0, 1), token("synonym", 1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_multi_word_synonyms() {
+        assert_eq!(
+            tokens(&analyzer(), "NY city"),
+            vec![
+                token("ny", 0, 2),
+                token("new", 0, 1),
+                token("york", 1, 1),
+                token("city", 2, 1),
+            ]
+        );
+        assert_eq!(
+            tokens(&analyzer(), "new york city"),
+            vec![
+                token("new", 0, 1),
+                token("ny", 0, 2),
+                token("york", 1, 1),
+                token("city", 2, 1),
+            ]
+        );
+        // the mapped words are replaced.
+        assert_eq!(
+            tokens(&analyzer(), "usa today"),
+            vec![
+                token("united", 0, 1),
+                token("states", 1, 1),
+                token("today", 2, 1)
+            ]
+        );
+        assert_eq!(
+            tokens(&analyzer(), "the big apple"),
+            vec![token("the", 0, 1), token("ny", 1, 2)]
+        );
+    }
+
+    #[test]
+    fn test_synonym_offsets() {
+        let mut token_stream = analyzer().token_stream("in ny");
+        let mut offsets = Vec::new();
+        token_stream.process(&mut |token| {
+            offsets.push((token.text.clone(), token.offset_from, token.offset_to))
+        });
+        assert_eq!(
+            offsets,
+            vec![
+                ("in".to_string(), 0, 2),
+                ("ny".to_string(), 3, 5),
+                ("new".to_string(), 3, 5),
+                ("york".to_string(), 3, 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_words_separated_by_stop_word() {
+        let analyzer = TextAnalyzer::from(SimpleTokenizer)
+            .filter(StopWordFilter::remove(vec!["the"]))
+            .filter(SynonymFilter::from_solr_rules("ny, new york").unwrap());
+        assert_eq!(
+            tokens(&analyzer, "new the york"),
+            vec![token("new", 0, 1), token("york", 2, 1)]
+        );
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert!(SynonymFilter::from_solr_rules("a, , b").is_err());
+        assert!(SynonymFilter::from_solr_rules("a => b => c").is_err());
+        assert!(SynonymFilter::from_solr_rules("a =>").is_err());
+        assert!(SynonymFilter::open("missing-synonyms.txt").is_err());
+    }
+
+    #[test]
+    fn test_phrase_query_matches_either_form() -> crate::Result<()> {
+        let mut schema_builder = Schema::builder();
+        let indexing = TextFieldIndexing::default()
+            .set_tokenizer("synonyms")
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions);
+        let text = schema_builder.add_text_field(
+            "text",
+            TextOptions::default().set_indexing_options(indexing),
+        );
+        let index = Index::create_in_ram(schema_builder.build());
+        index.tokenizers().register("synonyms", analyzer());
+        let mut index_writer = index.writer_with_num_threads(1, 10_000_000)?;
+        index_writer.add_document(doc!(text => "I love NY city"))?;
+        index_writer.add_document(doc!(text => "I love new york city"))?;
+        index_writer.add_document(doc!(text => "new city in york"))?;
+        index_writer.commit()?;
+        let searcher = index.reader()?.searcher();
+        let query_parser = QueryParser::for_index(&index, vec![text]);
+        let matching_docs = |query: &str| -> crate::Result<Vec<u32>> {
+            let query = query_parser.parse_query(query).unwrap();
+            let mut docs: Vec<u32> = search_all(query.as_ref(), &searcher)?
+                .into_iter()
+                .map(|(_, doc_address)| doc_address.doc_id)
+                .collect();
+            docs.sort_unstable();
+            Ok(docs)
+        };
+        assert_eq!(matching_docs("\"ny city\"")?, vec![0, 1]);
+        assert_eq!(matching_docs("\"new york city\"")?, vec![0, 1]);
+        assert_eq!(matching_docs("\"love ny\"")?, vec![0, 1]);
+        assert_eq!(matching_docs("york")?, vec![0, 1, 2]);
+        Ok(())
+    }
+}
@@ -154,6 +154,12 @@ impl TextAnalyzer {
         }
     }
 
+    /// Appends a token filter to the chain, returning the updated analyzer.
+    pub fn filter<F: Into<BoxTokenFilter>>(mut self, token_filter: F) -> Self {
+        self.token_filters.push(token_filter.into());
+        self
+    }
+
     pub fn token_stream<'a>(&self, text: &'a str) -> BoxTokenStream<'a> {
         let mut token_stream = self.tokenizer.token_stream(text);
         for token_filter in &self.token_filters {
@@ -1,7 +1,50 @@
-use crate::{SimpleTokenizer, TextAnalyzer};
+use crate::{
+    CjkBigramTokenizer, JapaneseTokenizer, Language, LowerCaser, RawTokenizer, RemoveLongFilter,
+    SimpleTokenizer, Stemmer, StopWordFilter, TextAnalyzer,
+};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
+/// Tokens longer than this number of bytes are dropped by the language
+/// analyzers.
+const MAX_TOKEN_LEN: usize = 40;
+
+/// Names of the stemming analyzers registered by default.
+const STEMMING_ANALYZERS: &[(&str, Language)] = &[
+    ("ar_stem", Language::Arabic),
+    ("da_stem", Language::Danish),
+    ("nl_stem", Language::Dutch),
+    ("en_stem", Language::English),
+    ("fi_stem", Language::Finnish),
+    ("fr_stem", Language::French),
+    ("de_stem", Language::German),
+    ("el_stem", Language::Greek),
+    ("hu_stem", Language::Hungarian),
+    ("it_stem", Language::Italian),
+    ("no_stem", Language::Norwegian),
+    ("pt_stem", Language::Portuguese),
+    ("ro_stem", Language::Romanian),
+    ("ru_stem", Language::Russian),
+    ("es_stem", Language::Spanish),
+    ("sv_stem", Language::Swedish),
+    ("ta_stem", Language::Tamil),
+    ("tr_stem", Language::Turkish),
+];
+
+/// Registry of the `TextAnalyzer`s that text fields refer to by name.
+///
+/// The default manager provides:
+/// - `raw`: the whole text as a single token,
+/// - `default`: words split on non-alphanumeric characters, case preserved,
+/// - `en`: same as `default`, lower-cased, without English stop words nor the
+///   tokens longer than 40 bytes,
+/// - `en_stem`, `fr_stem`, `de_stem`, `es_stem`, ...: same as `default`,
+///   lower-cased, without the tokens longer than 40 bytes, and stemmed with
+///   the Snowball algorithm of the language,
+/// - `cjk`: bigrams of Chinese, Japanese and Korean characters, and lower-cased
+///   words of the other scripts,
+/// - `ja`: Japanese words segmented by the `JapaneseTokenizer` with its
+///   embedded dictionary, lower-cased.
 #[derive(Clone)]
 pub struct TokenizerManager {
     tokenizers: Arc<RwLock<HashMap<String, TextAnalyzer>>>,
@@ -29,8 +72,72 @@ impl Default for TokenizerManager {
         let manager = TokenizerManager {
             tokenizers: Arc::new(RwLock::new(HashMap::new())),
         };
+        manager.register("raw", TextAnalyzer::from(RawTokenizer));
         manager.register("default", TextAnalyzer::from(SimpleTokenizer));
+        manager.register(
+            "en",
+            TextAnalyzer::from(SimpleTokenizer)
+                .filter(RemoveLongFilter::limit(MAX_TOKEN_LEN))
+                .filter(LowerCaser)
+                .filter(StopWordFilter::english()),
+        );
+        manager.register(
+            "cjk",
+            TextAnalyzer::from(CjkBigramTokenizer).filter(LowerCaser),
+        );
+        manager.register(
+            "ja",
+            TextAnalyzer::from(JapaneseTokenizer::default()).filter(LowerCaser),
+        );
+        for &(tokenizer_name, language) in STEMMING_ANALYZERS {
+            manager.register(
+                tokenizer_name,
+                TextAnalyzer::from(SimpleTokenizer)
+                    .filter(RemoveLongFilter::limit(MAX_TOKEN_LEN))
+                    .filter(LowerCaser)
+                    .filter(Stemmer::new(language)),
+            );
+        }
 
         manager
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::TokenizerManager;
+
+    fn tokens(tokenizer_name: &str, text: &str) -> Vec<String> {
+        let analyzer = TokenizerManager::default().get(tokenizer_name).unwrap();
+        let mut token_stream = analyzer.token_stream(text);
+        let mut tokens = Vec::new();
+        token_stream.process(&mut |token| tokens.push(token.text.clone()));
+        tokens
+    }
+
+    #[test]
+    fn test_default_analyzers() {
+        assert_eq!(tokens("raw", "Hello, World"), vec!["Hello, World"]);
+        assert_eq!(tokens("default", "Hello, World"), vec!["Hello", "World"]);
+        let max_len_word = "a".repeat(40);
+        let long_word = "b".repeat(41);
+        assert_eq!(
+            tokens(
+                "en",
+                &format!("THE Quick {} {} fox", max_len_word, long_word)
+            ),
+            vec!["quick", &max_len_word, "fox"]
+        );
+        assert_eq!(
+            tokens("en_stem", "The Cats running"),
+            vec!["the", "cat", "run"]
+        );
+        assert_eq!(tokens("fr_stem", "Les Chevaux"), vec!["le", "cheval"]);
+        assert_eq!(tokens("cjk", "東京のRust"), vec!["東京", "京の", "rust"]);
+        assert_eq!(tokens("ja", "東京のRust"), vec!["東京", "の", "rust"]);
+        assert_eq!(
+            tokens("ja", "私は毎朝コーヒーを飲みます。"),
+            vec!["私", "は", "毎朝", "コーヒー", "を", "飲み", "ます"]
+        );
+    }
+}